@@ -175,6 +175,45 @@ impl ArchiveReader {
     where
         S: WriteStore + Clone,
         <S as ReadStore>::Error: std::error::Error,
+    {
+        self.for_each_checkpoint(checkpoint_range, |summary, contents| {
+            let verified_checkpoint = Self::get_or_insert_verified_checkpoint(&store, summary)?;
+            let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
+            // Insert content
+            store
+                .insert_checkpoint_contents(&verified_checkpoint, verified_contents)
+                .map_err(|e| anyhow!("Failed to insert content: {e}"))?;
+            // Update highest synced watermark
+            store
+                .update_highest_synced_checkpoint(&verified_checkpoint)
+                .map_err(|e| anyhow!("Failed to update watermark: {e}"))?;
+            txn_counter.fetch_add(contents.size() as u64, Ordering::Relaxed);
+            self.archive_reader_metrics
+                .archive_txns_read
+                .with_label_values(&[&self.bucket])
+                .inc_by(contents.size() as u64);
+            checkpoint_counter.fetch_add(1, Ordering::Relaxed);
+            self.archive_reader_metrics
+                .archive_checkpoints_read
+                .with_label_values(&[&self.bucket])
+                .inc_by(1);
+            Ok::<(), anyhow::Error>(())
+        })
+        .await
+    }
+
+    /// Stream the checkpoints of the given range from the archive in sequence number order,
+    /// calling `f` on each summary and its full contents. Summaries are not verified, only the
+    /// contents are checked against the summaries. Fails if the latest available checkpoint in
+    /// archive is older than the start of the range, otherwise reads as many checkpoints as
+    /// possible until the end of the range.
+    pub async fn for_each_checkpoint<F>(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+        mut f: F,
+    ) -> Result<()>
+    where
+        F: FnMut(CertifiedCheckpointSummary, CheckpointContents) -> Result<()>,
     {
         let manifest = self.manifest.lock().await.clone();
 
@@ -258,55 +297,31 @@ impl ArchiveReader {
             .boxed()
             .buffered(self.concurrency)
             .try_for_each(|(summary_data, content_data)| {
-                let result: Result<(), anyhow::Error> = make_iterator::<
-                    CertifiedCheckpointSummary,
-                    Reader<Bytes>,
-                >(
-                    SUMMARY_FILE_MAGIC, summary_data.reader()
-                )
-                .and_then(|s| {
-                    make_iterator::<CheckpointContents, Reader<Bytes>>(
-                        CHECKPOINT_FILE_MAGIC,
-                        content_data.reader(),
+                let result: Result<(), anyhow::Error> =
+                    make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+                        SUMMARY_FILE_MAGIC,
+                        summary_data.reader(),
                     )
-                    .map(|c| (s, c))
-                })
-                .and_then(|(summary_iter, content_iter)| {
-                    summary_iter
-                        .zip(content_iter)
-                        .filter(|(s, _c)| {
-                            s.sequence_number >= checkpoint_range.start
-                                && s.sequence_number < checkpoint_range.end
-                        })
-                        .try_for_each(|(summary, contents)| {
-                            let verified_checkpoint =
-                                Self::get_or_insert_verified_checkpoint(&store, summary)?;
-                            // Verify content
-                            let digest = verified_checkpoint.content_digest;
-                            contents.verify_digests(digest)?;
-                            let verified_contents =
-                                VerifiedCheckpointContents::new_unchecked(contents.clone());
-                            // Insert content
-                            store
-                                .insert_checkpoint_contents(&verified_checkpoint, verified_contents)
-                                .map_err(|e| anyhow!("Failed to insert content: {e}"))?;
-                            // Update highest synced watermark
-                            store
-                                .update_highest_synced_checkpoint(&verified_checkpoint)
-                                .map_err(|e| anyhow!("Failed to update watermark: {e}"))?;
-                            txn_counter.fetch_add(contents.size() as u64, Ordering::Relaxed);
-                            self.archive_reader_metrics
-                                .archive_txns_read
-                                .with_label_values(&[&self.bucket])
-                                .inc_by(contents.size() as u64);
-                            checkpoint_counter.fetch_add(1, Ordering::Relaxed);
-                            self.archive_reader_metrics
-                                .archive_checkpoints_read
-                                .with_label_values(&[&self.bucket])
-                                .inc_by(1);
-                            Ok::<(), anyhow::Error>(())
-                        })
-                });
+                    .and_then(|s| {
+                        make_iterator::<CheckpointContents, Reader<Bytes>>(
+                            CHECKPOINT_FILE_MAGIC,
+                            content_data.reader(),
+                        )
+                        .map(|c| (s, c))
+                    })
+                    .and_then(|(summary_iter, content_iter)| {
+                        summary_iter
+                            .zip(content_iter)
+                            .filter(|(s, _c)| {
+                                s.sequence_number >= checkpoint_range.start
+                                    && s.sequence_number < checkpoint_range.end
+                            })
+                            .try_for_each(|(summary, contents)| {
+                                // Verify content
+                                contents.verify_digests(summary.content_digest)?;
+                                f(summary, contents)
+                            })
+                    });
                 futures::future::ready(result)
             })
             .await
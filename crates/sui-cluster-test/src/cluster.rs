@@ -328,6 +328,7 @@ pub async fn new_wallet_context_from_cluster(
         }],
         active_address: Some(address),
         active_env: Some("localnet".to_string()),
+        gas_budget_margin: None,
     }
     .persisted(&wallet_config_path)
     .save()
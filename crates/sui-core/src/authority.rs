@@ -14,7 +14,6 @@ use futures::stream::FuturesUnordered;
 use itertools::Itertools;
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
-use move_core_types::language_storage::ModuleId;
 use move_core_types::value::MoveStructLayout;
 use mysten_metrics::{TX_TYPE_SHARED_OBJ_TX, TX_TYPE_SINGLE_WRITER_TX};
 use parking_lot::Mutex;
@@ -59,12 +58,14 @@ use sui_config::node::{
 use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_framework::{BuiltInFramework, SystemPackage};
 use sui_json_rpc_types::{
-    Checkpoint, DevInspectResults, DryRunTransactionBlockResponse, EventFilter, SuiEvent,
-    SuiMoveValue, SuiObjectDataFilter, SuiTransactionBlockData, SuiTransactionBlockEffects,
-    SuiTransactionBlockEvents, TransactionFilter,
+    Checkpoint, DevInspectResults, DryRunTransactionBlockResponse, EventFilter, Filter, SuiEvent,
+    SuiMoveValue, SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiTransactionBlockData, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
+    TransactionFilter,
 };
 use sui_macros::{fail_point, fail_point_async};
 use sui_protocol_config::{ProtocolConfig, SupportedProtocolVersions};
+use sui_storage::event_query::EventQueryPlan;
 use sui_storage::indexes::{CoinInfo, ObjectIndexChanges};
 use sui_storage::IndexStore;
 use sui_types::committee::{EpochId, ProtocolVersion};
@@ -257,6 +258,10 @@ const POSITIVE_INT_BUCKETS: &[f64] = &[
     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000., 10000., 20000., 50000.,
 ];
 
+/// Maximum number of event index rows scanned by one event query. Filters evaluated on the
+/// candidate events, e.g. `MoveEventField`, can otherwise walk the whole event table for a page.
+pub const MAX_EVENT_QUERY_SCANNED_ROWS: usize = 10_000;
+
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1., 2., 3., 4., 5., 6., 7., 8., 9., 10., 20.,
     30., 60., 90.,
@@ -572,6 +577,14 @@ impl AuthorityMetrics {
 ///
 /// Typically instantiated with Box::pin(keypair) where keypair is a `KeyPair`
 ///
+/// Result of [`AuthorityState::query_events`].
+pub struct EventQueryResult {
+    pub events: Vec<SuiEvent>,
+    /// Set when the query stopped after scanning [`MAX_EVENT_QUERY_SCANNED_ROWS`] rows before
+    /// filling the page, the query can be resumed from this cursor.
+    pub scan_cursor: Option<EventID>,
+}
+
 pub type StableSyncAuthoritySigner = Pin<Arc<dyn Signer<AuthoritySignature> + Send + Sync>>;
 
 pub struct AuthorityState {
@@ -1205,10 +1218,13 @@ impl AuthorityState {
         Ok((inner_temp_store, effects, execution_error_opt.err()))
     }
 
+    /// Execute `transaction` without committing its effects. With `show_gas_profile`, the
+    /// response includes a gas profile of the execution.
     pub async fn dry_exec_transaction(
         &self,
         transaction: TransactionData,
         transaction_digest: TransactionDigest,
+        show_gas_profile: bool,
     ) -> SuiResult<(
         DryRunTransactionBlockResponse,
         BTreeMap<ObjectID, (ObjectRef, Object, WriteKind)>,
@@ -1288,10 +1304,23 @@ impl AuthorityState {
         let silent = true;
         // don't bother with paranoid checks in dry run
         let enable_move_vm_paranoid_checks = false;
-        let executor =
-            sui_execution::executor(protocol_config, enable_move_vm_paranoid_checks, silent)
-                .expect("Creating an executor should not fail here");
+        let executor = sui_execution::executor(
+            protocol_config,
+            enable_move_vm_paranoid_checks,
+            silent,
+            None,
+        )
+        .expect("Creating an executor should not fail here");
 
+        let mut gas_charger = GasCharger::new(
+            transaction_digest,
+            gas_object_refs,
+            gas_status,
+            protocol_config,
+        );
+        if show_gas_profile {
+            gas_charger.enable_gas_profile();
+        }
         let expensive_checks = false;
         let (inner_temp_store, effects, _execution_error) = executor
             .execute_transaction_to_effects(
@@ -1306,18 +1335,14 @@ impl AuthorityState {
                     .epoch_start_timestamp(),
                 temporary_store,
                 shared_object_refs,
-                &mut GasCharger::new(
-                    transaction_digest,
-                    gas_object_refs,
-                    gas_status,
-                    protocol_config,
-                ),
+                &mut gas_charger,
                 kind,
                 signer,
                 transaction_digest,
                 transaction_dependencies,
             );
         let tx_digest = *effects.transaction_digest();
+        let gas_profile = gas_charger.take_gas_profile();
 
         let module_cache =
             TemporaryModuleResolver::new(&inner_temp_store, epoch_store.module_cache().clone());
@@ -1346,6 +1371,7 @@ impl AuthorityState {
                 )?,
                 object_changes,
                 balance_changes,
+                gas_profile,
             },
             inner_temp_store.written,
             effects,
@@ -1359,6 +1385,7 @@ impl AuthorityState {
         sender: SuiAddress,
         transaction_kind: TransactionKind,
         gas_price: Option<u64>,
+        show_trace: bool,
     ) -> SuiResult<DevInspectResults> {
         let epoch_store = self.load_epoch_store_one_call_per_task();
         if !self.is_fullnode(&epoch_store) {
@@ -1424,8 +1451,18 @@ impl AuthorityState {
             self.expensive_safety_check_config
                 .enable_move_vm_paranoid_checks(),
             silent,
+            None,
         )
         .expect("Creating an executor should not fail here");
+        let mut gas_charger = GasCharger::new(
+            transaction_digest,
+            vec![gas_object_ref],
+            gas_status,
+            protocol_config,
+        );
+        if show_trace {
+            gas_charger.move_gas_status_mut().enable_trace();
+        }
         let expensive_checks = false;
         let (inner_temp_store, effects, execution_result) = executor.dev_inspect_transaction(
             protocol_config,
@@ -1439,12 +1476,7 @@ impl AuthorityState {
                 .epoch_start_timestamp(),
             temporary_store,
             shared_object_refs,
-            &mut GasCharger::new(
-                transaction_digest,
-                vec![gas_object_ref],
-                gas_status,
-                protocol_config,
-            ),
+            &mut gas_charger,
             transaction_kind,
             sender,
             transaction_digest,
@@ -1458,6 +1490,7 @@ impl AuthorityState {
             effects,
             inner_temp_store.events.clone(),
             execution_result,
+            gas_charger.move_gas_status_mut().take_trace(),
             &module_cache,
         )
     }
@@ -1782,6 +1815,35 @@ impl AuthorityState {
             self.metrics
                 .post_processing_total_events_emitted
                 .inc_by(events.data.len() as u64);
+
+            // Resolving the layouts is not free, only stream objects when someone listens.
+            if self.subscription_handler.has_object_subscribers() {
+                let objects: Vec<SuiObjectData> = written
+                    .values()
+                    .filter_map(|(object_ref, object, _)| {
+                        object
+                            .get_layout(ObjectFormatOptions::default(), &module_resolver)
+                            .map_err(anyhow::Error::from)
+                            .and_then(|layout| {
+                                SuiObjectData::try_from((
+                                    *object_ref,
+                                    object.clone(),
+                                    layout,
+                                    SuiObjectDataOptions::full_content().with_bcs(),
+                                ))
+                            })
+                            .tap_err(|e| {
+                                warn!(
+                                    ?tx_digest,
+                                    object_id = ?object_ref.0,
+                                    "Post processing - Couldn't convert object for subscription: {e}"
+                                )
+                            })
+                            .ok()
+                    })
+                    .collect();
+                self.subscription_handler.process_objects(&objects).await?;
+            }
         };
         Ok(())
     }
@@ -2621,6 +2683,24 @@ impl AuthorityState {
         }
     }
 
+    /// Read the latest version of an object at or before `version`. Versions which are not
+    /// found, and versions at which the object was deleted or wrapped, are not distinguished.
+    pub fn get_past_object_read_lt_or_eq_version(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<PastObjectRead> {
+        let Some(object) = self.database.find_object_lt_or_eq_version(*object_id, version) else {
+            return Ok(PastObjectRead::VersionNotFound(*object_id, version));
+        };
+        let layout = self.get_object_layout(&object)?;
+        Ok(PastObjectRead::VersionFound(
+            object.compute_object_reference(),
+            object,
+            layout,
+        ))
+    }
+
     fn read_object_at_version(
         &self,
         object_id: &ObjectID,
@@ -3109,117 +3189,90 @@ impl AuthorityState {
         cursor: Option<EventID>,
         limit: usize,
         descending: bool,
-    ) -> SuiResult<Vec<SuiEvent>> {
+    ) -> SuiResult<EventQueryResult> {
         let index_store = self.get_indexes()?;
 
-        //Get the tx_num from tx_digest
-        let (tx_num, event_num) = if let Some(cursor) = cursor.as_ref() {
+        // Get the position of the cursor event in the event indexes
+        let mut after = if let Some(cursor) = cursor.as_ref() {
             let tx_seq = index_store.get_transaction_seq(&cursor.tx_digest)?.ok_or(
                 SuiError::TransactionNotFound {
                     digest: cursor.tx_digest,
                 },
             )?;
-            (tx_seq, cursor.event_seq as usize)
-        } else if descending {
-            (u64::MAX, usize::MAX)
+            Some((tx_seq, cursor.event_seq as usize))
         } else {
-            (0, 0)
+            None
         };
 
-        let limit = limit + 1;
-        let mut event_keys = match query {
-            EventFilter::All(filters) => {
-                if filters.is_empty() {
-                    index_store.all_events(tx_num, event_num, limit, descending)?
-                } else {
-                    return Err(SuiError::UserInputError {
-                        error: UserInputError::Unsupported(
-                            "This query type does not currently support filter combinations"
-                                .to_string(),
-                        ),
-                    });
-                }
-            }
-            EventFilter::Transaction(digest) => {
-                index_store.events_by_transaction(&digest, tx_num, event_num, limit, descending)?
-            }
-            EventFilter::MoveModule { package, module } => {
-                let module_id = ModuleId::new(package.into(), module);
-                index_store.events_by_module_id(&module_id, tx_num, event_num, limit, descending)?
-            }
-            EventFilter::MoveEventType(struct_name) => index_store
-                .events_by_move_event_struct_name(
-                    &struct_name,
-                    tx_num,
-                    event_num,
-                    limit,
-                    descending,
-                )?,
-            EventFilter::Sender(sender) => {
-                index_store.events_by_sender(&sender, tx_num, event_num, limit, descending)?
+        let plan = EventQueryPlan::new(&query);
+        // Without a residual filter every candidate is a result, otherwise keep scanning in
+        // batches until the page is full or the scans are exhausted.
+        let batch_size = limit.max(1);
+        let mut events = vec![];
+        let mut scanned_rows = 0;
+        let mut last_scanned = None;
+        while events.len() < limit {
+            if scanned_rows >= MAX_EVENT_QUERY_SCANNED_ROWS {
+                // Let the caller resume the scan after the last scanned event.
+                return Ok(EventQueryResult {
+                    events,
+                    scan_cursor: last_scanned,
+                });
             }
-            EventFilter::TimeRange {
-                start_time,
-                end_time,
-            } => index_store
-                .event_iterator(start_time, end_time, tx_num, event_num, limit, descending)?,
-            EventFilter::MoveEventModule { package, module } => index_store
-                .events_by_move_event_module(
-                    &ModuleId::new(package.into(), module),
-                    tx_num,
-                    event_num,
-                    limit,
-                    descending,
-                )?,
-            // not using "_ =>" because we want to make sure we remember to add new variants here
-            EventFilter::Package(_)
-            | EventFilter::MoveEventField { .. }
-            | EventFilter::Any(_)
-            | EventFilter::And(_, _)
-            | EventFilter::Or(_, _) => {
-                return Err(SuiError::UserInputError {
-                    error: UserInputError::Unsupported(
-                        "This query type is not supported by the full node.".to_string(),
-                    ),
+            let event_keys = index_store.scan_events(&plan, after, batch_size, descending)?;
+            let exhausted = event_keys.len() < batch_size;
+            scanned_rows += event_keys.len();
+            after = event_keys.last().map(|(id, _)| *id).or(after);
+            last_scanned = event_keys
+                .last()
+                .map(|((_, event_seq), (_, tx_digest, _))| EventID {
+                    tx_digest: *tx_digest,
+                    event_seq: *event_seq as u64,
                 })
-            }
-        };
+                .or(last_scanned);
 
-        // skip one event if exclusive cursor is provided,
-        // otherwise truncate to the original limit.
-        if cursor.is_some() {
-            if !event_keys.is_empty() {
-                event_keys.remove(0);
-            }
-        } else {
-            event_keys.truncate(limit - 1);
-        }
-        let keys = event_keys.iter().map(|(digest, _, seq, _)| (*digest, *seq));
+            let keys = event_keys
+                .iter()
+                .map(|((_, event_seq), (digest, _, _))| (*digest, *event_seq));
 
-        let stored_events = self
-            .database
-            .perpetual_tables
-            .events
-            .multi_get(keys)?
-            .into_iter()
-            .zip(event_keys.into_iter())
-            .map(|(e, (digest, tx_digest, event_seq, timestamp))| {
-                e.map(|e| (e, tx_digest, event_seq, timestamp))
-                    .ok_or(SuiError::TransactionEventsNotFound { digest })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+            let stored_events = self
+                .database
+                .perpetual_tables
+                .events
+                .multi_get(keys)?
+                .into_iter()
+                .zip(event_keys.into_iter())
+                .map(|(e, ((_, event_seq), (digest, tx_digest, timestamp)))| {
+                    e.map(|e| (e, tx_digest, event_seq, timestamp))
+                        .ok_or(SuiError::TransactionEventsNotFound { digest })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
 
-        let mut events = vec![];
-        for (e, tx_digest, event_seq, timestamp) in stored_events {
-            events.push(SuiEvent::try_from(
-                e,
-                tx_digest,
-                event_seq as u64,
-                Some(timestamp),
-                &**self.epoch_store.load().module_cache(),
-            )?)
+            for (e, tx_digest, event_seq, timestamp) in stored_events {
+                let event = SuiEvent::try_from(
+                    e,
+                    tx_digest,
+                    event_seq as u64,
+                    Some(timestamp),
+                    &**self.epoch_store.load().module_cache(),
+                )?;
+                if plan
+                    .residual
+                    .as_ref()
+                    .map_or(true, |filter| filter.matches(&event))
+                {
+                    events.push(event);
+                }
+            }
+            if exhausted {
+                break;
+            }
         }
-        Ok(events)
+        events.truncate(limit);
+        Ok(EventQueryResult {
+            events,
+            scan_cursor: None,
+        })
     }
 
     pub async fn insert_genesis_object(&self, object: Object) {
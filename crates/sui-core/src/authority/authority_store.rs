@@ -3,7 +3,6 @@
 
 use std::cmp::Ordering;
 use std::hash::Hash;
-use std::ops::Not;
 use std::sync::Arc;
 use std::{iter, mem, thread};
 
@@ -328,13 +327,7 @@ impl AuthorityStore {
         &self,
         event_digest: &TransactionEventsDigest,
     ) -> Result<Option<TransactionEvents>, TypedStoreError> {
-        let data = self
-            .perpetual_tables
-            .events
-            .range_iter((*event_digest, 0)..=(*event_digest, usize::MAX))
-            .map(|(_, e)| e)
-            .collect::<Vec<_>>();
-        Ok(data.is_empty().not().then_some(TransactionEvents { data }))
+        self.perpetual_tables.get_events(event_digest)
     }
 
     pub fn multi_get_events(
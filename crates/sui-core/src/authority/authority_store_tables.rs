@@ -9,12 +9,13 @@ use std::path::Path;
 use sui_types::accumulator::Accumulator;
 use sui_types::base_types::SequenceNumber;
 use sui_types::digests::TransactionEventsDigest;
-use sui_types::effects::TransactionEffects;
+use sui_types::effects::{TransactionEffects, TransactionEvents};
 use sui_types::storage::MarkerKind;
 use typed_store::metrics::SamplingInterval;
 use typed_store::rocks::util::{empty_compaction_filter, reference_count_merge_operator};
 use typed_store::rocks::{
-    default_db_options, read_size_from_env, DBBatch, DBMap, DBOptions, MetricConf, ReadWriteOptions,
+    default_db_options, read_size_from_env, DBBatch, DBMap, DBOptions, MetricConf,
+    ReadWriteOptions, TypedStoreError,
 };
 use typed_store::traits::{Map, TableSummary, TypedStoreDebug};
 
@@ -320,6 +321,18 @@ impl AuthorityPerpetualTables {
         Ok(self.effects.get(&effect_digest)?)
     }
 
+    pub fn get_events(
+        &self,
+        event_digest: &TransactionEventsDigest,
+    ) -> Result<Option<TransactionEvents>, TypedStoreError> {
+        let data = self
+            .events
+            .range_iter((*event_digest, 0)..=(*event_digest, usize::MAX))
+            .map(|(_, e)| e)
+            .collect::<Vec<_>>();
+        Ok((!data.is_empty()).then_some(TransactionEvents { data }))
+    }
+
     // DEPRECATED as the backing table has been moved to authority_per_epoch_store.
     // Please do not add new accessors/callsites.
     pub fn get_checkpoint_sequence_number(
@@ -29,6 +29,7 @@ use itertools::izip;
 use mysten_metrics::{spawn_monitored_task, MonitoredFutureExt};
 use prometheus::Registry;
 use sui_config::node::CheckpointExecutorConfig;
+use sui_json_rpc_types::Checkpoint;
 use sui_macros::{fail_point, fail_point_async};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::executable_transaction::VerifiedExecutableTransaction;
@@ -52,6 +53,7 @@ use typed_store::Map;
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::authority::AuthorityStore;
 use crate::state_accumulator::StateAccumulator;
+use crate::subscription_handler::SubscriptionHandler;
 use crate::transaction_manager::TransactionManager;
 use crate::{authority::EffectsNotifyRead, checkpoints::CheckpointStore};
 
@@ -72,6 +74,7 @@ pub struct CheckpointExecutor {
     authority_store: Arc<AuthorityStore>,
     tx_manager: Arc<TransactionManager>,
     accumulator: Arc<StateAccumulator>,
+    subscription_handler: Option<Arc<SubscriptionHandler>>,
     config: CheckpointExecutorConfig,
     metrics: Arc<CheckpointExecutorMetrics>,
 }
@@ -83,6 +86,7 @@ impl CheckpointExecutor {
         authority_store: Arc<AuthorityStore>,
         tx_manager: Arc<TransactionManager>,
         accumulator: Arc<StateAccumulator>,
+        subscription_handler: Option<Arc<SubscriptionHandler>>,
         config: CheckpointExecutorConfig,
         prometheus_registry: &Registry,
     ) -> Self {
@@ -92,6 +96,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            subscription_handler,
             config,
             metrics: CheckpointExecutorMetrics::new(prometheus_registry),
         }
@@ -110,6 +115,7 @@ impl CheckpointExecutor {
             authority_store,
             tx_manager,
             accumulator,
+            subscription_handler: None,
             config: Default::default(),
             metrics: CheckpointExecutorMetrics::new_for_tests(),
         }
@@ -187,6 +193,7 @@ impl CheckpointExecutor {
                 // guarantees that we will also ratchet the watermarks in order.
                 Some(Ok(checkpoint)) = pending.next() => {
                     self.process_executed_checkpoint(&checkpoint);
+                    self.notify_checkpoint_subscribers(&checkpoint).await;
                     highest_executed = Some(checkpoint);
 
                     // Estimate TPS every 10k transactions or 30 sec
@@ -281,6 +288,46 @@ impl CheckpointExecutor {
         checkpoint.report_checkpoint_age_ms(&self.metrics.last_executed_checkpoint_age_ms);
     }
 
+    /// Stream the executed checkpoint to checkpoint subscribers, if this node serves them.
+    /// Like `process_executed_checkpoint`, this is called in checkpoint sequence number order.
+    async fn notify_checkpoint_subscribers(&self, checkpoint: &VerifiedCheckpoint) {
+        let Some(subscription_handler) = &self.subscription_handler else {
+            return;
+        };
+        let contents = match self
+            .checkpoint_store
+            .get_checkpoint_contents(&checkpoint.content_digest)
+        {
+            Ok(Some(contents)) => contents,
+            Ok(None) => {
+                warn!(
+                    sequence_number = ?checkpoint.sequence_number(),
+                    "Checkpoint contents not found, skipping checkpoint subscription"
+                );
+                return;
+            }
+            Err(e) => {
+                warn!(
+                    sequence_number = ?checkpoint.sequence_number(),
+                    "Failed to read checkpoint contents for subscription: {e}"
+                );
+                return;
+            }
+        };
+        let signature = checkpoint.auth_sig().signature.clone();
+        let checkpoint: Checkpoint = (
+            checkpoint.clone().into_inner().into_data(),
+            contents,
+            signature,
+        )
+            .into();
+        subscription_handler
+            .process_checkpoint(&checkpoint)
+            .await
+            .tap_err(|e| warn!("Failed to process checkpoint subscription: {e}"))
+            .ok();
+    }
+
     async fn schedule_synced_checkpoints(
         &self,
         pending: &mut CheckpointExecutionBuffer,
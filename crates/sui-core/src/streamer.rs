@@ -134,6 +134,11 @@ where
         ReceiverStream::new(rx)
     }
 
+    /// Whether anyone is subscribed, lets producers skip building expensive data.
+    pub fn has_subscribers(&self) -> bool {
+        !self.subscribers.read().is_empty()
+    }
+
     pub async fn send(&self, data: T) -> Result<(), SuiError> {
         self.streamer_queue
             .send(data)
@@ -12,8 +12,8 @@ use tracing::{error, instrument, trace};
 
 use crate::streamer::Streamer;
 use sui_json_rpc_types::{
-    EffectsWithInput, EventFilter, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
-    TransactionFilter,
+    Checkpoint, EffectsWithInput, EventFilter, Filter, SuiObjectData, SuiObjectDataFilter,
+    SuiTransactionBlockEffects, SuiTransactionBlockEvents, TransactionFilter,
 };
 use sui_json_rpc_types::{SuiEvent, SuiTransactionBlockEffectsAPI};
 use sui_types::error::SuiResult;
@@ -59,9 +59,21 @@ impl SubscriptionMetrics {
     }
 }
 
+/// Checkpoint subscriptions are not filtered, every executed checkpoint is streamed.
+#[derive(Clone)]
+struct AllCheckpoints;
+
+impl Filter<Checkpoint> for AllCheckpoints {
+    fn matches(&self, _item: &Checkpoint) -> bool {
+        true
+    }
+}
+
 pub struct SubscriptionHandler {
     event_streamer: Streamer<SuiEvent, SuiEvent, EventFilter>,
     transaction_streamer: Streamer<EffectsWithInput, SuiTransactionBlockEffects, TransactionFilter>,
+    checkpoint_streamer: Streamer<Checkpoint, Checkpoint, AllCheckpoints>,
+    object_streamer: Streamer<SuiObjectData, SuiObjectData, SuiObjectDataFilter>,
 }
 
 impl SubscriptionHandler {
@@ -69,7 +81,17 @@ impl SubscriptionHandler {
         let metrics = Arc::new(SubscriptionMetrics::new(registry));
         Self {
             event_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, metrics.clone(), "event"),
-            transaction_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, metrics, "tx"),
+            transaction_streamer: Streamer::spawn(
+                EVENT_DISPATCH_BUFFER_SIZE,
+                metrics.clone(),
+                "tx",
+            ),
+            checkpoint_streamer: Streamer::spawn(
+                EVENT_DISPATCH_BUFFER_SIZE,
+                metrics.clone(),
+                "checkpoint",
+            ),
+            object_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE, metrics, "object"),
         }
     }
 }
@@ -108,6 +130,42 @@ impl SubscriptionHandler {
         Ok(())
     }
 
+    /// Dispatch an executed checkpoint to checkpoint subscribers. Must be called in checkpoint
+    /// sequence number order.
+    #[instrument(level = "debug", skip_all, fields(sequence_number = checkpoint.sequence_number), err)]
+    pub async fn process_checkpoint(&self, checkpoint: &Checkpoint) -> SuiResult {
+        trace!(
+            sequence_number = checkpoint.sequence_number,
+            "Processing checkpoint subscription"
+        );
+        if let Err(e) = self.checkpoint_streamer.send(checkpoint.clone()).await {
+            error!(error =? e, "Failed to send checkpoint to dispatch");
+        }
+        Ok(())
+    }
+
+    /// Dispatch the new versions of objects written by a transaction to object subscribers.
+    /// Objects are expected to carry all fields, subscribers project them with their own options.
+    #[instrument(level = "debug", skip_all, fields(num_objects = objects.len()), err)]
+    pub async fn process_objects(&self, objects: &[SuiObjectData]) -> SuiResult {
+        trace!(
+            num_objects = objects.len(),
+            "Processing object subscription"
+        );
+        for object in objects {
+            if let Err(e) = self.object_streamer.send(object.clone()).await {
+                error!(error =? e, "Failed to send object to dispatch");
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether there is any object subscriber, converting written objects to [SuiObjectData]
+    /// requires resolving their layouts so producers should skip it when nobody is listening.
+    pub fn has_object_subscribers(&self) -> bool {
+        self.object_streamer.has_subscribers()
+    }
+
     pub fn subscribe_events(&self, filter: EventFilter) -> impl Stream<Item = SuiEvent> {
         self.event_streamer.subscribe(filter)
     }
@@ -118,4 +176,15 @@ impl SubscriptionHandler {
     ) -> impl Stream<Item = SuiTransactionBlockEffects> {
         self.transaction_streamer.subscribe(filter)
     }
+
+    pub fn subscribe_checkpoints(&self) -> impl Stream<Item = Checkpoint> {
+        self.checkpoint_streamer.subscribe(AllCheckpoints)
+    }
+
+    pub fn subscribe_objects(
+        &self,
+        filter: SuiObjectDataFilter,
+    ) -> impl Stream<Item = SuiObjectData> {
+        self.object_streamer.subscribe(filter)
+    }
 }
@@ -36,6 +36,7 @@ use sui_types::effects::TransactionEffects;
 use sui_types::epoch_data::EpochData;
 use sui_types::error::UserInputError;
 use sui_types::execution_status::{ExecutionFailureStatus, ExecutionStatus};
+use sui_types::execution_trace::TraceEvent;
 use sui_types::gas_coin::GasCoin;
 use sui_types::messages_consensus::ConsensusCommitPrologue;
 use sui_types::object::Data;
@@ -221,6 +222,7 @@ async fn test_dry_run_transaction_block() {
         .dry_exec_transaction(
             transaction.data().intent_message().value.clone(),
             transaction_digest,
+            false,
         )
         .await
         .unwrap();
@@ -252,7 +254,7 @@ async fn test_dry_run_transaction_block() {
         txn_data.gas_price(),
     );
     let (response, _, _, _) = fullnode
-        .dry_exec_transaction(txn_data, transaction_digest)
+        .dry_exec_transaction(txn_data, transaction_digest, false)
         .await
         .unwrap();
     let gas_usage_no_gas = response.effects.gas_cost_summary();
@@ -260,6 +262,62 @@ async fn test_dry_run_transaction_block() {
     assert_eq!(gas_usage, gas_usage_no_gas);
 }
 
+#[tokio::test]
+async fn test_dry_run_transaction_block_gas_profile() {
+    let (_, fullnode, transaction, _, _) =
+        construct_shared_object_transaction_with_sequence_number(None).await;
+    let txn_data = transaction.data().intent_message().value.clone();
+    let transaction_digest = *transaction.digest();
+
+    let (response, _, _, _) = fullnode
+        .dry_exec_transaction(txn_data.clone(), transaction_digest, false)
+        .await
+        .unwrap();
+    assert!(response.gas_profile.is_none());
+
+    let (response, _, _, _) = fullnode
+        .dry_exec_transaction(txn_data, transaction_digest, true)
+        .await
+        .unwrap();
+    assert_eq!(*response.effects.status(), SuiExecutionStatus::Success);
+    let profile = serde_json::to_value(response.gas_profile.unwrap()).unwrap();
+
+    let frames: Vec<_> = profile["shared"]["frames"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|frame| frame["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(frames[0], transaction_digest.to_string());
+    assert!(frames.contains(&"input objects"));
+    assert!(frames.iter().any(|f| f.starts_with("0: MoveCall")));
+    assert!(frames
+        .iter()
+        .any(|f| f.ends_with("object_basics::set_value")));
+    assert!(frames.contains(&"storage cost"));
+
+    // Computation is an evented profile in which every frame opened is closed.
+    let computation = &profile["profiles"][0];
+    assert_eq!(computation["type"], "evented");
+    let events = computation["events"].as_array().unwrap();
+    let opened = events.iter().filter(|e| e["type"] == "O").count();
+    let closed = events.iter().filter(|e| e["type"] == "C").count();
+    assert_eq!(opened, closed);
+    assert!(computation["endValue"].as_u64().unwrap() > 0);
+
+    // Storage is a sampled profile weighted by the storage charges of the transaction.
+    let gas_usage = response.effects.gas_cost_summary();
+    let storage = &profile["profiles"][1];
+    assert_eq!(storage["type"], "sampled");
+    let weights: Vec<_> = storage["weights"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w.as_u64().unwrap())
+        .collect();
+    assert!(weights.contains(&gas_usage.storage_cost));
+}
+
 #[tokio::test]
 async fn test_dry_run_no_gas_big_transfer() {
     let (sender, sender_key): (_, AccountKeyPair) = get_key_pair();
@@ -286,6 +344,7 @@ async fn test_dry_run_no_gas_big_transfer() {
         .dry_exec_transaction(
             signed.data().intent_message().value.clone(),
             *signed.digest(),
+            false,
         )
         .await
         .unwrap();
@@ -572,7 +631,7 @@ async fn test_dev_inspect_dynamic_field() {
     };
     let kind = TransactionKind::programmable(pt);
     let DevInspectResults { error, .. } = fullnode
-        .dev_inspect_transaction_block(sender, kind, None)
+        .dev_inspect_transaction_block(sender, kind, None, false)
         .await
         .unwrap();
     // produces an error
@@ -876,7 +935,7 @@ async fn test_dev_inspect_gas_coin_argument() {
     };
     let kind = TransactionKind::programmable(pt);
     let results = fullnode
-        .dev_inspect_transaction_block(sender, kind, None)
+        .dev_inspect_transaction_block(sender, kind, None, false)
         .await
         .unwrap()
         .results
@@ -906,6 +965,65 @@ async fn test_dev_inspect_gas_coin_argument() {
     assert!(return_values.is_empty());
 }
 
+#[tokio::test]
+async fn test_dev_inspect_trace() {
+    let (sender, _sender_key): (_, AccountKeyPair) = get_key_pair();
+    let (_validator, fullnode, object_basics) =
+        init_state_with_ids_and_object_basics_with_fullnode(vec![(sender, ObjectID::random())])
+            .await;
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .move_call(
+                object_basics.0,
+                Identifier::new("object_basics").unwrap(),
+                Identifier::new("create").unwrap(),
+                vec![],
+                vec![
+                    CallArg::Pure(bcs::to_bytes(&(16_u64)).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+                ],
+            )
+            .unwrap();
+        builder.finish()
+    };
+    let kind = TransactionKind::programmable(pt);
+    let DevInspectResults { effects, trace, .. } = fullnode
+        .dev_inspect_transaction_block(sender, kind.clone(), None, true)
+        .await
+        .unwrap();
+    let trace = trace.unwrap();
+    assert_eq!(trace.commands.len(), 1);
+    let events = &trace.commands[0].events;
+    let TraceEvent::CallStart {
+        depth: 0,
+        function,
+        arguments,
+    } = &events[0]
+    else {
+        panic!("Command must start with the call to create: {events:?}");
+    };
+    assert!(function.ends_with("::object_basics::create"));
+    assert_eq!(arguments[0], "16");
+    assert!(events.iter().any(|event| matches!(
+        event,
+        TraceEvent::CallEnd { depth: 0, function: f, .. } if f == function
+    )));
+    let created = effects.created()[0].object_id();
+    assert!(trace
+        .object_changes
+        .iter()
+        .any(|change| change.object_id == created && !change.deleted));
+
+    // the trace is only recorded when requested
+    let DevInspectResults { trace, .. } = fullnode
+        .dev_inspect_transaction_block(sender, kind, None, false)
+        .await
+        .unwrap();
+    assert!(trace.is_none());
+}
+
 #[tokio::test]
 async fn test_dev_inspect_gas_price() {
     let (_, fullnode, _object_basics) =
@@ -921,7 +1039,7 @@ async fn test_dev_inspect_gas_price() {
     };
     let kind = TransactionKind::programmable(pt);
     let error = fullnode
-        .dev_inspect_transaction_block(sender, kind.clone(), Some(1))
+        .dev_inspect_transaction_block(sender, kind.clone(), Some(1), false)
         .await
         .unwrap_err();
     assert!(
@@ -935,7 +1053,12 @@ async fn test_dev_inspect_gas_price() {
     let epoch_store = fullnode.epoch_store_for_testing();
     let protocol_config = epoch_store.protocol_config();
     let error = fullnode
-        .dev_inspect_transaction_block(sender, kind, Some(protocol_config.max_gas_price() + 1))
+        .dev_inspect_transaction_block(
+            sender,
+            kind,
+            Some(protocol_config.max_gas_price() + 1),
+            false,
+        )
         .await
         .unwrap_err();
     assert!(
@@ -984,6 +1107,7 @@ async fn test_dev_inspect_uses_unbound_object() {
             sender,
             kind,
             Some(fullnode.reference_gas_price_for_testing().unwrap()),
+            false,
         )
         .await;
     let Err(err) = result else { panic!() };
@@ -1023,6 +1147,7 @@ async fn test_dry_run_on_validator() {
         .dry_exec_transaction(
             transaction.data().intent_message().value.clone(),
             transaction_digest,
+            false,
         )
         .await;
     assert!(response.is_err());
@@ -1125,7 +1250,7 @@ async fn test_dry_run_dev_inspect_dynamic_field_too_new() {
     let rgp = fullnode.reference_gas_price_for_testing().unwrap();
     // dev inspect
     let DevInspectResults { effects, .. } = fullnode
-        .dev_inspect_transaction_block(sender, kind, Some(rgp))
+        .dev_inspect_transaction_block(sender, kind, Some(rgp), false)
         .await
         .unwrap();
     assert_eq!(effects.deleted().len(), 0);
@@ -1140,8 +1265,11 @@ async fn test_dry_run_dev_inspect_dynamic_field_too_new() {
     );
     let transaction = to_sender_signed_transaction(data.clone(), &sender_key);
     let digest = *transaction.digest();
-    let DryRunTransactionBlockResponse { effects, .. } =
-        fullnode.dry_exec_transaction(data, digest).await.unwrap().0;
+    let DryRunTransactionBlockResponse { effects, .. } = fullnode
+        .dry_exec_transaction(data, digest, false)
+        .await
+        .unwrap()
+        .0;
     assert_eq!(effects.deleted().len(), 0);
 }
 
@@ -1178,7 +1306,7 @@ async fn test_dry_run_dev_inspect_max_gas_version() {
     let kind = TransactionKind::programmable(pt.clone());
     // dev inspect
     let DevInspectResults { effects, .. } = fullnode
-        .dev_inspect_transaction_block(sender, kind, Some(rgp + 100))
+        .dev_inspect_transaction_block(sender, kind, Some(rgp + 100), false)
         .await
         .unwrap();
     assert_eq!(effects.status(), &SuiExecutionStatus::Success);
@@ -1193,8 +1321,11 @@ async fn test_dry_run_dev_inspect_max_gas_version() {
     );
     let transaction = to_sender_signed_transaction(data.clone(), &sender_key);
     let digest = *transaction.digest();
-    let DryRunTransactionBlockResponse { effects, .. } =
-        fullnode.dry_exec_transaction(data, digest).await.unwrap().0;
+    let DryRunTransactionBlockResponse { effects, .. } = fullnode
+        .dry_exec_transaction(data, digest, false)
+        .await
+        .unwrap()
+        .0;
     assert_eq!(effects.status(), &SuiExecutionStatus::Success);
 }
 
@@ -4434,7 +4565,7 @@ pub async fn call_dev_inspect(
     let kind = TransactionKind::programmable(builder.finish());
     let rgp = authority.reference_gas_price_for_testing().unwrap();
     authority
-        .dev_inspect_transaction_block(*sender, kind, Some(rgp))
+        .dev_inspect_transaction_block(*sender, kind, Some(rgp), false)
         .await
 }
 
@@ -5104,6 +5235,7 @@ async fn test_for_inc_201_dev_inspect() {
             sender,
             kind,
             Some(fullnode.reference_gas_price_for_testing().unwrap() + 1000),
+            false,
         )
         .await
         .unwrap();
@@ -5158,6 +5290,7 @@ async fn test_for_inc_201_dry_run() {
         .dry_exec_transaction(
             signed.data().intent_message().value.clone(),
             *signed.digest(),
+            false,
         )
         .await
         .unwrap();
@@ -1272,7 +1272,7 @@ mod tests {
         let res = SuiClientCommands::PayAllSui {
             input_coins: vec![*bad_gas.id()],
             recipient: SuiAddress::random_for_testing_only(),
-            gas_budget: 2_000_000,
+            gas_budget: Some(2_000_000),
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
         }
@@ -1397,7 +1397,7 @@ mod tests {
         let res = SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![tiny_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
@@ -1481,7 +1481,7 @@ mod tests {
         SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![reasonable_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
@@ -1497,7 +1497,7 @@ mod tests {
             SuiClientCommands::TransferSui {
                 to: destination_address,
                 sui_coin_object_id: *gas.id(),
-                gas_budget: 50000000,
+                gas_budget: Some(50000000),
                 amount: None,
                 serialize_unsigned_transaction: false,
                 serialize_signed_transaction: false,
@@ -1554,7 +1554,7 @@ mod tests {
         let _res = SuiClientCommands::SplitCoin {
             coin_id: *gases[0].id(),
             amounts: Some(vec![tiny_value]),
-            gas_budget: 50000000,
+            gas_budget: Some(50000000),
             gas: None,
             count: None,
             serialize_unsigned_transaction: false,
@@ -1570,7 +1570,7 @@ mod tests {
             SuiClientCommands::TransferSui {
                 to: destination_address,
                 sui_coin_object_id: *gas.id(),
-                gas_budget: 50000000,
+                gas_budget: Some(50000000),
                 amount: None,
                 serialize_unsigned_transaction: false,
                 serialize_signed_transaction: false,
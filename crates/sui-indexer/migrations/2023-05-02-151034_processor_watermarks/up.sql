@@ -0,0 +1,6 @@
+CREATE TABLE processor_watermarks
+(
+    processor_name              TEXT        PRIMARY KEY,
+    -- last checkpoint committed by the processor
+    checkpoint_sequence_number  BIGINT      NOT NULL
+);
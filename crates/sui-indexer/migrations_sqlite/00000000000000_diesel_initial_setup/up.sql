@@ -0,0 +1,4 @@
+-- SQLite counterpart of the Postgres diesel setup migration.
+-- The `updated_at` helper functions are plpgsql only and no indexer table uses them,
+-- this migration is kept so that migration versions line up across both backends.
+SELECT 1;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS addresses;
+DROP TABLE IF EXISTS active_addresses;
+DROP TABLE IF EXISTS address_stats;
@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS objects_history_insert;
+DROP TRIGGER IF EXISTS objects_history_update;
+DROP TRIGGER IF EXISTS objects_history_delete;
+DROP TABLE IF EXISTS objects;
+DROP TABLE IF EXISTS objects_history;
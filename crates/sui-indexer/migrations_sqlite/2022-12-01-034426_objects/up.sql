@@ -0,0 +1,88 @@
+-- NOTE: SQLite has neither enum nor composite types:
+-- `owner_type` and `object_status` are stored as their snake_case names,
+-- `bcs` is the BCS serialized list of (name, bytes) pairs.
+CREATE TABLE objects
+(
+    epoch                  BIGINT       NOT NULL,
+    checkpoint             BIGINT       NOT NULL,
+    object_id              VARCHAR(66)  PRIMARY KEY,
+    version                BIGINT       NOT NULL,
+    object_digest          VARCHAR(44)  NOT NULL,
+    -- owner related
+    owner_type             TEXT         NOT NULL,
+    -- only non-null for objects with an owner,
+    -- the owner can be an account or an object.
+    owner_address          VARCHAR(66),
+    -- only non-null for shared objects
+    initial_shared_version BIGINT,
+    previous_transaction   VARCHAR(44)  NOT NULL,
+    object_type            VARCHAR      NOT NULL,
+    object_status          TEXT         NOT NULL,
+    has_public_transfer    BOOLEAN      NOT NULL,
+    storage_rebate         BIGINT       NOT NULL,
+    bcs                    BLOB         NOT NULL
+);
+CREATE INDEX objects_owner_address ON objects (owner_type, owner_address);
+CREATE INDEX objects_tx_digest ON objects (previous_transaction);
+
+-- NOTE: SQLite has no table partitioning, objects_history is a single table.
+CREATE TABLE objects_history
+(
+    epoch                  BIGINT       NOT NULL,
+    checkpoint             BIGINT       NOT NULL,
+    object_id              VARCHAR(66)  NOT NULL,
+    version                BIGINT       NOT NULL,
+    object_digest          VARCHAR(44)  NOT NULL,
+    owner_type             TEXT         NOT NULL,
+    owner_address          VARCHAR(66),
+    old_owner_type         TEXT,
+    old_owner_address      VARCHAR(66),
+    initial_shared_version BIGINT,
+    previous_transaction   VARCHAR(44)  NOT NULL,
+    object_type            VARCHAR      NOT NULL,
+    object_status          TEXT         NOT NULL,
+    has_public_transfer    BOOLEAN      NOT NULL,
+    storage_rebate         BIGINT       NOT NULL,
+    bcs                    BLOB         NOT NULL,
+    CONSTRAINT objects_history_pk PRIMARY KEY (object_id, version, checkpoint)
+);
+CREATE INDEX objects_history_checkpoint_index ON objects_history (checkpoint);
+CREATE INDEX objects_history_id_version_index ON objects_history (object_id, version);
+CREATE INDEX objects_history_owner_index ON objects_history (owner_type, owner_address);
+CREATE INDEX objects_history_old_owner_index ON objects_history (old_owner_type, old_owner_address);
+
+-- Same bookkeeping as `objects_modified_func` of the Postgres schema, one trigger per operation.
+CREATE TRIGGER objects_history_insert
+    AFTER INSERT
+    ON objects
+    FOR EACH ROW
+BEGIN
+    INSERT OR IGNORE INTO objects_history
+    VALUES (NEW.epoch, NEW.checkpoint, NEW.object_id, NEW.version, NEW.object_digest, NEW.owner_type,
+            NEW.owner_address, NULL, NULL,
+            NEW.initial_shared_version,
+            NEW.previous_transaction, NEW.object_type, NEW.object_status, NEW.has_public_transfer,
+            NEW.storage_rebate, NEW.bcs);
+END;
+
+CREATE TRIGGER objects_history_update
+    AFTER UPDATE
+    ON objects
+    FOR EACH ROW
+BEGIN
+    INSERT OR IGNORE INTO objects_history
+    VALUES (NEW.epoch, NEW.checkpoint, NEW.object_id, NEW.version, NEW.object_digest, NEW.owner_type,
+            NEW.owner_address, OLD.owner_type, OLD.owner_address,
+            NEW.initial_shared_version,
+            NEW.previous_transaction, NEW.object_type, NEW.object_status, NEW.has_public_transfer,
+            NEW.storage_rebate, NEW.bcs);
+END;
+
+CREATE TRIGGER objects_history_delete
+    AFTER DELETE
+    ON objects
+    FOR EACH ROW
+BEGIN
+    -- object deleted from the main table, archive the history for that object
+    DELETE FROM objects_history WHERE object_id = OLD.object_id;
+END;
@@ -0,0 +1,11 @@
+CREATE TABLE packages
+(
+    package_id VARCHAR(66) NOT NULL,
+    version    BIGINT      NOT NULL,
+    author     VARCHAR(66) NOT NULL,
+    -- BCS serialized list of (module name, module bytes) pairs
+    data       BLOB        NOT NULL,
+    CONSTRAINT packages_pk PRIMARY KEY (package_id, version)
+);
+
+CREATE INDEX packages_package_id ON packages (package_id);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS input_objects;
+DROP TABLE IF EXISTS move_calls;
+DROP TABLE IF EXISTS recipients;
+DROP TABLE IF EXISTS changed_objects;
@@ -0,0 +1,3 @@
+DROP VIEW IF EXISTS epoch_move_call_metrics;
+DROP VIEW IF EXISTS epoch_network_metrics;
+DROP TABLE IF EXISTS epochs;
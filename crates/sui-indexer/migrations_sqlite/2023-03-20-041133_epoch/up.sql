@@ -0,0 +1,95 @@
+-- NOTE: SQLite has no materialized views, the epoch metrics views of the Postgres schema
+-- are plain views here and hence need no refresh trigger on `epochs`.
+CREATE VIEW epoch_network_metrics AS
+WITH checkpoints_30d AS (
+  SELECT
+    MAX(sequence_number) AS sequence_number,
+    SUM(total_successful_transactions) AS total_successful_transactions,
+    timestamp_ms
+  FROM
+    checkpoints
+  WHERE
+    timestamp_ms > (CAST(strftime('%s', 'now') AS BIGINT) - 30 * 24 * 60 * 60) * 1000
+  GROUP BY
+    timestamp_ms
+),
+tps_data AS (
+  SELECT
+    sequence_number,
+    total_successful_transactions,
+    LAG(timestamp_ms) OVER (ORDER BY timestamp_ms DESC) - timestamp_ms AS time_diff
+  FROM
+    checkpoints_30d
+)
+SELECT
+  CAST(MAX(total_successful_transactions * 1000.0 / time_diff) AS DOUBLE) AS tps_30_days
+FROM
+  tps_data
+WHERE
+  time_diff IS NOT NULL;
+
+-- NOTE: SQLite has no array columns, `next_epoch_committee` and `epoch_commitments` are
+-- BCS serialized lists of byte arrays and `next_epoch_committee_stake` is a JSON array.
+CREATE TABLE epochs
+(
+    epoch                           BIGINT PRIMARY KEY,
+    first_checkpoint_id             BIGINT NOT NULL,
+    last_checkpoint_id              BIGINT,
+    epoch_start_timestamp           BIGINT NOT NULL,
+    epoch_end_timestamp             BIGINT,
+    epoch_total_transactions        BIGINT NOT NULL,
+
+    -- end of epoch data
+    next_epoch_version              BIGINT,
+    next_epoch_committee            BLOB   NOT NULL,
+    next_epoch_committee_stake      TEXT   NOT NULL,
+    epoch_commitments               BLOB   NOT NULL,
+
+    protocol_version                BIGINT,
+    reference_gas_price             BIGINT,
+    total_stake                     BIGINT,
+    storage_fund_reinvestment       BIGINT,
+    storage_charge                  BIGINT,
+    storage_rebate                  BIGINT,
+    storage_fund_balance            BIGINT,
+    stake_subsidy_amount            BIGINT,
+    total_gas_fees                  BIGINT,
+    total_stake_rewards_distributed BIGINT,
+    leftover_storage_fund_inflow    BIGINT
+);
+CREATE INDEX epochs_start_index ON epochs (epoch_start_timestamp ASC);
+CREATE INDEX epochs_end_index ON epochs (epoch_end_timestamp ASC);
+
+CREATE VIEW epoch_move_call_metrics AS
+SELECT * FROM
+(SELECT CAST(3 AS BIGINT) AS day, move_package, move_module, move_function, COUNT(*) AS count
+ FROM move_calls
+ WHERE epoch >
+       (SELECT MIN(epoch)
+        FROM epochs
+        WHERE epoch_start_timestamp > (CAST(strftime('%s', 'now') AS BIGINT) - 3 * 24 * 60 * 60) * 1000)
+ GROUP BY move_package, move_module, move_function
+ ORDER BY count DESC
+ LIMIT 10)
+UNION ALL
+SELECT * FROM
+(SELECT CAST(7 AS BIGINT) AS day, move_package, move_module, move_function, COUNT(*) AS count
+ FROM move_calls
+ WHERE epoch >
+       (SELECT MIN(epoch)
+        FROM epochs
+        WHERE epoch_start_timestamp > (CAST(strftime('%s', 'now') AS BIGINT) - 7 * 24 * 60 * 60) * 1000)
+ GROUP BY move_package, move_module, move_function
+ ORDER BY count DESC
+ LIMIT 10)
+UNION ALL
+SELECT * FROM
+(SELECT CAST(30 AS BIGINT) AS day, move_package, move_module, move_function, COUNT(*) AS count
+ FROM move_calls
+ WHERE epoch >
+       (SELECT MIN(epoch)
+        FROM epochs
+        WHERE epoch_start_timestamp > (CAST(strftime('%s', 'now') AS BIGINT) - 30 * 24 * 60 * 60) * 1000)
+ GROUP BY move_package, move_module, move_function
+ ORDER BY count DESC
+ LIMIT 10);
@@ -0,0 +1,5 @@
+DROP VIEW IF EXISTS network_metrics;
+DROP VIEW IF EXISTS real_time_tps;
+DROP TABLE IF EXISTS system_states;
+DROP TABLE IF EXISTS validators;
+DROP TABLE IF EXISTS at_risk_validators;
@@ -0,0 +1,2 @@
+ALTER TABLE events
+    ADD COLUMN parsed_json TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+ALTER TABLE checkpoints
+    ADD COLUMN validator_signature TEXT NOT NULL DEFAULT '';
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use futures::future::join_all;
+use futures::StreamExt;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::types::SubscriptionResult;
@@ -13,19 +14,21 @@ use jsonrpsee::{RpcModule, SubscriptionSink};
 
 use move_core_types::identifier::Identifier;
 use sui_core::subscription_handler::SubscriptionHandler;
-use sui_json_rpc::api::{cap_page_limit, IndexerApiClient, IndexerApiServer};
-use sui_json_rpc::indexer_api::spawn_subscription;
+use sui_json_rpc::api::{cap_page_limit, IndexerApiClient, IndexerApiServer, ReadApiClient};
+use sui_json_rpc::indexer_api::{resume_subscription, spawn_subscription};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectDataFilter,
-    SuiObjectResponse, SuiObjectResponseQuery, SuiTransactionBlockResponseQuery,
-    TransactionBlocksPage, TransactionFilter,
+    Checkpoint, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent,
+    SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
+    SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionBlocksPage, TransactionFilter,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::sui_serde::BigInt;
 
 use crate::errors::IndexerError;
 use crate::store::IndexerStore;
@@ -454,8 +457,32 @@ where
         df_obj_resp
     }
 
-    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(sink, self.subscription_handler.subscribe_events(filter));
+    fn subscribe_event(
+        &self,
+        sink: SubscriptionSink,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+    ) -> SubscriptionResult {
+        let fullnode = self.fullnode.clone();
+        let subscription_handler = self.subscription_handler.clone();
+        let live_filter = filter.clone();
+        let stream = resume_subscription(
+            cursor,
+            move |cursor: Option<EventID>| {
+                let fullnode = fullnode.clone();
+                let filter = filter.clone();
+                async move {
+                    Ok::<_, anyhow::Error>(
+                        fullnode
+                            .query_events(filter, cursor, None, Some(false))
+                            .await?,
+                    )
+                }
+            },
+            move || subscription_handler.subscribe_events(live_filter),
+            |event: &SuiEvent| event.id.clone(),
+        );
+        spawn_subscription(sink, stream);
         Ok(())
     }
 
@@ -463,14 +490,98 @@ where
         &self,
         sink: SubscriptionSink,
         filter: TransactionFilter,
+        cursor: Option<TransactionDigest>,
+    ) -> SubscriptionResult {
+        let fullnode = self.fullnode.clone();
+        let subscription_handler = self.subscription_handler.clone();
+        let live_filter = filter.clone();
+        let stream = resume_subscription(
+            cursor,
+            move |cursor: Option<TransactionDigest>| {
+                let fullnode = fullnode.clone();
+                let query = SuiTransactionBlockResponseQuery::new(
+                    Some(filter.clone()),
+                    Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+                );
+                async move {
+                    let page = fullnode
+                        .query_transaction_blocks(query, cursor, None, Some(false))
+                        .await?;
+                    let data = page
+                        .data
+                        .into_iter()
+                        .map(|tx| {
+                            tx.effects.ok_or_else(|| {
+                                anyhow!("Missing effects of transaction {}", tx.digest)
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok::<_, anyhow::Error>(Page {
+                        data,
+                        next_cursor: page.next_cursor,
+                        has_next_page: page.has_next_page,
+                    })
+                }
+            },
+            move || subscription_handler.subscribe_transactions(live_filter),
+            |effects: &SuiTransactionBlockEffects| *effects.transaction_digest(),
+        );
+        spawn_subscription(sink, stream);
+        Ok(())
+    }
+
+    fn subscribe_checkpoint(
+        &self,
+        sink: SubscriptionSink,
+        cursor: Option<BigInt<u64>>,
     ) -> SubscriptionResult {
+        let fullnode = self.fullnode.clone();
+        let subscription_handler = self.subscription_handler.clone();
+        let stream = resume_subscription(
+            cursor,
+            move |cursor: Option<BigInt<u64>>| {
+                let fullnode = fullnode.clone();
+                async move {
+                    let mut page = fullnode.get_checkpoints(cursor, None, false).await?;
+                    // The fullnode returns the cursor itself again once the cursor is at the tip.
+                    if let Some(cursor) = cursor {
+                        page.data.retain(|c| c.sequence_number > *cursor);
+                    }
+                    Ok::<_, anyhow::Error>(page)
+                }
+            },
+            move || subscription_handler.subscribe_checkpoints(),
+            |checkpoint: &Checkpoint| checkpoint.sequence_number,
+        );
+        spawn_subscription(sink, stream);
+        Ok(())
+    }
+
+    fn subscribe_object(
+        &self,
+        sink: SubscriptionSink,
+        filter: SuiObjectDataFilter,
+        options: Option<SuiObjectDataOptions>,
+    ) -> SubscriptionResult {
+        let options = options.unwrap_or_default();
         spawn_subscription(
             sink,
-            self.subscription_handler.subscribe_transactions(filter),
+            self.subscription_handler
+                .subscribe_objects(filter)
+                .map(move |object| object.with_options(&options)),
         );
         Ok(())
     }
 
+    fn subscribe_owned_objects(
+        &self,
+        sink: SubscriptionSink,
+        address: SuiAddress,
+        options: Option<SuiObjectDataOptions>,
+    ) -> SubscriptionResult {
+        self.subscribe_object(sink, SuiObjectDataFilter::AddressOwner(address), options)
+    }
+
     async fn resolve_name_service_address(&self, name: String) -> RpcResult<Option<SuiAddress>> {
         self.fullnode.resolve_name_service_address(name).await
     }
@@ -246,6 +246,25 @@ where
         past_obj_resp
     }
 
+    async fn try_get_past_object_lt_or_eq_version(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        let past_obj_guard = self
+            .state
+            .indexer_metrics()
+            .try_get_past_object_lt_or_eq_version_latency
+            .start_timer();
+        let past_obj_resp = self
+            .fullnode
+            .try_get_past_object_lt_or_eq_version(object_id, version, options)
+            .await;
+        past_obj_guard.stop_and_record();
+        past_obj_resp
+    }
+
     async fn try_multi_get_past_objects(
         &self,
         past_objects: Vec<SuiGetPastObjectRequest>,
@@ -10,7 +10,9 @@ use jsonrpsee::RpcModule;
 use sui_json_rpc::api::{WriteApiClient, WriteApiServer};
 use sui_json_rpc::SuiRpcModule;
 use sui_json_rpc_types::{
+    DevInspectArgs,
     DevInspectResults,
+    DryRunArgs,
     DryRunTransactionBlockResponse,
     // TODO(gegaowp): temp. disable fast-path
     // SuiTransactionBlockEffectsAPI,
@@ -104,17 +106,27 @@ where
         tx_bytes: Base64,
         gas_price: Option<BigInt<u64>>,
         epoch: Option<BigInt<u64>>,
+        additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults> {
         self.fullnode
-            .dev_inspect_transaction_block(sender_address, tx_bytes, gas_price, epoch)
+            .dev_inspect_transaction_block(
+                sender_address,
+                tx_bytes,
+                gas_price,
+                epoch,
+                additional_args,
+            )
             .await
     }
 
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
+        additional_args: Option<DryRunArgs>,
     ) -> RpcResult<DryRunTransactionBlockResponse> {
-        self.fullnode.dry_run_transaction_block(tx_bytes).await
+        self.fullnode
+            .dry_run_transaction_block(tx_bytes, additional_args)
+            .await
     }
 }
 
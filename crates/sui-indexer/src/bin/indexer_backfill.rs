@@ -0,0 +1,57 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use tracing::{error, info};
+
+use sui_indexer::errors::IndexerError;
+use sui_indexer::metrics::IndexerMetrics;
+use sui_indexer::start_prometheus_server;
+use sui_indexer::store::{PgIndexerStore, SqliteIndexerStore};
+use sui_indexer::{new_pg_connection_pool, new_sqlite_connection_pool};
+use sui_indexer::{BackfillConfig, DbBackend, Indexer};
+
+#[tokio::main]
+async fn main() -> Result<(), IndexerError> {
+    // NOTE: this is to print out tracing like info, warn & error.
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let config = BackfillConfig::parse();
+    info!("Parsed backfill config: {:#?}", config);
+    let (_registry_service, registry) = start_prometheus_server(
+        // NOTE: this parses the input host addr and port number for socket addr,
+        // so unwrap() is safe here.
+        format!(
+            "{}:{}",
+            config.client_metric_host, config.client_metric_port
+        )
+        .parse()
+        .unwrap(),
+        "",
+    )?;
+    let indexer_metrics = IndexerMetrics::new(&registry);
+
+    match config.db_backend {
+        DbBackend::Postgres => {
+            let blocking_cp = new_pg_connection_pool(&config.db_url).map_err(|e| {
+                error!(
+                    "Failed creating Postgres connection pool with error {:?}",
+                    e
+                );
+                e
+            })?;
+            let store = PgIndexerStore::new(blocking_cp, indexer_metrics.clone());
+            Indexer::backfill(&config, &registry, store, indexer_metrics).await
+        }
+        DbBackend::Sqlite => {
+            let blocking_cp = new_sqlite_connection_pool(&config.db_url).map_err(|e| {
+                error!("Failed creating SQLite connection pool with error {:?}", e);
+                e
+            })?;
+            let store = SqliteIndexerStore::new(blocking_cp, indexer_metrics.clone());
+            Indexer::backfill(&config, &registry, store, indexer_metrics).await
+        }
+    }
+}
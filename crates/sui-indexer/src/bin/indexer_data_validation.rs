@@ -5,7 +5,10 @@ use anyhow::Result;
 use clap::Parser;
 use tracing::{error, info, warn};
 
-use sui_indexer::new_rpc_client;
+use sui_indexer::metrics::IndexerMetrics;
+use sui_indexer::store::{PgIndexerStore, SqliteIndexerStore};
+use sui_indexer::{new_pg_connection_pool, new_rpc_client, new_sqlite_connection_pool};
+use sui_indexer::{start_prometheus_server, DbBackend, Indexer, ValidationConfig};
 use sui_json_rpc_types::{
     CheckpointId, ObjectChange, SuiObjectDataOptions, SuiTransactionBlockResponseOptions,
 };
@@ -13,14 +16,26 @@ use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::digests::TransactionDigest;
 
+const CONTINUOUS_COMMAND: &str = "continuous";
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // NOTE: this is to print out tracing like info, warn & error.
     let _guard = telemetry_subscribers::TelemetryConfig::new()
         .with_env()
         .init();
+    // NOTE: the spot check stays the default, so that its flag-only invocation keeps working.
+    match std::env::args().nth(1).as_deref() {
+        Some(CONTINUOUS_COMMAND) => {
+            let config = ValidationConfig::parse_from(std::env::args().skip(1));
+            continuous_validation(&config).await
+        }
+        _ => spot_check(&TestConfig::parse()).await,
+    }
+}
+
+async fn spot_check(config: &TestConfig) -> Result<()> {
     info!("Running correctness check for indexer...");
-    let config = TestConfig::parse();
     let fn_rpc_client = new_rpc_client(&config.fn_rpc_client_url).await?;
     let indexer_rpc_client = new_rpc_client(&config.indexer_rpc_client_url).await?;
 
@@ -37,7 +52,7 @@ async fn main() -> Result<()> {
     if end_checkpoint <= fn_latest_checkpoint && end_checkpoint <= indexer_latest_checkpoint {
         for checkpoint in config.start_checkpoint..=end_checkpoint {
             info!("Checking checkpoint {}", checkpoint);
-            check_checkpoint(&config, &fn_rpc_client, &indexer_rpc_client, checkpoint).await?;
+            check_checkpoint(config, &fn_rpc_client, &indexer_rpc_client, checkpoint).await?;
         }
     } else {
         error!("Start checkpoint is not available in both FN and Indexer, start: {}, FN latest: {}, indexer latest: {}", 
@@ -46,6 +61,36 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+async fn continuous_validation(config: &ValidationConfig) -> Result<()> {
+    info!("Running continuous validation for indexer...");
+    let (_registry_service, registry) = start_prometheus_server(
+        // NOTE: this parses the input host addr and port number for socket addr,
+        // so unwrap() is safe here.
+        format!(
+            "{}:{}",
+            config.client_metric_host, config.client_metric_port
+        )
+        .parse()
+        .unwrap(),
+        "",
+    )?;
+    let indexer_metrics = IndexerMetrics::new(&registry);
+
+    match config.db_backend {
+        DbBackend::Postgres => {
+            let blocking_cp = new_pg_connection_pool(&config.db_url)?;
+            let store = PgIndexerStore::new(blocking_cp, indexer_metrics.clone());
+            Indexer::validate(config, store, indexer_metrics).await?;
+        }
+        DbBackend::Sqlite => {
+            let blocking_cp = new_sqlite_connection_pool(&config.db_url)?;
+            let store = SqliteIndexerStore::new(blocking_cp, indexer_metrics.clone());
+            Indexer::validate(config, store, indexer_metrics).await?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn check_checkpoint(
     config: &TestConfig,
     fn_client: &SuiClient,
@@ -190,8 +235,13 @@ fn get_object_id_and_version(object_change: &ObjectChange) -> Option<(ObjectID,
     }
 }
 
+/// Compares a range of checkpoints served by the fullnode and by the indexer RPC.
 #[derive(Parser)]
-#[clap(name = "Transactions Test")]
+#[clap(
+    name = "Transactions Test",
+    after_help = "Run `indexer_data_validation continuous --help` to continuously compare \
+                  sampled checkpoints of the fullnode with the indexer DB instead."
+)]
 pub struct TestConfig {
     #[clap(long)]
     pub fn_rpc_client_url: String,
@@ -0,0 +1,298 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reindexes a range of already indexed checkpoints for selected tables, reading checkpoints from
+//! the checkpoint archive instead of the fullnode. Batches of checkpoints are indexed by parallel
+//! workers, and each batch replaces its rows in one DB transaction, so that a backfill can be
+//! stopped and run again at any time. The live checkpoint handler is not affected, since only
+//! checkpoints it has already committed can be backfilled.
+//!
+//! NOTE: the archive holds transactions and effects, but neither events nor output objects, so
+//! only the tables derived from those can be backfilled.
+
+use std::collections::BTreeSet;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+
+use clap::{Parser, ValueEnum};
+use futures::{StreamExt, TryStreamExt};
+use move_bytecode_utils::module_cache::GetModule;
+use tracing::{info, warn};
+
+use sui_archival::reader::ArchiveReader;
+use sui_json_rpc_types::{
+    Checkpoint as RpcCheckpoint, SuiTransactionBlock, SuiTransactionBlockEffects,
+    SuiTransactionBlockEvents,
+};
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::base_types::ExecutionData;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+};
+use sui_types::transaction::SenderSignedData;
+
+use crate::errors::IndexerError;
+use crate::handlers::checkpoint_handler::index_checkpoint;
+use crate::metrics::IndexerMetrics;
+use crate::store::{CheckpointData, IndexerStore, TemporaryBackfillStore};
+use crate::types::CheckpointTransactionBlockResponse;
+use crate::DbBackend;
+
+const DB_COMMIT_RETRY_INTERVAL_IN_MILLIS: u64 = 100;
+
+/// Tables which can be rebuilt from the checkpoint archive.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BackfillTable {
+    Checkpoints,
+    Transactions,
+    InputObjects,
+    ChangedObjects,
+    MoveCalls,
+    Recipients,
+}
+
+#[derive(Parser, Clone, Debug)]
+#[clap(
+    name = "Sui indexer backfill",
+    about = "Reindexes a range of checkpoints from the checkpoint archive",
+    rename_all = "kebab-case"
+)]
+pub struct BackfillConfig {
+    #[clap(long, value_enum, default_value = "postgres")]
+    pub db_backend: DbBackend,
+    /// Postgres connection url, or path of the database file with `--db-backend sqlite`.
+    #[clap(long)]
+    pub db_url: String,
+    #[clap(long)]
+    pub start_checkpoint: CheckpointSequenceNumber,
+    /// Last checkpoint to backfill, inclusive. Defaults to the latest indexed checkpoint.
+    #[clap(long)]
+    pub end_checkpoint: Option<CheckpointSequenceNumber>,
+    #[clap(long, value_enum, multiple_values = true, required = true)]
+    pub tables: Vec<BackfillTable>,
+    /// Number of batches indexed and committed in parallel.
+    #[clap(long, default_value = "4")]
+    pub workers: NonZeroUsize,
+    /// Number of checkpoints committed in one DB transaction.
+    #[clap(long, default_value = "100")]
+    pub batch_size: NonZeroUsize,
+    #[clap(long, default_value = "5")]
+    pub archive_download_concurrency: NonZeroUsize,
+    /// Store of the checkpoint archive, a local `--directory` by default.
+    #[clap(flatten)]
+    pub archive_store_config: ObjectStoreConfig,
+    #[clap(long, default_value = "0.0.0.0")]
+    pub client_metric_host: String,
+    #[clap(long, default_value = "9184")]
+    pub client_metric_port: u16,
+}
+
+pub struct BackfillHandler<S> {
+    state: S,
+    archive: ArchiveReader,
+    metrics: IndexerMetrics,
+    config: BackfillConfig,
+}
+
+impl<S> BackfillHandler<S>
+where
+    S: IndexerStore + Clone + Sync + Send + 'static,
+    S::ModuleCache: GetModule,
+{
+    pub fn new(
+        state: S,
+        archive: ArchiveReader,
+        metrics: IndexerMetrics,
+        config: &BackfillConfig,
+    ) -> Self {
+        Self {
+            state,
+            archive,
+            metrics,
+            config: config.clone(),
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), IndexerError> {
+        // NOTE: -1 is returned when checkpoints table is empty.
+        let last_seq_from_db = self.state.get_latest_checkpoint_sequence_number().await?;
+        if last_seq_from_db < 0 {
+            return Err(IndexerError::InvalidArgumentError(
+                "No checkpoint has been indexed yet, nothing to backfill".to_string(),
+            ));
+        }
+        let last_seq_from_db = last_seq_from_db as CheckpointSequenceNumber;
+        let start = self.config.start_checkpoint;
+        let end = self.config.end_checkpoint.unwrap_or(last_seq_from_db);
+        // NOTE: checkpoints after the watermark of the live indexer are not backfilled,
+        // otherwise the live indexer would skip them or index them twice.
+        if end > last_seq_from_db {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "End checkpoint {} is after the latest indexed checkpoint {}",
+                end, last_seq_from_db
+            )));
+        }
+        if start > end {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "Start checkpoint {} is after end checkpoint {}",
+                start, end
+            )));
+        }
+        let latest_archived_seq = self.archive.latest_available_checkpoint().await?;
+        if end > latest_archived_seq {
+            return Err(IndexerError::InvalidArgumentError(format!(
+                "End checkpoint {} is after the latest archived checkpoint {}",
+                end, latest_archived_seq
+            )));
+        }
+
+        info!(
+            "Backfilling tables {:?} for checkpoints {} to {}...",
+            self.config.tables, start, end
+        );
+        let batch_size = self.config.batch_size.get() as u64;
+        let batches = (start..=end)
+            .step_by(batch_size as usize)
+            .map(|batch_start| batch_start..std::cmp::min(batch_start + batch_size, end + 1));
+        futures::stream::iter(batches)
+            .map(|batch| self.backfill_batch(batch))
+            .buffer_unordered(self.config.workers.get())
+            .try_collect::<Vec<_>>()
+            .await?;
+        info!("Backfill of checkpoints {} to {} completed", start, end);
+        Ok(())
+    }
+
+    async fn backfill_batch(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<(), IndexerError> {
+        let mut archived_checkpoints = vec![];
+        self.archive
+            .for_each_checkpoint(checkpoint_range.clone(), |summary, contents| {
+                archived_checkpoints.push((summary, contents));
+                Ok(())
+            })
+            .await?;
+        if archived_checkpoints.len() as u64 != checkpoint_range.end - checkpoint_range.start {
+            return Err(IndexerError::UncategorizedError(anyhow::anyhow!(
+                "Archive has {} checkpoints of range {:?}",
+                archived_checkpoints.len(),
+                checkpoint_range
+            )));
+        }
+
+        let index_timer = self.metrics.checkpoint_index_latency.start_timer();
+        let indexed_checkpoints = archived_checkpoints
+            .into_iter()
+            .map(|(summary, contents)| {
+                index_checkpoint(&self.archived_checkpoint_data(summary, contents)?)
+            })
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+        index_timer.stop_and_record();
+
+        let tables: BTreeSet<_> = self.config.tables.iter().copied().collect();
+        let mut checkpoints = vec![];
+        let mut transactions = vec![];
+        let mut input_objects = vec![];
+        let mut changed_objects = vec![];
+        let mut move_calls = vec![];
+        let mut recipients = vec![];
+        for indexed_checkpoint in indexed_checkpoints {
+            checkpoints.push(indexed_checkpoint.checkpoint);
+            transactions.extend(indexed_checkpoint.transactions);
+            input_objects.extend(indexed_checkpoint.input_objects);
+            changed_objects.extend(indexed_checkpoint.changed_objects);
+            move_calls.extend(indexed_checkpoint.move_calls);
+            recipients.extend(indexed_checkpoint.recipients);
+        }
+        let backfill = TemporaryBackfillStore {
+            checkpoint_range: checkpoint_range.clone(),
+            checkpoints: tables
+                .contains(&BackfillTable::Checkpoints)
+                .then_some(checkpoints),
+            transactions: tables
+                .contains(&BackfillTable::Transactions)
+                .then_some(transactions),
+            input_objects: tables
+                .contains(&BackfillTable::InputObjects)
+                .then_some(input_objects),
+            changed_objects: tables
+                .contains(&BackfillTable::ChangedObjects)
+                .then_some(changed_objects),
+            move_calls: tables
+                .contains(&BackfillTable::MoveCalls)
+                .then_some(move_calls),
+            recipients: tables
+                .contains(&BackfillTable::Recipients)
+                .then_some(recipients),
+        };
+
+        let backfill_db_guard = self.metrics.backfill_db_commit_latency.start_timer();
+        let mut backfill_commit_res = self.state.persist_backfill(&backfill).await;
+        while let Err(e) = backfill_commit_res {
+            warn!(
+                "Indexer backfill commit of checkpoints {:?} failed with error: {:?}, retrying after {:?} milli-secs...",
+                checkpoint_range, e, DB_COMMIT_RETRY_INTERVAL_IN_MILLIS
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(
+                DB_COMMIT_RETRY_INTERVAL_IN_MILLIS,
+            ))
+            .await;
+            backfill_commit_res = self.state.persist_backfill(&backfill).await;
+        }
+        backfill_db_guard.stop_and_record();
+        self.metrics
+            .total_checkpoint_backfilled
+            .inc_by(checkpoint_range.end - checkpoint_range.start);
+        info!(
+            "Backfilled checkpoints {} to {}",
+            checkpoint_range.start,
+            checkpoint_range.end - 1
+        );
+        Ok(())
+    }
+
+    /// Builds the checkpoint data of one archived checkpoint, so that it is indexed the same way
+    /// the checkpoint handler does. Events and changed objects are left empty as they are not in
+    /// the archive, so neither events, object changes nor packages are indexed from it.
+    fn archived_checkpoint_data(
+        &self,
+        summary: CertifiedCheckpointSummary,
+        contents: FullCheckpointContents,
+    ) -> Result<CheckpointData, IndexerError> {
+        let signature = summary.auth_sig().signature.clone();
+        let summary = summary.into_data();
+        let sequence_number = summary.sequence_number;
+        let timestamp_ms = summary.timestamp_ms;
+
+        let transactions = contents
+            .iter()
+            .map(|execution_data| {
+                let ExecutionData {
+                    transaction,
+                    effects,
+                } = execution_data.clone();
+                let data: SenderSignedData = transaction.into_data();
+                let raw_transaction = bcs::to_bytes(&data)?;
+                Ok::<_, IndexerError>(CheckpointTransactionBlockResponse {
+                    digest: *effects.transaction_digest(),
+                    transaction: SuiTransactionBlock::try_from(data, self.state.module_cache())?,
+                    raw_transaction,
+                    effects: SuiTransactionBlockEffects::try_from(effects)?,
+                    events: SuiTransactionBlockEvents { data: vec![] },
+                    timestamp_ms,
+                    confirmed_local_execution: None,
+                    checkpoint: sequence_number,
+                })
+            })
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+
+        Ok(CheckpointData {
+            checkpoint: RpcCheckpoint::from((summary, contents.checkpoint_contents(), signature)),
+            transactions,
+            changed_objects: vec![],
+        })
+    }
+}
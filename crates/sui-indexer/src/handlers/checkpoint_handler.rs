@@ -160,7 +160,9 @@ where
         loop {
             let download_futures = (next_cursor_sequence_number
                 ..next_cursor_sequence_number + current_parallel_downloads as i64)
-                .map(|seq_num| self.download_checkpoint_data(seq_num as u64));
+                .map(|seq_num| {
+                    download_checkpoint_data(&self.http_client, &self.metrics, seq_num as u64)
+                });
             let download_results = join_all(download_futures).await;
             let mut downloaded_checkpoints = vec![];
             // NOTE: Push sequentially and if one of the downloads failed,
@@ -272,6 +274,25 @@ where
                         .process_tx(data.transaction_data(), &tx.effects, &tx.events)
                         .await?;
                 }
+                // NOTE: changed objects are fetched with bcs only, so object subscribers
+                // of the indexer don't receive parsed content.
+                let written_objects: Vec<SuiObjectData> = checkpoint
+                    .changed_objects
+                    .iter()
+                    .filter(|(status, _)| {
+                        matches!(
+                            status,
+                            ObjectStatus::Created | ObjectStatus::Mutated | ObjectStatus::Unwrapped
+                        )
+                    })
+                    .map(|(_, object)| object.clone())
+                    .collect();
+                self.subscription_handler
+                    .process_objects(&written_objects)
+                    .await?;
+                self.subscription_handler
+                    .process_checkpoint(&checkpoint.checkpoint)
+                    .await?;
                 ws_guard.stop_and_record();
             }
         }
@@ -489,89 +510,6 @@ where
         }
     }
 
-    /// Download all the data we need for one checkpoint.
-    async fn download_checkpoint_data(
-        &self,
-        seq: CheckpointSequenceNumber,
-    ) -> Result<CheckpointData, IndexerError> {
-        let latest_fn_checkpoint_seq = self
-            .http_client
-            .get_latest_checkpoint_sequence_number()
-            .await
-            .map_err(|e| {
-                IndexerError::FullNodeReadingError(format!(
-                    "Failed to get latest checkpoint sequence number and error {:?}",
-                    e
-                ))
-            })?;
-        self.metrics
-            .latest_fullnode_checkpoint_sequence_number
-            .set((*latest_fn_checkpoint_seq) as i64);
-
-        let mut checkpoint = self
-            .http_client
-            .get_checkpoint(seq.into())
-            .await
-            .map_err(|e| {
-                IndexerError::FullNodeReadingError(format!(
-                    "Failed to get checkpoint with sequence number {} and error {:?}",
-                    seq, e
-                ))
-            });
-        let fn_checkpoint_guard = self
-            .metrics
-            .fullnode_checkpoint_wait_and_download_latency
-            .start_timer();
-        while checkpoint.is_err() {
-            // sleep for 0.1 second and retry if latest checkpoint is not available yet
-            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-            // TODO(gegaowp): figure how to only measure successful checkpoint download time
-            checkpoint = self
-                .http_client
-                .get_checkpoint(seq.into())
-                .await
-                .map_err(|e| {
-                    IndexerError::FullNodeReadingError(format!(
-                        "Failed to get checkpoint with sequence number {} and error {:?}",
-                        seq, e
-                    ))
-                })
-        }
-        fn_checkpoint_guard.stop_and_record();
-        // unwrap here is safe because we checked for error above
-        let checkpoint = checkpoint.unwrap();
-
-        let fn_transaction_guard = self
-            .metrics
-            .fullnode_transaction_download_latency
-            .start_timer();
-        let transactions = join_all(checkpoint.transactions.chunks(MULTI_GET_CHUNK_SIZE).map(
-            |digests| multi_get_full_transactions(self.http_client.clone(), digests.to_vec()),
-        ))
-        .await
-        .into_iter()
-        .try_fold(vec![], |mut acc, chunk| {
-            acc.extend(chunk?);
-            Ok::<_, IndexerError>(acc)
-        })?;
-        fn_transaction_guard.stop_and_record();
-
-        let fn_object_guard = self.metrics.fullnode_object_download_latency.start_timer();
-        let object_changes = transactions
-            .iter()
-            .flat_map(|tx| get_object_changes(&tx.effects))
-            .collect::<Vec<_>>();
-        let changed_objects =
-            fetch_changed_objects(self.http_client.clone(), object_changes).await?;
-        fn_object_guard.stop_and_record();
-
-        Ok(CheckpointData {
-            checkpoint,
-            transactions,
-            changed_objects,
-        })
-    }
-
     async fn index_checkpoint_and_epoch(
         &self,
         data: &CheckpointData,
@@ -579,87 +517,9 @@ where
         let CheckpointData {
             checkpoint,
             transactions,
-            changed_objects,
+            ..
         } = data;
 
-        // Index transaction
-        let temp_tx_store_iter = transactions
-            .iter()
-            .map(|tx| TemporaryTransactionBlockResponseStore::from(tx.clone()));
-        let db_transactions: Vec<Transaction> = temp_tx_store_iter
-            .map(|tx| tx.try_into())
-            .collect::<Result<Vec<Transaction>, _>>()?;
-
-        // Index events
-        let events = transactions
-            .iter()
-            .flat_map(|tx| tx.events.data.iter().map(move |event| event.clone().into()))
-            .collect::<Vec<_>>();
-
-        // Index objects
-        let tx_objects = changed_objects
-            .iter()
-            // Unwrap safe here as we requested previous tx data in the request.
-            .fold(BTreeMap::<_, Vec<_>>::new(), |mut acc, (status, o)| {
-                if let Some(digest) = &o.previous_transaction {
-                    acc.entry(*digest).or_default().push((status, o));
-                }
-                acc
-            });
-
-        let objects_changes = transactions
-            .iter()
-            .map(|tx| {
-                let changed_db_objects = tx_objects
-                    .get(&tx.digest)
-                    .unwrap_or(&vec![])
-                    .iter()
-                    .map(|(status, o)| {
-                        Object::from(
-                            checkpoint.epoch,
-                            Some(checkpoint.sequence_number),
-                            status,
-                            o,
-                        )
-                    })
-                    .collect::<Vec<_>>();
-                let deleted_objects = get_deleted_db_objects(
-                    &tx.effects,
-                    checkpoint.epoch,
-                    Some(checkpoint.sequence_number),
-                );
-
-                TransactionObjectChanges {
-                    changed_objects: changed_db_objects,
-                    deleted_objects,
-                }
-            })
-            .collect();
-
-        // Index packages
-        let packages = Self::index_packages(transactions, changed_objects)?;
-
-        // Store input objects, move calls and recipients separately for transaction query indexing.
-        let input_objects = transactions
-            .iter()
-            .map(|tx| tx.get_input_objects(checkpoint.epoch))
-            .collect::<Result<Vec<_>, _>>()?
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
-        let changed_objects = transactions
-            .iter()
-            .flat_map(|tx| tx.get_changed_objects(checkpoint.epoch))
-            .collect();
-        let move_calls = transactions
-            .iter()
-            .flat_map(|tx| tx.get_move_calls(checkpoint.epoch))
-            .collect();
-        let recipients = transactions
-            .iter()
-            .flat_map(|tx| tx.get_recipients(checkpoint.epoch))
-            .collect();
-
         // NOTE: Index epoch when object checkpoint index has reached the same checkpoint,
         // because epoch info is based on the latest system state object by the current checkpoint.
         let epoch_index = if checkpoint.epoch == 0 && checkpoint.sequence_number == 0 {
@@ -771,69 +631,228 @@ where
         } else {
             None
         };
-        let total_transactions = db_transactions.iter().map(|t| t.transaction_count).sum();
-        let total_successful_transaction_blocks = db_transactions
-            .iter()
-            .filter(|t| t.execution_success)
-            .count();
-        let total_successful_transactions = db_transactions
-            .iter()
-            .filter(|t| t.execution_success)
-            .map(|t| t.transaction_count)
-            .sum();
-
-        Ok((
-            TemporaryCheckpointStore {
-                checkpoint: Checkpoint::from(
-                    checkpoint,
-                    total_transactions,
-                    total_successful_transactions,
-                    total_successful_transaction_blocks as i64,
-                )?,
-                transactions: db_transactions,
-                events,
-                object_changes: objects_changes,
-                packages,
-                input_objects,
-                changed_objects,
-                move_calls,
-                recipients,
-            },
-            epoch_index,
-        ))
+        Ok((index_checkpoint(data)?, epoch_index))
     }
+}
 
-    fn index_packages(
-        transactions: &[CheckpointTransactionBlockResponse],
-        changed_objects: &[(ObjectStatus, SuiObjectData)],
-    ) -> Result<Vec<Package>, IndexerError> {
-        let object_map = changed_objects
-            .iter()
-            .filter_map(|(_, o)| {
-                if let SuiRawData::Package(p) = &o
-                    .bcs
-                    .as_ref()
-                    .expect("Expect the content field to be non-empty from data fetching")
-                {
-                    Some((o.object_id, p))
-                } else {
-                    None
-                }
-            })
-            .collect::<BTreeMap<_, _>>();
+/// Index the rows of one checkpoint, without its epoch.
+pub fn index_checkpoint(data: &CheckpointData) -> Result<TemporaryCheckpointStore, IndexerError> {
+    let CheckpointData {
+        checkpoint,
+        transactions,
+        changed_objects,
+    } = data;
+
+    // Index transaction
+    let temp_tx_store_iter = transactions
+        .iter()
+        .map(|tx| TemporaryTransactionBlockResponseStore::from(tx.clone()));
+    let db_transactions: Vec<Transaction> = temp_tx_store_iter
+        .map(|tx| tx.try_into())
+        .collect::<Result<Vec<Transaction>, _>>()?;
+
+    // Index events
+    let events = transactions
+        .iter()
+        .flat_map(|tx| tx.events.data.iter().map(move |event| event.clone().into()))
+        .collect::<Vec<_>>();
+
+    // Index objects
+    let tx_objects = changed_objects
+        .iter()
+        // Unwrap safe here as we requested previous tx data in the request.
+        .fold(BTreeMap::<_, Vec<_>>::new(), |mut acc, (status, o)| {
+            if let Some(digest) = &o.previous_transaction {
+                acc.entry(*digest).or_default().push((status, o));
+            }
+            acc
+        });
 
-        transactions
-            .iter()
-            .flat_map(|tx| {
-                tx.effects.created().iter().map(|oref| {
-                    object_map
-                        .get(&oref.reference.object_id)
-                        .map(|o| Package::try_from(*tx.transaction.data.sender(), o))
+    let objects_changes = transactions
+        .iter()
+        .map(|tx| {
+            let changed_db_objects = tx_objects
+                .get(&tx.digest)
+                .unwrap_or(&vec![])
+                .iter()
+                .map(|(status, o)| {
+                    Object::from(
+                        checkpoint.epoch,
+                        Some(checkpoint.sequence_number),
+                        status,
+                        o,
+                    )
                 })
+                .collect::<Vec<_>>();
+            let deleted_objects = get_deleted_db_objects(
+                &tx.effects,
+                checkpoint.epoch,
+                Some(checkpoint.sequence_number),
+            );
+
+            TransactionObjectChanges {
+                changed_objects: changed_db_objects,
+                deleted_objects,
+            }
+        })
+        .collect();
+
+    // Index packages
+    let packages = index_packages(transactions, changed_objects)?;
+
+    // Store input objects, move calls and recipients separately for transaction query indexing.
+    let input_objects = transactions
+        .iter()
+        .map(|tx| tx.get_input_objects(checkpoint.epoch))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    let changed_objects = transactions
+        .iter()
+        .flat_map(|tx| tx.get_changed_objects(checkpoint.epoch))
+        .collect();
+    let move_calls = transactions
+        .iter()
+        .flat_map(|tx| tx.get_move_calls(checkpoint.epoch))
+        .collect();
+    let recipients = transactions
+        .iter()
+        .flat_map(|tx| tx.get_recipients(checkpoint.epoch))
+        .collect();
+
+    let total_transactions = db_transactions.iter().map(|t| t.transaction_count).sum();
+    let total_successful_transaction_blocks = db_transactions
+        .iter()
+        .filter(|t| t.execution_success)
+        .count();
+    let total_successful_transactions = db_transactions
+        .iter()
+        .filter(|t| t.execution_success)
+        .map(|t| t.transaction_count)
+        .sum();
+
+    Ok(TemporaryCheckpointStore {
+        checkpoint: Checkpoint::from(
+            checkpoint,
+            total_transactions,
+            total_successful_transactions,
+            total_successful_transaction_blocks as i64,
+        )?,
+        transactions: db_transactions,
+        events,
+        object_changes: objects_changes,
+        packages,
+        input_objects,
+        changed_objects,
+        move_calls,
+        recipients,
+    })
+}
+
+fn index_packages(
+    transactions: &[CheckpointTransactionBlockResponse],
+    changed_objects: &[(ObjectStatus, SuiObjectData)],
+) -> Result<Vec<Package>, IndexerError> {
+    let object_map = changed_objects
+        .iter()
+        .filter_map(|(_, o)| {
+            if let SuiRawData::Package(p) = &o
+                .bcs
+                .as_ref()
+                .expect("Expect the content field to be non-empty from data fetching")
+            {
+                Some((o.object_id, p))
+            } else {
+                None
+            }
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    transactions
+        .iter()
+        .flat_map(|tx| {
+            tx.effects.created().iter().map(|oref| {
+                object_map
+                    .get(&oref.reference.object_id)
+                    .map(|o| Package::try_from(*tx.transaction.data.sender(), o))
             })
-            .flatten()
-            .collect()
+        })
+        .flatten()
+        .collect()
+}
+
+/// Download all the data we need for one checkpoint.
+pub async fn download_checkpoint_data(
+    http_client: &HttpClient,
+    metrics: &IndexerMetrics,
+    seq: CheckpointSequenceNumber,
+) -> Result<CheckpointData, IndexerError> {
+    let latest_fn_checkpoint_seq = http_client
+        .get_latest_checkpoint_sequence_number()
+        .await
+        .map_err(|e| {
+            IndexerError::FullNodeReadingError(format!(
+                "Failed to get latest checkpoint sequence number and error {:?}",
+                e
+            ))
+        })?;
+    metrics
+        .latest_fullnode_checkpoint_sequence_number
+        .set((*latest_fn_checkpoint_seq) as i64);
+
+    let mut checkpoint = http_client.get_checkpoint(seq.into()).await.map_err(|e| {
+        IndexerError::FullNodeReadingError(format!(
+            "Failed to get checkpoint with sequence number {} and error {:?}",
+            seq, e
+        ))
+    });
+    let fn_checkpoint_guard = metrics
+        .fullnode_checkpoint_wait_and_download_latency
+        .start_timer();
+    while checkpoint.is_err() {
+        // sleep for 0.1 second and retry if latest checkpoint is not available yet
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        // TODO(gegaowp): figure how to only measure successful checkpoint download time
+        checkpoint = http_client.get_checkpoint(seq.into()).await.map_err(|e| {
+            IndexerError::FullNodeReadingError(format!(
+                "Failed to get checkpoint with sequence number {} and error {:?}",
+                seq, e
+            ))
+        })
     }
+    fn_checkpoint_guard.stop_and_record();
+    // unwrap here is safe because we checked for error above
+    let checkpoint = checkpoint.unwrap();
+
+    let fn_transaction_guard = metrics.fullnode_transaction_download_latency.start_timer();
+    let transactions = join_all(
+        checkpoint
+            .transactions
+            .chunks(MULTI_GET_CHUNK_SIZE)
+            .map(|digests| multi_get_full_transactions(http_client.clone(), digests.to_vec())),
+    )
+    .await
+    .into_iter()
+    .try_fold(vec![], |mut acc, chunk| {
+        acc.extend(chunk?);
+        Ok::<_, IndexerError>(acc)
+    })?;
+    fn_transaction_guard.stop_and_record();
+
+    let fn_object_guard = metrics.fullnode_object_download_latency.start_timer();
+    let object_changes = transactions
+        .iter()
+        .flat_map(|tx| get_object_changes(&tx.effects))
+        .collect::<Vec<_>>();
+    let changed_objects = fetch_changed_objects(http_client.clone(), object_changes).await?;
+    fn_object_guard.stop_and_record();
+
+    Ok(CheckpointData {
+        checkpoint,
+        transactions,
+        changed_objects,
+    })
 }
 
 // TODO(gegaowp): re-orgnize object util functions below
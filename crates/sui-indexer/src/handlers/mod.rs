@@ -1,4 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod backfill_handler;
 pub mod checkpoint_handler;
+pub mod validation_handler;
@@ -0,0 +1,684 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Continuously validates the indexed data against the fullnode. Each round samples checkpoints
+//! committed by the indexer, indexes them again from the fullnode's checkpoint and transaction
+//! effects, and compares the expected rows with the rows in the DB: checkpoints, transactions,
+//! object versions, balance changes and events. Discrepancies are logged and counted per kind,
+//! and with `--repair` the checkpoint, transaction and transaction index rows are rewritten,
+//! as well as the events of transactions whose events are missing.
+//!
+//! NOTE: checkpoints are final on Sui, so a discrepancy is never caused by a reorg, but the most
+//! recent checkpoints are skipped, as objects of a checkpoint are committed after the checkpoint.
+//! Checkpoints are only sampled within `--lookback-checkpoints`, which should not exceed the
+//! retention of pruned tables.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Parser;
+use futures::future::join_all;
+use jsonrpsee::http_client::HttpClient;
+use tracing::{info, warn};
+
+use sui_json_rpc::api::ReadApiClient;
+use sui_json_rpc::{get_balance_changes, ObjectProvider};
+use sui_json_rpc_types::{
+    BalanceChange, EventFilter, SuiEvent, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::{Object, ObjectRead};
+
+use crate::errors::IndexerError;
+use crate::handlers::checkpoint_handler::{download_checkpoint_data, index_checkpoint};
+use crate::metrics::IndexerMetrics;
+use crate::models::checkpoints::Checkpoint;
+use crate::models::events::Event;
+use crate::models::transactions::Transaction;
+use crate::store::{
+    CheckpointData, IndexerStore, TemporaryBackfillStore, TemporaryCheckpointStore,
+};
+use crate::types::CheckpointTransactionBlockResponse;
+use crate::DbBackend;
+
+const MULTI_GET_CHUNK_SIZE: usize = 50;
+
+// discrepancy kinds, used as metric labels
+const CHECKPOINTS: &str = "checkpoints";
+const TRANSACTIONS: &str = "transactions";
+const OBJECT_VERSIONS: &str = "object_versions";
+const BALANCES: &str = "balances";
+const EVENTS: &str = "events";
+
+#[derive(Parser, Clone, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct ValidationConfig {
+    #[clap(long, value_enum, default_value = "postgres")]
+    pub db_backend: DbBackend,
+    /// Postgres connection url, or path of the database file with `--db-backend sqlite`.
+    #[clap(long)]
+    pub db_url: String,
+    /// Url of the fullnode the indexed data is validated against.
+    #[clap(long)]
+    pub rpc_client_url: String,
+    /// Number of checkpoints validated in each round.
+    #[clap(long, default_value = "10")]
+    pub samples_per_round: NonZeroUsize,
+    #[clap(long, default_value = "60")]
+    pub validation_interval_secs: u64,
+    /// Checkpoints are sampled among this many latest indexed checkpoints, all by default.
+    #[clap(long)]
+    pub lookback_checkpoints: Option<u64>,
+    /// Number of latest indexed checkpoints which are never sampled.
+    #[clap(long, default_value = "100")]
+    pub checkpoint_lag: u64,
+    /// Rewrite the rows of checkpoints with discrepancies where possible.
+    #[clap(long)]
+    pub repair: bool,
+    #[clap(long, default_value = "0.0.0.0")]
+    pub client_metric_host: String,
+    #[clap(long, default_value = "9184")]
+    pub client_metric_port: u16,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum EventsValidation {
+    Matching,
+    Diverged,
+    /// Events of the transaction are not in the DB, they can be repaired.
+    Missing,
+}
+
+pub struct ValidationHandler<S> {
+    state: S,
+    http_client: HttpClient,
+    metrics: IndexerMetrics,
+    config: ValidationConfig,
+}
+
+impl<S> ValidationHandler<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    pub fn new(
+        state: S,
+        http_client: HttpClient,
+        metrics: IndexerMetrics,
+        config: &ValidationConfig,
+    ) -> Self {
+        Self {
+            state,
+            http_client,
+            metrics,
+            config: config.clone(),
+        }
+    }
+
+    pub async fn run(&self) -> Result<(), IndexerError> {
+        info!("Indexer validation started...");
+        loop {
+            let latest_checkpoint = self.state.get_latest_checkpoint_sequence_number().await?;
+            for checkpoint in self.sample_checkpoints(latest_checkpoint) {
+                // NOTE: a failed validation, e.g. of an unavailable fullnode, is not a discrepancy.
+                if let Err(e) = self.validate_checkpoint(checkpoint).await {
+                    warn!(
+                        "Failed validating checkpoint {} with error {:?}, skipping...",
+                        checkpoint, e
+                    );
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(self.config.validation_interval_secs)).await;
+        }
+    }
+
+    fn sample_checkpoints(&self, latest_checkpoint: i64) -> Vec<CheckpointSequenceNumber> {
+        let end_checkpoint = latest_checkpoint + 1 - self.config.checkpoint_lag as i64;
+        if end_checkpoint <= 0 {
+            return vec![];
+        }
+        let end_checkpoint = end_checkpoint as u64;
+        let start_checkpoint = self
+            .config
+            .lookback_checkpoints
+            .map_or(0, |lookback| end_checkpoint.saturating_sub(lookback));
+        let range_len = (end_checkpoint - start_checkpoint) as usize;
+        let sample_count = self.config.samples_per_round.get().min(range_len);
+        let mut checkpoints: Vec<_> =
+            rand::seq::index::sample(&mut rand::thread_rng(), range_len, sample_count)
+                .into_iter()
+                .map(|i| start_checkpoint + i as u64)
+                .collect();
+        checkpoints.sort();
+        checkpoints
+    }
+
+    async fn validate_checkpoint(&self, seq: CheckpointSequenceNumber) -> Result<(), IndexerError> {
+        let data = download_checkpoint_data(&self.http_client, &self.metrics, seq).await?;
+        let expected = index_checkpoint(&data)?;
+
+        let checkpoints_diverged = !self.checkpoint_matches(&expected.checkpoint).await?;
+        if checkpoints_diverged {
+            self.report_discrepancy(seq, CHECKPOINTS, data.checkpoint.digest.to_string());
+        }
+        let diverged_transactions = self.diverged_transactions(&expected).await?;
+        for digest in &diverged_transactions {
+            self.report_discrepancy(seq, TRANSACTIONS, digest.clone());
+        }
+        for (object_id, version) in self.diverged_object_versions(&expected).await? {
+            self.report_discrepancy(
+                seq,
+                OBJECT_VERSIONS,
+                format!("{object_id} of version {}", version.value()),
+            );
+        }
+        for digest in self.diverged_balance_changes(&data).await? {
+            self.report_discrepancy(seq, BALANCES, digest.to_string());
+        }
+        let mut missing_events = vec![];
+        for tx in &data.transactions {
+            match self.validate_events(tx.digest, &tx.events.data).await? {
+                EventsValidation::Matching => {}
+                EventsValidation::Diverged => {
+                    self.report_discrepancy(seq, EVENTS, tx.digest.to_string());
+                }
+                EventsValidation::Missing => {
+                    self.report_discrepancy(seq, EVENTS, tx.digest.to_string());
+                    missing_events.extend(tx.events.data.iter().cloned().map(Event::from));
+                }
+            }
+        }
+        self.metrics.total_checkpoint_validated.inc();
+
+        if self.config.repair {
+            if checkpoints_diverged || !diverged_transactions.is_empty() {
+                self.repair_checkpoint(seq, expected).await?;
+            }
+            if !missing_events.is_empty() {
+                self.state.persist_events(&missing_events).await?;
+                info!(
+                    "Repaired {} missing events of checkpoint {}",
+                    missing_events.len(),
+                    seq
+                );
+                self.metrics
+                    .total_validation_repaired
+                    .with_label_values(&[EVENTS])
+                    .inc_by(missing_events.len() as u64);
+            }
+        }
+        Ok(())
+    }
+
+    // Compares the checkpoint rows, as the RPC checkpoint also has fields which are not stored,
+    // like the checkpoint commitments. A checkpoint missing from the DB is a discrepancy.
+    async fn checkpoint_matches(&self, expected: &Checkpoint) -> Result<bool, IndexerError> {
+        let seq = expected.sequence_number;
+        let stored = self.state.get_indexer_checkpoints(seq - 1, 1).await?;
+        Ok(match stored.first() {
+            Some(stored) if stored.sequence_number == seq => stored == expected,
+            _ => false,
+        })
+    }
+
+    async fn diverged_transactions(
+        &self,
+        expected: &TemporaryCheckpointStore,
+    ) -> Result<Vec<String>, IndexerError> {
+        let digests: Vec<_> = expected
+            .transactions
+            .iter()
+            .map(|tx| tx.transaction_digest.clone())
+            .collect();
+        let stored_transactions: HashMap<_, _> = self
+            .state
+            .multi_get_transactions_by_digests(&digests)
+            .await?
+            .into_iter()
+            .map(|tx| (tx.transaction_digest.clone(), tx))
+            .collect();
+        Ok(expected
+            .transactions
+            .iter()
+            .filter(|tx| {
+                !stored_transactions
+                    .get(&tx.transaction_digest)
+                    .map_or(false, |stored_tx| {
+                        // ids are assigned by the DB, and fast path transactions are confirmed
+                        // by the local execution.
+                        Transaction {
+                            id: None,
+                            confirmed_local_execution: tx.confirmed_local_execution,
+                            ..stored_tx.clone()
+                        } == **tx
+                    })
+            })
+            .map(|tx| tx.transaction_digest.clone())
+            .collect())
+    }
+
+    async fn validate_events(
+        &self,
+        digest: TransactionDigest,
+        expected: &[SuiEvent],
+    ) -> Result<EventsValidation, IndexerError> {
+        let stored = self
+            .state
+            .get_events(EventFilter::Transaction(digest), None, None, false)
+            .await?
+            .data;
+        Ok(if stored == expected {
+            EventsValidation::Matching
+        } else if stored.is_empty() {
+            EventsValidation::Missing
+        } else {
+            EventsValidation::Diverged
+        })
+    }
+
+    async fn diverged_object_versions(
+        &self,
+        expected: &TemporaryCheckpointStore,
+    ) -> Result<Vec<(ObjectID, SequenceNumber)>, IndexerError> {
+        let mut diverged_objects = vec![];
+        for object in expected
+            .object_changes
+            .iter()
+            .flat_map(|changes| &changes.changed_objects)
+        {
+            let object_id = ObjectID::from_str(&object.object_id)?;
+            let version = SequenceNumber::from_u64(object.version as u64);
+            let matches = match self.state.get_object(object_id, Some(version)).await? {
+                ObjectRead::Exists(object_ref, _, _) => {
+                    object_ref.2.base58_encode() == object.object_digest
+                }
+                ObjectRead::NotExists(_) | ObjectRead::Deleted(_) => false,
+            };
+            if !matches {
+                diverged_objects.push((object_id, version));
+            }
+        }
+        Ok(diverged_objects)
+    }
+
+    // Balance changes of the fullnode, compared with the balance changes of the object versions
+    // in the DB. Failed transactions only change the gas balance, which is part of the effects.
+    async fn diverged_balance_changes(
+        &self,
+        data: &CheckpointData,
+    ) -> Result<Vec<TransactionDigest>, IndexerError> {
+        let expected_balance_changes = self.fetch_balance_changes(&data.transactions).await?;
+        let object_provider = IndexerObjectProvider { state: &self.state };
+        let mut diverged_transactions = vec![];
+        for tx in data
+            .transactions
+            .iter()
+            .filter(|tx| tx.effects.status().is_ok())
+        {
+            let expected = expected_balance_changes
+                .get(&tx.digest)
+                .cloned()
+                .unwrap_or_default();
+            let stored = match stored_balance_changes(&object_provider, tx).await {
+                Ok(stored) => stored,
+                Err(e) => {
+                    warn!(
+                        "Failed computing balance changes of transaction {} with error {:?}",
+                        tx.digest, e
+                    );
+                    diverged_transactions.push(tx.digest);
+                    continue;
+                }
+            };
+            if sort_balance_changes(stored) != sort_balance_changes(expected) {
+                diverged_transactions.push(tx.digest);
+            }
+        }
+        Ok(diverged_transactions)
+    }
+
+    async fn fetch_balance_changes(
+        &self,
+        transactions: &[CheckpointTransactionBlockResponse],
+    ) -> Result<BTreeMap<TransactionDigest, Vec<BalanceChange>>, IndexerError> {
+        let digests: Vec<_> = transactions.iter().map(|tx| tx.digest).collect();
+        let responses = join_all(digests.chunks(MULTI_GET_CHUNK_SIZE).map(|digests| {
+            self.http_client.multi_get_transaction_blocks(
+                digests.to_vec(),
+                Some(SuiTransactionBlockResponseOptions::new().with_balance_changes()),
+            )
+        }))
+        .await
+        .into_iter()
+        .try_fold(vec![], |mut acc, chunk| {
+            acc.extend(chunk.map_err(|e| {
+                IndexerError::FullNodeReadingError(format!(
+                    "Failed to get balance changes of transactions with error: {:?}",
+                    e
+                ))
+            })?);
+            Ok::<_, IndexerError>(acc)
+        })?;
+        Ok(responses
+            .into_iter()
+            .map(|response| {
+                (
+                    response.digest,
+                    response.balance_changes.unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    async fn repair_checkpoint(
+        &self,
+        seq: CheckpointSequenceNumber,
+        expected: TemporaryCheckpointStore,
+    ) -> Result<(), IndexerError> {
+        let TemporaryCheckpointStore {
+            checkpoint,
+            transactions,
+            input_objects,
+            changed_objects,
+            move_calls,
+            recipients,
+            ..
+        } = expected;
+        let transaction_count = transactions.len();
+        // NOTE: transactions are upserted and the index rows of the checkpoint are replaced,
+        // the same way as when backfilling the checkpoint.
+        self.state
+            .persist_backfill(&TemporaryBackfillStore {
+                checkpoint_range: seq..seq + 1,
+                checkpoints: Some(vec![checkpoint]),
+                transactions: Some(transactions),
+                input_objects: Some(input_objects),
+                changed_objects: Some(changed_objects),
+                move_calls: Some(move_calls),
+                recipients: Some(recipients),
+            })
+            .await?;
+        info!(
+            "Repaired checkpoint {} with {} transactions",
+            seq, transaction_count
+        );
+        self.metrics
+            .total_validation_repaired
+            .with_label_values(&[TRANSACTIONS])
+            .inc_by(transaction_count as u64);
+        Ok(())
+    }
+
+    fn report_discrepancy(&self, seq: CheckpointSequenceNumber, kind: &str, detail: String) {
+        warn!(
+            "Indexer {} of checkpoint {} diverged from the fullnode: {}",
+            kind, seq, detail
+        );
+        self.metrics
+            .total_validation_discrepancies
+            .with_label_values(&[kind])
+            .inc();
+    }
+}
+
+async fn stored_balance_changes<S>(
+    object_provider: &IndexerObjectProvider<'_, S>,
+    tx: &CheckpointTransactionBlockResponse,
+) -> Result<Vec<BalanceChange>, IndexerError>
+where
+    S: IndexerStore + Sync + Send,
+{
+    // NOTE: digests are not passed, so that a diverged object version is reported instead of
+    // failing the digest assertion of the balance change computation.
+    let unwrapped_then_deleted: HashSet<_> = tx
+        .effects
+        .unwrapped_then_deleted()
+        .iter()
+        .map(|o| o.object_id)
+        .collect();
+    let modified_at_versions: Vec<_> = tx
+        .effects
+        .modified_at_versions()
+        .into_iter()
+        .filter(|(id, _)| !unwrapped_then_deleted.contains(id))
+        .map(|(id, version)| (id, version, None))
+        .collect();
+    let all_mutated: Vec<_> = tx
+        .effects
+        .all_changed_objects()
+        .into_iter()
+        .map(|(o, _)| (o.reference.object_id, o.reference.version, None))
+        .collect();
+    get_balance_changes(object_provider, &modified_at_versions, &all_mutated).await
+}
+
+fn sort_balance_changes(mut balance_changes: Vec<BalanceChange>) -> Vec<BalanceChange> {
+    balance_changes.sort_by_key(|b| (b.owner.to_string(), b.coin_type.to_string()));
+    balance_changes
+}
+
+struct IndexerObjectProvider<'a, S> {
+    state: &'a S,
+}
+
+#[async_trait]
+impl<'a, S> ObjectProvider for IndexerObjectProvider<'a, S>
+where
+    S: IndexerStore + Sync + Send,
+{
+    type Error = IndexerError;
+
+    async fn get_object(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Object, Self::Error> {
+        match self.state.get_object(*id, Some(*version)).await? {
+            ObjectRead::Exists(_, object, _) => Ok(object),
+            ObjectRead::NotExists(_) | ObjectRead::Deleted(_) => {
+                Err(IndexerError::UncategorizedError(anyhow!(
+                    "Object {id} of version {version} is not in the DB"
+                )))
+            }
+        }
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        _id: &ObjectID,
+        _version: &SequenceNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        Err(IndexerError::NotSupportedError(
+            "Finding object versions is not needed by balance changes".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use clap::Parser;
+    use jsonrpsee::http_client::HttpClientBuilder;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::StructTag;
+    use prometheus::Registry;
+
+    use sui_json_rpc_types::SuiEvent;
+    use sui_types::base_types::{ObjectID, SuiAddress};
+    use sui_types::digests::TransactionDigest;
+    use sui_types::event::EventID;
+
+    use crate::errors::IndexerError;
+    use crate::metrics::IndexerMetrics;
+    use crate::models::transactions::Transaction;
+    use crate::store::sqlite_store_tests::{test_checkpoint, test_store, test_transaction};
+    use crate::store::{IndexerStore, SqliteIndexerStore, TemporaryCheckpointStore};
+
+    use super::{EventsValidation, ValidationConfig, ValidationHandler};
+
+    fn test_handler(
+        store: &SqliteIndexerStore,
+        args: &[&str],
+    ) -> ValidationHandler<SqliteIndexerStore> {
+        let config = ValidationConfig::parse_from(
+            [
+                "continuous",
+                "--db-url",
+                "indexer.db",
+                "--rpc-client-url",
+                "unused",
+            ]
+            .iter()
+            .chain(args),
+        );
+        // The fullnode is never reached by the tested comparisons.
+        let http_client = HttpClientBuilder::default()
+            .build("http://127.0.0.1:1")
+            .unwrap();
+        ValidationHandler::new(
+            store.clone(),
+            http_client,
+            IndexerMetrics::new(&Registry::default()),
+            &config,
+        )
+    }
+
+    fn expected_checkpoint(seq: i64, transactions: Vec<Transaction>) -> TemporaryCheckpointStore {
+        TemporaryCheckpointStore {
+            checkpoint: test_checkpoint(seq, 0, &transactions),
+            transactions,
+            events: vec![],
+            object_changes: vec![],
+            packages: vec![],
+            input_objects: vec![],
+            changed_objects: vec![],
+            move_calls: vec![],
+            recipients: vec![],
+        }
+    }
+
+    #[test]
+    fn test_sample_checkpoints() {
+        let (_dir, store) = test_store();
+
+        // Checkpoints within the lag are never sampled.
+        let handler = test_handler(&store, &["--checkpoint-lag", "10"]);
+        assert!(handler.sample_checkpoints(-1).is_empty());
+        assert!(handler.sample_checkpoints(9).is_empty());
+        assert_eq!(handler.sample_checkpoints(10), vec![0]);
+
+        // Samples are capped by the number of sampled checkpoints.
+        let handler = test_handler(
+            &store,
+            &["--checkpoint-lag", "10", "--samples-per-round", "5"],
+        );
+        assert_eq!(handler.sample_checkpoints(12), vec![0, 1, 2]);
+
+        // Checkpoints are sampled among the lookback checkpoints before the lag.
+        let handler = test_handler(
+            &store,
+            &[
+                "--checkpoint-lag",
+                "10",
+                "--samples-per-round",
+                "5",
+                "--lookback-checkpoints",
+                "20",
+            ],
+        );
+        assert_eq!(handler.sample_checkpoints(13), vec![0, 1, 2, 3]);
+        for _ in 0..10 {
+            let samples = handler.sample_checkpoints(109);
+            assert_eq!(samples.len(), 5);
+            assert!(samples.windows(2).all(|w| w[0] < w[1]));
+            assert!(samples.iter().all(|seq| (80..100).contains(seq)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_matches() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let handler = test_handler(&store, &[]);
+        let expected = expected_checkpoint(0, vec![test_transaction(0)]);
+        store
+            .persist_checkpoint_transactions(&expected.checkpoint, &expected.transactions)
+            .await?;
+
+        assert!(handler.checkpoint_matches(&expected.checkpoint).await?);
+        let mut diverged = expected.checkpoint.clone();
+        diverged.total_gas_cost += 1;
+        assert!(!handler.checkpoint_matches(&diverged).await?);
+        // A checkpoint missing from the DB is a discrepancy.
+        let missing = test_checkpoint(1, 0, &[]);
+        assert!(!handler.checkpoint_matches(&missing).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_diverged_transactions() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let handler = test_handler(&store, &[]);
+        let stored_transactions = vec![test_transaction(0), test_transaction(0)];
+        store
+            .persist_checkpoint_transactions(
+                &test_checkpoint(0, 0, &stored_transactions),
+                &stored_transactions,
+            )
+            .await?;
+
+        let matching = Transaction {
+            // Only transactions of the fast path are confirmed by the local execution.
+            confirmed_local_execution: Some(true),
+            ..stored_transactions[0].clone()
+        };
+        let modified = Transaction {
+            gas_budget: 2_000,
+            ..stored_transactions[1].clone()
+        };
+        let missing = test_transaction(0);
+        let expected = expected_checkpoint(0, vec![matching, modified.clone(), missing.clone()]);
+
+        assert_eq!(
+            handler.diverged_transactions(&expected).await?,
+            vec![modified.transaction_digest, missing.transaction_digest]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_events() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let handler = test_handler(&store, &[]);
+        let digest = TransactionDigest::random();
+        let event = SuiEvent {
+            id: EventID {
+                tx_digest: digest,
+                event_seq: 0,
+            },
+            package_id: ObjectID::random(),
+            transaction_module: Identifier::new("counter").unwrap(),
+            sender: SuiAddress::random_for_testing_only(),
+            type_: StructTag::from_str("0x2::counter::Incremented").unwrap(),
+            parsed_json: serde_json::Value::Null,
+            bcs: vec![0; 8],
+            timestamp_ms: Some(1_000),
+        };
+
+        assert_eq!(
+            handler.validate_events(digest, &[]).await?,
+            EventsValidation::Matching
+        );
+        assert_eq!(
+            handler.validate_events(digest, &[event]).await?,
+            EventsValidation::Missing
+        );
+        Ok(())
+    }
+}
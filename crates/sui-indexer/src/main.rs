@@ -2,14 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::Parser;
+use prometheus::Registry;
 use tracing::{error, info};
 
 use sui_indexer::errors::IndexerError;
 use sui_indexer::metrics::IndexerMetrics;
 use sui_indexer::start_prometheus_server;
-use sui_indexer::store::PgIndexerStore;
-use sui_indexer::utils::reset_database;
+use sui_indexer::store::{PgIndexerStore, SqliteIndexerStore};
+use sui_indexer::utils::{reset_database, reset_sqlite_database, run_sqlite_migrations};
 use sui_indexer::{get_pg_pool_connection, new_pg_connection_pool, Indexer, IndexerConfig};
+use sui_indexer::{get_sqlite_pool_connection, new_sqlite_connection_pool, DbBackend};
 
 #[tokio::main]
 async fn main() -> Result<(), IndexerError> {
@@ -32,6 +34,9 @@ async fn main() -> Result<(), IndexerError> {
         indexer_config.rpc_client_url.as_str(),
     )?;
     let indexer_metrics = IndexerMetrics::new(&registry);
+    if indexer_config.db_backend == DbBackend::Sqlite {
+        return start_sqlite_indexer(&indexer_config, &registry, indexer_metrics).await;
+    }
     let db_url = indexer_config.get_db_url().map_err(|e| {
         IndexerError::PgPoolConnectionError(format!(
             "Failed parsing database url with error {:?}",
@@ -66,3 +71,46 @@ async fn main() -> Result<(), IndexerError> {
 
     Indexer::start(&indexer_config, &registry, store, indexer_metrics, None).await
 }
+
+async fn start_sqlite_indexer(
+    indexer_config: &IndexerConfig,
+    registry: &Registry,
+    indexer_metrics: IndexerMetrics,
+) -> Result<(), IndexerError> {
+    let db_url = indexer_config.get_db_url().map_err(|e| {
+        IndexerError::SqlitePoolConnectionError(format!(
+            "Failed parsing database url with error {:?}",
+            e
+        ))
+    })?;
+    let blocking_cp = new_sqlite_connection_pool(&db_url).map_err(|e| {
+        error!("Failed creating SQLite connection pool with error {:?}", e);
+        e
+    })?;
+    let mut conn = get_sqlite_pool_connection(&blocking_cp).map_err(|e| {
+        error!(
+            "Failed getting SQLite connection from connection pool with error {:?}",
+            e
+        );
+        e
+    })?;
+    // NOTE: unlike Postgres, the SQLite database file is usually created by the indexer itself,
+    // so the schema is always brought up to date on start.
+    if indexer_config.reset_db {
+        reset_sqlite_database(&mut conn, /* drop_all */ true)
+    } else {
+        run_sqlite_migrations(&mut conn)
+    }
+    .map_err(|e| {
+        let db_err_msg = format!(
+            "Failed migrating database with url: {:?} and error: {:?}",
+            db_url, e
+        );
+        error!("{}", db_err_msg);
+        IndexerError::SqliteResetError(db_err_msg)
+    })?;
+    drop(conn);
+    let store = SqliteIndexerStore::new(blocking_cp, indexer_metrics.clone());
+
+    Indexer::start(indexer_config, registry, store, indexer_metrics, None).await
+}
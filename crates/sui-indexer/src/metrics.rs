@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::{
-    register_histogram_with_registry, register_int_counter_with_registry,
-    register_int_gauge_with_registry, Histogram, IntCounter, IntGauge, Registry,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 
 /// Prometheus metrics for sui-indexer.
@@ -21,9 +23,21 @@ pub struct IndexerMetrics {
     pub total_transaction_committed: IntCounter,
     pub total_object_change_committed: IntCounter,
     pub total_epoch_committed: IntCounter,
+    pub total_checkpoint_backfilled: IntCounter,
     pub latest_fullnode_checkpoint_sequence_number: IntGauge,
     pub latest_indexer_checkpoint_sequence_number: IntGauge,
     pub latest_indexer_object_checkpoint_sequence_number: IntGauge,
+    pub latest_processor_checkpoint_sequence_number: IntGaugeVec,
+    pub total_checkpoint_processor_errors: IntCounterVec,
+    // data retention, labelled by table
+    pub total_rows_pruned: IntCounterVec,
+    pub total_partitions_dropped: IntCounterVec,
+    pub first_retained_epoch: IntGaugeVec,
+    pub prune_batch_latency: HistogramVec,
+    // data validation against the fullnode, labelled by kind of discrepancy
+    pub total_checkpoint_validated: IntCounter,
+    pub total_validation_discrepancies: IntCounterVec,
+    pub total_validation_repaired: IntCounterVec,
     // checkpoint E2E latency is:
     // fullnode_download_latency + checkpoint_index_latency + db_commit_latency
     pub fullnode_checkpoint_wait_and_download_latency: Histogram,
@@ -36,6 +50,7 @@ pub struct IndexerMetrics {
     pub object_mutation_db_commit_latency: Histogram,
     pub object_deletion_db_commit_latency: Histogram,
     pub epoch_db_commit_latency: Histogram,
+    pub backfill_db_commit_latency: Histogram,
     // latency of event websocket subscription
     pub subscription_process_latency: Histogram,
     pub transaction_per_checkpoint: Histogram,
@@ -46,6 +61,7 @@ pub struct IndexerMetrics {
     pub get_object_latency: Histogram,
     pub multi_get_objects_latency: Histogram,
     pub try_get_past_object_latency: Histogram,
+    pub try_get_past_object_lt_or_eq_version_latency: Histogram,
     pub try_multi_get_past_objects_latency: Histogram,
     pub get_checkpoint_latency: Histogram,
     pub get_checkpoints_latency: Histogram,
@@ -101,6 +117,12 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            total_checkpoint_backfilled: register_int_counter_with_registry!(
+                "total_checkpoint_backfilled",
+                "Total number of checkpoint backfilled from the archive",
+                registry,
+            )
+            .unwrap(),
             latest_fullnode_checkpoint_sequence_number: register_int_gauge_with_registry!(
                 "latest_fullnode_checkpoint_sequence_number",
                 "Latest checkpoint sequence number from the Full Node",
@@ -119,6 +141,69 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            latest_processor_checkpoint_sequence_number: register_int_gauge_vec_with_registry!(
+                "latest_processor_checkpoint_sequence_number",
+                "Latest checkpoint sequence number committed by each checkpoint processor",
+                &["processor"],
+                registry,
+            )
+            .unwrap(),
+            total_checkpoint_processor_errors: register_int_counter_vec_with_registry!(
+                "total_checkpoint_processor_errors",
+                "Total number of errors of each checkpoint processor, which is retried after them",
+                &["processor"],
+                registry,
+            )
+            .unwrap(),
+            total_rows_pruned: register_int_counter_vec_with_registry!(
+                "total_rows_pruned",
+                "Total number of rows pruned from each table",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            total_partitions_dropped: register_int_counter_vec_with_registry!(
+                "total_partitions_dropped",
+                "Total number of expired epoch partitions dropped from each table",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            first_retained_epoch: register_int_gauge_vec_with_registry!(
+                "first_retained_epoch",
+                "First epoch whose rows are retained in each table pruned by epochs",
+                &["table"],
+                registry,
+            )
+            .unwrap(),
+            prune_batch_latency: register_histogram_vec_with_registry!(
+                "prune_batch_latency",
+                "Time spent pruning a batch of rows from each table",
+                &["table"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            total_checkpoint_validated: register_int_counter_with_registry!(
+                "total_checkpoint_validated",
+                "Total number of checkpoint validated against the fullnode",
+                registry,
+            )
+            .unwrap(),
+            total_validation_discrepancies: register_int_counter_vec_with_registry!(
+                "total_validation_discrepancies",
+                "Total number of rows diverged from the fullnode, by kind of discrepancy",
+                &["kind"],
+                registry,
+            )
+            .unwrap(),
+            total_validation_repaired: register_int_counter_vec_with_registry!(
+                "total_validation_repaired",
+                "Total number of rows repaired by the validation, by kind of discrepancy",
+                &["kind"],
+                registry,
+            )
+            .unwrap(),
             fullnode_checkpoint_wait_and_download_latency: register_histogram_with_registry!(
                 "fullnode_checkpoint_wait_and_download_latency",
                 "Time spent in waiting for a new checkpoint from the Full Node",
@@ -189,6 +274,13 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            backfill_db_commit_latency: register_histogram_with_registry!(
+                "backfill_db_commit_latency",
+                "Time spent commiting a batch of backfilled checkpoints to the db",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             subscription_process_latency: register_histogram_with_registry!(
                 "subscription_process_latency",
                 "Time spent in process Websocket subscription",
@@ -238,6 +330,13 @@ impl IndexerMetrics {
                 registry
             )
             .unwrap(),
+            try_get_past_object_lt_or_eq_version_latency: register_histogram_with_registry!(
+                "try_get_past_object_lt_or_eq_version_latency",
+                "Time spent in try_get_past_object_lt_or_eq_version on the fullnode behind.",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry
+            )
+            .unwrap(),
             try_multi_get_past_objects_latency: register_histogram_with_registry!(
                 "try_multi_get_past_objects_latency",
                 "Time spent in try_multi_get_past_objects on the fullnode behind.",
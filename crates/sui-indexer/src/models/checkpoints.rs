@@ -14,7 +14,7 @@ use sui_types::messages_checkpoint::EndOfEpochData;
 use crate::errors::IndexerError;
 use crate::schema::checkpoints::{self};
 
-#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[derive(Queryable, Insertable, Debug, Clone, Default, PartialEq)]
 #[diesel(table_name = checkpoints)]
 pub struct Checkpoint {
     pub sequence_number: i64,
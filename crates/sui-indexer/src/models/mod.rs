@@ -10,6 +10,7 @@ pub mod network_metrics;
 pub mod objects;
 pub mod owners;
 pub mod packages;
+pub mod processor_watermarks;
 pub mod system_state;
 pub mod transaction_index;
 pub mod transactions;
@@ -0,0 +1,13 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::schema::processor_watermarks;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = processor_watermarks)]
+pub struct ProcessorWatermark {
+    pub processor_name: String,
+    pub checkpoint_sequence_number: i64,
+}
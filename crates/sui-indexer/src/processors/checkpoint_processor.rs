@@ -0,0 +1,325 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use jsonrpsee::http_client::HttpClient;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::errors::IndexerError;
+use crate::handlers::checkpoint_handler::download_checkpoint_data;
+use crate::metrics::IndexerMetrics;
+use crate::store::{CheckpointData, IndexerStore};
+
+const DB_COMMIT_RETRY_INTERVAL_IN_MILLIS: u64 = 100;
+const CHECKPOINT_CACHE_SIZE: usize = 100;
+
+/// An application specific processor, run by the indexer next to its own tables, for example to
+/// index the trades of a DEX from its events into a table of its own.
+///
+/// Checkpoints are delivered one at a time in sequence number order, once the indexer has
+/// committed them. Each processor keeps its own watermark, which is only advanced after
+/// `process_checkpoint` returned successfully, so a processor added later starts from genesis
+/// and a processor which failed resumes from its last processed checkpoint.
+///
+/// Delivery is at-least-once: the last checkpoint can be delivered again after a failure or a
+/// restart, so committing a checkpoint must be idempotent, e.g. by upserting rows keyed on the
+/// transaction digest or event id.
+#[async_trait]
+pub trait CheckpointProcessor: Send + Sync + 'static {
+    /// Unique name of the processor, its watermark is stored under this name.
+    fn name(&self) -> &str;
+
+    async fn process_checkpoint(&self, checkpoint: &CheckpointData) -> Result<(), IndexerError>;
+}
+
+type CachedCheckpoint = Arc<OnceCell<Arc<CheckpointData>>>;
+
+/// Checkpoints downloaded for the checkpoint processors, shared by all their runners so that
+/// processors at the same checkpoints download each of them once. Only the latest
+/// `CHECKPOINT_CACHE_SIZE` checkpoints are kept, a processor lagging behind them downloads
+/// its checkpoints by itself.
+#[derive(Clone)]
+pub struct CheckpointDataCache {
+    http_client: HttpClient,
+    metrics: IndexerMetrics,
+    checkpoints: Arc<Mutex<BTreeMap<CheckpointSequenceNumber, CachedCheckpoint>>>,
+}
+
+impl CheckpointDataCache {
+    pub fn new(http_client: HttpClient, metrics: IndexerMetrics) -> Self {
+        Self {
+            http_client,
+            metrics,
+            checkpoints: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Returns the data of checkpoint `seq`, concurrent callers wait for the same download.
+    pub async fn get(
+        &self,
+        seq: CheckpointSequenceNumber,
+    ) -> Result<Arc<CheckpointData>, IndexerError> {
+        let checkpoint = {
+            let mut checkpoints = self.checkpoints.lock().unwrap();
+            let checkpoint = checkpoints.entry(seq).or_default().clone();
+            while checkpoints.len() > CHECKPOINT_CACHE_SIZE {
+                checkpoints.pop_first();
+            }
+            checkpoint
+        };
+        let data = checkpoint
+            .get_or_try_init(|| async {
+                download_checkpoint_data(&self.http_client, &self.metrics, seq)
+                    .await
+                    .map(Arc::new)
+            })
+            .await?;
+        Ok(data.clone())
+    }
+
+    #[cfg(test)]
+    fn insert(&self, data: CheckpointData) {
+        let seq = data.checkpoint.sequence_number;
+        let checkpoint = Arc::new(OnceCell::new_with(Some(Arc::new(data))));
+        self.checkpoints.lock().unwrap().insert(seq, checkpoint);
+    }
+}
+
+pub struct CheckpointProcessorRunner<S> {
+    store: S,
+    checkpoint_cache: CheckpointDataCache,
+    metrics: IndexerMetrics,
+    processor: Arc<dyn CheckpointProcessor>,
+}
+
+impl<S> CheckpointProcessorRunner<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    pub fn new(
+        store: S,
+        checkpoint_cache: CheckpointDataCache,
+        metrics: IndexerMetrics,
+        processor: Arc<dyn CheckpointProcessor>,
+    ) -> Self {
+        Self {
+            store,
+            checkpoint_cache,
+            metrics,
+            processor,
+        }
+    }
+
+    pub fn processor_name(&self) -> &str {
+        self.processor.name()
+    }
+
+    pub async fn start(&self) -> Result<(), IndexerError> {
+        let result = self.process_checkpoints().await;
+        if result.is_err() {
+            self.metrics
+                .total_checkpoint_processor_errors
+                .with_label_values(&[self.processor.name()])
+                .inc();
+        }
+        result
+    }
+
+    async fn process_checkpoints(&self) -> Result<(), IndexerError> {
+        let name = self.processor.name();
+        info!("Indexer checkpoint processor {} started...", name);
+        let mut last_processed_checkpoint = self.store.get_processor_watermark(name).await?;
+        if last_processed_checkpoint >= 0 {
+            info!(
+                "Checkpoint processor {} resuming from checkpoint {}",
+                name, last_processed_checkpoint
+            );
+        }
+        loop {
+            // NOTE: only checkpoints committed by the indexer are processed, so that processors
+            // can read the indexer tables of the checkpoints they process.
+            let latest_checkpoint = self.store.get_latest_checkpoint_sequence_number().await?;
+            if last_processed_checkpoint >= latest_checkpoint {
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                continue;
+            }
+            let next_checkpoint = last_processed_checkpoint + 1;
+            let checkpoint_data = self.checkpoint_cache.get(next_checkpoint as u64).await?;
+            self.processor.process_checkpoint(&checkpoint_data).await?;
+
+            let mut watermark_commit_res = self
+                .store
+                .persist_processor_watermark(name, next_checkpoint)
+                .await;
+            while let Err(e) = watermark_commit_res {
+                warn!(
+                    "Checkpoint processor {} watermark commit failed with error: {:?}, retrying after {:?} milli-secs...",
+                    name, e, DB_COMMIT_RETRY_INTERVAL_IN_MILLIS
+                );
+                tokio::time::sleep(std::time::Duration::from_millis(
+                    DB_COMMIT_RETRY_INTERVAL_IN_MILLIS,
+                ))
+                .await;
+                watermark_commit_res = self
+                    .store
+                    .persist_processor_watermark(name, next_checkpoint)
+                    .await;
+            }
+            self.metrics
+                .latest_processor_checkpoint_sequence_number
+                .with_label_values(&[name])
+                .set(next_checkpoint);
+            last_processed_checkpoint = next_checkpoint;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use jsonrpsee::http_client::HttpClientBuilder;
+    use prometheus::Registry;
+
+    use crate::errors::IndexerError;
+    use crate::metrics::IndexerMetrics;
+    use crate::store::sqlite_store_tests::{test_checkpoint, test_store};
+    use crate::store::{CheckpointData, IndexerStore, SqliteIndexerStore};
+
+    use super::{CheckpointDataCache, CheckpointProcessor, CheckpointProcessorRunner};
+
+    const PROCESSOR_NAME: &str = "test_processor";
+
+    /// Records the checkpoints it is given, and fails once on `fail_at`.
+    struct TestProcessor {
+        processed: Mutex<Vec<u64>>,
+        fail_at: Option<u64>,
+        failed: AtomicBool,
+    }
+
+    impl TestProcessor {
+        fn new(fail_at: Option<u64>) -> Arc<Self> {
+            Arc::new(Self {
+                processed: Mutex::new(vec![]),
+                fail_at,
+                failed: AtomicBool::new(false),
+            })
+        }
+
+        fn processed(&self) -> Vec<u64> {
+            self.processed.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl CheckpointProcessor for TestProcessor {
+        fn name(&self) -> &str {
+            PROCESSOR_NAME
+        }
+
+        async fn process_checkpoint(&self, data: &CheckpointData) -> Result<(), IndexerError> {
+            let seq = data.checkpoint.sequence_number;
+            self.processed.lock().unwrap().push(seq);
+            if Some(seq) == self.fail_at && !self.failed.swap(true, Ordering::SeqCst) {
+                return Err(IndexerError::UncategorizedError(anyhow::anyhow!(
+                    "Failed processing checkpoint {seq}"
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    /// Commits checkpoints 0 to `last` and returns a runner of `processor`, whose checkpoint
+    /// cache holds all of them so that nothing is downloaded.
+    async fn setup(
+        store: &SqliteIndexerStore,
+        last: i64,
+        processor: Arc<TestProcessor>,
+    ) -> CheckpointProcessorRunner<SqliteIndexerStore> {
+        let metrics = IndexerMetrics::new(&Registry::default());
+        let http_client = HttpClientBuilder::default()
+            .build("http://127.0.0.1:1")
+            .unwrap();
+        let checkpoint_cache = CheckpointDataCache::new(http_client, metrics.clone());
+        for seq in 0..=last {
+            let checkpoint = test_checkpoint(seq, 0, &[]);
+            store
+                .persist_checkpoint_transactions(&checkpoint, &[])
+                .await
+                .unwrap();
+            checkpoint_cache.insert(CheckpointData {
+                checkpoint: checkpoint.into_rpc(None).unwrap(),
+                transactions: vec![],
+                changed_objects: vec![],
+            });
+        }
+        CheckpointProcessorRunner::new(store.clone(), checkpoint_cache, metrics, processor)
+    }
+
+    /// Runs the processor until its watermark reaches `target`.
+    async fn run_until_watermark(
+        store: &SqliteIndexerStore,
+        runner: CheckpointProcessorRunner<SqliteIndexerStore>,
+        target: i64,
+    ) {
+        let handle = tokio::spawn(async move { runner.start().await });
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while store.get_processor_watermark(PROCESSOR_NAME).await.unwrap() < target {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Processor should reach the target watermark");
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_resume_from_watermark() {
+        let (_dir, store) = test_store();
+        store
+            .persist_processor_watermark(PROCESSOR_NAME, 1)
+            .await
+            .unwrap();
+        let processor = TestProcessor::new(None);
+        let runner = setup(&store, 4, processor.clone()).await;
+
+        run_until_watermark(&store, runner, 4).await;
+        assert_eq!(processor.processed(), vec![2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_at_least_once_delivery() {
+        let (_dir, store) = test_store();
+        let processor = TestProcessor::new(Some(2));
+        let runner = setup(&store, 4, processor.clone()).await;
+
+        // The failed checkpoint stops the processor without advancing its watermark.
+        assert!(runner.start().await.is_err());
+        assert_eq!(processor.processed(), vec![0, 1, 2]);
+        assert_eq!(
+            store.get_processor_watermark(PROCESSOR_NAME).await.unwrap(),
+            1
+        );
+        assert_eq!(
+            runner
+                .metrics
+                .total_checkpoint_processor_errors
+                .with_label_values(&[PROCESSOR_NAME])
+                .get(),
+            1
+        );
+
+        // A restarted processor is given the failed checkpoint again.
+        run_until_watermark(&store, runner, 4).await;
+        assert_eq!(processor.processed(), vec![0, 1, 2, 2, 3, 4]);
+    }
+}
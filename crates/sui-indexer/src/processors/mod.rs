@@ -3,5 +3,7 @@
 
 pub mod address_processor;
 pub mod checkpoint_metrics_processor;
+pub mod checkpoint_processor;
 pub mod object_processor;
 pub mod processor_orchestrator;
+pub mod pruning_processor;
@@ -9,12 +9,16 @@ use tracing::{error, info, warn};
 
 use crate::processors::address_processor::AddressProcessor;
 use crate::processors::checkpoint_metrics_processor::CheckpointMetricsProcessor;
+use crate::processors::checkpoint_processor::CheckpointProcessorRunner;
 use crate::processors::object_processor::ObjectProcessor;
+use crate::processors::pruning_processor::PruningProcessor;
 use crate::store::IndexerStore;
 
 pub struct ProcessorOrchestrator<S> {
     store: S,
     prometheus_registry: Registry,
+    checkpoint_processors: Vec<CheckpointProcessorRunner<S>>,
+    pruning_processor: Option<PruningProcessor<S>>,
 }
 
 impl<S> ProcessorOrchestrator<S>
@@ -25,9 +29,19 @@ where
         Self {
             store,
             prometheus_registry: prometheus_registry.clone(),
+            checkpoint_processors: vec![],
+            pruning_processor: None,
         }
     }
 
+    pub fn add_checkpoint_processor(&mut self, checkpoint_processor: CheckpointProcessorRunner<S>) {
+        self.checkpoint_processors.push(checkpoint_processor);
+    }
+
+    pub fn set_pruning_processor(&mut self, pruning_processor: PruningProcessor<S>) {
+        self.pruning_processor = Some(pruning_processor);
+    }
+
     pub async fn run_forever(&mut self) {
         info!("Processor orchestrator started...");
         let object_processor = ObjectProcessor::new(self.store.clone(), &self.prometheus_registry);
@@ -96,7 +110,62 @@ where
                 );
             }
         });
-        try_join_all(vec![obj_handle, addr_handle, cp_metrics_handle])
+        let mut handles = vec![obj_handle, addr_handle, cp_metrics_handle];
+        for checkpoint_processor in std::mem::take(&mut self.checkpoint_processors) {
+            // NOTE: checkpoint processors are retried forever instead of giving up after the
+            // default 15 minutes, as a stopped processor would never catch up again.
+            let backoff = ExponentialBackoff {
+                max_elapsed_time: None,
+                ..ExponentialBackoff::default()
+            };
+            handles.push(tokio::task::spawn(async move {
+                let processor_result = retry(backoff, || async {
+                    let processor_exec_res = checkpoint_processor.start().await;
+                    if let Err(e) = &processor_exec_res {
+                        warn!(
+                            "Indexer checkpoint processor {} failed with error: {:?}, retrying...",
+                            checkpoint_processor.processor_name(),
+                            e
+                        );
+                    }
+                    Ok(processor_exec_res?)
+                })
+                .await;
+                if let Err(e) = processor_result {
+                    error!(
+                        "Indexer checkpoint processor {} failed after retries with error {:?}",
+                        checkpoint_processor.processor_name(),
+                        e
+                    );
+                }
+            }));
+        }
+        if let Some(pruning_processor) = self.pruning_processor.take() {
+            let backoff = ExponentialBackoff {
+                max_elapsed_time: None,
+                ..ExponentialBackoff::default()
+            };
+            handles.push(tokio::task::spawn(async move {
+                let pruning_result = retry(backoff, || async {
+                    let pruning_exec_res = pruning_processor.start().await;
+                    if let Err(e) = &pruning_exec_res {
+                        warn!(
+                            "Indexer pruning processor failed with error: {:?}, retrying...",
+                            e
+                        );
+                    }
+                    Ok(pruning_exec_res?)
+                })
+                .await;
+                if let Err(e) = pruning_result {
+                    error!(
+                        "Indexer pruning processor failed after retries with error {:?}",
+                        e
+                    );
+                }
+            }));
+        }
+        try_join_all(handles)
             .await
             .expect("Processor orchestrator should not run into errors.");
     }
@@ -0,0 +1,265 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use tracing::info;
+
+use crate::errors::IndexerError;
+use crate::metrics::IndexerMetrics;
+use crate::store::{IndexerStore, PrunableTable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Keep the rows of the last N epochs, including the current epoch. For `objects_history`
+    /// the latest version of each object before them is kept as well.
+    Epochs(u64),
+    /// Keep only the latest version of each object, only for `objects_history`.
+    LatestVersions,
+}
+
+/// Retention of a table, parsed from `<table>=<epochs to keep>` or `objects-history=latest`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableRetention {
+    pub table: PrunableTable,
+    pub policy: RetentionPolicy,
+}
+
+impl FromStr for TableRetention {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (table, policy) = s.split_once('=').ok_or_else(|| {
+            format!("Invalid retention {s}, expected <table>=<epochs> or objects-history=latest")
+        })?;
+        let table = <PrunableTable as ValueEnum>::from_str(table, true)?;
+        let policy = match policy {
+            "latest" if table == PrunableTable::ObjectsHistory => RetentionPolicy::LatestVersions,
+            "latest" => {
+                return Err(format!(
+                    "Only objects-history can retain the latest versions, not {}",
+                    table.table_name()
+                ))
+            }
+            epochs => match epochs.parse::<u64>() {
+                Ok(epochs) if epochs > 0 => RetentionPolicy::Epochs(epochs),
+                _ => {
+                    return Err(format!(
+                        "Invalid retention {epochs} of {}, expected a positive number of epochs",
+                        table.table_name()
+                    ))
+                }
+            },
+        };
+        Ok(Self { table, policy })
+    }
+}
+
+/// Prunes the tables with a retention policy in the background, so that the indexer only keeps
+/// recent history. Rows are deleted in batches of `batch_size`, one DB transaction per batch,
+/// so that pruning does not block the indexer writing new checkpoints; expired epoch partitions
+/// are dropped instead where the table is partitioned, except for `objects_history`.
+pub struct PruningProcessor<S> {
+    store: S,
+    metrics: IndexerMetrics,
+    retention: Vec<TableRetention>,
+    pruning_interval: Duration,
+    batch_size: usize,
+}
+
+impl<S> PruningProcessor<S>
+where
+    S: IndexerStore + Sync + Send + 'static,
+{
+    pub fn new(
+        store: S,
+        metrics: IndexerMetrics,
+        retention: Vec<TableRetention>,
+        pruning_interval: Duration,
+        batch_size: NonZeroUsize,
+    ) -> Self {
+        Self {
+            store,
+            metrics,
+            retention,
+            pruning_interval,
+            batch_size: batch_size.get(),
+        }
+    }
+
+    pub async fn start(&self) -> Result<(), IndexerError> {
+        info!(
+            "Indexer pruning processor started with retention {:?}...",
+            self.retention
+        );
+        loop {
+            for retention in &self.retention {
+                match retention.policy {
+                    RetentionPolicy::Epochs(epochs_to_keep) => {
+                        self.prune_epochs(retention.table, epochs_to_keep).await?
+                    }
+                    RetentionPolicy::LatestVersions => self.prune_object_versions().await?,
+                }
+            }
+            tokio::time::sleep(self.pruning_interval).await;
+        }
+    }
+
+    async fn prune_epochs(
+        &self,
+        table: PrunableTable,
+        epochs_to_keep: u64,
+    ) -> Result<(), IndexerError> {
+        let cutoff = match self.store.get_prune_cutoff(epochs_to_keep).await? {
+            Some(cutoff) => cutoff,
+            // fewer epochs than retained so far
+            None => return Ok(()),
+        };
+        let table_name = table.table_name();
+        let dropped_partitions = self.store.drop_pruned_partitions(table, &cutoff).await?;
+        if !dropped_partitions.is_empty() {
+            info!(
+                "Dropped expired partitions {:?} of table {}",
+                dropped_partitions, table_name
+            );
+            self.metrics
+                .total_partitions_dropped
+                .with_label_values(&[table_name])
+                .inc_by(dropped_partitions.len() as u64);
+        }
+
+        let mut total_pruned = 0;
+        loop {
+            let prune_guard = self
+                .metrics
+                .prune_batch_latency
+                .with_label_values(&[table_name])
+                .start_timer();
+            let pruned = self
+                .store
+                .prune_table(table, &cutoff, self.batch_size)
+                .await?;
+            prune_guard.stop_and_record();
+            self.metrics
+                .total_rows_pruned
+                .with_label_values(&[table_name])
+                .inc_by(pruned as u64);
+            total_pruned += pruned;
+            if pruned < self.batch_size {
+                break;
+            }
+        }
+        if total_pruned > 0 {
+            info!(
+                "Pruned {} rows before epoch {} from table {}",
+                total_pruned, cutoff.epoch, table_name
+            );
+        }
+        self.metrics
+            .first_retained_epoch
+            .with_label_values(&[table_name])
+            .set(cutoff.epoch);
+        Ok(())
+    }
+
+    async fn prune_object_versions(&self) -> Result<(), IndexerError> {
+        let table_name = PrunableTable::ObjectsHistory.table_name();
+        let mut total_pruned = 0;
+        loop {
+            let prune_guard = self
+                .metrics
+                .prune_batch_latency
+                .with_label_values(&[table_name])
+                .start_timer();
+            let pruned = self
+                .store
+                .prune_objects_history_versions(self.batch_size)
+                .await?;
+            prune_guard.stop_and_record();
+            self.metrics
+                .total_rows_pruned
+                .with_label_values(&[table_name])
+                .inc_by(pruned as u64);
+            total_pruned += pruned;
+            if pruned < self.batch_size {
+                break;
+            }
+        }
+        if total_pruned > 0 {
+            info!(
+                "Pruned {} old object versions from table {}",
+                total_pruned, table_name
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::store::PrunableTable;
+
+    use super::{RetentionPolicy, TableRetention};
+
+    #[test]
+    fn test_parse_table_retention() {
+        let cases = [
+            (
+                "transactions=10",
+                PrunableTable::Transactions,
+                RetentionPolicy::Epochs(10),
+            ),
+            (
+                "move-calls=1",
+                PrunableTable::MoveCalls,
+                RetentionPolicy::Epochs(1),
+            ),
+            (
+                "Events=2",
+                PrunableTable::Events,
+                RetentionPolicy::Epochs(2),
+            ),
+            (
+                "objects-history=3",
+                PrunableTable::ObjectsHistory,
+                RetentionPolicy::Epochs(3),
+            ),
+            (
+                "objects-history=latest",
+                PrunableTable::ObjectsHistory,
+                RetentionPolicy::LatestVersions,
+            ),
+        ];
+        for (retention, table, policy) in cases {
+            assert_eq!(
+                TableRetention::from_str(retention).unwrap(),
+                TableRetention { table, policy },
+                "{retention}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_table_retention() {
+        for retention in [
+            "transactions",
+            "transactions=",
+            "transactions=0",
+            "transactions=-1",
+            "transactions=ten",
+            "transactions=latest",
+            "objects=10",
+            "=10",
+        ] {
+            assert!(
+                TableRetention::from_str(retention).is_err(),
+                "{retention} should be rejected"
+            );
+        }
+    }
+}
@@ -229,6 +229,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    processor_watermarks (processor_name) {
+        processor_name -> Text,
+        checkpoint_sequence_number -> Int8,
+    }
+}
+
 diesel::table! {
     recipients (id) {
         id -> Int8,
@@ -361,6 +368,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     objects,
     objects_history,
     packages,
+    processor_watermarks,
     recipients,
     system_states,
     transactions,
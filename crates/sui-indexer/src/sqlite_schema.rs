@@ -0,0 +1,185 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tables of the SQLite schema in `migrations_sqlite` whose columns differ from the Postgres
+//! schema: SQLite has no array, enum or composite column types, so arrays are stored as JSON
+//! text, enums as their snake_case names and lists of bytes as BCS blobs.
+//! All other tables share their definition with the Postgres schema in `crate::schema`.
+
+diesel::table! {
+    at_risk_validators (epoch, address) {
+        epoch -> BigInt,
+        address -> Text,
+        epoch_count -> BigInt,
+        reported_by -> Text,
+    }
+}
+
+diesel::table! {
+    checkpoints (sequence_number) {
+        sequence_number -> BigInt,
+        checkpoint_digest -> Text,
+        epoch -> BigInt,
+        transactions -> Text,
+        previous_checkpoint_digest -> Nullable<Text>,
+        end_of_epoch -> Bool,
+        total_gas_cost -> BigInt,
+        total_computation_cost -> BigInt,
+        total_storage_cost -> BigInt,
+        total_storage_rebate -> BigInt,
+        total_transaction_blocks -> BigInt,
+        total_transactions -> BigInt,
+        total_successful_transaction_blocks -> BigInt,
+        total_successful_transactions -> BigInt,
+        network_total_transactions -> BigInt,
+        timestamp_ms -> BigInt,
+        validator_signature -> Text,
+    }
+}
+
+diesel::table! {
+    epochs (epoch) {
+        epoch -> BigInt,
+        first_checkpoint_id -> BigInt,
+        last_checkpoint_id -> Nullable<BigInt>,
+        epoch_start_timestamp -> BigInt,
+        epoch_end_timestamp -> Nullable<BigInt>,
+        epoch_total_transactions -> BigInt,
+        next_epoch_version -> Nullable<BigInt>,
+        next_epoch_committee -> Binary,
+        next_epoch_committee_stake -> Text,
+        epoch_commitments -> Binary,
+        protocol_version -> Nullable<BigInt>,
+        reference_gas_price -> Nullable<BigInt>,
+        total_stake -> Nullable<BigInt>,
+        storage_fund_reinvestment -> Nullable<BigInt>,
+        storage_charge -> Nullable<BigInt>,
+        storage_rebate -> Nullable<BigInt>,
+        storage_fund_balance -> Nullable<BigInt>,
+        stake_subsidy_amount -> Nullable<BigInt>,
+        total_gas_fees -> Nullable<BigInt>,
+        total_stake_rewards_distributed -> Nullable<BigInt>,
+        leftover_storage_fund_inflow -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    objects (object_id) {
+        epoch -> BigInt,
+        checkpoint -> BigInt,
+        object_id -> Text,
+        version -> BigInt,
+        object_digest -> Text,
+        owner_type -> Text,
+        owner_address -> Nullable<Text>,
+        initial_shared_version -> Nullable<BigInt>,
+        previous_transaction -> Text,
+        object_type -> Text,
+        object_status -> Text,
+        has_public_transfer -> Bool,
+        storage_rebate -> BigInt,
+        bcs -> Binary,
+    }
+}
+
+diesel::table! {
+    objects_history (object_id, version, checkpoint) {
+        epoch -> BigInt,
+        checkpoint -> BigInt,
+        object_id -> Text,
+        version -> BigInt,
+        object_digest -> Text,
+        owner_type -> Text,
+        owner_address -> Nullable<Text>,
+        old_owner_type -> Nullable<Text>,
+        old_owner_address -> Nullable<Text>,
+        initial_shared_version -> Nullable<BigInt>,
+        previous_transaction -> Text,
+        object_type -> Text,
+        object_status -> Text,
+        has_public_transfer -> Bool,
+        storage_rebate -> BigInt,
+        bcs -> Binary,
+    }
+}
+
+diesel::table! {
+    packages (package_id, version) {
+        package_id -> Text,
+        version -> BigInt,
+        author -> Text,
+        data -> Binary,
+    }
+}
+
+diesel::table! {
+    system_states (epoch) {
+        epoch -> BigInt,
+        protocol_version -> BigInt,
+        system_state_version -> BigInt,
+        storage_fund -> BigInt,
+        reference_gas_price -> BigInt,
+        safe_mode -> Bool,
+        epoch_start_timestamp_ms -> BigInt,
+        epoch_duration_ms -> BigInt,
+        stake_subsidy_start_epoch -> BigInt,
+        stake_subsidy_epoch_counter -> BigInt,
+        stake_subsidy_balance -> BigInt,
+        stake_subsidy_current_epoch_amount -> BigInt,
+        total_stake -> BigInt,
+        pending_active_validators_id -> Text,
+        pending_active_validators_size -> BigInt,
+        pending_removals -> Text,
+        staking_pool_mappings_id -> Text,
+        staking_pool_mappings_size -> BigInt,
+        inactive_pools_id -> Text,
+        inactive_pools_size -> BigInt,
+        validator_candidates_id -> Text,
+        validator_candidates_size -> BigInt,
+    }
+}
+
+diesel::table! {
+    transactions (id) {
+        id -> BigInt,
+        transaction_digest -> Text,
+        sender -> Text,
+        recipients -> Text,
+        checkpoint_sequence_number -> Nullable<BigInt>,
+        timestamp_ms -> Nullable<BigInt>,
+        transaction_kind -> Text,
+        transaction_count -> BigInt,
+        execution_success -> Bool,
+        created -> Text,
+        mutated -> Text,
+        deleted -> Text,
+        unwrapped -> Text,
+        wrapped -> Text,
+        move_calls -> Text,
+        gas_object_id -> Text,
+        gas_object_sequence -> BigInt,
+        gas_object_digest -> Text,
+        gas_budget -> BigInt,
+        total_gas_cost -> BigInt,
+        computation_cost -> BigInt,
+        storage_cost -> BigInt,
+        storage_rebate -> BigInt,
+        non_refundable_storage_fee -> BigInt,
+        gas_price -> BigInt,
+        raw_transaction -> Binary,
+        transaction_content -> Text,
+        transaction_effects_content -> Text,
+        confirmed_local_execution -> Nullable<Bool>,
+    }
+}
+
+diesel::allow_tables_to_appear_in_same_query!(
+    at_risk_validators,
+    checkpoints,
+    epochs,
+    objects,
+    objects_history,
+    packages,
+    system_states,
+    transactions,
+);
@@ -0,0 +1,1120 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pieces of [`PgIndexerStore`](super::PgIndexerStore) and
+//! [`SqliteIndexerStore`](super::SqliteIndexerStore) which do not depend on the database: the
+//! async [`IndexerStore`](super::IndexerStore) methods, conversions of rows to RPC types, and
+//! the SQL both databases accept.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use diesel::sql_types::VarChar;
+use diesel::QueryableByName;
+use fastcrypto::hash::Digest;
+use fastcrypto::traits::ToFromBytes;
+use move_core_types::identifier::Identifier;
+
+use sui_json_rpc_types::{
+    EpochInfo, EventPage, MoveCallMetrics, MoveFunctionName, SuiEvent, SuiTransactionBlock,
+    SuiTransactionBlockEffects, SuiTransactionBlockEvents, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
+};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::committee::ProtocolVersion;
+use sui_types::crypto::AuthorityPublicKeyBytes;
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::{
+    CheckpointCommitment, ECMHLiveObjectSetDigest, EndOfEpochData,
+};
+
+use crate::errors::IndexerError;
+use crate::models::checkpoint_metrics::CheckpointMetrics;
+use crate::models::checkpoints::Checkpoint;
+use crate::models::epoch::DBEpochInfo;
+use crate::models::network_metrics::DBMoveCallMetrics;
+use crate::models::system_state::DBValidatorSummary;
+use crate::models::transactions::Transaction;
+
+pub(crate) const MAX_EVENT_PAGE_SIZE: usize = 1000;
+
+#[derive(QueryableByName, Debug, Clone)]
+pub(crate) struct TempDigestTable {
+    #[diesel(sql_type = VarChar)]
+    pub digest_name: String,
+}
+
+/// Number of events to query for a page of at most `limit` events: one more than the page, to
+/// tell if there is a next page.
+pub(crate) fn event_query_limit(limit: Option<usize>) -> Result<usize, IndexerError> {
+    let page_limit = limit.unwrap_or(MAX_EVENT_PAGE_SIZE);
+    if page_limit > MAX_EVENT_PAGE_SIZE {
+        Err(IndexerError::InvalidArgumentError(format!(
+            "Limit {} exceeds the maximum page size {}",
+            page_limit, MAX_EVENT_PAGE_SIZE
+        )))?;
+    }
+    // fetch one more item to tell if there is next page
+    Ok(page_limit + 1)
+}
+
+/// Page of the events queried with a limit of `query_limit`, see [`event_query_limit`].
+pub(crate) fn into_event_page(mut events: Vec<SuiEvent>, query_limit: usize) -> EventPage {
+    // reset to original limit for checking and truncating
+    let page_limit = query_limit - 1;
+    let has_next_page = events.len() > page_limit;
+    events.truncate(page_limit);
+    let next_cursor = events.last().map(|e| e.id.clone());
+    EventPage {
+        data: events,
+        next_cursor,
+        has_next_page,
+    }
+}
+
+/// Response for the indexed transaction `tx`, where `get_events` reads the events of the
+/// transaction when they are requested.
+pub(crate) fn compose_transaction_block_response(
+    tx: Transaction,
+    options: Option<&SuiTransactionBlockResponseOptions>,
+    get_events: impl FnOnce(TransactionDigest) -> Result<EventPage, IndexerError>,
+) -> Result<SuiTransactionBlockResponse, IndexerError> {
+    let transaction: SuiTransactionBlock =
+        serde_json::from_str(&tx.transaction_content).map_err(|err| {
+            IndexerError::InsertableParsingError(format!(
+                "Failed converting transaction JSON {:?} to SuiTransactionBlock with error: {:?}",
+                tx.transaction_content, err
+            ))
+        })?;
+    let effects: SuiTransactionBlockEffects =
+        serde_json::from_str(&tx.transaction_effects_content).map_err(|err| {
+            IndexerError::InsertableParsingError(format!(
+                "Failed converting transaction effect JSON {:?} to SuiTransactionBlockEffects with error: {:?}",
+                tx.transaction_effects_content, err
+            ))
+        })?;
+
+    let tx_digest: TransactionDigest = tx.transaction_digest.parse().map_err(|e| {
+        IndexerError::InsertableParsingError(format!(
+            "Failed to parse transaction digest {} : {:?}",
+            tx.transaction_digest, e
+        ))
+    })?;
+
+    let (mut tx_opt, mut effects_opt, mut raw_tx) = (None, None, vec![]);
+    let (object_changes, balance_changes, mut events) = (None, None, None);
+    if let Some(options) = options {
+        //TODO Object changes and balance changes are not supported atm due to async issues
+        if options.show_balance_changes {
+            return Err(IndexerError::NotSupportedError(
+                "Object cache is not supported".to_string(),
+            ));
+            // let object_cache = ObjectProviderCache::new(self.clone());
+            // balance_changes =
+            //     Some(get_balance_changes_from_effect(&object_cache, &effects).await?);
+        }
+        if options.show_object_changes {
+            return Err(IndexerError::NotSupportedError(
+                "Object cache is not supported".to_string(),
+            ));
+            // let object_cache = ObjectProviderCache::new(self.clone());
+            // object_changes = Some(
+            //     get_object_changes(
+            //         &object_cache,
+            //         sender,
+            //         &effects.modified_at_versions(),
+            //         effects.all_changed_objects(),
+            //         effects.all_deleted_objects(),
+            //     )
+            //     .await?,
+            // );
+        }
+        if options.show_events {
+            let event_page = get_events(tx_digest)?;
+            events = Some(SuiTransactionBlockEvents {
+                data: event_page.data,
+            });
+        }
+        if options.show_input {
+            tx_opt = Some(transaction);
+        }
+        if options.show_raw_input {
+            raw_tx = tx.raw_transaction;
+        }
+        if options.show_effects {
+            effects_opt = Some(effects);
+        }
+    }
+
+    Ok(SuiTransactionBlockResponse {
+        digest: tx_digest,
+        transaction: tx_opt,
+        raw_transaction: raw_tx,
+        effects: effects_opt,
+        confirmed_local_execution: tx.confirmed_local_execution,
+        timestamp_ms: tx.timestamp_ms.map(|t| t as u64),
+        checkpoint: tx.checkpoint_sequence_number.map(|c| c as u64),
+        events,
+        object_changes,
+        balance_changes,
+        errors: vec![],
+    })
+}
+
+/// End of epoch data of a checkpoint closing an epoch, from the columns of the epoch.
+pub(crate) fn end_of_epoch_data(
+    next_epoch_version: Option<i64>,
+    next_epoch_committee: &[Option<Vec<u8>>],
+    next_epoch_committee_stake: &[Option<i64>],
+    epoch_commitments: &[Option<Vec<u8>>],
+) -> Result<Option<EndOfEpochData>, IndexerError> {
+    let next_epoch_committee = next_epoch_committee
+        .iter()
+        .flatten()
+        .zip(next_epoch_committee_stake.iter().flatten())
+        .map(|(name, vote)| {
+            AuthorityPublicKeyBytes::from_bytes(name.as_slice()).map(|b| (b, (*vote) as u64))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let epoch_commitments = epoch_commitments
+        .iter()
+        .flatten()
+        .flat_map(|v| {
+            if let Ok(v) = v.clone().try_into() {
+                return Some(CheckpointCommitment::ECMHLiveObjectSetDigest(
+                    ECMHLiveObjectSetDigest::from(Digest::new(v)),
+                ));
+            }
+            None
+        })
+        .collect::<Vec<_>>();
+
+    Ok(
+        next_epoch_version.map(|next_epoch_protocol_version| EndOfEpochData {
+            next_epoch_committee,
+            next_epoch_protocol_version: ProtocolVersion::from(next_epoch_protocol_version as u64),
+            epoch_commitments,
+        }),
+    )
+}
+
+/// Joins the epochs with the validators of each epoch.
+pub(crate) fn into_epoch_infos(
+    epoch_info: Vec<DBEpochInfo>,
+    validators: Vec<DBValidatorSummary>,
+) -> Result<Vec<EpochInfo>, IndexerError> {
+    let mut validators =
+        validators
+            .into_iter()
+            .fold(BTreeMap::<i64, Vec<_>>::new(), |mut acc, v| {
+                acc.entry(v.epoch).or_default().push(v);
+                acc
+            });
+
+    epoch_info
+        .into_iter()
+        .map(|info| {
+            let epoch = info.epoch;
+            info.to_epoch_info(validators.remove(&epoch).unwrap_or_default())
+        })
+        .collect()
+}
+
+pub(crate) fn into_move_call_metrics(metrics: Vec<DBMoveCallMetrics>) -> MoveCallMetrics {
+    let mut d3 = vec![];
+    let mut d7 = vec![];
+    let mut d30 = vec![];
+    for m in metrics {
+        let package = ObjectID::from_str(&m.move_package);
+        let module = Identifier::from_str(m.move_module.as_str());
+        let function = Identifier::from_str(m.move_function.as_str());
+        if let (Ok(package), Ok(module), Ok(function)) = (package, module, function) {
+            let fun = MoveFunctionName {
+                package,
+                module,
+                function,
+            };
+            match m.day {
+                3 => d3.push((fun, m.count as usize)),
+                7 => d7.push((fun, m.count as usize)),
+                30 => d30.push((fun, m.count as usize)),
+                _ => {}
+            }
+        }
+    }
+    MoveCallMetrics {
+        rank_3_days: d3,
+        rank_7_days: d7,
+        rank_30_days: d30,
+    }
+}
+
+/// Metrics at `current_checkpoint`, rolling `last_checkpoint_metrics` over the checkpoints `cps`
+/// since, the last of which is of `epoch`.
+pub(crate) fn rolling_checkpoint_metrics(
+    current_checkpoint: i64,
+    epoch: i64,
+    last_checkpoint_metrics: &CheckpointMetrics,
+    cps: &[Checkpoint],
+    real_time_tps: f64,
+    peak_tps_30d: f64,
+) -> CheckpointMetrics {
+    let (
+        rolling_tx_delta,
+        rolling_tx_blocks_delta,
+        rolling_successful_tx_delta,
+        rolling_successful_tx_blocks_delta,
+    ) = cps.iter().fold(
+        (0, 0, 0, 0),
+        |(tx_delta, tx_blocks_delta, successful_tx_delta, successful_tx_blocks_delta), cp| {
+            (
+                tx_delta + cp.total_transactions,
+                tx_blocks_delta + cp.total_transaction_blocks,
+                successful_tx_delta + cp.total_successful_transactions,
+                successful_tx_blocks_delta + cp.total_successful_transaction_blocks,
+            )
+        },
+    );
+
+    CheckpointMetrics {
+        checkpoint: current_checkpoint,
+        epoch,
+        real_time_tps,
+        peak_tps_30d,
+        rolling_total_transactions: last_checkpoint_metrics.rolling_total_transactions
+            + rolling_tx_delta,
+        rolling_total_transaction_blocks: last_checkpoint_metrics.rolling_total_transaction_blocks
+            + rolling_tx_blocks_delta,
+        rolling_total_successful_transactions: last_checkpoint_metrics
+            .rolling_total_successful_transactions
+            + rolling_successful_tx_delta,
+        rolling_total_successful_transaction_blocks: last_checkpoint_metrics
+            .rolling_total_successful_transaction_blocks
+            + rolling_successful_tx_blocks_delta,
+    }
+}
+
+fn id_cursor_clause(start_sequence: Option<i64>, is_descending: bool) -> String {
+    if let Some(start_sequence) = start_sequence {
+        if is_descending {
+            format!("AND id < {}", start_sequence)
+        } else {
+            format!("AND id > {}", start_sequence)
+        }
+    } else {
+        "".to_string()
+    }
+}
+
+fn order(is_descending: bool) -> &'static str {
+    if is_descending {
+        "DESC"
+    } else {
+        "ASC"
+    }
+}
+
+/// Query of the digests of the transactions with `object_id` in `table`, either `input_objects`
+/// or `changed_objects`, optionally at `version`.
+pub(crate) fn transaction_digests_by_object_sql(
+    table: &str,
+    object_id: ObjectID,
+    version: Option<i64>,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    format!(
+        "SELECT transaction_digest as digest_name
+         FROM {}
+         WHERE object_id = '{}' {} {}
+         ORDER BY id {} LIMIT {}",
+        table,
+        object_id,
+        id_cursor_clause(start_sequence, is_descending),
+        if let Some(version) = version {
+            format!("AND object_version = {}", version)
+        } else {
+            "".to_string()
+        },
+        order(is_descending),
+        limit
+    )
+}
+
+pub(crate) fn transaction_digests_by_move_call_sql(
+    package_name: ObjectID,
+    module_name: Option<&Identifier>,
+    function_name: Option<&Identifier>,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    // note: module_name and function_name are user-controlled, which is scary.
+    // however, but valid Move identifiers can only contain 0-9, a-z, A-Z, and _,
+    // so it is safe to use them as-is in the query below
+    format!(
+        "SELECT transaction_digest as digest_name
+         FROM move_calls
+         WHERE move_package = '{}' {} {} {}
+         ORDER BY id {} LIMIT {}",
+        package_name,
+        id_cursor_clause(start_sequence, is_descending),
+        if let Some(module_name) = module_name {
+            format!("AND move_module = '{}'", module_name)
+        } else {
+            "".to_string()
+        },
+        if let Some(function_name) = function_name {
+            format!("AND move_function = '{}'", function_name)
+        } else {
+            "".to_string()
+        },
+        order(is_descending),
+        limit
+    )
+}
+
+pub(crate) fn transaction_digests_by_recipient_sql(
+    from: Option<SuiAddress>,
+    to: SuiAddress,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    format!(
+        "SELECT transaction_digest as digest_name FROM recipients
+         WHERE recipient = '{}' {} {}
+         ORDER BY id {} LIMIT {}",
+        to,
+        id_cursor_clause(start_sequence, is_descending),
+        if let Some(from) = from {
+            format!("AND sender = '{}'", from)
+        } else {
+            "".to_string()
+        },
+        order(is_descending),
+        limit
+    )
+}
+
+pub(crate) fn transaction_digests_by_address_sql(
+    address: SuiAddress,
+    start_sequence: Option<i64>,
+    limit: usize,
+    is_descending: bool,
+) -> String {
+    format!(
+        "SELECT transaction_digest as digest_name FROM (
+            SELECT transaction_digest, max(id) AS max_id
+            FROM recipients
+            WHERE (recipient = '{}' OR sender = '{}')
+            {} GROUP BY transaction_digest
+            ORDER BY max_id {} LIMIT {}
+        ) AS t",
+        address,
+        address,
+        id_cursor_clause(start_sequence, is_descending),
+        order(is_descending),
+        limit
+    )
+}
+
+/// Query of the address stats at the last checkpoint of every epoch.
+pub(crate) fn epoch_address_stats_sql(is_descending: bool) -> String {
+    format!(
+        "WITH ranked_rows AS (
+            SELECT
+              checkpoint, epoch, timestamp_ms, cumulative_addresses, cumulative_active_addresses, daily_active_addresses,
+              row_number() OVER(PARTITION BY epoch ORDER BY checkpoint DESC) as row_num
+            FROM
+              address_stats
+          )
+          SELECT
+            checkpoint, epoch, timestamp_ms, cumulative_addresses, cumulative_active_addresses, daily_active_addresses
+          FROM ranked_rows
+          WHERE row_num = 1 ORDER BY epoch {}",
+        order(is_descending),
+    )
+}
+
+/// Implements [`IndexerStore`](super::IndexerStore) for `$store` by running its blocking method
+/// of the same name on the blocking thread pool. `$store` reads modules with `$module_resolver`.
+macro_rules! impl_indexer_store {
+    ($store:ty, $module_resolver:ty) => {
+        impl $store {
+            async fn spawn_blocking<F, R>(&self, f: F) -> Result<R, IndexerError>
+            where
+                F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
+                R: Send + 'static,
+            {
+                let this = self.clone();
+                tokio::task::spawn_blocking(move || f(this))
+                    .await
+                    .map_err(Into::into)
+                    .and_then(std::convert::identity)
+            }
+        }
+
+        #[async_trait]
+        impl IndexerStore for $store {
+            type ModuleCache = SyncModuleCache<$module_resolver>;
+
+            async fn get_latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError> {
+                self.spawn_blocking(|this| this.get_latest_checkpoint_sequence_number())
+                    .await
+            }
+
+            async fn get_checkpoint(
+                &self,
+                id: CheckpointId,
+            ) -> Result<sui_json_rpc_types::Checkpoint, IndexerError> {
+                self.spawn_blocking(move |this| this.get_checkpoint(id))
+                    .await
+            }
+
+            async fn get_checkpoints(
+                &self,
+                cursor: Option<CheckpointId>,
+                limit: usize,
+            ) -> Result<Vec<sui_json_rpc_types::Checkpoint>, IndexerError> {
+                self.spawn_blocking(move |this| this.get_checkpoints(cursor, limit))
+                    .await
+            }
+
+            async fn get_indexer_checkpoint(&self) -> Result<Checkpoint, IndexerError> {
+                self.spawn_blocking(|this| this.get_indexer_checkpoint())
+                    .await
+            }
+
+            async fn get_indexer_checkpoints(
+                &self,
+                cursor: i64,
+                limit: usize,
+            ) -> Result<Vec<Checkpoint>, IndexerError> {
+                self.spawn_blocking(move |this| this.get_indexer_checkpoints(cursor, limit))
+                    .await
+            }
+
+            async fn get_checkpoint_sequence_number(
+                &self,
+                digest: CheckpointDigest,
+            ) -> Result<CheckpointSequenceNumber, IndexerError> {
+                self.spawn_blocking(move |this| this.get_checkpoint_sequence_number(digest))
+                    .await
+            }
+
+            async fn get_event(&self, id: EventID) -> Result<Event, IndexerError> {
+                self.spawn_blocking(move |this| this.get_event(id)).await
+            }
+
+            async fn get_events(
+                &self,
+                query: EventFilter,
+                cursor: Option<EventID>,
+                limit: Option<usize>,
+                descending_order: bool,
+            ) -> Result<EventPage, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_events(query, cursor, limit, descending_order)
+                })
+                .await
+            }
+
+            async fn get_object(
+                &self,
+                object_id: ObjectID,
+                version: Option<SequenceNumber>,
+            ) -> Result<ObjectRead, IndexerError> {
+                self.spawn_blocking(move |this| this.get_object(object_id, version))
+                    .await
+            }
+
+            async fn query_objects_history(
+                &self,
+                filter: SuiObjectDataFilter,
+                at_checkpoint: CheckpointSequenceNumber,
+                cursor: Option<ObjectID>,
+                limit: usize,
+            ) -> Result<Vec<ObjectRead>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.query_objects_history(filter, at_checkpoint, cursor, limit)
+                })
+                .await
+            }
+
+            async fn query_latest_objects(
+                &self,
+                filter: SuiObjectDataFilter,
+                cursor: Option<ObjectID>,
+                limit: usize,
+            ) -> Result<Vec<ObjectRead>, IndexerError> {
+                self.spawn_blocking(move |this| this.query_latest_objects(filter, cursor, limit))
+                    .await
+            }
+
+            async fn get_total_transaction_number_from_checkpoints(
+                &self,
+            ) -> Result<i64, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_total_transaction_number_from_checkpoints()
+                })
+                .await
+            }
+
+            async fn get_transaction_by_digest(
+                &self,
+                tx_digest: &str,
+            ) -> Result<Transaction, IndexerError> {
+                let tx_digest = tx_digest.to_owned();
+                self.spawn_blocking(move |this| this.get_transaction_by_digest(&tx_digest))
+                    .await
+            }
+
+            async fn multi_get_transactions_by_digests(
+                &self,
+                tx_digests: &[String],
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                let tx_digests = tx_digests.to_owned();
+                self.spawn_blocking(move |this| this.multi_get_transactions_by_digests(&tx_digests))
+                    .await
+            }
+
+            async fn compose_sui_transaction_block_response(
+                &self,
+                tx: Transaction,
+                options: Option<&SuiTransactionBlockResponseOptions>,
+            ) -> Result<SuiTransactionBlockResponse, IndexerError> {
+                let options = options.cloned();
+                self.spawn_blocking(move |this| {
+                    this.compose_sui_transaction_block_response(tx, options.as_ref())
+                })
+                .await
+            }
+
+            async fn get_all_transaction_page(
+                &self,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_all_transaction_page(start_sequence, limit, is_descending)
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_checkpoint(
+                &self,
+                checkpoint_sequence_number: i64,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_checkpoint(
+                        checkpoint_sequence_number,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_transaction_kinds(
+                &self,
+                kind_names: Vec<String>,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_transaction_kinds(
+                        kind_names,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_sender_address(
+                &self,
+                sender_address: String,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_sender_address(
+                        sender_address,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_recipient_address(
+                &self,
+                sender_address: Option<SuiAddress>,
+                recipient_address: SuiAddress,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_recipient_address(
+                        sender_address,
+                        recipient_address,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_address(
+                &self,
+                address: SuiAddress,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_address(
+                        address,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_input_object(
+                &self,
+                object_id: ObjectID,
+                version: Option<i64>,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_input_object(
+                        object_id,
+                        version,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_changed_object(
+                &self,
+                object_id: ObjectID,
+                version: Option<i64>,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_changed_object(
+                        object_id,
+                        version,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_page_by_move_call(
+                &self,
+                package: ObjectID,
+                module: Option<Identifier>,
+                function: Option<Identifier>,
+                start_sequence: Option<i64>,
+                limit: usize,
+                is_descending: bool,
+            ) -> Result<Vec<Transaction>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_page_by_move_call(
+                        package,
+                        module,
+                        function,
+                        start_sequence,
+                        limit,
+                        is_descending,
+                    )
+                })
+                .await
+            }
+
+            async fn get_transaction_sequence_by_digest(
+                &self,
+                tx_digest: Option<String>,
+                is_descending: bool,
+            ) -> Result<Option<i64>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_transaction_sequence_by_digest(tx_digest, is_descending)
+                })
+                .await
+            }
+
+            async fn get_move_call_sequence_by_digest(
+                &self,
+                tx_digest: Option<String>,
+                is_descending: bool,
+            ) -> Result<Option<i64>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_move_call_sequence_by_digest(tx_digest, is_descending)
+                })
+                .await
+            }
+
+            async fn get_input_object_sequence_by_digest(
+                &self,
+                tx_digest: Option<String>,
+                is_descending: bool,
+            ) -> Result<Option<i64>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_input_object_sequence_by_digest(tx_digest, is_descending)
+                })
+                .await
+            }
+
+            async fn get_changed_object_sequence_by_digest(
+                &self,
+                tx_digest: Option<String>,
+                is_descending: bool,
+            ) -> Result<Option<i64>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_changed_object_sequence_by_digest(tx_digest, is_descending)
+                })
+                .await
+            }
+
+            async fn get_recipient_sequence_by_digest(
+                &self,
+                tx_digest: Option<String>,
+                is_descending: bool,
+            ) -> Result<Option<i64>, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_recipient_sequence_by_digest(tx_digest, is_descending)
+                })
+                .await
+            }
+
+            async fn get_network_metrics(&self) -> Result<NetworkMetrics, IndexerError> {
+                self.spawn_blocking(move |this| this.get_network_metrics())
+                    .await
+            }
+
+            async fn get_move_call_metrics(&self) -> Result<MoveCallMetrics, IndexerError> {
+                self.spawn_blocking(move |this| this.get_move_call_metrics())
+                    .await
+            }
+
+            async fn persist_fast_path(
+                &self,
+                tx: Transaction,
+                tx_object_changes: TransactionObjectChanges,
+            ) -> Result<usize, IndexerError> {
+                self.spawn_blocking(move |this| this.persist_fast_path(tx, tx_object_changes))
+                    .await
+            }
+
+            async fn persist_checkpoint_transactions(
+                &self,
+                checkpoint: &Checkpoint,
+                transactions: &[Transaction],
+            ) -> Result<usize, IndexerError> {
+                let checkpoint = checkpoint.to_owned();
+                let transactions = transactions.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.persist_checkpoint_transactions(&checkpoint, &transactions)
+                })
+                .await
+            }
+
+            async fn persist_object_changes(
+                &self,
+                tx_object_changes: &[TransactionObjectChanges],
+                object_mutation_latency: Histogram,
+                object_deletion_latency: Histogram,
+            ) -> Result<(), IndexerError> {
+                let tx_object_changes = tx_object_changes.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.persist_object_changes(
+                        &tx_object_changes,
+                        object_mutation_latency,
+                        object_deletion_latency,
+                    )
+                })
+                .await
+            }
+
+            async fn persist_events(&self, events: &[Event]) -> Result<(), IndexerError> {
+                let events = events.to_owned();
+                self.spawn_blocking(move |this| this.persist_events(&events))
+                    .await
+            }
+
+            async fn persist_addresses(
+                &self,
+                addresses: &[Address],
+                active_addresses: &[ActiveAddress],
+            ) -> Result<(), IndexerError> {
+                let addresses = addresses.to_owned();
+                let active_addresses = active_addresses.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.persist_addresses(&addresses, &active_addresses)
+                })
+                .await
+            }
+
+            async fn persist_packages(&self, packages: &[Package]) -> Result<(), IndexerError> {
+                let packages = packages.to_owned();
+                self.spawn_blocking(move |this| this.persist_packages(&packages))
+                    .await
+            }
+
+            async fn persist_transaction_index_tables(
+                &self,
+                input_objects: &[InputObject],
+                changed_objects: &[ChangedObject],
+                move_calls: &[MoveCall],
+                recipients: &[Recipient],
+            ) -> Result<(), IndexerError> {
+                let input_objects = input_objects.to_owned();
+                let changed_objects = changed_objects.to_owned();
+                let move_calls = move_calls.to_owned();
+                let recipients = recipients.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.persist_transaction_index_tables(
+                        &input_objects,
+                        &changed_objects,
+                        &move_calls,
+                        &recipients,
+                    )
+                })
+                .await
+            }
+
+            async fn persist_backfill(
+                &self,
+                data: &TemporaryBackfillStore,
+            ) -> Result<(), IndexerError> {
+                let data = data.to_owned();
+                self.spawn_blocking(move |this| this.persist_backfill(&data))
+                    .await
+            }
+
+            async fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError> {
+                let data = data.to_owned();
+                self.spawn_blocking(move |this| this.persist_epoch(&data))
+                    .await
+            }
+
+            async fn get_network_total_transactions_previous_epoch(
+                &self,
+                epoch: i64,
+            ) -> Result<i64, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.get_network_total_transactions_previous_epoch(epoch)
+                })
+                .await
+            }
+
+            async fn get_epochs(
+                &self,
+                cursor: Option<EpochId>,
+                limit: usize,
+                descending_order: Option<bool>,
+            ) -> Result<Vec<EpochInfo>, IndexerError> {
+                self.spawn_blocking(move |this| this.get_epochs(cursor, limit, descending_order))
+                    .await
+            }
+
+            async fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError> {
+                self.spawn_blocking(move |this| this.get_current_epoch())
+                    .await
+            }
+
+            fn module_cache(&self) -> &Self::ModuleCache {
+                &self.module_cache
+            }
+
+            fn indexer_metrics(&self) -> &IndexerMetrics {
+                &self.metrics
+            }
+
+            async fn get_last_address_processed_checkpoint(&self) -> Result<i64, IndexerError> {
+                self.spawn_blocking(move |this| this.get_last_address_processed_checkpoint())
+                    .await
+            }
+
+            async fn calculate_address_stats(
+                &self,
+                checkpoint: i64,
+            ) -> Result<AddressStats, IndexerError> {
+                self.spawn_blocking(move |this| this.calculate_address_stats(checkpoint))
+                    .await
+            }
+
+            async fn persist_address_stats(
+                &self,
+                addr_stats: &AddressStats,
+            ) -> Result<(), IndexerError> {
+                let addr_stats = addr_stats.to_owned();
+                self.spawn_blocking(move |this| this.persist_address_stats(&addr_stats))
+                    .await
+            }
+
+            async fn get_latest_address_stats(&self) -> Result<AddressStats, IndexerError> {
+                self.spawn_blocking(move |this| this.get_latest_address_stats())
+                    .await
+            }
+
+            async fn get_checkpoint_address_stats(
+                &self,
+                checkpoint: i64,
+            ) -> Result<AddressStats, IndexerError> {
+                self.spawn_blocking(move |this| this.get_checkpoint_address_stats(checkpoint))
+                    .await
+            }
+
+            async fn get_all_epoch_address_stats(
+                &self,
+                descending_order: Option<bool>,
+            ) -> Result<Vec<AddressStats>, IndexerError> {
+                self.spawn_blocking(move |this| this.get_all_epoch_address_stats(descending_order))
+                    .await
+            }
+
+            async fn calculate_checkpoint_metrics(
+                &self,
+                current_checkpoint: i64,
+                last_checkpoint_metrics: &CheckpointMetrics,
+                checkpoints: &[Checkpoint],
+            ) -> Result<CheckpointMetrics, IndexerError> {
+                let last_checkpoint_metrics = last_checkpoint_metrics.to_owned();
+                let checkpoints = checkpoints.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.calculate_checkpoint_metrics(
+                        current_checkpoint,
+                        &last_checkpoint_metrics,
+                        &checkpoints,
+                    )
+                })
+                .await
+            }
+
+            async fn persist_checkpoint_metrics(
+                &self,
+                checkpoint_metrics: &CheckpointMetrics,
+            ) -> Result<(), IndexerError> {
+                let checkpoint_metrics = checkpoint_metrics.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.persist_checkpoint_metrics(&checkpoint_metrics)
+                })
+                .await
+            }
+
+            async fn get_latest_checkpoint_metrics(
+                &self,
+            ) -> Result<CheckpointMetrics, IndexerError> {
+                self.spawn_blocking(move |this| this.get_latest_checkpoint_metrics())
+                    .await
+            }
+
+            async fn get_processor_watermark(
+                &self,
+                processor_name: &str,
+            ) -> Result<i64, IndexerError> {
+                let processor_name = processor_name.to_owned();
+                self.spawn_blocking(move |this| this.get_processor_watermark(&processor_name))
+                    .await
+            }
+
+            async fn persist_processor_watermark(
+                &self,
+                processor_name: &str,
+                checkpoint: i64,
+            ) -> Result<(), IndexerError> {
+                let processor_name = processor_name.to_owned();
+                self.spawn_blocking(move |this| {
+                    this.persist_processor_watermark(&processor_name, checkpoint)
+                })
+                .await
+            }
+
+            async fn get_prune_cutoff(
+                &self,
+                epochs_to_keep: u64,
+            ) -> Result<Option<PruneCutoff>, IndexerError> {
+                self.spawn_blocking(move |this| this.get_prune_cutoff(epochs_to_keep))
+                    .await
+            }
+
+            async fn drop_pruned_partitions(
+                &self,
+                table: PrunableTable,
+                cutoff: &PruneCutoff,
+            ) -> Result<Vec<String>, IndexerError> {
+                let cutoff = *cutoff;
+                self.spawn_blocking(move |this| this.drop_pruned_partitions(table, &cutoff))
+                    .await
+            }
+
+            async fn prune_table(
+                &self,
+                table: PrunableTable,
+                cutoff: &PruneCutoff,
+                batch_size: usize,
+            ) -> Result<usize, IndexerError> {
+                let cutoff = *cutoff;
+                self.spawn_blocking(move |this| this.prune_table(table, &cutoff, batch_size))
+                    .await
+            }
+
+            async fn prune_objects_history_versions(
+                &self,
+                batch_size: usize,
+            ) -> Result<usize, IndexerError> {
+                self.spawn_blocking(move |this| this.prune_objects_history_versions(batch_size))
+                    .await
+            }
+
+            async fn calculate_real_time_tps(
+                &self,
+                current_checkpoint: i64,
+            ) -> Result<f64, IndexerError> {
+                self.spawn_blocking(move |this| this.calculate_real_time_tps(current_checkpoint))
+                    .await
+            }
+
+            async fn calculate_peak_tps_30d(
+                &self,
+                current_checkpoint: i64,
+                current_timestamp_ms: i64,
+            ) -> Result<f64, IndexerError> {
+                self.spawn_blocking(move |this| {
+                    this.calculate_peak_tps_30d(current_checkpoint, current_timestamp_ms)
+                })
+                .await
+            }
+        }
+    };
+}
+
+pub(crate) use impl_indexer_store;
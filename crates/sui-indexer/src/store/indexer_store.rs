@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::ops::Range;
+
 use async_trait::async_trait;
+use clap::ValueEnum;
 use prometheus::Histogram;
 
 use move_core_types::identifier::Identifier;
@@ -241,6 +244,9 @@ pub trait IndexerStore {
         move_calls: &[MoveCall],
         recipients: &[Recipient],
     ) -> Result<(), IndexerError>;
+    // NOTE: replaces the rows of the backfilled checkpoint range in the selected tables,
+    // so that a batch can be retried or reindexed any number of times.
+    async fn persist_backfill(&self, data: &TemporaryBackfillStore) -> Result<(), IndexerError>;
 
     async fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError>;
     async fn get_network_total_transactions_previous_epoch(
@@ -288,6 +294,35 @@ pub trait IndexerStore {
     ) -> Result<(), IndexerError>;
     async fn get_latest_checkpoint_metrics(&self) -> Result<CheckpointMetrics, IndexerError>;
 
+    /// methods for checkpoint processor watermarks
+    async fn get_processor_watermark(&self, processor_name: &str) -> Result<i64, IndexerError>;
+    async fn persist_processor_watermark(
+        &self,
+        processor_name: &str,
+        checkpoint: i64,
+    ) -> Result<(), IndexerError>;
+
+    /// methods for data retention
+    async fn get_prune_cutoff(
+        &self,
+        epochs_to_keep: u64,
+    ) -> Result<Option<PruneCutoff>, IndexerError>;
+    async fn drop_pruned_partitions(
+        &self,
+        table: PrunableTable,
+        cutoff: &PruneCutoff,
+    ) -> Result<Vec<String>, IndexerError>;
+    async fn prune_table(
+        &self,
+        table: PrunableTable,
+        cutoff: &PruneCutoff,
+        batch_size: usize,
+    ) -> Result<usize, IndexerError>;
+    async fn prune_objects_history_versions(
+        &self,
+        batch_size: usize,
+    ) -> Result<usize, IndexerError>;
+
     /// TPS related methods
     async fn calculate_real_time_tps(&self, current_checkpoint: i64) -> Result<f64, IndexerError>;
     async fn calculate_peak_tps_30d(
@@ -352,6 +387,55 @@ pub struct TemporaryCheckpointStore {
     pub recipients: Vec<Recipient>,
 }
 
+// Per checkpoint range backfilling, tables which are not backfilled are None
+#[derive(Clone, Debug)]
+pub struct TemporaryBackfillStore {
+    pub checkpoint_range: Range<CheckpointSequenceNumber>,
+    pub checkpoints: Option<Vec<Checkpoint>>,
+    pub transactions: Option<Vec<Transaction>>,
+    pub input_objects: Option<Vec<InputObject>>,
+    pub changed_objects: Option<Vec<ChangedObject>>,
+    pub move_calls: Option<Vec<MoveCall>>,
+    pub recipients: Option<Vec<Recipient>>,
+}
+
+/// Tables whose old rows can be pruned by the indexer.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PrunableTable {
+    Transactions,
+    Events,
+    Checkpoints,
+    InputObjects,
+    ChangedObjects,
+    MoveCalls,
+    Recipients,
+    ObjectsHistory,
+}
+
+impl PrunableTable {
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            PrunableTable::Transactions => "transactions",
+            PrunableTable::Events => "events",
+            PrunableTable::Checkpoints => "checkpoints",
+            PrunableTable::InputObjects => "input_objects",
+            PrunableTable::ChangedObjects => "changed_objects",
+            PrunableTable::MoveCalls => "move_calls",
+            PrunableTable::Recipients => "recipients",
+            PrunableTable::ObjectsHistory => "objects_history",
+        }
+    }
+}
+
+// Rows of epochs before `epoch` are pruned, i.e. rows of checkpoints before `checkpoint`,
+// or events emitted before `timestamp_ms` as events are not keyed on checkpoints.
+#[derive(Clone, Copy, Debug)]
+pub struct PruneCutoff {
+    pub epoch: i64,
+    pub checkpoint: i64,
+    pub timestamp_ms: i64,
+}
+
 #[derive(Clone, Debug)]
 pub struct TransactionObjectChanges {
     pub changed_objects: Vec<Object>,
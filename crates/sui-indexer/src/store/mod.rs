@@ -3,11 +3,18 @@
 
 pub use indexer_store::*;
 pub use pg_indexer_store::PgIndexerStore;
+pub use sqlite_indexer_store::SqliteIndexerStore;
 
+mod common;
 mod indexer_store;
 mod module_resolver;
 mod pg_indexer_store;
 mod query;
+mod sqlite_indexer_store;
+mod sqlite_models;
+
+#[cfg(test)]
+pub(crate) use sqlite_indexer_store::tests as sqlite_store_tests;
 
 mod diesel_marco {
     macro_rules! read_only_blocking {
@@ -32,6 +39,27 @@ mod diesel_marco {
                 .map_err(|e| IndexerError::PostgresWriteError(e.to_string()))
         }};
     }
+
+    // NOTE: SQLite has no read-only transactions and a single writer, write transactions
+    // take the database lock upfront so that concurrent writers queue on the busy timeout.
+    macro_rules! sqlite_read_only_blocking {
+        ($pool:expr, $query:expr) => {{
+            let mut sqlite_pool_conn = crate::get_sqlite_pool_connection($pool)?;
+            diesel::Connection::transaction(&mut *sqlite_pool_conn, $query)
+                .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+        }};
+    }
+
+    macro_rules! sqlite_transactional_blocking {
+        ($pool:expr, $query:expr) => {{
+            let mut sqlite_pool_conn = crate::get_sqlite_pool_connection($pool)?;
+            sqlite_pool_conn
+                .immediate_transaction($query)
+                .map_err(|e| IndexerError::SqliteWriteError(e.to_string()))
+        }};
+    }
     pub(crate) use read_only_blocking;
+    pub(crate) use sqlite_read_only_blocking;
+    pub(crate) use sqlite_transactional_blocking;
     pub(crate) use transactional_blocking;
 }
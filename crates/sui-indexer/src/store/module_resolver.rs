@@ -4,15 +4,17 @@
 use diesel::pg::sql_types::Bytea;
 use diesel::sql_types::Text;
 use diesel::QueryableByName;
-use diesel::RunQueryDsl;
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use move_core_types::language_storage::ModuleId;
 use move_core_types::resolver::ModuleResolver;
 
 use sui_types::base_types::ObjectID;
 
 use crate::errors::{Context, IndexerError};
-use crate::store::diesel_marco::read_only_blocking;
-use crate::PgConnectionPool;
+use crate::sqlite_schema::packages;
+use crate::store::diesel_marco::{read_only_blocking, sqlite_read_only_blocking};
+use crate::store::sqlite_models::named_bcs_from_bytes;
+use crate::{PgConnectionPool, SqliteConnectionPool};
 
 pub struct IndexerModuleResolver {
     cp: PgConnectionPool,
@@ -54,3 +56,42 @@ impl ModuleResolver for IndexerModuleResolver {
         Ok(Some(module_bytes.data))
     }
 }
+
+pub struct SqliteModuleResolver {
+    cp: SqliteConnectionPool,
+}
+
+impl SqliteModuleResolver {
+    pub fn new(cp: SqliteConnectionPool) -> Self {
+        Self { cp }
+    }
+}
+
+impl ModuleResolver for SqliteModuleResolver {
+    type Error = IndexerError;
+
+    fn get_module(&self, id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        let package_id = ObjectID::from(*id.address()).to_string();
+        let module_name = id.name().to_string();
+
+        // Modules are stored as one BCS blob per package version, so the latest version
+        // is read and the module picked out of it here.
+        let package_data = sqlite_read_only_blocking!(&self.cp, |conn| {
+            packages::table
+                .select(packages::data)
+                .filter(packages::package_id.eq(package_id))
+                .order(packages::version.desc())
+                .first::<Vec<u8>>(conn)
+                .optional()
+        })
+        .context("Error reading module.")?;
+
+        let Some(package_data) = package_data else {
+            return Ok(None);
+        };
+        Ok(named_bcs_from_bytes(&package_data)?
+            .into_iter()
+            .find(|module| module.0 == module_name)
+            .map(|module| module.1))
+    }
+}
@@ -2,7 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::BTreeMap;
-use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::anyhow;
@@ -15,30 +14,21 @@ use diesel::upsert::excluded;
 use diesel::ExpressionMethods;
 use diesel::{OptionalExtension, QueryableByName};
 use diesel::{QueryDsl, RunQueryDsl};
-use fastcrypto::hash::Digest;
-use fastcrypto::traits::ToFromBytes;
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use move_core_types::identifier::Identifier;
 use prometheus::Histogram;
 use tracing::info;
 
 use sui_json_rpc_types::{
-    CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics, MoveFunctionName,
-    NetworkMetrics, SuiEvent, SuiObjectDataFilter,
-};
-use sui_json_rpc_types::{
-    SuiTransactionBlock, SuiTransactionBlockEffects, SuiTransactionBlockEvents,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics, NetworkMetrics, SuiEvent,
+    SuiObjectDataFilter,
 };
+use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
-use sui_types::committee::{EpochId, ProtocolVersion};
-use sui_types::crypto::AuthorityPublicKeyBytes;
+use sui_types::committee::EpochId;
 use sui_types::digests::CheckpointDigest;
-use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
-use sui_types::messages_checkpoint::{
-    CheckpointCommitment, CheckpointSequenceNumber, ECMHLiveObjectSetDigest, EndOfEpochData,
-};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::ObjectRead;
 
 use crate::errors::{Context, IndexerError};
@@ -53,22 +43,31 @@ use crate::models::objects::{
     compose_object_bulk_insert_update_query, group_and_sort_objects, Object,
 };
 use crate::models::packages::Package;
+use crate::models::processor_watermarks::ProcessorWatermark;
 use crate::models::system_state::DBValidatorSummary;
 use crate::models::transaction_index::{ChangedObject, InputObject, MoveCall, Recipient};
 use crate::models::transactions::Transaction;
 use crate::schema::{
     active_addresses, address_stats, addresses, changed_objects, checkpoint_metrics, checkpoints,
-    epochs, events, input_objects, move_calls, objects, objects_history, packages, recipients,
-    system_states, transactions, validators,
+    epochs, events, input_objects, move_calls, objects, objects_history, packages,
+    processor_watermarks, recipients, system_states, transactions, validators,
+};
+use crate::store::common::{
+    compose_transaction_block_response, end_of_epoch_data, epoch_address_stats_sql,
+    event_query_limit, impl_indexer_store, into_epoch_infos, into_event_page,
+    into_move_call_metrics, rolling_checkpoint_metrics, transaction_digests_by_address_sql,
+    transaction_digests_by_move_call_sql, transaction_digests_by_object_sql,
+    transaction_digests_by_recipient_sql, TempDigestTable,
 };
 use crate::store::diesel_marco::{read_only_blocking, transactional_blocking};
 use crate::store::module_resolver::IndexerModuleResolver;
 use crate::store::query::DBFilter;
 use crate::store::TransactionObjectChanges;
-use crate::store::{IndexerStore, TemporaryEpochStore};
+use crate::store::{
+    IndexerStore, PrunableTable, PruneCutoff, TemporaryBackfillStore, TemporaryEpochStore,
+};
 use crate::PgConnectionPool;
 
-const MAX_EVENT_PAGE_SIZE: usize = 1000;
 const PG_COMMIT_CHUNK_SIZE: usize = 1000;
 
 const GET_PARTITION_SQL: &str = r#"
@@ -83,11 +82,24 @@ WHERE parent.relkind = 'p'
 GROUP BY table_name;
 "#;
 
-#[derive(QueryableByName, Debug, Clone)]
-struct TempDigestTable {
-    #[diesel(sql_type = VarChar)]
-    digest_name: String,
-}
+const GET_TABLE_PARTITIONS_SQL: &str = r#"
+SELECT child.relname AS partition_name
+FROM pg_inherits
+         JOIN pg_class parent ON pg_inherits.inhparent = parent.oid
+         JOIN pg_class child ON pg_inherits.inhrelid = child.oid
+WHERE parent.relname = $1;
+"#;
+
+// Deletes at most $1 versions of objects_history which are older than the latest object version.
+const PRUNE_OBJECTS_HISTORY_VERSIONS_SQL: &str = r#"
+DELETE FROM objects_history
+WHERE (object_id, version, checkpoint) IN
+      (SELECT h.object_id, h.version, h.checkpoint
+       FROM objects_history h
+                JOIN objects o ON h.object_id = o.object_id
+       WHERE h.version < o.version
+       LIMIT $1);
+"#;
 
 #[derive(Clone)]
 pub struct PgIndexerStore {
@@ -240,36 +252,12 @@ impl PgIndexerStore {
                         Vec<Option<Vec<u8>>>,
                     )>(conn)?;
 
-                let next_epoch_committee = next_epoch_committee
-                    .iter()
-                    .flatten()
-                    .zip(next_epoch_committee_stake.iter().flatten())
-                    .map(|(name, vote)| {
-                        AuthorityPublicKeyBytes::from_bytes(name.as_slice())
-                            .map(|b| (b, (*vote) as u64))
-                    })
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                let epoch_commitments = epoch_commitments
-                    .iter()
-                    .flatten()
-                    .flat_map(|v| {
-                        if let Ok(v) = v.clone().try_into() {
-                            return Some(CheckpointCommitment::ECMHLiveObjectSetDigest(
-                                ECMHLiveObjectSetDigest::from(Digest::new(v)),
-                            ));
-                        }
-                        None
-                    })
-                    .collect::<Vec<_>>();
-
-                next_version.map(|next_epoch_protocol_version| EndOfEpochData {
-                    next_epoch_committee,
-                    next_epoch_protocol_version: ProtocolVersion::from(
-                        next_epoch_protocol_version as u64,
-                    ),
-                    epoch_commitments,
-                })
+                end_of_epoch_data(
+                    next_version,
+                    &next_epoch_committee,
+                    &next_epoch_committee_stake,
+                    &epoch_commitments,
+                )?
             } else {
                 None
             };
@@ -407,15 +395,7 @@ impl PgIndexerStore {
             }
         }
 
-        let mut page_limit = limit.unwrap_or(MAX_EVENT_PAGE_SIZE);
-        if page_limit > MAX_EVENT_PAGE_SIZE {
-            Err(IndexerError::InvalidArgumentError(format!(
-                "Limit {} exceeds the maximum page size {}",
-                page_limit, MAX_EVENT_PAGE_SIZE
-            )))?;
-        }
-        // fetch one more item to tell if there is next page
-        page_limit += 1;
+        let query_limit = event_query_limit(limit)?;
 
         let pg_cursor =
             if let Some(cursor) = cursor {
@@ -443,20 +423,11 @@ impl PgIndexerStore {
         })
         .context("Failed reading events from PostgresDB")?;
 
-        let mut sui_event_vec = events_vec
+        let sui_event_vec = events_vec
             .into_iter()
             .map(|event| event.try_into(&self.module_cache))
             .collect::<Result<Vec<SuiEvent>, _>>()?;
-        // reset to original limit for checking and truncating
-        page_limit -= 1;
-        let has_next_page = sui_event_vec.len() > page_limit;
-        sui_event_vec.truncate(page_limit);
-        let next_cursor = sui_event_vec.last().map(|e| e.id.clone());
-        Ok(EventPage {
-            data: sui_event_vec,
-            next_cursor,
-            has_next_page,
-        })
+        Ok(into_event_page(sui_event_vec, query_limit))
     }
 
     fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError> {
@@ -485,89 +456,13 @@ impl PgIndexerStore {
         tx: Transaction,
         options: Option<&SuiTransactionBlockResponseOptions>,
     ) -> Result<SuiTransactionBlockResponse, IndexerError> {
-        let transaction: SuiTransactionBlock =
-            serde_json::from_str(&tx.transaction_content).map_err(|err| {
-                IndexerError::InsertableParsingError(format!(
-                    "Failed converting transaction JSON {:?} to SuiTransactionBlock with error: {:?}",
-                    tx.transaction_content, err
-                ))
-            })?;
-        let effects: SuiTransactionBlockEffects = serde_json::from_str(&tx.transaction_effects_content).map_err(|err| {
-        IndexerError::InsertableParsingError(format!(
-            "Failed converting transaction effect JSON {:?} to SuiTransactionBlockEffects with error: {:?}",
-            tx.transaction_effects_content, err
-        ))
-        })?;
-
-        let tx_digest: TransactionDigest = tx.transaction_digest.parse().map_err(|e| {
-            IndexerError::InsertableParsingError(format!(
-                "Failed to parse transaction digest {} : {:?}",
-                tx.transaction_digest, e
-            ))
-        })?;
-
-        let (mut tx_opt, mut effects_opt, mut raw_tx) = (None, None, vec![]);
-        let (object_changes, balance_changes, mut events) = (None, None, None);
-        if let Some(options) = options {
-            //TODO Object changes and balance changes are not supported atm due to async issues
-            if options.show_balance_changes {
-                return Err(IndexerError::NotSupportedError(
-                    "Object cache is not supported".to_string(),
-                ));
-                // let object_cache = ObjectProviderCache::new(self.clone());
-                // balance_changes =
-                //     Some(get_balance_changes_from_effect(&object_cache, &effects).await?);
-            }
-            if options.show_object_changes {
-                return Err(IndexerError::NotSupportedError(
-                    "Object cache is not supported".to_string(),
-                ));
-                // let object_cache = ObjectProviderCache::new(self.clone());
-                // object_changes = Some(
-                //     get_object_changes(
-                //         &object_cache,
-                //         sender,
-                //         &effects.modified_at_versions(),
-                //         effects.all_changed_objects(),
-                //         effects.all_deleted_objects(),
-                //     )
-                //     .await?,
-                // );
-            }
-            if options.show_events {
-                let event_page = self.get_events(
-                    EventFilter::Transaction(tx_digest),
-                    None,
-                    None,
-                    /* descending_order */ false,
-                )?;
-                events = Some(SuiTransactionBlockEvents {
-                    data: event_page.data,
-                });
-            }
-            if options.show_input {
-                tx_opt = Some(transaction);
-            }
-            if options.show_raw_input {
-                raw_tx = tx.raw_transaction;
-            }
-            if options.show_effects {
-                effects_opt = Some(effects);
-            }
-        }
-
-        Ok(SuiTransactionBlockResponse {
-            digest: tx_digest,
-            transaction: tx_opt,
-            raw_transaction: raw_tx,
-            effects: effects_opt,
-            confirmed_local_execution: tx.confirmed_local_execution,
-            timestamp_ms: tx.timestamp_ms.map(|t| t as u64),
-            checkpoint: tx.checkpoint_sequence_number.map(|c| c as u64),
-            events,
-            object_changes,
-            balance_changes,
-            errors: vec![],
+        compose_transaction_block_response(tx, options, |tx_digest| {
+            self.get_events(
+                EventFilter::Transaction(tx_digest),
+                None,
+                None,
+                /* descending_order */ false,
+            )
         })
     }
 
@@ -982,28 +877,13 @@ impl PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name
-             FROM input_objects
-             WHERE object_id = '{}' {} {}
-             ORDER BY id {} LIMIT {}",
+        let sql_query = transaction_digests_by_object_sql(
+            "input_objects",
             object_id,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(version) = version {
-                format!("AND version = {}", version)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
+            version,
+            start_sequence,
+            limit,
+            is_descending,
         );
         let tx_digests: Vec<String> = read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(sql_query).load(conn))
                 .context(&format!("Failed reading transaction digests by input object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"))?
@@ -1021,28 +901,13 @@ impl PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name
-             FROM changed_objects
-             WHERE object_id = '{}' {} {}
-             ORDER BY id {} LIMIT {}",
+        let sql_query = transaction_digests_by_object_sql(
+            "changed_objects",
             object_id,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(version) = version {
-                format!("AND version = {}", version)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
+            version,
+            start_sequence,
+            limit,
+            is_descending,
         );
         let tx_digests: Vec<String> = read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(sql_query).load(conn))
                 .context(&format!("Failed reading transaction digests by changed object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"))?
@@ -1061,36 +926,13 @@ impl PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        // note: module_name and function_name are user-controlled, which is scary.
-        // however, but valid Move identifiers can only contain 0-9, a-z, A-Z, and _,
-        // so it is safe to use them as-is in the query below
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name
-             FROM move_calls
-             WHERE move_package = '{}' {} {} {}
-             ORDER BY id {} LIMIT {}",
+        let sql_query = transaction_digests_by_move_call_sql(
             package_name,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(module_name) = module_name.clone() {
-                format!("AND move_module = '{}'", module_name)
-            } else {
-                "".to_string()
-            },
-            if let Some(function_name) = function_name.clone() {
-                format!("AND move_function = '{}'", function_name)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
+            module_name.as_ref(),
+            function_name.as_ref(),
+            start_sequence,
+            limit,
+            is_descending,
         );
         let tx_digests: Vec<String> = read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(sql_query).load(conn))
                 .context(&format!(
@@ -1110,28 +952,8 @@ impl PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name FROM recipients
-             WHERE recipient = '{}' {} {}
-             ORDER BY id {} LIMIT {}",
-            to,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if let Some(from) = from {
-                format!("AND sender = '{}'", from)
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
-        );
+        let sql_query =
+            transaction_digests_by_recipient_sql(from, to, start_sequence, limit, is_descending);
         let tx_digests: Vec<String> = read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(sql_query).load(conn))
                 .context(&format!("Failed reading transaction digests by recipient address {to} with start_sequence {start_sequence:?} and limit {limit}"))?
                 .into_iter()
@@ -1147,28 +969,8 @@ impl PgIndexerStore {
         limit: usize,
         is_descending: bool,
     ) -> Result<Vec<Transaction>, IndexerError> {
-        let sql_query = format!(
-            "SELECT transaction_digest as digest_name FROM (
-                SELECT transaction_digest, max(id) AS max_id
-                FROM recipients
-                WHERE recipient = '{}' OR sender = '{}'
-                {} GROUP BY transaction_digest
-                ORDER BY max_id {} LIMIT {}
-            ) AS t",
-            address,
-            address,
-            if let Some(start_sequence) = start_sequence {
-                if is_descending {
-                    format!("AND id < {}", start_sequence)
-                } else {
-                    format!("AND id > {}", start_sequence)
-                }
-            } else {
-                "".to_string()
-            },
-            if is_descending { "DESC" } else { "ASC" },
-            limit
-        );
+        let sql_query =
+            transaction_digests_by_address_sql(address, start_sequence, limit, is_descending);
         let tx_digests: Vec<String> = read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(sql_query).load(conn))
                 .context(&format!("Failed reading transaction digests by address {address} with start_sequence {start_sequence:?} and limit {limit}"))?
                 .into_iter()
@@ -1187,32 +989,7 @@ impl PgIndexerStore {
                 .get_results::<DBMoveCallMetrics>(conn)
         })?;
 
-        let mut d3 = vec![];
-        let mut d7 = vec![];
-        let mut d30 = vec![];
-        for m in metrics {
-            let package = ObjectID::from_str(&m.move_package);
-            let module = Identifier::from_str(m.move_module.as_str());
-            let function = Identifier::from_str(m.move_function.as_str());
-            if let (Ok(package), Ok(module), Ok(function)) = (package, module, function) {
-                let fun = MoveFunctionName {
-                    package,
-                    module,
-                    function,
-                };
-                match m.day {
-                    3 => d3.push((fun, m.count as usize)),
-                    7 => d7.push((fun, m.count as usize)),
-                    30 => d30.push((fun, m.count as usize)),
-                    _ => {}
-                }
-            }
-        }
-        Ok(MoveCallMetrics {
-            rank_3_days: d3,
-            rank_7_days: d7,
-            rank_30_days: d30,
-        })
+        Ok(into_move_call_metrics(metrics))
     }
 
     fn persist_fast_path(
@@ -1441,6 +1218,181 @@ impl PgIndexerStore {
         Ok(())
     }
 
+    fn persist_backfill(&self, data: &TemporaryBackfillStore) -> Result<(), IndexerError> {
+        let start = data.checkpoint_range.start as i64;
+        let end = data.checkpoint_range.end as i64;
+        transactional_blocking!(&self.blocking_cp, |conn| {
+            // NOTE: transactions are upserted instead of deleted and re-inserted, to keep
+            // their sequence ids, which transaction pages are ordered by.
+            if let Some(transactions) = &data.transactions {
+                for transaction_chunk in transactions.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(transactions::table)
+                        .values(transaction_chunk)
+                        .on_conflict(transactions::transaction_digest)
+                        .do_update()
+                        .set((
+                            transactions::sender.eq(excluded(transactions::sender)),
+                            transactions::recipients.eq(excluded(transactions::recipients)),
+                            transactions::checkpoint_sequence_number
+                                .eq(excluded(transactions::checkpoint_sequence_number)),
+                            transactions::timestamp_ms.eq(excluded(transactions::timestamp_ms)),
+                            transactions::transaction_kind
+                                .eq(excluded(transactions::transaction_kind)),
+                            transactions::transaction_count
+                                .eq(excluded(transactions::transaction_count)),
+                            transactions::execution_success
+                                .eq(excluded(transactions::execution_success)),
+                            transactions::created.eq(excluded(transactions::created)),
+                            transactions::mutated.eq(excluded(transactions::mutated)),
+                            transactions::deleted.eq(excluded(transactions::deleted)),
+                            transactions::unwrapped.eq(excluded(transactions::unwrapped)),
+                            transactions::wrapped.eq(excluded(transactions::wrapped)),
+                            transactions::move_calls.eq(excluded(transactions::move_calls)),
+                            transactions::gas_object_id.eq(excluded(transactions::gas_object_id)),
+                            transactions::gas_object_sequence
+                                .eq(excluded(transactions::gas_object_sequence)),
+                            transactions::gas_object_digest
+                                .eq(excluded(transactions::gas_object_digest)),
+                            transactions::gas_budget.eq(excluded(transactions::gas_budget)),
+                            transactions::total_gas_cost.eq(excluded(transactions::total_gas_cost)),
+                            transactions::computation_cost
+                                .eq(excluded(transactions::computation_cost)),
+                            transactions::storage_cost.eq(excluded(transactions::storage_cost)),
+                            transactions::storage_rebate.eq(excluded(transactions::storage_rebate)),
+                            transactions::non_refundable_storage_fee
+                                .eq(excluded(transactions::non_refundable_storage_fee)),
+                            transactions::gas_price.eq(excluded(transactions::gas_price)),
+                            transactions::raw_transaction
+                                .eq(excluded(transactions::raw_transaction)),
+                            transactions::transaction_content
+                                .eq(excluded(transactions::transaction_content)),
+                            transactions::transaction_effects_content
+                                .eq(excluded(transactions::transaction_effects_content)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling transactions to PostgresDB")?;
+                }
+            }
+
+            if let Some(move_calls) = &data.move_calls {
+                diesel::delete(
+                    move_calls::table
+                        .filter(move_calls::checkpoint_sequence_number.ge(start))
+                        .filter(move_calls::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled move_calls from PostgresDB")?;
+                for move_calls_chunk in move_calls.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(move_calls::table)
+                        .values(move_calls_chunk)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling move_calls to PostgresDB")?;
+                }
+            }
+
+            if let Some(input_objects) = &data.input_objects {
+                diesel::delete(
+                    input_objects::table
+                        .filter(input_objects::checkpoint_sequence_number.ge(start))
+                        .filter(input_objects::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled input_objects from PostgresDB")?;
+                for input_objects_chunk in input_objects.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(input_objects::table)
+                        .values(input_objects_chunk)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling input_objects to PostgresDB")?;
+                }
+            }
+
+            if let Some(changed_objects) = &data.changed_objects {
+                diesel::delete(
+                    changed_objects::table
+                        .filter(changed_objects::checkpoint_sequence_number.ge(start))
+                        .filter(changed_objects::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled changed_objects from PostgresDB")?;
+                for changed_objects_chunk in changed_objects.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(changed_objects::table)
+                        .values(changed_objects_chunk)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling changed_objects to PostgresDB")?;
+                }
+            }
+
+            if let Some(recipients) = &data.recipients {
+                diesel::delete(
+                    recipients::table
+                        .filter(recipients::checkpoint_sequence_number.ge(start))
+                        .filter(recipients::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled recipients from PostgresDB")?;
+                for recipients_chunk in recipients.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(recipients::table)
+                        .values(recipients_chunk)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling recipients to PostgresDB")?;
+                }
+            }
+
+            // Commit backfilled checkpoints last, like the checkpoint handler does.
+            if let Some(checkpoints) = &data.checkpoints {
+                for checkpoint_chunk in checkpoints.chunks(PG_COMMIT_CHUNK_SIZE) {
+                    diesel::insert_into(checkpoints::table)
+                        .values(checkpoint_chunk)
+                        .on_conflict(checkpoints::sequence_number)
+                        .do_update()
+                        .set((
+                            checkpoints::checkpoint_digest
+                                .eq(excluded(checkpoints::checkpoint_digest)),
+                            checkpoints::epoch.eq(excluded(checkpoints::epoch)),
+                            checkpoints::transactions.eq(excluded(checkpoints::transactions)),
+                            checkpoints::previous_checkpoint_digest
+                                .eq(excluded(checkpoints::previous_checkpoint_digest)),
+                            checkpoints::end_of_epoch.eq(excluded(checkpoints::end_of_epoch)),
+                            checkpoints::total_gas_cost.eq(excluded(checkpoints::total_gas_cost)),
+                            checkpoints::total_computation_cost
+                                .eq(excluded(checkpoints::total_computation_cost)),
+                            checkpoints::total_storage_cost
+                                .eq(excluded(checkpoints::total_storage_cost)),
+                            checkpoints::total_storage_rebate
+                                .eq(excluded(checkpoints::total_storage_rebate)),
+                            checkpoints::total_transaction_blocks
+                                .eq(excluded(checkpoints::total_transaction_blocks)),
+                            checkpoints::total_transactions
+                                .eq(excluded(checkpoints::total_transactions)),
+                            checkpoints::total_successful_transaction_blocks
+                                .eq(excluded(checkpoints::total_successful_transaction_blocks)),
+                            checkpoints::total_successful_transactions
+                                .eq(excluded(checkpoints::total_successful_transactions)),
+                            checkpoints::network_total_transactions
+                                .eq(excluded(checkpoints::network_total_transactions)),
+                            checkpoints::timestamp_ms.eq(excluded(checkpoints::timestamp_ms)),
+                            checkpoints::validator_signature
+                                .eq(excluded(checkpoints::validator_signature)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling checkpoints to PostgresDB")?;
+                }
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
     fn get_network_total_transactions_previous_epoch(
         &self,
         epoch: i64,
@@ -1565,21 +1517,7 @@ impl PgIndexerStore {
                 ))
             })?;
 
-        let mut validators =
-            validators
-                .into_iter()
-                .fold(BTreeMap::<i64, Vec<_>>::new(), |mut acc, v| {
-                    acc.entry(v.epoch).or_default().push(v);
-                    acc
-                });
-
-        epoch_info
-            .into_iter()
-            .map(|info| {
-                let epoch = info.epoch;
-                info.to_epoch_info(validators.remove(&epoch).unwrap_or_default())
-            })
-            .collect()
+        into_epoch_infos(epoch_info, validators)
     }
 
     fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError> {
@@ -1685,20 +1623,7 @@ impl PgIndexerStore {
         descending_order: Option<bool>,
     ) -> Result<Vec<AddressStats>, IndexerError> {
         let is_descending = descending_order.unwrap_or_default();
-        let epoch_addr_stats_query = format!(
-            "WITH ranked_rows AS (
-                SELECT
-                  checkpoint, epoch, timestamp_ms, cumulative_addresses, cumulative_active_addresses, daily_active_addresses,
-                  row_number() OVER(PARTITION BY epoch ORDER BY checkpoint DESC) as row_num
-                FROM
-                  address_stats
-              )
-              SELECT
-                checkpoint, epoch, timestamp_ms, cumulative_addresses, cumulative_active_addresses, daily_active_addresses
-              FROM ranked_rows
-              WHERE row_num = 1 ORDER BY epoch {}",
-              if is_descending { "DESC" } else { "ASC" },
-        );
+        let epoch_addr_stats_query = epoch_address_stats_sql(is_descending);
 
         let db_addr_stats = read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(
             epoch_addr_stats_query
@@ -1733,40 +1658,14 @@ impl PgIndexerStore {
         let peak_tps_30d = self.calculate_peak_tps_30d(current_checkpoint, last_cp_timestamp_ms)?;
         let real_time_tps = self.calculate_real_time_tps(current_checkpoint)?;
 
-        let (
-            rolling_tx_delta,
-            rolling_tx_blocks_delta,
-            rolling_successful_tx_delta,
-            rolling_successful_tx_blocks_delta,
-        ) = cps.iter().fold(
-            (0, 0, 0, 0),
-            |(tx_delta, tx_blocks_delta, successful_tx_delta, successful_tx_blocks_delta), cp| {
-                (
-                    tx_delta + cp.total_transactions,
-                    tx_blocks_delta + cp.total_transaction_blocks,
-                    successful_tx_delta + cp.total_successful_transactions,
-                    successful_tx_blocks_delta + cp.total_successful_transaction_blocks,
-                )
-            },
-        );
-
-        Ok(CheckpointMetrics {
-            checkpoint: current_checkpoint,
-            epoch: last_cp.epoch,
+        Ok(rolling_checkpoint_metrics(
+            current_checkpoint,
+            last_cp.epoch,
+            last_checkpoint_metrics,
+            cps,
             real_time_tps,
             peak_tps_30d,
-            rolling_total_transactions: last_checkpoint_metrics.rolling_total_transactions
-                + rolling_tx_delta,
-            rolling_total_transaction_blocks: last_checkpoint_metrics
-                .rolling_total_transaction_blocks
-                + rolling_tx_blocks_delta,
-            rolling_total_successful_transactions: last_checkpoint_metrics
-                .rolling_total_successful_transactions
-                + rolling_successful_tx_delta,
-            rolling_total_successful_transaction_blocks: last_checkpoint_metrics
-                .rolling_total_successful_transaction_blocks
-                + rolling_successful_tx_blocks_delta,
-        })
+        ))
     }
 
     fn persist_checkpoint_metrics(
@@ -1782,6 +1681,167 @@ impl PgIndexerStore {
         Ok(())
     }
 
+    fn get_processor_watermark(&self, processor_name: &str) -> Result<i64, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            processor_watermarks::dsl::processor_watermarks
+                .select(processor_watermarks::checkpoint_sequence_number)
+                .filter(processor_watermarks::processor_name.eq(processor_name))
+                .first::<i64>(conn)
+                .optional()
+                // -1 to differentiate between no checkpoints and the first checkpoint
+                .map(|o| o.unwrap_or(-1))
+        })
+        .context(&format!(
+            "Failed reading watermark of processor {processor_name} from PostgresDB"
+        ))
+    }
+
+    fn persist_processor_watermark(
+        &self,
+        processor_name: &str,
+        checkpoint: i64,
+    ) -> Result<(), IndexerError> {
+        let watermark = ProcessorWatermark {
+            processor_name: processor_name.to_string(),
+            checkpoint_sequence_number: checkpoint,
+        };
+        transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::insert_into(processor_watermarks::table)
+                .values(&watermark)
+                .on_conflict(processor_watermarks::processor_name)
+                .do_update()
+                .set(
+                    processor_watermarks::checkpoint_sequence_number
+                        .eq(excluded(processor_watermarks::checkpoint_sequence_number)),
+                )
+                .execute(conn)
+        })
+        .context(&format!(
+            "Failed persisting watermark of processor {processor_name} to PostgresDB"
+        ))?;
+        Ok(())
+    }
+
+    /// data retention methods
+    fn get_prune_cutoff(&self, epochs_to_keep: u64) -> Result<Option<PruneCutoff>, IndexerError> {
+        let current_epoch = read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .select(max(epochs::epoch))
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed reading current epoch from PostgresDB")?;
+        let cutoff_epoch = match current_epoch {
+            Some(current_epoch) if current_epoch + 1 > epochs_to_keep as i64 => {
+                current_epoch + 1 - epochs_to_keep as i64
+            }
+            _ => return Ok(None),
+        };
+        let cutoff = read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .select((epochs::first_checkpoint_id, epochs::epoch_start_timestamp))
+                .filter(epochs::epoch.eq(cutoff_epoch))
+                .first::<(i64, i64)>(conn)
+                .optional()
+        })
+        .context(&format!(
+            "Failed reading epoch {cutoff_epoch} from PostgresDB"
+        ))?;
+        Ok(cutoff.map(|(checkpoint, timestamp_ms)| PruneCutoff {
+            epoch: cutoff_epoch,
+            checkpoint,
+            timestamp_ms,
+        }))
+    }
+
+    fn drop_pruned_partitions(
+        &self,
+        table: PrunableTable,
+        cutoff: &PruneCutoff,
+    ) -> Result<Vec<String>, IndexerError> {
+        #[derive(QueryableByName, Debug, Clone)]
+        struct TablePartition {
+            #[diesel(sql_type = VarChar)]
+            partition_name: String,
+        }
+
+        // NOTE: objects_history partitions also hold the latest versions of objects which have
+        // not changed since, its rows are pruned one by one to keep them.
+        if table == PrunableTable::ObjectsHistory {
+            return Ok(vec![]);
+        }
+        let table_name = table.table_name();
+        let partitions: Vec<TablePartition> = read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(GET_TABLE_PARTITIONS_SQL)
+                .bind::<VarChar, _>(table_name)
+                .load(conn)
+        })
+        .context(&format!(
+            "Failed reading partitions of table {table_name} from PostgresDB"
+        ))?;
+        // epoch partitions are named `<table>_partition_<epoch>`,
+        // the fast-path partition of uncheckpointed rows is not one of them.
+        let partition_prefix = format!("{table_name}_partition_");
+        let mut epoch_partitions: Vec<(i64, String)> = partitions
+            .into_iter()
+            .filter_map(|p| {
+                p.partition_name
+                    .strip_prefix(&partition_prefix)
+                    .and_then(|epoch| epoch.parse().ok())
+                    .map(|epoch| (epoch, p.partition_name.clone()))
+            })
+            .collect();
+        epoch_partitions.sort();
+        // the last partition is open-ended and receives new rows, it is never dropped.
+        epoch_partitions.pop();
+        let expired_partitions: Vec<String> = epoch_partitions
+            .into_iter()
+            .filter(|(epoch, _)| *epoch < cutoff.epoch)
+            .map(|(_, partition_name)| partition_name)
+            .collect();
+        if expired_partitions.is_empty() {
+            return Ok(vec![]);
+        }
+        transactional_blocking!(&self.blocking_cp, |conn| {
+            for partition_name in &expired_partitions {
+                diesel::sql_query(format!("DROP TABLE {partition_name};")).execute(conn)?;
+            }
+            Ok::<(), diesel::result::Error>(())
+        })
+        .context(&format!(
+            "Failed dropping expired partitions of table {table_name} from PostgresDB"
+        ))?;
+        Ok(expired_partitions)
+    }
+
+    fn prune_table(
+        &self,
+        table: PrunableTable,
+        cutoff: &PruneCutoff,
+        batch_size: usize,
+    ) -> Result<usize, IndexerError> {
+        let (prune_query, cutoff_value) = prune_table_query(table, cutoff);
+        transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(&prune_query)
+                .bind::<BigInt, _>(cutoff_value)
+                .bind::<BigInt, _>(batch_size as i64)
+                .execute(conn)
+        })
+        .context(&format!(
+            "Failed pruning table {} before epoch {} from PostgresDB",
+            table.table_name(),
+            cutoff.epoch
+        ))
+    }
+
+    fn prune_objects_history_versions(&self, batch_size: usize) -> Result<usize, IndexerError> {
+        transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(PRUNE_OBJECTS_HISTORY_VERSIONS_SQL)
+                .bind::<BigInt, _>(batch_size as i64)
+                .execute(conn)
+        })
+        .context("Failed pruning old object versions of objects_history from PostgresDB")
+    }
+
     /// TPS related methods
     fn calculate_real_time_tps(&self, current_checkpoint: i64) -> Result<f64, IndexerError> {
         let real_time_tps_query = format!(
@@ -1872,582 +1932,9 @@ impl PgIndexerStore {
             .context("Failed calculating peak TPS in 30 days from PostgresDB")?;
         Ok(peak_tps_30d.tps)
     }
-
-    async fn spawn_blocking<F, R>(&self, f: F) -> Result<R, IndexerError>
-    where
-        F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
-        R: Send + 'static,
-    {
-        let this = self.clone();
-        tokio::task::spawn_blocking(move || f(this))
-            .await
-            .map_err(Into::into)
-            .and_then(std::convert::identity)
-    }
 }
 
-#[async_trait]
-impl IndexerStore for PgIndexerStore {
-    type ModuleCache = SyncModuleCache<IndexerModuleResolver>;
-
-    async fn get_latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError> {
-        self.spawn_blocking(|this| this.get_latest_checkpoint_sequence_number())
-            .await
-    }
-
-    async fn get_checkpoint(
-        &self,
-        id: CheckpointId,
-    ) -> Result<sui_json_rpc_types::Checkpoint, IndexerError> {
-        self.spawn_blocking(move |this| this.get_checkpoint(id))
-            .await
-    }
-
-    async fn get_checkpoints(
-        &self,
-        cursor: Option<CheckpointId>,
-        limit: usize,
-    ) -> Result<Vec<sui_json_rpc_types::Checkpoint>, IndexerError> {
-        self.spawn_blocking(move |this| this.get_checkpoints(cursor, limit))
-            .await
-    }
-
-    async fn get_indexer_checkpoint(&self) -> Result<Checkpoint, IndexerError> {
-        self.spawn_blocking(|this| this.get_indexer_checkpoint())
-            .await
-    }
-
-    async fn get_indexer_checkpoints(
-        &self,
-        cursor: i64,
-        limit: usize,
-    ) -> Result<Vec<Checkpoint>, IndexerError> {
-        self.spawn_blocking(move |this| this.get_indexer_checkpoints(cursor, limit))
-            .await
-    }
-
-    async fn get_checkpoint_sequence_number(
-        &self,
-        digest: CheckpointDigest,
-    ) -> Result<CheckpointSequenceNumber, IndexerError> {
-        self.spawn_blocking(move |this| this.get_checkpoint_sequence_number(digest))
-            .await
-    }
-
-    async fn get_event(&self, id: EventID) -> Result<Event, IndexerError> {
-        self.spawn_blocking(move |this| this.get_event(id)).await
-    }
-
-    async fn get_events(
-        &self,
-        query: EventFilter,
-        cursor: Option<EventID>,
-        limit: Option<usize>,
-        descending_order: bool,
-    ) -> Result<EventPage, IndexerError> {
-        self.spawn_blocking(move |this| this.get_events(query, cursor, limit, descending_order))
-            .await
-    }
-
-    async fn get_object(
-        &self,
-        object_id: ObjectID,
-        version: Option<SequenceNumber>,
-    ) -> Result<ObjectRead, IndexerError> {
-        self.spawn_blocking(move |this| this.get_object(object_id, version))
-            .await
-    }
-
-    async fn query_objects_history(
-        &self,
-        filter: SuiObjectDataFilter,
-        at_checkpoint: CheckpointSequenceNumber,
-        cursor: Option<ObjectID>,
-        limit: usize,
-    ) -> Result<Vec<ObjectRead>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.query_objects_history(filter, at_checkpoint, cursor, limit)
-        })
-        .await
-    }
-
-    async fn query_latest_objects(
-        &self,
-        filter: SuiObjectDataFilter,
-        cursor: Option<ObjectID>,
-        limit: usize,
-    ) -> Result<Vec<ObjectRead>, IndexerError> {
-        self.spawn_blocking(move |this| this.query_latest_objects(filter, cursor, limit))
-            .await
-    }
-
-    async fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError> {
-        self.spawn_blocking(move |this| this.get_total_transaction_number_from_checkpoints())
-            .await
-    }
-
-    async fn get_transaction_by_digest(
-        &self,
-        tx_digest: &str,
-    ) -> Result<Transaction, IndexerError> {
-        let tx_digest = tx_digest.to_owned();
-        self.spawn_blocking(move |this| this.get_transaction_by_digest(&tx_digest))
-            .await
-    }
-
-    async fn multi_get_transactions_by_digests(
-        &self,
-        tx_digests: &[String],
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        let tx_digests = tx_digests.to_owned();
-        self.spawn_blocking(move |this| this.multi_get_transactions_by_digests(&tx_digests))
-            .await
-    }
-
-    async fn compose_sui_transaction_block_response(
-        &self,
-        tx: Transaction,
-        options: Option<&SuiTransactionBlockResponseOptions>,
-    ) -> Result<SuiTransactionBlockResponse, IndexerError> {
-        let options = options.cloned();
-        self.spawn_blocking(move |this| {
-            this.compose_sui_transaction_block_response(tx, options.as_ref())
-        })
-        .await
-    }
-
-    async fn get_all_transaction_page(
-        &self,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_all_transaction_page(start_sequence, limit, is_descending)
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_checkpoint(
-        &self,
-        checkpoint_sequence_number: i64,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_checkpoint(
-                checkpoint_sequence_number,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_transaction_kinds(
-        &self,
-        kind_names: Vec<String>,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_transaction_kinds(
-                kind_names,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_sender_address(
-        &self,
-        sender_address: String,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_sender_address(
-                sender_address,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_recipient_address(
-        &self,
-        sender_address: Option<SuiAddress>,
-        recipient_address: SuiAddress,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_recipient_address(
-                sender_address,
-                recipient_address,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_address(
-        &self,
-        address: SuiAddress,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_address(address, start_sequence, limit, is_descending)
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_input_object(
-        &self,
-        object_id: ObjectID,
-        version: Option<i64>,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_input_object(
-                object_id,
-                version,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_changed_object(
-        &self,
-        object_id: ObjectID,
-        version: Option<i64>,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_changed_object(
-                object_id,
-                version,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_page_by_move_call(
-        &self,
-        package: ObjectID,
-        module: Option<Identifier>,
-        function: Option<Identifier>,
-        start_sequence: Option<i64>,
-        limit: usize,
-        is_descending: bool,
-    ) -> Result<Vec<Transaction>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_page_by_move_call(
-                package,
-                module,
-                function,
-                start_sequence,
-                limit,
-                is_descending,
-            )
-        })
-        .await
-    }
-
-    async fn get_transaction_sequence_by_digest(
-        &self,
-        tx_digest: Option<String>,
-        is_descending: bool,
-    ) -> Result<Option<i64>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_transaction_sequence_by_digest(tx_digest, is_descending)
-        })
-        .await
-    }
-
-    async fn get_move_call_sequence_by_digest(
-        &self,
-        tx_digest: Option<String>,
-        is_descending: bool,
-    ) -> Result<Option<i64>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_move_call_sequence_by_digest(tx_digest, is_descending)
-        })
-        .await
-    }
-
-    async fn get_input_object_sequence_by_digest(
-        &self,
-        tx_digest: Option<String>,
-        is_descending: bool,
-    ) -> Result<Option<i64>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_input_object_sequence_by_digest(tx_digest, is_descending)
-        })
-        .await
-    }
-
-    async fn get_changed_object_sequence_by_digest(
-        &self,
-        tx_digest: Option<String>,
-        is_descending: bool,
-    ) -> Result<Option<i64>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_changed_object_sequence_by_digest(tx_digest, is_descending)
-        })
-        .await
-    }
-
-    async fn get_recipient_sequence_by_digest(
-        &self,
-        tx_digest: Option<String>,
-        is_descending: bool,
-    ) -> Result<Option<i64>, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.get_recipient_sequence_by_digest(tx_digest, is_descending)
-        })
-        .await
-    }
-
-    async fn get_network_metrics(&self) -> Result<NetworkMetrics, IndexerError> {
-        self.spawn_blocking(move |this| this.get_network_metrics())
-            .await
-    }
-
-    async fn get_move_call_metrics(&self) -> Result<MoveCallMetrics, IndexerError> {
-        self.spawn_blocking(move |this| this.get_move_call_metrics())
-            .await
-    }
-
-    async fn persist_fast_path(
-        &self,
-        tx: Transaction,
-        tx_object_changes: TransactionObjectChanges,
-    ) -> Result<usize, IndexerError> {
-        self.spawn_blocking(move |this| this.persist_fast_path(tx, tx_object_changes))
-            .await
-    }
-
-    async fn persist_checkpoint_transactions(
-        &self,
-        checkpoint: &Checkpoint,
-        transactions: &[Transaction],
-    ) -> Result<usize, IndexerError> {
-        let checkpoint = checkpoint.to_owned();
-        let transactions = transactions.to_owned();
-        self.spawn_blocking(move |this| {
-            this.persist_checkpoint_transactions(&checkpoint, &transactions)
-        })
-        .await
-    }
-
-    async fn persist_object_changes(
-        &self,
-        tx_object_changes: &[TransactionObjectChanges],
-        object_mutation_latency: Histogram,
-        object_deletion_latency: Histogram,
-    ) -> Result<(), IndexerError> {
-        let tx_object_changes = tx_object_changes.to_owned();
-        self.spawn_blocking(move |this| {
-            this.persist_object_changes(
-                &tx_object_changes,
-                object_mutation_latency,
-                object_deletion_latency,
-            )
-        })
-        .await
-    }
-
-    async fn persist_events(&self, events: &[Event]) -> Result<(), IndexerError> {
-        let events = events.to_owned();
-        self.spawn_blocking(move |this| this.persist_events(&events))
-            .await
-    }
-
-    async fn persist_addresses(
-        &self,
-        addresses: &[Address],
-        active_addresses: &[ActiveAddress],
-    ) -> Result<(), IndexerError> {
-        let addresses = addresses.to_owned();
-        let active_addresses = active_addresses.to_owned();
-        self.spawn_blocking(move |this| this.persist_addresses(&addresses, &active_addresses))
-            .await
-    }
-
-    async fn persist_packages(&self, packages: &[Package]) -> Result<(), IndexerError> {
-        let packages = packages.to_owned();
-        self.spawn_blocking(move |this| this.persist_packages(&packages))
-            .await
-    }
-
-    async fn persist_transaction_index_tables(
-        &self,
-        input_objects: &[InputObject],
-        changed_objects: &[ChangedObject],
-        move_calls: &[MoveCall],
-        recipients: &[Recipient],
-    ) -> Result<(), IndexerError> {
-        let input_objects = input_objects.to_owned();
-        let changed_objects = changed_objects.to_owned();
-        let move_calls = move_calls.to_owned();
-        let recipients = recipients.to_owned();
-        self.spawn_blocking(move |this| {
-            this.persist_transaction_index_tables(
-                &input_objects,
-                &changed_objects,
-                &move_calls,
-                &recipients,
-            )
-        })
-        .await
-    }
-
-    async fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError> {
-        let data = data.to_owned();
-        self.spawn_blocking(move |this| this.persist_epoch(&data))
-            .await
-    }
-
-    async fn get_network_total_transactions_previous_epoch(
-        &self,
-        epoch: i64,
-    ) -> Result<i64, IndexerError> {
-        self.spawn_blocking(move |this| this.get_network_total_transactions_previous_epoch(epoch))
-            .await
-    }
-
-    async fn get_epochs(
-        &self,
-        cursor: Option<EpochId>,
-        limit: usize,
-        descending_order: Option<bool>,
-    ) -> Result<Vec<EpochInfo>, IndexerError> {
-        self.spawn_blocking(move |this| this.get_epochs(cursor, limit, descending_order))
-            .await
-    }
-
-    async fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError> {
-        self.spawn_blocking(move |this| this.get_current_epoch())
-            .await
-    }
-
-    fn module_cache(&self) -> &Self::ModuleCache {
-        &self.module_cache
-    }
-
-    fn indexer_metrics(&self) -> &IndexerMetrics {
-        &self.metrics
-    }
-
-    async fn get_last_address_processed_checkpoint(&self) -> Result<i64, IndexerError> {
-        self.spawn_blocking(move |this| this.get_last_address_processed_checkpoint())
-            .await
-    }
-
-    async fn calculate_address_stats(&self, checkpoint: i64) -> Result<AddressStats, IndexerError> {
-        self.spawn_blocking(move |this| this.calculate_address_stats(checkpoint))
-            .await
-    }
-
-    async fn persist_address_stats(&self, addr_stats: &AddressStats) -> Result<(), IndexerError> {
-        let addr_stats = addr_stats.to_owned();
-        self.spawn_blocking(move |this| this.persist_address_stats(&addr_stats))
-            .await
-    }
-
-    async fn get_latest_address_stats(&self) -> Result<AddressStats, IndexerError> {
-        self.spawn_blocking(move |this| this.get_latest_address_stats())
-            .await
-    }
-
-    async fn get_checkpoint_address_stats(
-        &self,
-        checkpoint: i64,
-    ) -> Result<AddressStats, IndexerError> {
-        self.spawn_blocking(move |this| this.get_checkpoint_address_stats(checkpoint))
-            .await
-    }
-
-    async fn get_all_epoch_address_stats(
-        &self,
-        descending_order: Option<bool>,
-    ) -> Result<Vec<AddressStats>, IndexerError> {
-        self.spawn_blocking(move |this| this.get_all_epoch_address_stats(descending_order))
-            .await
-    }
-
-    async fn calculate_checkpoint_metrics(
-        &self,
-        current_checkpoint: i64,
-        last_checkpoint_metrics: &CheckpointMetrics,
-        checkpoints: &[Checkpoint],
-    ) -> Result<CheckpointMetrics, IndexerError> {
-        let last_checkpoint_metrics = last_checkpoint_metrics.to_owned();
-        let checkpoints = checkpoints.to_owned();
-        self.spawn_blocking(move |this| {
-            this.calculate_checkpoint_metrics(
-                current_checkpoint,
-                &last_checkpoint_metrics,
-                &checkpoints,
-            )
-        })
-        .await
-    }
-
-    async fn persist_checkpoint_metrics(
-        &self,
-        checkpoint_metrics: &CheckpointMetrics,
-    ) -> Result<(), IndexerError> {
-        let checkpoint_metrics = checkpoint_metrics.to_owned();
-        self.spawn_blocking(move |this| this.persist_checkpoint_metrics(&checkpoint_metrics))
-            .await
-    }
-
-    async fn get_latest_checkpoint_metrics(&self) -> Result<CheckpointMetrics, IndexerError> {
-        self.spawn_blocking(move |this| this.get_latest_checkpoint_metrics())
-            .await
-    }
-
-    async fn calculate_real_time_tps(&self, current_checkpoint: i64) -> Result<f64, IndexerError> {
-        self.spawn_blocking(move |this| this.calculate_real_time_tps(current_checkpoint))
-            .await
-    }
-
-    async fn calculate_peak_tps_30d(
-        &self,
-        current_checkpoint: i64,
-        current_timestamp_ms: i64,
-    ) -> Result<f64, IndexerError> {
-        self.spawn_blocking(move |this| {
-            this.calculate_peak_tps_30d(current_checkpoint, current_timestamp_ms)
-        })
-        .await
-    }
-}
+impl_indexer_store!(PgIndexerStore, IndexerModuleResolver);
 
 fn persist_transaction_object_changes(
     conn: &mut PgConnection,
@@ -2558,6 +2045,49 @@ fn persist_transaction_object_changes(
     Ok(0)
 }
 
+// Returns the query deleting at most $2 rows before the cutoff $1 from the table, and the cutoff.
+fn prune_table_query(table: PrunableTable, cutoff: &PruneCutoff) -> (String, i64) {
+    let table_name = table.table_name();
+    match table {
+        PrunableTable::Events => (
+            "DELETE FROM events WHERE id IN \
+             (SELECT id FROM events WHERE event_time_ms < $1 LIMIT $2);"
+                .to_string(),
+            cutoff.timestamp_ms,
+        ),
+        PrunableTable::Checkpoints => (
+            "DELETE FROM checkpoints WHERE sequence_number IN \
+             (SELECT sequence_number FROM checkpoints WHERE sequence_number < $1 LIMIT $2);"
+                .to_string(),
+            cutoff.checkpoint,
+        ),
+        // NOTE: the latest version of each object before the cutoff is kept, as it is still the
+        // object at the retained checkpoints until a newer version of the object.
+        // checkpoint is -1 for objects of transactions which are not checkpointed yet.
+        PrunableTable::ObjectsHistory => (
+            "DELETE FROM objects_history WHERE (object_id, version, checkpoint) IN \
+             (SELECT h.object_id, h.version, h.checkpoint FROM objects_history h \
+             WHERE h.checkpoint >= 0 AND h.checkpoint < $1 AND EXISTS \
+             (SELECT 1 FROM objects_history n WHERE n.object_id = h.object_id \
+             AND n.version > h.version AND n.checkpoint >= 0 AND n.checkpoint <= $1) \
+             LIMIT $2);"
+                .to_string(),
+            cutoff.checkpoint,
+        ),
+        PrunableTable::Transactions
+        | PrunableTable::InputObjects
+        | PrunableTable::ChangedObjects
+        | PrunableTable::MoveCalls
+        | PrunableTable::Recipients => (
+            format!(
+                "DELETE FROM {table_name} WHERE id IN \
+                 (SELECT id FROM {table_name} WHERE checkpoint_sequence_number < $1 LIMIT $2);"
+            ),
+            cutoff.checkpoint,
+        ),
+    }
+}
+
 #[derive(Clone)]
 struct PartitionManager {
     cp: PgConnectionPool,
@@ -8,6 +8,13 @@ pub trait DBFilter<C> {
     fn to_objects_history_sql(&self, cursor: Option<C>, limit: usize, columns: Vec<&str>)
         -> String;
     fn to_latest_objects_sql(&self, cursor: Option<C>, limit: usize, columns: Vec<&str>) -> String;
+    /// Same as `to_objects_history_sql`, for SQLite which does not support `DISTINCT ON`.
+    fn to_sqlite_objects_history_sql(
+        &self,
+        cursor: Option<C>,
+        limit: usize,
+        columns: Vec<&str>,
+    ) -> String;
 }
 
 impl DBFilter<ObjectID> for SuiObjectDataFilter {
@@ -53,6 +60,50 @@ LIMIT {limit};"
         )
     }
 
+    fn to_sqlite_objects_history_sql(
+        &self,
+        cursor: Option<ObjectID>,
+        limit: usize,
+        columns: Vec<&str>,
+    ) -> String {
+        let inner_clauses = to_clauses(self);
+        let inner_clauses = if let Some(inner_clauses) = inner_clauses {
+            format!("\n      AND {inner_clauses}")
+        } else {
+            "".to_string()
+        };
+        let outer_clauses = to_outer_clauses(self);
+        let outer_clauses = if let Some(outer_clauses) = outer_clauses {
+            format!("\nAND {outer_clauses}")
+        } else {
+            "".to_string()
+        };
+        let cursor = if let Some(cursor) = cursor {
+            format!("\n      AND o.object_id > '{cursor}'")
+        } else {
+            "".to_string()
+        };
+
+        let columns = columns
+            .iter()
+            .map(|c| format!("t1.{c}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // NOTE: keep the first row of each object in the same order as the `DISTINCT ON`
+        // of the Postgres query, rows from checkpoint are picked over the ones from fast-path.
+        format!(
+            "SELECT {columns}
+FROM (SELECT o.*,
+             ROW_NUMBER() OVER (PARTITION BY o.object_id ORDER BY o.version, o.checkpoint DESC) AS row_num
+      FROM objects_history o
+      WHERE o.checkpoint <= ?{cursor}{inner_clauses}) AS t1
+WHERE t1.row_num = 1
+AND t1.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted'){outer_clauses}
+ORDER BY t1.object_id
+LIMIT {limit};"
+        )
+    }
+
     fn to_latest_objects_sql(
         &self,
         cursor: Option<ObjectID>,
@@ -245,6 +296,36 @@ LIMIT 100;";
         );
     }
 
+    #[test]
+    fn test_sqlite_address_filter() {
+        let address = SuiAddress::from_str(
+            "0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381",
+        )
+        .unwrap();
+        let filter = SuiObjectDataFilter::AddressOwner(address);
+
+        let expected_sql = "SELECT t1.epoch, t1.object_id
+FROM (SELECT o.*,
+             ROW_NUMBER() OVER (PARTITION BY o.object_id ORDER BY o.version, o.checkpoint DESC) AS row_num
+      FROM objects_history o
+      WHERE o.checkpoint <= ?
+      AND o.object_id > '0x0000000000000000000000000000000000000000000000000000000000000005'
+      AND ((o.owner_type = 'address_owner' AND o.owner_address = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381') OR (o.old_owner_type = 'address_owner' AND o.old_owner_address = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381'))) AS t1
+WHERE t1.row_num = 1
+AND t1.object_status NOT IN ('deleted', 'wrapped', 'unwrapped_then_deleted')
+AND t1.owner_address = '0x92dd4d9b0150c251661d821583ef078024ae9e9ee11063e216500861eec7f381'
+ORDER BY t1.object_id
+LIMIT 100;";
+        assert_eq!(
+            expected_sql,
+            filter.to_sqlite_objects_history_sql(
+                Some(ObjectID::from_single_byte(5)),
+                100,
+                vec!["epoch", "object_id"]
+            )
+        );
+    }
+
     #[test]
     fn test_move_module_filter() {
         let filter = SuiObjectDataFilter::MoveModule {
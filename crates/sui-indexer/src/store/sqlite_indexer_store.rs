@@ -0,0 +1,2450 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use cached::proc_macro::once;
+use diesel::dsl::{count, max};
+use diesel::sql_types::{BigInt, VarChar};
+use diesel::sqlite::SqliteConnection;
+use diesel::upsert::excluded;
+use diesel::ExpressionMethods;
+use diesel::{OptionalExtension, QueryableByName};
+use diesel::{QueryDsl, RunQueryDsl};
+use move_bytecode_utils::module_cache::SyncModuleCache;
+use move_core_types::identifier::Identifier;
+use prometheus::Histogram;
+use tracing::info;
+
+use sui_json_rpc_types::{
+    CheckpointId, EpochInfo, EventFilter, EventPage, MoveCallMetrics, NetworkMetrics, SuiEvent,
+    SuiObjectDataFilter,
+};
+use sui_json_rpc_types::{SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::committee::EpochId;
+use sui_types::digests::CheckpointDigest;
+use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::object::ObjectRead;
+
+use crate::errors::{Context, IndexerError};
+use crate::metrics::IndexerMetrics;
+use crate::models::addresses::{ActiveAddress, Address, AddressStats, DBAddressStats};
+use crate::models::checkpoint_metrics::{CheckpointMetrics, Tps};
+use crate::models::checkpoints::Checkpoint;
+use crate::models::epoch::DBEpochInfo;
+use crate::models::events::Event;
+use crate::models::network_metrics::{DBMoveCallMetrics, DBNetworkMetrics};
+use crate::models::objects::Object;
+use crate::models::packages::Package;
+use crate::models::processor_watermarks::ProcessorWatermark;
+use crate::models::system_state::DBValidatorSummary;
+use crate::models::transaction_index::{ChangedObject, InputObject, MoveCall, Recipient};
+use crate::models::transactions::Transaction;
+use crate::schema::{
+    active_addresses, address_stats, addresses, changed_objects, checkpoint_metrics, events,
+    input_objects, move_calls, processor_watermarks, recipients, validators,
+};
+use crate::sqlite_schema::{
+    checkpoints, epochs, objects, objects_history, packages, system_states, transactions,
+};
+use crate::store::common::{
+    compose_transaction_block_response, end_of_epoch_data, epoch_address_stats_sql,
+    event_query_limit, impl_indexer_store, into_epoch_infos, into_event_page,
+    into_move_call_metrics, rolling_checkpoint_metrics, transaction_digests_by_address_sql,
+    transaction_digests_by_move_call_sql, transaction_digests_by_object_sql,
+    transaction_digests_by_recipient_sql, TempDigestTable,
+};
+use crate::store::diesel_marco::{sqlite_read_only_blocking, sqlite_transactional_blocking};
+use crate::store::module_resolver::SqliteModuleResolver;
+use crate::store::query::DBFilter;
+use crate::store::sqlite_models::{
+    SqliteCheckpoint, SqliteEpochInfo, SqliteObject, SqlitePackage, SqliteSystemStateSummary,
+    SqliteTransaction,
+};
+use crate::store::TransactionObjectChanges;
+use crate::store::{
+    IndexerStore, PrunableTable, PruneCutoff, TemporaryBackfillStore, TemporaryEpochStore,
+};
+use crate::SqliteConnectionPool;
+
+// Deletes at most ? versions of objects_history which are older than the latest object version.
+const PRUNE_OBJECTS_HISTORY_VERSIONS_SQL: &str = r#"
+DELETE FROM objects_history
+WHERE rowid IN
+      (SELECT h.rowid
+       FROM objects_history h
+                JOIN objects o ON h.object_id = o.object_id
+       WHERE h.version < o.version
+       LIMIT ?);
+"#;
+
+/// An [`IndexerStore`] backed by a single SQLite database file, for local test clusters and
+/// small deployments that do not want to run Postgres. Schema is in `migrations_sqlite`.
+#[derive(Clone)]
+pub struct SqliteIndexerStore {
+    blocking_cp: SqliteConnectionPool,
+    module_cache: Arc<SyncModuleCache<SqliteModuleResolver>>,
+    metrics: IndexerMetrics,
+}
+
+impl SqliteIndexerStore {
+    pub fn new(blocking_cp: SqliteConnectionPool, metrics: IndexerMetrics) -> Self {
+        let module_cache = Arc::new(SyncModuleCache::new(SqliteModuleResolver::new(
+            blocking_cp.clone(),
+        )));
+        SqliteIndexerStore {
+            blocking_cp,
+            module_cache,
+            metrics,
+        }
+    }
+
+    fn get_latest_checkpoint_sequence_number(&self) -> Result<i64, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::dsl::checkpoints
+                .select(max(checkpoints::sequence_number))
+                .first::<Option<i64>>(conn)
+                // -1 to differentiate between no checkpoints and the first checkpoint
+                .map(|o| o.unwrap_or(-1))
+        })
+        .context("Failed reading latest checkpoint sequence number from SQLite DB")
+    }
+
+    fn get_checkpoint(
+        &self,
+        id: CheckpointId,
+    ) -> Result<sui_json_rpc_types::Checkpoint, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let cp: SqliteCheckpoint = match id {
+                CheckpointId::SequenceNumber(seq) => checkpoints::dsl::checkpoints
+                    .filter(checkpoints::sequence_number.eq(seq as i64))
+                    .limit(1)
+                    .first(conn),
+                CheckpointId::Digest(digest) => checkpoints::dsl::checkpoints
+                    .filter(checkpoints::checkpoint_digest.eq(digest.base58_encode()))
+                    .limit(1)
+                    .first(conn),
+            }?;
+            let cp = Checkpoint::try_from(cp)?;
+            let end_of_epoch_data = if cp.end_of_epoch {
+                let epoch: SqliteEpochInfo = epochs::dsl::epochs
+                    .filter(epochs::epoch.eq(cp.epoch))
+                    .first(conn)?;
+                let epoch = DBEpochInfo::try_from(epoch)?;
+
+                end_of_epoch_data(
+                    epoch.next_epoch_version,
+                    &epoch.next_epoch_committee,
+                    &epoch.next_epoch_committee_stake,
+                    &epoch.epoch_commitments,
+                )?
+            } else {
+                None
+            };
+            cp.into_rpc(end_of_epoch_data)
+        })
+        .context(format!("Failed reading checkpoint {:?} from SQLite DB", id).as_str())
+    }
+
+    fn get_indexer_checkpoint(&self) -> Result<Checkpoint, IndexerError> {
+        let cp: SqliteCheckpoint = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::dsl::checkpoints
+                .order_by(checkpoints::sequence_number.desc())
+                .limit(1)
+                .first(conn)
+        })
+        .context("Failed reading indexer checkpoint from SQLite DB")?;
+        cp.try_into()
+    }
+
+    fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointId>,
+        limit: usize,
+    ) -> Result<Vec<sui_json_rpc_types::Checkpoint>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let cp_vec: Vec<SqliteCheckpoint> = match cursor {
+                Some(CheckpointId::SequenceNumber(seq)) => checkpoints::dsl::checkpoints
+                    .filter(checkpoints::sequence_number.gt(seq as i64))
+                    .order_by(checkpoints::sequence_number)
+                    .limit(limit as i64)
+                    .load::<SqliteCheckpoint>(conn)?,
+                Some(CheckpointId::Digest(digest)) => Err(anyhow!(
+                    "CheckpointId::Digest: {} is not supported as cursor in get_checkpoints.",
+                    digest
+                ))?,
+                None => checkpoints::dsl::checkpoints
+                    .order_by(checkpoints::sequence_number)
+                    .limit(limit as i64)
+                    .load::<SqliteCheckpoint>(conn)?,
+            };
+            cp_vec
+                .into_iter()
+                .map(|cp| Checkpoint::try_from(cp)?.into_rpc(None))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .context(
+            format!(
+                "Failed reading checkpoints with cursor: {:?} and limit: {} from SQLite DB",
+                cursor, limit
+            )
+            .as_str(),
+        )
+    }
+
+    fn get_indexer_checkpoints(
+        &self,
+        cursor: i64,
+        limit: usize,
+    ) -> Result<Vec<Checkpoint>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::dsl::checkpoints
+                .filter(checkpoints::sequence_number.gt(cursor))
+                .order_by(checkpoints::sequence_number)
+                .limit(limit as i64)
+                .load::<SqliteCheckpoint>(conn)
+        })
+        .context(
+            format!(
+                "Failed reading checkpoints with cursor: {} and limit: {} from SQLite DB",
+                cursor, limit
+            )
+            .as_str(),
+        )?
+        .into_iter()
+        .map(Checkpoint::try_from)
+        .collect()
+    }
+
+    fn get_checkpoint_sequence_number(
+        &self,
+        digest: CheckpointDigest,
+    ) -> Result<CheckpointSequenceNumber, IndexerError> {
+        Ok(
+            sqlite_read_only_blocking!(&self.blocking_cp, |conn| checkpoints::dsl::checkpoints
+                .select(checkpoints::sequence_number)
+                .filter(checkpoints::checkpoint_digest.eq(digest.base58_encode()))
+                .first::<i64>(conn))
+            .context("Failed reading checkpoint seq number from SQLite DB")? as u64,
+        )
+    }
+
+    fn get_event(&self, id: EventID) -> Result<Event, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| events::table
+            .filter(events::dsl::transaction_digest.eq(id.tx_digest.base58_encode()))
+            .filter(events::dsl::event_sequence.eq(id.event_seq as i64))
+            .first::<Event>(conn))
+        .context("Failed reading event from SQLite DB")
+    }
+
+    fn get_events(
+        &self,
+        query: EventFilter,
+        cursor: Option<EventID>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> Result<EventPage, IndexerError> {
+        let mut boxed_query = events::table.into_boxed();
+        match query {
+            EventFilter::All(..) => {}
+            EventFilter::Transaction(digest) => {
+                boxed_query =
+                    boxed_query.filter(events::dsl::transaction_digest.eq(digest.base58_encode()));
+            }
+            EventFilter::MoveModule { package, module } => {
+                boxed_query = boxed_query
+                    .filter(events::dsl::package.eq(package.to_string()))
+                    .filter(events::dsl::module.eq(module.to_string()));
+            }
+            EventFilter::MoveEventType(struct_name) => {
+                boxed_query =
+                    boxed_query.filter(events::dsl::event_type.eq(struct_name.to_string()));
+            }
+            EventFilter::Sender(sender) => {
+                boxed_query = boxed_query.filter(events::dsl::sender.eq(sender.to_string()));
+            }
+            EventFilter::TimeRange {
+                start_time,
+                end_time,
+            } => {
+                boxed_query = boxed_query
+                    .filter(events::dsl::event_time_ms.ge(start_time as i64))
+                    .filter(events::dsl::event_time_ms.lt(end_time as i64));
+            }
+            _ => {
+                return Err(IndexerError::NotSupportedError(format!(
+                    "Filter type [{query:?}] not supported by the Indexer."
+                )))
+            }
+        }
+
+        let query_limit = event_query_limit(limit)?;
+
+        let sqlite_cursor = if let Some(cursor) = cursor {
+            Some(
+                self.get_event(cursor)?
+                    .id
+                    .ok_or_else(|| IndexerError::SqliteReadError("Event ID is None".to_string()))?,
+            )
+        } else {
+            None
+        };
+
+        let events_vec: Vec<Event> = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(sqlite_cursor) = sqlite_cursor {
+                if descending_order {
+                    boxed_query = boxed_query.filter(events::dsl::id.lt(sqlite_cursor));
+                } else {
+                    boxed_query = boxed_query.filter(events::dsl::id.gt(sqlite_cursor));
+                }
+            }
+            if descending_order {
+                boxed_query = boxed_query.order(events::id.desc());
+            } else {
+                boxed_query = boxed_query.order(events::id.asc());
+            }
+            boxed_query.limit(query_limit as i64).load(conn)
+        })
+        .context("Failed reading events from SQLite DB")?;
+
+        let sui_event_vec = events_vec
+            .into_iter()
+            .map(|event| event.try_into(&self.module_cache))
+            .collect::<Result<Vec<SuiEvent>, _>>()?;
+        Ok(into_event_page(sui_event_vec, query_limit))
+    }
+
+    fn get_total_transaction_number_from_checkpoints(&self) -> Result<i64, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::dsl::checkpoints
+                .select(checkpoints::dsl::network_total_transactions)
+                .order(checkpoints::dsl::network_total_transactions.desc())
+                .first::<i64>(conn)
+        })
+        .context("Failed reading total transaction number")
+    }
+
+    fn get_transaction_by_digest(&self, tx_digest: &str) -> Result<Transaction, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            transactions::dsl::transactions
+                .filter(transactions::dsl::transaction_digest.eq(tx_digest))
+                .first::<SqliteTransaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction with digest {tx_digest}"
+        ))?
+        .try_into()
+    }
+
+    fn compose_sui_transaction_block_response(
+        &self,
+        tx: Transaction,
+        options: Option<&SuiTransactionBlockResponseOptions>,
+    ) -> Result<SuiTransactionBlockResponse, IndexerError> {
+        compose_transaction_block_response(tx, options, |tx_digest| {
+            self.get_events(
+                EventFilter::Transaction(tx_digest),
+                None,
+                None,
+                /* descending_order */ false,
+            )
+        })
+    }
+
+    fn multi_get_transactions_by_digests(
+        &self,
+        tx_digests: &[String],
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let transactions = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            transactions::dsl::transactions
+                .filter(transactions::dsl::transaction_digest.eq_any(tx_digests))
+                .load::<SqliteTransaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading transactions with digests {tx_digests:?}"
+        ))?;
+        // sort the transactions by the order of the input digests
+        let digest_indices: BTreeMap<_, _> = tx_digests
+            .iter()
+            .enumerate()
+            .map(|(i, digest)| (digest, i))
+            .collect();
+        let mut sorted_transactions = into_transactions(transactions)?;
+        sorted_transactions.sort_by_key(|tx| digest_indices.get(&tx.transaction_digest));
+        Ok(sorted_transactions)
+    }
+
+    fn get_transaction_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(digest) = &tx_digest {
+                let mut boxed_query = transactions::dsl::transactions
+                    .filter(transactions::dsl::transaction_digest.eq(digest))
+                    .select(transactions::dsl::id)
+                    .into_boxed();
+                if is_descending {
+                    boxed_query = boxed_query.order(transactions::dsl::id.desc());
+                } else {
+                    boxed_query = boxed_query.order(transactions::dsl::id.asc());
+                }
+                Some(boxed_query.first::<i64>(conn))
+            } else {
+                None
+            }
+            .transpose()
+        })
+        .context(&format!(
+            "Failed reading transaction sequence with digest {tx_digest:?}"
+        ))
+    }
+
+    fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        let object = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(version) = version {
+                objects_history::dsl::objects_history
+                    .select((
+                        objects_history::epoch,
+                        objects_history::checkpoint,
+                        objects_history::object_id,
+                        objects_history::version,
+                        objects_history::object_digest,
+                        objects_history::owner_type,
+                        objects_history::owner_address,
+                        objects_history::initial_shared_version,
+                        objects_history::previous_transaction,
+                        objects_history::object_type,
+                        objects_history::object_status,
+                        objects_history::has_public_transfer,
+                        objects_history::storage_rebate,
+                        objects_history::bcs,
+                    ))
+                    .filter(objects_history::object_id.eq(object_id.to_string()))
+                    .filter(objects_history::version.eq(version.value() as i64))
+                    // pick data from checkpoint if available
+                    .order(objects_history::checkpoint.desc())
+                    .first::<SqliteObject>(conn)
+                    .optional()
+            } else {
+                objects::dsl::objects
+                    .filter(objects::dsl::object_id.eq(object_id.to_string()))
+                    .first::<SqliteObject>(conn)
+                    .optional()
+            }
+        })
+        .context(&format!("Failed reading object with id {object_id}"))?;
+
+        match object {
+            None => Ok(ObjectRead::NotExists(object_id)),
+            Some(o) => Object::try_from(o)?.try_into_object_read(&self.module_cache),
+        }
+    }
+
+    fn query_objects_history(
+        &self,
+        filter: SuiObjectDataFilter,
+        at_checkpoint: CheckpointSequenceNumber,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRead>, IndexerError> {
+        let objects = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let columns = vec![
+                "epoch",
+                "checkpoint",
+                "object_id",
+                "version",
+                "object_digest",
+                "owner_type",
+                "owner_address",
+                "initial_shared_version",
+                "previous_transaction",
+                "object_type",
+                "object_status",
+                "has_public_transfer",
+                "storage_rebate",
+                "bcs",
+            ];
+            diesel::sql_query(filter.to_sqlite_objects_history_sql(cursor, limit, columns))
+                .bind::<BigInt, _>(at_checkpoint as i64)
+                .get_results::<SqliteObject>(conn)
+        })?;
+
+        objects
+            .into_iter()
+            .map(|object| Object::try_from(object)?.try_into_object_read(&self.module_cache))
+            .collect()
+    }
+
+    // NOTE: same as the Postgres store, only supports query by address owner
+    fn query_latest_objects(
+        &self,
+        filter: SuiObjectDataFilter,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectRead>, IndexerError> {
+        let columns = vec![
+            "epoch",
+            "checkpoint",
+            "object_id",
+            "version",
+            "object_digest",
+            "owner_type",
+            "owner_address",
+            "initial_shared_version",
+            "previous_transaction",
+            "object_type",
+            "object_status",
+            "has_public_transfer",
+            "storage_rebate",
+            "bcs",
+        ];
+
+        let objects = sqlite_read_only_blocking!(&self.blocking_cp, |conn| diesel::sql_query(
+            filter.to_latest_objects_sql(cursor, limit, columns)
+        )
+        .get_results::<SqliteObject>(conn))?;
+
+        objects
+            .into_iter()
+            .map(|object| Object::try_from(object)?.try_into_object_read(&self.module_cache))
+            .collect()
+    }
+
+    fn get_move_call_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(digest) = &tx_digest {
+                let mut boxed_query = move_calls::dsl::move_calls
+                    .filter(move_calls::dsl::transaction_digest.eq(digest))
+                    .select(move_calls::dsl::id)
+                    .into_boxed();
+                if is_descending {
+                    boxed_query = boxed_query.order(move_calls::dsl::id.desc());
+                } else {
+                    boxed_query = boxed_query.order(move_calls::dsl::id.asc());
+                }
+                Some(boxed_query.first::<i64>(conn))
+            } else {
+                None
+            }
+            .transpose()
+        })
+        .context(&format!(
+            "Failed reading move call sequence with digest {tx_digest:?}"
+        ))
+    }
+
+    fn get_input_object_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(digest) = &tx_digest {
+                let mut boxed_query = input_objects::dsl::input_objects
+                    .filter(input_objects::dsl::transaction_digest.eq(digest))
+                    .select(input_objects::dsl::id)
+                    .into_boxed();
+                if is_descending {
+                    boxed_query = boxed_query.order(input_objects::dsl::id.desc());
+                } else {
+                    boxed_query = boxed_query.order(input_objects::dsl::id.asc());
+                }
+                Some(boxed_query.first::<i64>(conn))
+            } else {
+                None
+            }
+            .transpose()
+        })
+        .context(&format!(
+            "Failed reading input object sequence with digest {tx_digest:?}"
+        ))
+    }
+
+    fn get_changed_object_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(digest) = &tx_digest {
+                let mut boxed_query = changed_objects::dsl::changed_objects
+                    .filter(changed_objects::dsl::transaction_digest.eq(digest))
+                    .select(changed_objects::dsl::id)
+                    .into_boxed();
+                if is_descending {
+                    boxed_query = boxed_query.order(changed_objects::dsl::id.desc());
+                } else {
+                    boxed_query = boxed_query.order(changed_objects::dsl::id.asc());
+                }
+                Some(boxed_query.first::<i64>(conn))
+            } else {
+                None
+            }
+            .transpose()
+        })
+        .context(&format!(
+            "Failed reading changed object sequence with digest {tx_digest:?}"
+        ))
+    }
+
+    fn get_recipient_sequence_by_digest(
+        &self,
+        tx_digest: Option<String>,
+        is_descending: bool,
+    ) -> Result<Option<i64>, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            if let Some(digest) = &tx_digest {
+                let mut boxed_query = recipients::dsl::recipients
+                    .filter(recipients::dsl::transaction_digest.eq(digest))
+                    .select(recipients::dsl::id)
+                    .into_boxed();
+                // NOTE: if the query is ORDER BY id ASC, we want to skip the LAST
+                // recipient of the cursor transaction, thus we should order by id DESC;
+                // Same thing for the other way around.
+                if is_descending {
+                    boxed_query = boxed_query.order(recipients::dsl::id.asc());
+                } else {
+                    boxed_query = boxed_query.order(recipients::dsl::id.desc());
+                }
+                Some(boxed_query.first::<i64>(conn))
+            } else {
+                None
+            }
+            .transpose()
+        })
+        .context(&format!(
+            "Failed reading recipients sequence with digest {tx_digest:?}"
+        ))
+    }
+
+    fn get_all_transaction_page(
+        &self,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let transactions = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let mut boxed_query = transactions::dsl::transactions.into_boxed();
+            if let Some(start_sequence) = start_sequence {
+                if is_descending {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.lt(start_sequence));
+                } else {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.gt(start_sequence));
+                }
+            }
+
+            if is_descending {
+                boxed_query = boxed_query.order(transactions::dsl::id.desc());
+            } else {
+                boxed_query = boxed_query.order(transactions::dsl::id.asc());
+            }
+            boxed_query
+                .limit(limit as i64)
+                .load::<SqliteTransaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading all transaction digests with start_sequence {start_sequence:?} and limit {limit}"
+        ))?;
+        into_transactions(transactions)
+    }
+
+    fn get_transaction_page_by_checkpoint(
+        &self,
+        checkpoint_sequence_number: i64,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let transactions = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let mut boxed_query = transactions::dsl::transactions
+                .filter(
+                    transactions::dsl::checkpoint_sequence_number.eq(checkpoint_sequence_number),
+                )
+                .into_boxed();
+            if let Some(start_sequence) = start_sequence {
+                if is_descending {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.lt(start_sequence));
+                } else {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.gt(start_sequence));
+                }
+            }
+
+            if is_descending {
+                boxed_query = boxed_query.order(transactions::dsl::id.desc());
+            } else {
+                boxed_query = boxed_query.order(transactions::dsl::id.asc());
+            }
+            boxed_query
+                .limit(limit as i64)
+                .load::<SqliteTransaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests with checkpoint_sequence_number {checkpoint_sequence_number:?} and start_sequence {start_sequence:?} and limit {limit}"
+        ))?;
+        into_transactions(transactions)
+    }
+
+    fn get_transaction_page_by_transaction_kinds(
+        &self,
+        kinds: Vec<String>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let transactions = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let mut boxed_query = transactions::dsl::transactions
+                .filter(transactions::dsl::transaction_kind.eq_any(kinds.clone()))
+                .into_boxed();
+            if let Some(start_sequence) = start_sequence {
+                if is_descending {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.lt(start_sequence));
+                } else {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.gt(start_sequence));
+                }
+            }
+
+            if is_descending {
+                boxed_query = boxed_query.order(transactions::dsl::id.desc());
+            } else {
+                boxed_query = boxed_query.order(transactions::dsl::id.asc());
+            }
+            boxed_query
+                .limit(limit as i64)
+                .load::<SqliteTransaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests with kind {kinds:?} and start_sequence {start_sequence:?} and limit {limit}"
+        ))?;
+        into_transactions(transactions)
+    }
+
+    fn get_transaction_page_by_sender_address(
+        &self,
+        sender_address: String,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let transactions = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let mut boxed_query = transactions::dsl::transactions
+                .filter(transactions::dsl::sender.eq(sender_address.clone()))
+                .into_boxed();
+            if let Some(start_sequence) = start_sequence {
+                if is_descending {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.lt(start_sequence));
+                } else {
+                    boxed_query = boxed_query.filter(transactions::dsl::id.gt(start_sequence));
+                }
+            }
+
+            if is_descending {
+                boxed_query = boxed_query.order(transactions::dsl::id.desc());
+            } else {
+                boxed_query = boxed_query.order(transactions::dsl::id.asc());
+            }
+            boxed_query
+                .limit(limit as i64)
+                .load::<SqliteTransaction>(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests by sender address {sender_address} with start_sequence {start_sequence:?} and limit {limit}"
+        ))?;
+        into_transactions(transactions)
+    }
+
+    fn get_transaction_page_by_input_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<i64>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = transaction_digests_by_object_sql(
+            "input_objects",
+            object_id,
+            version,
+            start_sequence,
+            limit,
+            is_descending,
+        );
+        let tx_digests: Vec<String> = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(sql_query).load(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests by input object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"
+        ))?
+        .into_iter()
+        .map(|table: TempDigestTable| table.digest_name)
+        .collect();
+        self.multi_get_transactions_by_digests(&tx_digests)
+    }
+
+    fn get_transaction_page_by_changed_object(
+        &self,
+        object_id: ObjectID,
+        version: Option<i64>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = transaction_digests_by_object_sql(
+            "changed_objects",
+            object_id,
+            version,
+            start_sequence,
+            limit,
+            is_descending,
+        );
+        let tx_digests: Vec<String> = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(sql_query).load(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests by changed object ID {object_id} and version {version:?} with start_sequence {start_sequence:?} and limit {limit}"
+        ))?
+        .into_iter()
+        .map(|table: TempDigestTable| table.digest_name)
+        .collect();
+        self.multi_get_transactions_by_digests(&tx_digests)
+    }
+
+    fn get_transaction_page_by_move_call(
+        &self,
+        package_name: ObjectID,
+        module_name: Option<Identifier>,
+        function_name: Option<Identifier>,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query = transaction_digests_by_move_call_sql(
+            package_name,
+            module_name.as_ref(),
+            function_name.as_ref(),
+            start_sequence,
+            limit,
+            is_descending,
+        );
+        let tx_digests: Vec<String> = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(sql_query).load(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests with package_name {} module_name {:?} and function_name {:?} and start_sequence {:?} and limit {}",
+            package_name, module_name, function_name, start_sequence, limit
+        ))?
+        .into_iter()
+        .map(|table: TempDigestTable| table.digest_name)
+        .collect();
+        self.multi_get_transactions_by_digests(&tx_digests)
+    }
+
+    fn get_transaction_page_by_recipient_address(
+        &self,
+        from: Option<SuiAddress>,
+        to: SuiAddress,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query =
+            transaction_digests_by_recipient_sql(from, to, start_sequence, limit, is_descending);
+        let tx_digests: Vec<String> = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(sql_query).load(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests by recipient address {to} with start_sequence {start_sequence:?} and limit {limit}"
+        ))?
+        .into_iter()
+        .map(|table: TempDigestTable| table.digest_name)
+        .collect();
+        self.multi_get_transactions_by_digests(&tx_digests)
+    }
+
+    fn get_transaction_page_by_address(
+        &self,
+        address: SuiAddress,
+        start_sequence: Option<i64>,
+        limit: usize,
+        is_descending: bool,
+    ) -> Result<Vec<Transaction>, IndexerError> {
+        let sql_query =
+            transaction_digests_by_address_sql(address, start_sequence, limit, is_descending);
+        let tx_digests: Vec<String> = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(sql_query).load(conn)
+        })
+        .context(&format!(
+            "Failed reading transaction digests by address {address} with start_sequence {start_sequence:?} and limit {limit}"
+        ))?
+        .into_iter()
+        .map(|table: TempDigestTable| table.digest_name)
+        .collect();
+        self.multi_get_transactions_by_digests(&tx_digests)
+    }
+
+    fn get_network_metrics(&self) -> Result<NetworkMetrics, IndexerError> {
+        get_network_metrics_cached(&self.blocking_cp)
+    }
+
+    fn get_move_call_metrics(&self) -> Result<MoveCallMetrics, IndexerError> {
+        let metrics = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query("SELECT * FROM epoch_move_call_metrics;")
+                .get_results::<DBMoveCallMetrics>(conn)
+        })?;
+
+        Ok(into_move_call_metrics(metrics))
+    }
+
+    fn persist_fast_path(
+        &self,
+        tx: Transaction,
+        tx_object_changes: TransactionObjectChanges,
+    ) -> Result<usize, IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::insert_into(transactions::table)
+                .values(SqliteTransaction::from(&tx))
+                .on_conflict_do_nothing()
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed writing transactions to SQLite DB")?;
+
+            let deleted_objects: Vec<Object> = tx_object_changes
+                .deleted_objects
+                .iter()
+                .map(|deleted_object| deleted_object.clone().into())
+                .collect();
+
+            persist_transaction_object_changes(
+                conn,
+                tx_object_changes.changed_objects,
+                deleted_objects,
+                None,
+                None,
+            )
+        })
+    }
+
+    fn persist_checkpoint_transactions(
+        &self,
+        checkpoint: &Checkpoint,
+        transactions: &[Transaction],
+    ) -> Result<usize, IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            // Commit indexed transactions
+            for transaction in transactions {
+                diesel::insert_into(transactions::table)
+                    .values(SqliteTransaction::from(transaction))
+                    .on_conflict(transactions::transaction_digest)
+                    .do_update()
+                    .set((
+                        transactions::timestamp_ms.eq(excluded(transactions::timestamp_ms)),
+                        transactions::checkpoint_sequence_number
+                            .eq(excluded(transactions::checkpoint_sequence_number)),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing transactions to SQLite DB")?;
+            }
+
+            // Commit indexed checkpoint last, so that if the checkpoint is committed,
+            // all related data have been committed as well.
+            diesel::insert_into(checkpoints::table)
+                .values(SqliteCheckpoint::from(checkpoint))
+                .on_conflict_do_nothing()
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed writing checkpoint to SQLite DB")
+        })
+    }
+
+    fn persist_object_changes(
+        &self,
+        tx_object_changes: &[TransactionObjectChanges],
+        object_mutation_latency: Histogram,
+        object_deletion_latency: Histogram,
+    ) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            let mutated_objects: Vec<Object> = tx_object_changes
+                .iter()
+                .flat_map(|changes| changes.changed_objects.iter().cloned())
+                .collect();
+            let deleted_objects: Vec<Object> = tx_object_changes
+                .iter()
+                .flat_map(|changes| changes.deleted_objects.iter().cloned())
+                .map(|deleted_object| deleted_object.into())
+                .collect();
+            persist_transaction_object_changes(
+                conn,
+                mutated_objects,
+                deleted_objects,
+                Some(object_mutation_latency),
+                Some(object_deletion_latency),
+            )?;
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
+    fn persist_events(&self, events: &[Event]) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            for event in events {
+                diesel::insert_into(events::table)
+                    .values(event)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing events to SQLite DB")?;
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
+    fn persist_addresses(
+        &self,
+        addresses: &[Address],
+        active_addresses: &[ActiveAddress],
+    ) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            for address in addresses {
+                diesel::insert_into(addresses::table)
+                    .values(address)
+                    .on_conflict(addresses::account_address)
+                    .do_update()
+                    .set((
+                        addresses::last_appearance_time
+                            .eq(excluded(addresses::last_appearance_time)),
+                        addresses::last_appearance_tx.eq(excluded(addresses::last_appearance_tx)),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context(
+                        format!(
+                            "Failed writing addresses to SQLite DB with tx {}",
+                            address.last_appearance_tx
+                        )
+                        .as_str(),
+                    )?;
+            }
+            for active_address in active_addresses {
+                diesel::insert_into(active_addresses::table)
+                    .values(active_address)
+                    .on_conflict(active_addresses::account_address)
+                    .do_update()
+                    .set((
+                        active_addresses::last_appearance_time
+                            .eq(excluded(active_addresses::last_appearance_time)),
+                        active_addresses::last_appearance_tx
+                            .eq(excluded(active_addresses::last_appearance_tx)),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context(
+                        format!(
+                            "Failed writing active addresses to SQLite DB with tx {}",
+                            active_address.last_appearance_tx
+                        )
+                        .as_str(),
+                    )?;
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
+    fn persist_packages(&self, packages: &[Package]) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            for package in packages {
+                diesel::insert_into(packages::table)
+                    .values(SqlitePackage::from(package))
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing packages to SQLite DB")?;
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
+    fn persist_transaction_index_tables(
+        &self,
+        input_objects: &[InputObject],
+        changed_objects: &[ChangedObject],
+        move_calls: &[MoveCall],
+        recipients: &[Recipient],
+    ) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            // Commit indexed move calls
+            for move_call in move_calls {
+                diesel::insert_into(move_calls::table)
+                    .values(move_call)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing move_calls to SQLite DB")?;
+            }
+
+            // Commit indexed input objects
+            for input_object in input_objects {
+                diesel::insert_into(input_objects::table)
+                    .values(input_object)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing input_objects to SQLite DB")?;
+            }
+
+            // Commit indexed changed objects
+            for changed_object in changed_objects {
+                diesel::insert_into(changed_objects::table)
+                    .values(changed_object)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing changed_objects to SQLite DB")?;
+            }
+
+            // Commit indexed recipients
+            for recipient in recipients {
+                diesel::insert_into(recipients::table)
+                    .values(recipient)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed writing recipients to SQLite DB")?;
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
+    fn persist_backfill(&self, data: &TemporaryBackfillStore) -> Result<(), IndexerError> {
+        let start = data.checkpoint_range.start as i64;
+        let end = data.checkpoint_range.end as i64;
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            // NOTE: transactions are upserted instead of deleted and re-inserted, to keep
+            // their sequence ids, which transaction pages are ordered by.
+            if let Some(transactions) = &data.transactions {
+                for transaction in transactions {
+                    diesel::insert_into(transactions::table)
+                        .values(SqliteTransaction::from(transaction))
+                        .on_conflict(transactions::transaction_digest)
+                        .do_update()
+                        .set((
+                            transactions::sender.eq(excluded(transactions::sender)),
+                            transactions::recipients.eq(excluded(transactions::recipients)),
+                            transactions::checkpoint_sequence_number
+                                .eq(excluded(transactions::checkpoint_sequence_number)),
+                            transactions::timestamp_ms.eq(excluded(transactions::timestamp_ms)),
+                            transactions::transaction_kind
+                                .eq(excluded(transactions::transaction_kind)),
+                            transactions::transaction_count
+                                .eq(excluded(transactions::transaction_count)),
+                            transactions::execution_success
+                                .eq(excluded(transactions::execution_success)),
+                            transactions::created.eq(excluded(transactions::created)),
+                            transactions::mutated.eq(excluded(transactions::mutated)),
+                            transactions::deleted.eq(excluded(transactions::deleted)),
+                            transactions::unwrapped.eq(excluded(transactions::unwrapped)),
+                            transactions::wrapped.eq(excluded(transactions::wrapped)),
+                            transactions::move_calls.eq(excluded(transactions::move_calls)),
+                            transactions::gas_object_id.eq(excluded(transactions::gas_object_id)),
+                            transactions::gas_object_sequence
+                                .eq(excluded(transactions::gas_object_sequence)),
+                            transactions::gas_object_digest
+                                .eq(excluded(transactions::gas_object_digest)),
+                            transactions::gas_budget.eq(excluded(transactions::gas_budget)),
+                            transactions::total_gas_cost.eq(excluded(transactions::total_gas_cost)),
+                            transactions::computation_cost
+                                .eq(excluded(transactions::computation_cost)),
+                            transactions::storage_cost.eq(excluded(transactions::storage_cost)),
+                            transactions::storage_rebate.eq(excluded(transactions::storage_rebate)),
+                            transactions::non_refundable_storage_fee
+                                .eq(excluded(transactions::non_refundable_storage_fee)),
+                            transactions::gas_price.eq(excluded(transactions::gas_price)),
+                            transactions::raw_transaction
+                                .eq(excluded(transactions::raw_transaction)),
+                            transactions::transaction_content
+                                .eq(excluded(transactions::transaction_content)),
+                            transactions::transaction_effects_content
+                                .eq(excluded(transactions::transaction_effects_content)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling transactions to SQLite DB")?;
+                }
+            }
+
+            if let Some(move_calls) = &data.move_calls {
+                diesel::delete(
+                    move_calls::table
+                        .filter(move_calls::checkpoint_sequence_number.ge(start))
+                        .filter(move_calls::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled move_calls from SQLite DB")?;
+                for move_call in move_calls {
+                    diesel::insert_into(move_calls::table)
+                        .values(move_call)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling move_calls to SQLite DB")?;
+                }
+            }
+
+            if let Some(input_objects) = &data.input_objects {
+                diesel::delete(
+                    input_objects::table
+                        .filter(input_objects::checkpoint_sequence_number.ge(start))
+                        .filter(input_objects::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled input_objects from SQLite DB")?;
+                for input_object in input_objects {
+                    diesel::insert_into(input_objects::table)
+                        .values(input_object)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling input_objects to SQLite DB")?;
+                }
+            }
+
+            if let Some(changed_objects) = &data.changed_objects {
+                diesel::delete(
+                    changed_objects::table
+                        .filter(changed_objects::checkpoint_sequence_number.ge(start))
+                        .filter(changed_objects::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled changed_objects from SQLite DB")?;
+                for changed_object in changed_objects {
+                    diesel::insert_into(changed_objects::table)
+                        .values(changed_object)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling changed_objects to SQLite DB")?;
+                }
+            }
+
+            if let Some(recipients) = &data.recipients {
+                diesel::delete(
+                    recipients::table
+                        .filter(recipients::checkpoint_sequence_number.ge(start))
+                        .filter(recipients::checkpoint_sequence_number.lt(end)),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed deleting backfilled recipients from SQLite DB")?;
+                for recipient in recipients {
+                    diesel::insert_into(recipients::table)
+                        .values(recipient)
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling recipients to SQLite DB")?;
+                }
+            }
+
+            // Commit backfilled checkpoints last, like the checkpoint handler does.
+            if let Some(checkpoints) = &data.checkpoints {
+                for checkpoint in checkpoints {
+                    diesel::insert_into(checkpoints::table)
+                        .values(SqliteCheckpoint::from(checkpoint))
+                        .on_conflict(checkpoints::sequence_number)
+                        .do_update()
+                        .set((
+                            checkpoints::checkpoint_digest
+                                .eq(excluded(checkpoints::checkpoint_digest)),
+                            checkpoints::epoch.eq(excluded(checkpoints::epoch)),
+                            checkpoints::transactions.eq(excluded(checkpoints::transactions)),
+                            checkpoints::previous_checkpoint_digest
+                                .eq(excluded(checkpoints::previous_checkpoint_digest)),
+                            checkpoints::end_of_epoch.eq(excluded(checkpoints::end_of_epoch)),
+                            checkpoints::total_gas_cost.eq(excluded(checkpoints::total_gas_cost)),
+                            checkpoints::total_computation_cost
+                                .eq(excluded(checkpoints::total_computation_cost)),
+                            checkpoints::total_storage_cost
+                                .eq(excluded(checkpoints::total_storage_cost)),
+                            checkpoints::total_storage_rebate
+                                .eq(excluded(checkpoints::total_storage_rebate)),
+                            checkpoints::total_transaction_blocks
+                                .eq(excluded(checkpoints::total_transaction_blocks)),
+                            checkpoints::total_transactions
+                                .eq(excluded(checkpoints::total_transactions)),
+                            checkpoints::total_successful_transaction_blocks
+                                .eq(excluded(checkpoints::total_successful_transaction_blocks)),
+                            checkpoints::total_successful_transactions
+                                .eq(excluded(checkpoints::total_successful_transactions)),
+                            checkpoints::network_total_transactions
+                                .eq(excluded(checkpoints::network_total_transactions)),
+                            checkpoints::timestamp_ms.eq(excluded(checkpoints::timestamp_ms)),
+                            checkpoints::validator_signature
+                                .eq(excluded(checkpoints::validator_signature)),
+                        ))
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed backfilling checkpoints to SQLite DB")?;
+                }
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        Ok(())
+    }
+
+    fn get_network_total_transactions_previous_epoch(
+        &self,
+        epoch: i64,
+    ) -> Result<i64, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoints::table
+                .filter(checkpoints::epoch.eq(epoch - 1))
+                .select(max(checkpoints::network_total_transactions))
+                .first::<Option<i64>>(conn)
+                .map(|o| o.unwrap_or(0))
+        })
+        .context("Failed to count network transactions in previous epoch")
+    }
+
+    fn persist_epoch(&self, data: &TemporaryEpochStore) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            if let Some(last_epoch) = &data.last_epoch {
+                info!("Persisting at the end of epoch {}", last_epoch.epoch);
+                diesel::insert_into(epochs::table)
+                    .values(SqliteEpochInfo::from(last_epoch))
+                    .on_conflict(epochs::epoch)
+                    .do_update()
+                    .set((
+                        epochs::last_checkpoint_id.eq(excluded(epochs::last_checkpoint_id)),
+                        epochs::epoch_end_timestamp.eq(excluded(epochs::epoch_end_timestamp)),
+                        epochs::epoch_total_transactions
+                            .eq(excluded(epochs::epoch_total_transactions)),
+                        epochs::protocol_version.eq(excluded(epochs::protocol_version)),
+                        epochs::next_epoch_version.eq(excluded(epochs::next_epoch_version)),
+                        epochs::next_epoch_committee.eq(excluded(epochs::next_epoch_committee)),
+                        epochs::next_epoch_committee_stake
+                            .eq(excluded(epochs::next_epoch_committee_stake)),
+                        epochs::epoch_commitments.eq(excluded(epochs::epoch_commitments)),
+                        epochs::reference_gas_price.eq(excluded(epochs::reference_gas_price)),
+                        epochs::total_stake.eq(excluded(epochs::total_stake)),
+                        epochs::storage_fund_reinvestment
+                            .eq(excluded(epochs::storage_fund_reinvestment)),
+                        epochs::storage_charge.eq(excluded(epochs::storage_charge)),
+                        epochs::storage_rebate.eq(excluded(epochs::storage_rebate)),
+                        epochs::storage_fund_balance.eq(excluded(epochs::storage_fund_balance)),
+                        epochs::stake_subsidy_amount.eq(excluded(epochs::stake_subsidy_amount)),
+                        epochs::total_gas_fees.eq(excluded(epochs::total_gas_fees)),
+                        epochs::total_stake_rewards_distributed
+                            .eq(excluded(epochs::total_stake_rewards_distributed)),
+                        epochs::leftover_storage_fund_inflow
+                            .eq(excluded(epochs::leftover_storage_fund_inflow)),
+                    ))
+                    .execute(conn)?;
+                info!("Persisted epoch {}", last_epoch.epoch);
+            }
+            diesel::insert_into(system_states::table)
+                .values(SqliteSystemStateSummary::from(&data.system_state))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            for validator in &data.validators {
+                diesel::insert_into(validators::table)
+                    .values(validator)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            Ok::<(), IndexerError>(())
+        })?;
+        info!("Persisting initial state of epoch {}", data.new_epoch.epoch);
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::insert_into(epochs::table)
+                .values(SqliteEpochInfo::from(&data.new_epoch))
+                .on_conflict_do_nothing()
+                .execute(conn)
+        })?;
+        info!("Persisted initial state of epoch {}", data.new_epoch.epoch);
+        Ok(())
+    }
+
+    fn get_epochs(
+        &self,
+        cursor: Option<EpochId>,
+        limit: usize,
+        descending_order: Option<bool>,
+    ) -> Result<Vec<EpochInfo>, IndexerError> {
+        let is_descending = descending_order.unwrap_or_default();
+        let id = cursor
+            .map(|id| id as i64)
+            .unwrap_or(if is_descending { i64::MAX } else { -1 });
+        let mut query = epochs::dsl::epochs.into_boxed();
+        if is_descending {
+            query = query
+                .filter(epochs::epoch.lt(id))
+                .order_by(epochs::epoch.desc());
+        } else {
+            query = query
+                .filter(epochs::epoch.gt(id))
+                .order_by(epochs::epoch.asc());
+        }
+
+        let epoch_info: Vec<SqliteEpochInfo> =
+            sqlite_read_only_blocking!(&self.blocking_cp, |conn| query
+                .limit(limit as i64)
+                .load(conn))
+            .map_err(|e| {
+                IndexerError::SqliteReadError(format!(
+                    "Failed reading epochs from SQLite DB with error {:?}",
+                    e
+                ))
+            })?;
+
+        let validators: Vec<DBValidatorSummary> =
+            sqlite_read_only_blocking!(&self.blocking_cp, |conn| validators::dsl::validators
+                .filter(validators::epoch.gt(id))
+                .load(conn))
+            .map_err(|e| {
+                IndexerError::SqliteReadError(format!(
+                    "Failed reading validators from SQLite DB with error {:?}",
+                    e
+                ))
+            })?;
+
+        let epoch_info = epoch_info
+            .into_iter()
+            .map(DBEpochInfo::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        into_epoch_infos(epoch_info, validators)
+    }
+
+    fn get_current_epoch(&self) -> Result<EpochInfo, IndexerError> {
+        let epoch_info: SqliteEpochInfo = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::dsl::epochs
+                .order_by(epochs::epoch.desc())
+                .first::<SqliteEpochInfo>(conn)
+        })
+        .context("Failed reading current epoch")?;
+        let epoch_info = DBEpochInfo::try_from(epoch_info)?;
+
+        let validators: Vec<DBValidatorSummary> =
+            sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+                validators::dsl::validators
+                    .filter(validators::epoch.eq(epoch_info.epoch))
+                    .load(conn)
+            })
+            .context("Failed reading latest validator summary")?;
+
+        epoch_info.to_epoch_info(validators)
+    }
+
+    /// address stats methods
+    fn get_last_address_processed_checkpoint(&self) -> Result<i64, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            address_stats::dsl::address_stats
+                .select(max(address_stats::checkpoint))
+                .first::<Option<i64>>(conn)
+                // -1 to differentiate between no checkpoints and the first checkpoint
+                .map(|o| o.unwrap_or(-1))
+        })
+        .context("Failed reading latest object checkpoint from SQLite DB")
+    }
+
+    fn calculate_address_stats(&self, checkpoint: i64) -> Result<AddressStats, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            let (cp_sequence_number, cp_epoch, cp_timestamp_ms) = checkpoints::dsl::checkpoints
+                .select((
+                    checkpoints::sequence_number,
+                    checkpoints::epoch,
+                    checkpoints::timestamp_ms,
+                ))
+                .filter(checkpoints::sequence_number.eq(checkpoint))
+                .first::<(i64, i64, i64)>(conn)?;
+            let cumulative_addresses = addresses::dsl::addresses
+                .filter(addresses::first_appearance_time.le(cp_timestamp_ms))
+                .select(count(addresses::account_address))
+                .first(conn)?;
+            let cumulative_active_addresses = active_addresses::dsl::active_addresses
+                .filter(active_addresses::first_appearance_time.le(cp_timestamp_ms))
+                .select(count(active_addresses::account_address))
+                .first(conn)?;
+            let time_one_day_ago = cp_timestamp_ms - 1000 * 60 * 60 * 24;
+            let daily_active_addresses = active_addresses::dsl::active_addresses
+                .filter(active_addresses::first_appearance_time.le(cp_timestamp_ms))
+                .filter(active_addresses::last_appearance_time.gt(time_one_day_ago))
+                .select(count(active_addresses::account_address))
+                .first(conn)?;
+            Ok::<AddressStats, IndexerError>(AddressStats {
+                checkpoint: cp_sequence_number,
+                epoch: cp_epoch,
+                timestamp_ms: cp_timestamp_ms,
+                cumulative_addresses,
+                cumulative_active_addresses,
+                daily_active_addresses,
+            })
+        })
+        .context("Failed reading latest object checkpoint sequence number from SQLite DB")
+    }
+
+    fn persist_address_stats(&self, addr_stats: &AddressStats) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::insert_into(address_stats::dsl::address_stats)
+                .values(addr_stats)
+                .execute(conn)
+        })
+        .context("Failed persisting address stats to SQLite DB")?;
+        Ok(())
+    }
+
+    fn get_latest_address_stats(&self) -> Result<AddressStats, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            address_stats::dsl::address_stats
+                .order_by(address_stats::checkpoint.desc())
+                .first(conn)
+        })
+        .context("Failed reading latest address stats from SQLite DB")
+    }
+
+    fn get_checkpoint_address_stats(&self, checkpoint: i64) -> Result<AddressStats, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            address_stats::dsl::address_stats
+                .filter(address_stats::checkpoint.eq(checkpoint))
+                .order_by(address_stats::epoch.asc())
+                .first(conn)
+        })
+        .context(
+            format!(
+                "Failed reading address stats of checkpoint {} from SQLite DB",
+                checkpoint
+            )
+            .as_str(),
+        )
+    }
+
+    fn get_all_epoch_address_stats(
+        &self,
+        descending_order: Option<bool>,
+    ) -> Result<Vec<AddressStats>, IndexerError> {
+        let is_descending = descending_order.unwrap_or_default();
+        let epoch_addr_stats_query = epoch_address_stats_sql(is_descending);
+
+        let db_addr_stats = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(epoch_addr_stats_query).load::<DBAddressStats>(conn)
+        })
+        .context("Failed reading all epoch address stats from SQLite DB")?;
+        Ok(db_addr_stats
+            .into_iter()
+            .map(|db_addr_stats| db_addr_stats.into())
+            .collect())
+    }
+
+    /// checkpoint metrics methods
+    fn get_latest_checkpoint_metrics(&self) -> Result<CheckpointMetrics, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            checkpoint_metrics::dsl::checkpoint_metrics
+                .order_by(checkpoint_metrics::checkpoint.desc())
+                .first(conn)
+        })
+        .context("Failed reading latest checkpoint metrics from SQLite DB")
+    }
+
+    fn calculate_checkpoint_metrics(
+        &self,
+        current_checkpoint: i64,
+        last_checkpoint_metrics: &CheckpointMetrics,
+        cps: &[Checkpoint],
+    ) -> Result<CheckpointMetrics, IndexerError> {
+        let last_cp = cps.last().ok_or(IndexerError::SqliteReadError(
+            "Failed to get last checkpoint while calculating checkpoint metrics".to_string(),
+        ))?;
+        let last_cp_timestamp_ms = last_cp.timestamp_ms;
+        let peak_tps_30d = self.calculate_peak_tps_30d(current_checkpoint, last_cp_timestamp_ms)?;
+        let real_time_tps = self.calculate_real_time_tps(current_checkpoint)?;
+
+        Ok(rolling_checkpoint_metrics(
+            current_checkpoint,
+            last_cp.epoch,
+            last_checkpoint_metrics,
+            cps,
+            real_time_tps,
+            peak_tps_30d,
+        ))
+    }
+
+    fn persist_checkpoint_metrics(
+        &self,
+        checkpoint_metrics: &CheckpointMetrics,
+    ) -> Result<(), IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::insert_into(checkpoint_metrics::dsl::checkpoint_metrics)
+                .values(checkpoint_metrics)
+                .execute(conn)
+        })
+        .context("Failed persisting checkpoint metrics to SQLite DB")?;
+        Ok(())
+    }
+
+    fn get_processor_watermark(&self, processor_name: &str) -> Result<i64, IndexerError> {
+        sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            processor_watermarks::dsl::processor_watermarks
+                .select(processor_watermarks::checkpoint_sequence_number)
+                .filter(processor_watermarks::processor_name.eq(processor_name))
+                .first::<i64>(conn)
+                .optional()
+                // -1 to differentiate between no checkpoints and the first checkpoint
+                .map(|o| o.unwrap_or(-1))
+        })
+        .context(&format!(
+            "Failed reading watermark of processor {processor_name} from SQLite DB"
+        ))
+    }
+
+    fn persist_processor_watermark(
+        &self,
+        processor_name: &str,
+        checkpoint: i64,
+    ) -> Result<(), IndexerError> {
+        let watermark = ProcessorWatermark {
+            processor_name: processor_name.to_string(),
+            checkpoint_sequence_number: checkpoint,
+        };
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::insert_into(processor_watermarks::table)
+                .values(&watermark)
+                .on_conflict(processor_watermarks::processor_name)
+                .do_update()
+                .set(
+                    processor_watermarks::checkpoint_sequence_number
+                        .eq(excluded(processor_watermarks::checkpoint_sequence_number)),
+                )
+                .execute(conn)
+        })
+        .context(&format!(
+            "Failed persisting watermark of processor {processor_name} to SQLite DB"
+        ))?;
+        Ok(())
+    }
+
+    /// data retention methods
+    fn get_prune_cutoff(&self, epochs_to_keep: u64) -> Result<Option<PruneCutoff>, IndexerError> {
+        let current_epoch = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .select(max(epochs::epoch))
+                .first::<Option<i64>>(conn)
+        })
+        .context("Failed reading current epoch from SQLite DB")?;
+        let cutoff_epoch = match current_epoch {
+            Some(current_epoch) if current_epoch + 1 > epochs_to_keep as i64 => {
+                current_epoch + 1 - epochs_to_keep as i64
+            }
+            _ => return Ok(None),
+        };
+        let cutoff = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            epochs::table
+                .select((epochs::first_checkpoint_id, epochs::epoch_start_timestamp))
+                .filter(epochs::epoch.eq(cutoff_epoch))
+                .first::<(i64, i64)>(conn)
+                .optional()
+        })
+        .context(&format!(
+            "Failed reading epoch {cutoff_epoch} from SQLite DB"
+        ))?;
+        Ok(cutoff.map(|(checkpoint, timestamp_ms)| PruneCutoff {
+            epoch: cutoff_epoch,
+            checkpoint,
+            timestamp_ms,
+        }))
+    }
+
+    fn prune_table(
+        &self,
+        table: PrunableTable,
+        cutoff: &PruneCutoff,
+        batch_size: usize,
+    ) -> Result<usize, IndexerError> {
+        let table_name = table.table_name();
+        let (prune_condition, cutoff_value) = prune_table_condition(table, cutoff);
+        let prune_query = format!(
+            "DELETE FROM {table_name} WHERE rowid IN \
+             (SELECT rowid FROM {table_name} WHERE {prune_condition} LIMIT ?);"
+        );
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(&prune_query)
+                .bind::<BigInt, _>(cutoff_value)
+                .bind::<BigInt, _>(batch_size as i64)
+                .execute(conn)
+        })
+        .context(&format!(
+            "Failed pruning table {table_name} before epoch {} from SQLite DB",
+            cutoff.epoch
+        ))
+    }
+
+    fn prune_objects_history_versions(&self, batch_size: usize) -> Result<usize, IndexerError> {
+        sqlite_transactional_blocking!(&self.blocking_cp, |conn| {
+            diesel::sql_query(PRUNE_OBJECTS_HISTORY_VERSIONS_SQL)
+                .bind::<BigInt, _>(batch_size as i64)
+                .execute(conn)
+        })
+        .context("Failed pruning old object versions of objects_history from SQLite DB")
+    }
+
+    /// TPS related methods
+    fn calculate_real_time_tps(&self, current_checkpoint: i64) -> Result<f64, IndexerError> {
+        let real_time_tps_query = format!(
+            "WITH recent_checkpoints AS (
+                SELECT
+                  sequence_number,
+                  total_successful_transactions,
+                  timestamp_ms
+                FROM
+                  checkpoints
+                WHERE
+                  sequence_number <= {}
+                ORDER BY
+                  timestamp_ms DESC
+                LIMIT 100
+              ),
+              diff_checkpoints AS (
+                SELECT
+                  MAX(sequence_number) as sequence_number,
+                  SUM(total_successful_transactions) as total_successful_transactions,
+                  LAG(timestamp_ms) OVER (ORDER BY timestamp_ms DESC) - timestamp_ms AS time_diff
+                FROM
+                  recent_checkpoints
+                GROUP BY
+                  timestamp_ms
+              )
+              SELECT
+                CAST(total_successful_transactions * 1000.0 / time_diff AS DOUBLE) as tps
+              FROM
+                diff_checkpoints
+              WHERE
+                time_diff IS NOT NULL
+              ORDER BY sequence_number DESC LIMIT 1;
+            ",
+            current_checkpoint
+        );
+
+        let real_time_tps: Tps = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::RunQueryDsl::get_result(diesel::sql_query(real_time_tps_query), conn)
+        })
+        .context("Failed calculating real time TPS from SQLite DB")?;
+        Ok(real_time_tps.tps)
+    }
+
+    fn calculate_peak_tps_30d(
+        &self,
+        current_checkpoint: i64,
+        current_timestamp_ms: i64,
+    ) -> Result<f64, IndexerError> {
+        let peak_tps_30d_query = format!(
+            "WITH checkpoints_30d AS (
+                SELECT
+                  MAX(sequence_number) AS sequence_number,
+                  SUM(total_successful_transactions) AS total_successful_transactions,
+                  timestamp_ms
+                FROM
+                  checkpoints
+                WHERE
+                  sequence_number <= {} AND
+                  timestamp_ms > ({} - 30 * 24 * 60 * 60 * 1000)
+                GROUP BY
+                  timestamp_ms
+              ),
+              tps_data AS (
+                SELECT
+                  sequence_number,
+                  total_successful_transactions,
+                  timestamp_ms - LAG(timestamp_ms) OVER (ORDER BY sequence_number) AS time_diff
+                FROM
+                  checkpoints_30d
+              )
+              SELECT
+                CAST(MAX(total_successful_transactions * 1000.0 / time_diff) AS DOUBLE) as tps
+              FROM
+                tps_data
+              WHERE
+                time_diff IS NOT NULL;
+            ",
+            current_checkpoint, current_timestamp_ms
+        );
+        let peak_tps_30d: Tps = sqlite_read_only_blocking!(&self.blocking_cp, |conn| {
+            diesel::RunQueryDsl::get_result(diesel::sql_query(peak_tps_30d_query), conn)
+        })
+        .context("Failed calculating peak TPS in 30 days from SQLite DB")?;
+        Ok(peak_tps_30d.tps)
+    }
+
+    fn drop_pruned_partitions(
+        &self,
+        _table: PrunableTable,
+        _cutoff: &PruneCutoff,
+    ) -> Result<Vec<String>, IndexerError> {
+        // NOTE: SQLite has no table partitioning, rows are only pruned by `prune_table`.
+        Ok(vec![])
+    }
+}
+
+impl_indexer_store!(SqliteIndexerStore, SqliteModuleResolver);
+
+// Returns the condition selecting the rows of the table before the cutoff `?`, and the cutoff.
+fn prune_table_condition(table: PrunableTable, cutoff: &PruneCutoff) -> (&'static str, i64) {
+    match table {
+        PrunableTable::Events => ("event_time_ms < ?", cutoff.timestamp_ms),
+        PrunableTable::Checkpoints => ("sequence_number < ?", cutoff.checkpoint),
+        // NOTE: same as the Postgres store, the latest version of each object before the cutoff
+        // is kept. `?1` is bound once and used twice.
+        PrunableTable::ObjectsHistory => (
+            "checkpoint >= 0 AND checkpoint < ?1 AND EXISTS \
+             (SELECT 1 FROM objects_history n WHERE n.object_id = objects_history.object_id \
+             AND n.version > objects_history.version AND n.checkpoint >= 0 \
+             AND n.checkpoint <= ?1)",
+            cutoff.checkpoint,
+        ),
+        PrunableTable::Transactions
+        | PrunableTable::InputObjects
+        | PrunableTable::ChangedObjects
+        | PrunableTable::MoveCalls
+        | PrunableTable::Recipients => ("checkpoint_sequence_number < ?", cutoff.checkpoint),
+    }
+}
+
+fn into_transactions(rows: Vec<SqliteTransaction>) -> Result<Vec<Transaction>, IndexerError> {
+    rows.into_iter().map(Transaction::try_from).collect()
+}
+
+fn persist_transaction_object_changes(
+    conn: &mut SqliteConnection,
+    mut mutated_objects: Vec<Object>,
+    deleted_objects: Vec<Object>,
+    object_mutation_latency: Option<Histogram>,
+    object_deletion_latency: Option<Histogram>,
+) -> Result<usize, IndexerError> {
+    // NOTE: rows are upserted one by one, so unlike the Postgres store there is no need to
+    // split the objects into groups of distinct ids, ordering them by version is enough.
+    let object_mutation_guard = object_mutation_latency.map(|h| h.start_timer());
+    mutated_objects.sort_by_key(|o| o.version);
+    for mutated_object in &mutated_objects {
+        diesel::insert_into(objects::table)
+            .values(SqliteObject::from(mutated_object))
+            .on_conflict(objects::object_id)
+            .do_update()
+            .set((
+                objects::epoch.eq(excluded(objects::epoch)),
+                objects::checkpoint.eq(excluded(objects::checkpoint)),
+                objects::version.eq(excluded(objects::version)),
+                objects::object_digest.eq(excluded(objects::object_digest)),
+                objects::owner_type.eq(excluded(objects::owner_type)),
+                objects::owner_address.eq(excluded(objects::owner_address)),
+                objects::initial_shared_version.eq(excluded(objects::initial_shared_version)),
+                objects::previous_transaction.eq(excluded(objects::previous_transaction)),
+                objects::object_type.eq(excluded(objects::object_type)),
+                objects::object_status.eq(excluded(objects::object_status)),
+                objects::has_public_transfer.eq(excluded(objects::has_public_transfer)),
+                objects::storage_rebate.eq(excluded(objects::storage_rebate)),
+                objects::bcs.eq(excluded(objects::bcs)),
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                IndexerError::SqliteWriteError(format!(
+                    "Failed writing mutated objects to SQLite DB with error: {:?}",
+                    e
+                ))
+            })?;
+    }
+    if let Some(object_mutation_guard) = object_mutation_guard {
+        object_mutation_guard.stop_and_record();
+    }
+
+    let object_deletion_guard = object_deletion_latency.map(|h| h.start_timer());
+    for deleted_object in &deleted_objects {
+        diesel::insert_into(objects::table)
+            .values(SqliteObject::from(deleted_object))
+            .on_conflict(objects::object_id)
+            .do_update()
+            .set((
+                objects::epoch.eq(excluded(objects::epoch)),
+                objects::checkpoint.eq(excluded(objects::checkpoint)),
+                objects::version.eq(excluded(objects::version)),
+                objects::previous_transaction.eq(excluded(objects::previous_transaction)),
+                objects::object_status.eq(excluded(objects::object_status)),
+            ))
+            .execute(conn)
+            .map_err(|e| {
+                IndexerError::SqliteWriteError(format!(
+                    "Failed writing deleted objects to SQLite DB with error: {:?}",
+                    e
+                ))
+            })?;
+    }
+    if let Some(object_deletion_guard) = object_deletion_guard {
+        object_deletion_guard.stop_and_record();
+    }
+    Ok(0)
+}
+
+// Run this function only once every `time` seconds
+#[once(time = 60, sync_writes = true, result = true)]
+fn get_network_metrics_cached(cp: &SqliteConnectionPool) -> Result<NetworkMetrics, IndexerError> {
+    let metrics = sqlite_read_only_blocking!(cp, |conn| diesel::sql_query(
+        "SELECT * FROM network_metrics;"
+    )
+    .get_result::<DBNetworkMetrics>(conn))?;
+    Ok(metrics.into())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use diesel::sql_types::VarChar;
+    use diesel::{QueryDsl, QueryableByName, RunQueryDsl};
+    use fastcrypto::traits::EncodeDecodeBase64;
+    use tempfile::TempDir;
+
+    use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+    use sui_types::crypto::AggregateAuthoritySignature;
+    use sui_types::digests::{CheckpointDigest, ObjectDigest, TransactionDigest};
+    use sui_types::event::EventID;
+    use sui_types::object::ObjectRead;
+
+    use crate::errors::IndexerError;
+    use crate::models::checkpoints::Checkpoint;
+    use crate::models::events::Event;
+    use crate::models::objects::{DeletedObject, NamedBcsBytes, Object, ObjectStatus};
+    use crate::models::owners::OwnerType;
+    use crate::models::transaction_index::MoveCall;
+    use crate::models::transactions::Transaction;
+    use crate::schema::move_calls;
+    use crate::store::diesel_marco::sqlite_read_only_blocking;
+    use crate::store::{
+        IndexerStore, PrunableTable, PruneCutoff, TemporaryBackfillStore, TransactionObjectChanges,
+    };
+    use crate::test_utils::new_test_sqlite_store;
+
+    use super::SqliteIndexerStore;
+
+    /// Returns a store on a freshly migrated database file, the file is removed with the dir.
+    pub(crate) fn test_store() -> (TempDir, SqliteIndexerStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = new_test_sqlite_store(&dir.path().join("indexer.db")).unwrap();
+        (dir, store)
+    }
+
+    pub(crate) fn test_checkpoint(
+        sequence_number: i64,
+        epoch: i64,
+        transactions: &[Transaction],
+    ) -> Checkpoint {
+        Checkpoint {
+            sequence_number,
+            checkpoint_digest: CheckpointDigest::random().base58_encode(),
+            epoch,
+            transactions: transactions
+                .iter()
+                .map(|tx| Some(tx.transaction_digest.clone()))
+                .collect(),
+            previous_checkpoint_digest: Some(CheckpointDigest::random().base58_encode()),
+            total_transaction_blocks: transactions.len() as i64,
+            total_transactions: transactions.len() as i64,
+            // Assumes that all checkpoints have as many transactions as this one.
+            network_total_transactions: (sequence_number + 1) * transactions.len() as i64,
+            timestamp_ms: 1_000 * sequence_number,
+            validator_signature: AggregateAuthoritySignature::default().encode_base64(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn test_transaction(checkpoint: i64) -> Transaction {
+        Transaction {
+            id: None,
+            transaction_digest: TransactionDigest::random().base58_encode(),
+            sender: SuiAddress::random_for_testing_only().to_string(),
+            recipients: vec![Some(SuiAddress::random_for_testing_only().to_string())],
+            checkpoint_sequence_number: Some(checkpoint),
+            timestamp_ms: Some(1_000 * checkpoint),
+            transaction_kind: "ProgrammableTransaction".to_string(),
+            transaction_count: 1,
+            execution_success: true,
+            created: vec![Some(ObjectID::random().to_string())],
+            mutated: vec![],
+            deleted: vec![],
+            unwrapped: vec![],
+            wrapped: vec![],
+            move_calls: vec![],
+            gas_object_id: ObjectID::random().to_string(),
+            gas_object_sequence: 1,
+            gas_object_digest: ObjectDigest::random().base58_encode(),
+            gas_budget: 1_000,
+            total_gas_cost: 10,
+            computation_cost: 5,
+            storage_cost: 7,
+            storage_rebate: 2,
+            non_refundable_storage_fee: 0,
+            gas_price: 1,
+            raw_transaction: vec![1, 2, 3],
+            transaction_content: "{}".to_string(),
+            transaction_effects_content: "{}".to_string(),
+            confirmed_local_execution: None,
+        }
+    }
+
+    pub(crate) fn test_object(object_id: ObjectID, version: i64, checkpoint: i64) -> Object {
+        Object {
+            epoch: 0,
+            checkpoint,
+            object_id: object_id.to_string(),
+            version,
+            object_digest: ObjectDigest::random().base58_encode(),
+            owner_type: OwnerType::AddressOwner,
+            owner_address: Some(SuiAddress::random_for_testing_only().to_string()),
+            initial_shared_version: None,
+            previous_transaction: TransactionDigest::random().base58_encode(),
+            object_type: "0x2::coin::Coin<0x2::sui::SUI>".to_string(),
+            object_status: ObjectStatus::Mutated,
+            has_public_transfer: true,
+            storage_rebate: 1,
+            bcs: vec![NamedBcsBytes("object".to_string(), vec![version as u8; 40])],
+        }
+    }
+
+    pub(crate) fn test_event(transaction: &Transaction, event_sequence: i64) -> Event {
+        Event {
+            id: None,
+            transaction_digest: transaction.transaction_digest.clone(),
+            event_sequence,
+            sender: transaction.sender.clone(),
+            package: ObjectID::random().to_string(),
+            module: "counter".to_string(),
+            event_type: "0x2::counter::Incremented".to_string(),
+            event_time_ms: transaction.timestamp_ms,
+            event_bcs: vec![event_sequence as u8; 8],
+        }
+    }
+
+    #[derive(QueryableByName)]
+    pub(crate) struct ObjectVersion {
+        #[diesel(sql_type = diesel::sql_types::BigInt)]
+        pub version: i64,
+    }
+
+    /// Versions of `object_id` in objects_history, in ascending order.
+    pub(crate) fn history_versions(
+        store: &SqliteIndexerStore,
+        object_id: ObjectID,
+    ) -> Result<Vec<i64>, IndexerError> {
+        let versions = sqlite_read_only_blocking!(&store.blocking_cp, |conn| {
+            diesel::sql_query(
+                "SELECT version FROM objects_history WHERE object_id = ? ORDER BY version;",
+            )
+            .bind::<VarChar, _>(object_id.to_string())
+            .load::<ObjectVersion>(conn)
+        })?;
+        Ok(versions.into_iter().map(|v| v.version).collect())
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_and_transactions_round_trip() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        assert_eq!(store.get_latest_checkpoint_sequence_number().await?, -1);
+
+        for seq in 0..3 {
+            let transactions = vec![test_transaction(seq), test_transaction(seq)];
+            let checkpoint = test_checkpoint(seq, 0, &transactions);
+            store
+                .persist_checkpoint_transactions(&checkpoint, &transactions)
+                .await?;
+
+            let stored = store.get_indexer_checkpoint().await?;
+            assert_eq!(stored.sequence_number, seq);
+            assert_eq!(stored.checkpoint_digest, checkpoint.checkpoint_digest);
+            assert_eq!(
+                stored.previous_checkpoint_digest,
+                checkpoint.previous_checkpoint_digest
+            );
+            assert_eq!(stored.transactions, checkpoint.transactions);
+            assert_eq!(stored.timestamp_ms, checkpoint.timestamp_ms);
+            assert_eq!(stored.validator_signature, checkpoint.validator_signature);
+
+            for transaction in &transactions {
+                let mut stored = store
+                    .get_transaction_by_digest(&transaction.transaction_digest)
+                    .await?;
+                assert!(stored.id.is_some());
+                stored.id = None;
+                assert_eq!(&stored, transaction);
+            }
+        }
+        assert_eq!(store.get_latest_checkpoint_sequence_number().await?, 2);
+        assert_eq!(
+            store
+                .get_total_transaction_number_from_checkpoints()
+                .await?,
+            6
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_persist_transaction_is_idempotent() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        // A transaction first indexed from the fast path has no checkpoint yet.
+        let mut transaction = test_transaction(0);
+        transaction.checkpoint_sequence_number = None;
+        transaction.timestamp_ms = None;
+        let no_object_changes = TransactionObjectChanges {
+            changed_objects: vec![],
+            deleted_objects: vec![],
+        };
+        store
+            .persist_fast_path(transaction.clone(), no_object_changes)
+            .await?;
+
+        transaction.checkpoint_sequence_number = Some(0);
+        transaction.timestamp_ms = Some(42);
+        let checkpoint = test_checkpoint(0, 0, &[transaction.clone()]);
+        store
+            .persist_checkpoint_transactions(&checkpoint, &[transaction.clone()])
+            .await?;
+        // Persisting the same checkpoint again must not fail or duplicate rows.
+        store
+            .persist_checkpoint_transactions(&checkpoint, &[transaction.clone()])
+            .await?;
+
+        let stored = store
+            .get_transaction_by_digest(&transaction.transaction_digest)
+            .await?;
+        assert_eq!(stored.checkpoint_sequence_number, Some(0));
+        assert_eq!(stored.timestamp_ms, Some(42));
+        assert_eq!(store.get_latest_checkpoint_sequence_number().await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_object_changes_round_trip() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let metrics = store.indexer_metrics().clone();
+        let object_id = ObjectID::random();
+
+        // Versions are applied in order even when they are not sorted in the input.
+        let changes = TransactionObjectChanges {
+            changed_objects: vec![test_object(object_id, 2, 1), test_object(object_id, 1, 0)],
+            deleted_objects: vec![],
+        };
+        store
+            .persist_object_changes(
+                &[changes],
+                metrics.object_mutation_db_commit_latency.clone(),
+                metrics.object_deletion_db_commit_latency.clone(),
+            )
+            .await?;
+        assert_eq!(history_versions(&store, object_id)?, vec![1, 2]);
+
+        let deleted = DeletedObject {
+            epoch: 0,
+            checkpoint: Some(2),
+            object_id: object_id.to_string(),
+            version: 3,
+            object_digest: "DELETED".to_string(),
+            owner_type: OwnerType::AddressOwner,
+            previous_transaction: TransactionDigest::random().base58_encode(),
+            object_type: "DELETED".to_string(),
+            object_status: ObjectStatus::Deleted,
+            has_public_transfer: false,
+        };
+        let changes = TransactionObjectChanges {
+            changed_objects: vec![],
+            deleted_objects: vec![deleted],
+        };
+        store
+            .persist_object_changes(
+                &[changes],
+                metrics.object_mutation_db_commit_latency.clone(),
+                metrics.object_deletion_db_commit_latency.clone(),
+            )
+            .await?;
+        assert_eq!(history_versions(&store, object_id)?, vec![1, 2, 3]);
+
+        match store.get_object(object_id, None).await? {
+            ObjectRead::Deleted((id, version, _)) => {
+                assert_eq!(id, object_id);
+                assert_eq!(version, SequenceNumber::from_u64(3));
+            }
+            other => panic!("Expected object {object_id} to be deleted, got {other:?}"),
+        }
+        assert!(matches!(
+            store.get_object(ObjectID::random(), None).await?,
+            ObjectRead::NotExists(_)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_events_round_trip() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let transaction = test_transaction(0);
+        let events = vec![test_event(&transaction, 0), test_event(&transaction, 1)];
+        store.persist_events(&events).await?;
+
+        let tx_digest: TransactionDigest = transaction.transaction_digest.parse().unwrap();
+        for event in &events {
+            let stored = store
+                .get_event(EventID {
+                    tx_digest,
+                    event_seq: event.event_sequence as u64,
+                })
+                .await?;
+            assert_eq!(stored.transaction_digest, event.transaction_digest);
+            assert_eq!(stored.event_sequence, event.event_sequence);
+            assert_eq!(stored.sender, event.sender);
+            assert_eq!(stored.package, event.package);
+            assert_eq!(stored.module, event.module);
+            assert_eq!(stored.event_type, event.event_type);
+            assert_eq!(stored.event_time_ms, event.event_time_ms);
+            assert_eq!(stored.event_bcs, event.event_bcs);
+        }
+        assert!(store
+            .get_event(EventID {
+                tx_digest,
+                event_seq: 2,
+            })
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    fn test_move_call(transaction: &Transaction, move_function: &str) -> MoveCall {
+        MoveCall {
+            id: None,
+            transaction_digest: transaction.transaction_digest.clone(),
+            checkpoint_sequence_number: transaction.checkpoint_sequence_number.unwrap(),
+            epoch: 0,
+            sender: transaction.sender.clone(),
+            move_package: ObjectID::random().to_string(),
+            move_module: "counter".to_string(),
+            move_function: move_function.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_persist_backfill() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let mut transactions = vec![];
+        let mut checkpoints = vec![];
+        for seq in 0..4 {
+            let transaction = test_transaction(seq);
+            let checkpoint = test_checkpoint(seq, 0, &[transaction.clone()]);
+            store
+                .persist_checkpoint_transactions(&checkpoint, &[transaction.clone()])
+                .await?;
+            store
+                .persist_transaction_index_tables(
+                    &[],
+                    &[],
+                    &[test_move_call(&transaction, "indexed")],
+                    &[],
+                )
+                .await?;
+            transactions.push(
+                store
+                    .get_transaction_by_digest(&transaction.transaction_digest)
+                    .await?,
+            );
+            checkpoints.push(checkpoint);
+        }
+
+        // Backfill checkpoints 1 and 2, twice to check that a backfill can be run again.
+        let backfilled_transactions = transactions[1..3]
+            .iter()
+            .map(|tx| Transaction {
+                id: None,
+                gas_budget: 2_000,
+                ..tx.clone()
+            })
+            .collect::<Vec<_>>();
+        let backfill = TemporaryBackfillStore {
+            checkpoint_range: 1..3,
+            checkpoints: Some(
+                checkpoints[1..3]
+                    .iter()
+                    .map(|cp| Checkpoint {
+                        total_gas_cost: 100,
+                        ..cp.clone()
+                    })
+                    .collect(),
+            ),
+            transactions: Some(backfilled_transactions.clone()),
+            input_objects: None,
+            changed_objects: None,
+            move_calls: Some(
+                backfilled_transactions
+                    .iter()
+                    .map(|tx| test_move_call(tx, "backfilled"))
+                    .collect(),
+            ),
+            recipients: None,
+        };
+        store.persist_backfill(&backfill).await?;
+        store.persist_backfill(&backfill).await?;
+
+        let stored_move_calls = sqlite_read_only_blocking!(&store.blocking_cp, |conn| {
+            move_calls::table
+                .select((
+                    move_calls::checkpoint_sequence_number,
+                    move_calls::move_function,
+                ))
+                .order(move_calls::checkpoint_sequence_number)
+                .load::<(i64, String)>(conn)
+        })?;
+        assert_eq!(
+            stored_move_calls,
+            vec![
+                (0, "indexed".to_string()),
+                (1, "backfilled".to_string()),
+                (2, "backfilled".to_string()),
+                (3, "indexed".to_string()),
+            ]
+        );
+
+        for (seq, transaction) in transactions.iter().enumerate() {
+            let stored = store
+                .get_transaction_by_digest(&transaction.transaction_digest)
+                .await?;
+            // Backfilled transactions keep their sequence ids.
+            assert_eq!(stored.id, transaction.id);
+            let backfilled = (1..3).contains(&seq);
+            assert_eq!(stored.gas_budget, if backfilled { 2_000 } else { 1_000 });
+        }
+        let stored_checkpoints = store.get_indexer_checkpoints(-1, 4).await?;
+        assert_eq!(stored_checkpoints.len(), 4);
+        for (stored, checkpoint) in stored_checkpoints.iter().zip(&checkpoints) {
+            assert_eq!(stored.checkpoint_digest, checkpoint.checkpoint_digest);
+            assert_eq!(stored.transactions, checkpoint.transactions);
+        }
+        let stored_gas_costs = stored_checkpoints
+            .iter()
+            .map(|cp| cp.total_gas_cost)
+            .collect::<Vec<_>>();
+        assert_eq!(stored_gas_costs, vec![0, 100, 100, 0]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_table_in_batches() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        for seq in 0..5 {
+            let transaction = test_transaction(seq);
+            let checkpoint = test_checkpoint(seq, seq / 2, &[transaction.clone()]);
+            store
+                .persist_checkpoint_transactions(&checkpoint, &[transaction])
+                .await?;
+        }
+        let cutoff = PruneCutoff {
+            epoch: 1,
+            checkpoint: 3,
+            timestamp_ms: 3_000,
+        };
+
+        let mut pruned = vec![];
+        loop {
+            let batch = store
+                .prune_table(PrunableTable::Transactions, &cutoff, 2)
+                .await?;
+            pruned.push(batch);
+            if batch < 2 {
+                break;
+            }
+        }
+        assert_eq!(pruned, vec![2, 1]);
+        assert_eq!(
+            store
+                .prune_table(PrunableTable::Checkpoints, &cutoff, 10)
+                .await?,
+            3
+        );
+
+        let remaining = store.get_indexer_checkpoints(-1, 10).await?;
+        assert_eq!(
+            remaining
+                .iter()
+                .map(|cp| cp.sequence_number)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        for checkpoint in remaining {
+            let digest = checkpoint.transactions[0].clone().unwrap();
+            let transaction = store.get_transaction_by_digest(&digest).await?;
+            assert_eq!(
+                transaction.checkpoint_sequence_number,
+                Some(checkpoint.sequence_number)
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_objects_history() -> Result<(), IndexerError> {
+        let (_dir, store) = test_store();
+        let metrics = store.indexer_metrics().clone();
+        let changed = ObjectID::random();
+        let unchanged = ObjectID::random();
+        let changes = TransactionObjectChanges {
+            changed_objects: vec![
+                test_object(changed, 1, 0),
+                test_object(changed, 2, 1),
+                test_object(changed, 3, 2),
+                test_object(changed, 4, 4),
+                test_object(unchanged, 1, 0),
+            ],
+            deleted_objects: vec![],
+        };
+        store
+            .persist_object_changes(
+                &[changes],
+                metrics.object_mutation_db_commit_latency.clone(),
+                metrics.object_deletion_db_commit_latency.clone(),
+            )
+            .await?;
+
+        // The latest versions before the cutoff are kept, even those which are not in the
+        // retained epochs.
+        let cutoff = PruneCutoff {
+            epoch: 1,
+            checkpoint: 3,
+            timestamp_ms: 3_000,
+        };
+        let mut pruned = vec![];
+        loop {
+            let batch = store
+                .prune_table(PrunableTable::ObjectsHistory, &cutoff, 1)
+                .await?;
+            pruned.push(batch);
+            if batch < 1 {
+                break;
+            }
+        }
+        assert_eq!(pruned, vec![1, 1, 0]);
+        assert_eq!(history_versions(&store, changed)?, vec![3, 4]);
+        assert_eq!(history_versions(&store, unchanged)?, vec![1]);
+
+        // Only the versions older than the latest object version are pruned.
+        assert_eq!(store.prune_objects_history_versions(1).await?, 1);
+        assert_eq!(store.prune_objects_history_versions(1).await?, 0);
+        assert_eq!(history_versions(&store, changed)?, vec![4]);
+        assert_eq!(history_versions(&store, unchanged)?, vec![1]);
+        Ok(())
+    }
+}
@@ -0,0 +1,474 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Row types of the tables in `crate::sqlite_schema` and their conversions from and to the
+//! models shared with the Postgres store.
+
+use diesel::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::errors::IndexerError;
+use crate::models::checkpoints::Checkpoint;
+use crate::models::epoch::DBEpochInfo;
+use crate::models::objects::{NamedBcsBytes, Object};
+use crate::models::packages::Package;
+use crate::models::system_state::DBSystemStateSummary;
+use crate::models::transactions::Transaction;
+use crate::sqlite_schema::{checkpoints, epochs, objects, packages, system_states, transactions};
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = transactions)]
+pub(crate) struct SqliteTransaction {
+    #[diesel(deserialize_as = i64)]
+    pub id: Option<i64>,
+    pub transaction_digest: String,
+    pub sender: String,
+    pub recipients: String,
+    pub checkpoint_sequence_number: Option<i64>,
+    pub timestamp_ms: Option<i64>,
+    pub transaction_kind: String,
+    pub transaction_count: i64,
+    pub execution_success: bool,
+    pub created: String,
+    pub mutated: String,
+    pub deleted: String,
+    pub unwrapped: String,
+    pub wrapped: String,
+    pub move_calls: String,
+    pub gas_object_id: String,
+    pub gas_object_sequence: i64,
+    pub gas_object_digest: String,
+    pub gas_budget: i64,
+    pub total_gas_cost: i64,
+    pub computation_cost: i64,
+    pub storage_cost: i64,
+    pub storage_rebate: i64,
+    pub non_refundable_storage_fee: i64,
+    pub gas_price: i64,
+    pub raw_transaction: Vec<u8>,
+    pub transaction_content: String,
+    pub transaction_effects_content: String,
+    pub confirmed_local_execution: Option<bool>,
+}
+
+impl From<&Transaction> for SqliteTransaction {
+    fn from(tx: &Transaction) -> Self {
+        Self {
+            id: tx.id,
+            transaction_digest: tx.transaction_digest.clone(),
+            sender: tx.sender.clone(),
+            recipients: to_json(&tx.recipients),
+            checkpoint_sequence_number: tx.checkpoint_sequence_number,
+            timestamp_ms: tx.timestamp_ms,
+            transaction_kind: tx.transaction_kind.clone(),
+            transaction_count: tx.transaction_count,
+            execution_success: tx.execution_success,
+            created: to_json(&tx.created),
+            mutated: to_json(&tx.mutated),
+            deleted: to_json(&tx.deleted),
+            unwrapped: to_json(&tx.unwrapped),
+            wrapped: to_json(&tx.wrapped),
+            move_calls: to_json(&tx.move_calls),
+            gas_object_id: tx.gas_object_id.clone(),
+            gas_object_sequence: tx.gas_object_sequence,
+            gas_object_digest: tx.gas_object_digest.clone(),
+            gas_budget: tx.gas_budget,
+            total_gas_cost: tx.total_gas_cost,
+            computation_cost: tx.computation_cost,
+            storage_cost: tx.storage_cost,
+            storage_rebate: tx.storage_rebate,
+            non_refundable_storage_fee: tx.non_refundable_storage_fee,
+            gas_price: tx.gas_price,
+            raw_transaction: tx.raw_transaction.clone(),
+            transaction_content: tx.transaction_content.clone(),
+            transaction_effects_content: tx.transaction_effects_content.clone(),
+            confirmed_local_execution: tx.confirmed_local_execution,
+        }
+    }
+}
+
+impl TryFrom<SqliteTransaction> for Transaction {
+    type Error = IndexerError;
+
+    fn try_from(tx: SqliteTransaction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: tx.id,
+            transaction_digest: tx.transaction_digest,
+            sender: tx.sender,
+            recipients: from_json(&tx.recipients)?,
+            checkpoint_sequence_number: tx.checkpoint_sequence_number,
+            timestamp_ms: tx.timestamp_ms,
+            transaction_kind: tx.transaction_kind,
+            transaction_count: tx.transaction_count,
+            execution_success: tx.execution_success,
+            created: from_json(&tx.created)?,
+            mutated: from_json(&tx.mutated)?,
+            deleted: from_json(&tx.deleted)?,
+            unwrapped: from_json(&tx.unwrapped)?,
+            wrapped: from_json(&tx.wrapped)?,
+            move_calls: from_json(&tx.move_calls)?,
+            gas_object_id: tx.gas_object_id,
+            gas_object_sequence: tx.gas_object_sequence,
+            gas_object_digest: tx.gas_object_digest,
+            gas_budget: tx.gas_budget,
+            total_gas_cost: tx.total_gas_cost,
+            computation_cost: tx.computation_cost,
+            storage_cost: tx.storage_cost,
+            storage_rebate: tx.storage_rebate,
+            non_refundable_storage_fee: tx.non_refundable_storage_fee,
+            gas_price: tx.gas_price,
+            raw_transaction: tx.raw_transaction,
+            transaction_content: tx.transaction_content,
+            transaction_effects_content: tx.transaction_effects_content,
+            confirmed_local_execution: tx.confirmed_local_execution,
+        })
+    }
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = checkpoints)]
+pub(crate) struct SqliteCheckpoint {
+    pub sequence_number: i64,
+    pub checkpoint_digest: String,
+    pub epoch: i64,
+    pub transactions: String,
+    pub previous_checkpoint_digest: Option<String>,
+    pub end_of_epoch: bool,
+    pub total_gas_cost: i64,
+    pub total_computation_cost: i64,
+    pub total_storage_cost: i64,
+    pub total_storage_rebate: i64,
+    pub total_transaction_blocks: i64,
+    pub total_transactions: i64,
+    pub total_successful_transaction_blocks: i64,
+    pub total_successful_transactions: i64,
+    pub network_total_transactions: i64,
+    pub timestamp_ms: i64,
+    pub validator_signature: String,
+}
+
+impl From<&Checkpoint> for SqliteCheckpoint {
+    fn from(cp: &Checkpoint) -> Self {
+        Self {
+            sequence_number: cp.sequence_number,
+            checkpoint_digest: cp.checkpoint_digest.clone(),
+            epoch: cp.epoch,
+            transactions: to_json(&cp.transactions),
+            previous_checkpoint_digest: cp.previous_checkpoint_digest.clone(),
+            end_of_epoch: cp.end_of_epoch,
+            total_gas_cost: cp.total_gas_cost,
+            total_computation_cost: cp.total_computation_cost,
+            total_storage_cost: cp.total_storage_cost,
+            total_storage_rebate: cp.total_storage_rebate,
+            total_transaction_blocks: cp.total_transaction_blocks,
+            total_transactions: cp.total_transactions,
+            total_successful_transaction_blocks: cp.total_successful_transaction_blocks,
+            total_successful_transactions: cp.total_successful_transactions,
+            network_total_transactions: cp.network_total_transactions,
+            timestamp_ms: cp.timestamp_ms,
+            validator_signature: cp.validator_signature.clone(),
+        }
+    }
+}
+
+impl TryFrom<SqliteCheckpoint> for Checkpoint {
+    type Error = IndexerError;
+
+    fn try_from(cp: SqliteCheckpoint) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence_number: cp.sequence_number,
+            checkpoint_digest: cp.checkpoint_digest,
+            epoch: cp.epoch,
+            transactions: from_json(&cp.transactions)?,
+            previous_checkpoint_digest: cp.previous_checkpoint_digest,
+            end_of_epoch: cp.end_of_epoch,
+            total_gas_cost: cp.total_gas_cost,
+            total_computation_cost: cp.total_computation_cost,
+            total_storage_cost: cp.total_storage_cost,
+            total_storage_rebate: cp.total_storage_rebate,
+            total_transaction_blocks: cp.total_transaction_blocks,
+            total_transactions: cp.total_transactions,
+            total_successful_transaction_blocks: cp.total_successful_transaction_blocks,
+            total_successful_transactions: cp.total_successful_transactions,
+            network_total_transactions: cp.network_total_transactions,
+            timestamp_ms: cp.timestamp_ms,
+            validator_signature: cp.validator_signature,
+        })
+    }
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = epochs)]
+pub(crate) struct SqliteEpochInfo {
+    pub epoch: i64,
+    pub first_checkpoint_id: i64,
+    pub last_checkpoint_id: Option<i64>,
+    pub epoch_start_timestamp: i64,
+    pub epoch_end_timestamp: Option<i64>,
+    pub epoch_total_transactions: i64,
+    pub next_epoch_version: Option<i64>,
+    pub next_epoch_committee: Vec<u8>,
+    pub next_epoch_committee_stake: String,
+    pub epoch_commitments: Vec<u8>,
+    pub protocol_version: Option<i64>,
+    pub reference_gas_price: Option<i64>,
+    pub total_stake: Option<i64>,
+    pub storage_fund_reinvestment: Option<i64>,
+    pub storage_charge: Option<i64>,
+    pub storage_rebate: Option<i64>,
+    pub storage_fund_balance: Option<i64>,
+    pub stake_subsidy_amount: Option<i64>,
+    pub total_gas_fees: Option<i64>,
+    pub total_stake_rewards_distributed: Option<i64>,
+    pub leftover_storage_fund_inflow: Option<i64>,
+}
+
+impl From<&DBEpochInfo> for SqliteEpochInfo {
+    fn from(e: &DBEpochInfo) -> Self {
+        Self {
+            epoch: e.epoch,
+            first_checkpoint_id: e.first_checkpoint_id,
+            last_checkpoint_id: e.last_checkpoint_id,
+            epoch_start_timestamp: e.epoch_start_timestamp,
+            epoch_end_timestamp: e.epoch_end_timestamp,
+            epoch_total_transactions: e.epoch_total_transactions,
+            next_epoch_version: e.next_epoch_version,
+            next_epoch_committee: to_bcs(&e.next_epoch_committee),
+            next_epoch_committee_stake: to_json(&e.next_epoch_committee_stake),
+            epoch_commitments: to_bcs(&e.epoch_commitments),
+            protocol_version: e.protocol_version,
+            reference_gas_price: e.reference_gas_price,
+            total_stake: e.total_stake,
+            storage_fund_reinvestment: e.storage_fund_reinvestment,
+            storage_charge: e.storage_charge,
+            storage_rebate: e.storage_rebate,
+            storage_fund_balance: e.storage_fund_balance,
+            stake_subsidy_amount: e.stake_subsidy_amount,
+            total_gas_fees: e.total_gas_fees,
+            total_stake_rewards_distributed: e.total_stake_rewards_distributed,
+            leftover_storage_fund_inflow: e.leftover_storage_fund_inflow,
+        }
+    }
+}
+
+impl TryFrom<SqliteEpochInfo> for DBEpochInfo {
+    type Error = IndexerError;
+
+    fn try_from(e: SqliteEpochInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            epoch: e.epoch,
+            first_checkpoint_id: e.first_checkpoint_id,
+            last_checkpoint_id: e.last_checkpoint_id,
+            epoch_start_timestamp: e.epoch_start_timestamp,
+            epoch_end_timestamp: e.epoch_end_timestamp,
+            epoch_total_transactions: e.epoch_total_transactions,
+            next_epoch_version: e.next_epoch_version,
+            next_epoch_committee: bcs::from_bytes(&e.next_epoch_committee)?,
+            next_epoch_committee_stake: from_json(&e.next_epoch_committee_stake)?,
+            epoch_commitments: bcs::from_bytes(&e.epoch_commitments)?,
+            protocol_version: e.protocol_version,
+            reference_gas_price: e.reference_gas_price,
+            total_stake: e.total_stake,
+            storage_fund_reinvestment: e.storage_fund_reinvestment,
+            storage_charge: e.storage_charge,
+            storage_rebate: e.storage_rebate,
+            storage_fund_balance: e.storage_fund_balance,
+            stake_subsidy_amount: e.stake_subsidy_amount,
+            total_gas_fees: e.total_gas_fees,
+            total_stake_rewards_distributed: e.total_stake_rewards_distributed,
+            leftover_storage_fund_inflow: e.leftover_storage_fund_inflow,
+        })
+    }
+}
+
+// NOTE: also used to read `objects_history` rows, which share these columns.
+#[derive(Queryable, Insertable, QueryableByName, Debug, Clone)]
+#[diesel(table_name = objects)]
+pub(crate) struct SqliteObject {
+    pub epoch: i64,
+    pub checkpoint: i64,
+    pub object_id: String,
+    pub version: i64,
+    pub object_digest: String,
+    pub owner_type: String,
+    pub owner_address: Option<String>,
+    pub initial_shared_version: Option<i64>,
+    pub previous_transaction: String,
+    pub object_type: String,
+    pub object_status: String,
+    pub has_public_transfer: bool,
+    pub storage_rebate: i64,
+    pub bcs: Vec<u8>,
+}
+
+impl From<&Object> for SqliteObject {
+    fn from(o: &Object) -> Self {
+        Self {
+            epoch: o.epoch,
+            checkpoint: o.checkpoint,
+            object_id: o.object_id.clone(),
+            version: o.version,
+            object_digest: o.object_digest.clone(),
+            owner_type: enum_to_text(&o.owner_type),
+            owner_address: o.owner_address.clone(),
+            initial_shared_version: o.initial_shared_version,
+            previous_transaction: o.previous_transaction.clone(),
+            object_type: o.object_type.clone(),
+            object_status: enum_to_text(&o.object_status),
+            has_public_transfer: o.has_public_transfer,
+            storage_rebate: o.storage_rebate,
+            bcs: named_bcs_to_bytes(&o.bcs),
+        }
+    }
+}
+
+impl TryFrom<SqliteObject> for Object {
+    type Error = IndexerError;
+
+    fn try_from(o: SqliteObject) -> Result<Self, Self::Error> {
+        Ok(Self {
+            epoch: o.epoch,
+            checkpoint: o.checkpoint,
+            object_id: o.object_id,
+            version: o.version,
+            object_digest: o.object_digest,
+            owner_type: enum_from_text(&o.owner_type)?,
+            owner_address: o.owner_address,
+            initial_shared_version: o.initial_shared_version,
+            previous_transaction: o.previous_transaction,
+            object_type: o.object_type,
+            object_status: enum_from_text(&o.object_status)?,
+            has_public_transfer: o.has_public_transfer,
+            storage_rebate: o.storage_rebate,
+            bcs: named_bcs_from_bytes(&o.bcs)?,
+        })
+    }
+}
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = packages)]
+pub(crate) struct SqlitePackage {
+    pub package_id: String,
+    pub version: i64,
+    pub author: String,
+    pub data: Vec<u8>,
+}
+
+impl From<&Package> for SqlitePackage {
+    fn from(p: &Package) -> Self {
+        Self {
+            package_id: p.package_id.clone(),
+            version: p.version,
+            author: p.author.clone(),
+            data: named_bcs_to_bytes(&p.data),
+        }
+    }
+}
+
+#[derive(Insertable, Debug, Clone)]
+#[diesel(table_name = system_states)]
+pub(crate) struct SqliteSystemStateSummary {
+    pub epoch: i64,
+    pub protocol_version: i64,
+    pub system_state_version: i64,
+    pub storage_fund: i64,
+    pub reference_gas_price: i64,
+    pub safe_mode: bool,
+    pub epoch_start_timestamp_ms: i64,
+    pub epoch_duration_ms: i64,
+    pub stake_subsidy_start_epoch: i64,
+    pub stake_subsidy_epoch_counter: i64,
+    pub stake_subsidy_balance: i64,
+    pub stake_subsidy_current_epoch_amount: i64,
+    pub total_stake: i64,
+    pub pending_active_validators_id: String,
+    pub pending_active_validators_size: i64,
+    pub pending_removals: String,
+    pub staking_pool_mappings_id: String,
+    pub staking_pool_mappings_size: i64,
+    pub inactive_pools_id: String,
+    pub inactive_pools_size: i64,
+    pub validator_candidates_id: String,
+    pub validator_candidates_size: i64,
+}
+
+impl From<&DBSystemStateSummary> for SqliteSystemStateSummary {
+    fn from(s: &DBSystemStateSummary) -> Self {
+        Self {
+            epoch: s.epoch,
+            protocol_version: s.protocol_version,
+            system_state_version: s.system_state_version,
+            storage_fund: s.storage_fund,
+            reference_gas_price: s.reference_gas_price,
+            safe_mode: s.safe_mode,
+            epoch_start_timestamp_ms: s.epoch_start_timestamp_ms,
+            epoch_duration_ms: s.epoch_duration_ms,
+            stake_subsidy_start_epoch: s.stake_subsidy_start_epoch,
+            stake_subsidy_epoch_counter: s.stake_subsidy_epoch_counter,
+            stake_subsidy_balance: s.stake_subsidy_balance,
+            stake_subsidy_current_epoch_amount: s.stake_subsidy_current_epoch_amount,
+            total_stake: s.total_stake,
+            pending_active_validators_id: s.pending_active_validators_id.clone(),
+            pending_active_validators_size: s.pending_active_validators_size,
+            pending_removals: to_json(&s.pending_removals),
+            staking_pool_mappings_id: s.staking_pool_mappings_id.clone(),
+            staking_pool_mappings_size: s.staking_pool_mappings_size,
+            inactive_pools_id: s.inactive_pools_id.clone(),
+            inactive_pools_size: s.inactive_pools_size,
+            validator_candidates_id: s.validator_candidates_id.clone(),
+            validator_candidates_size: s.validator_candidates_size,
+        }
+    }
+}
+
+/// Decodes the module list of a package stored by [`SqlitePackage`].
+pub(crate) fn named_bcs_from_bytes(bytes: &[u8]) -> Result<Vec<NamedBcsBytes>, IndexerError> {
+    let named_bytes: Vec<(String, Vec<u8>)> = bcs::from_bytes(bytes)?;
+    Ok(named_bytes
+        .into_iter()
+        .map(|(name, bytes)| NamedBcsBytes(name, bytes))
+        .collect())
+}
+
+fn named_bcs_to_bytes(named_bytes: &[NamedBcsBytes]) -> Vec<u8> {
+    let named_bytes: Vec<(&String, &Vec<u8>)> = named_bytes
+        .iter()
+        .map(|NamedBcsBytes(name, bytes)| (name, bytes))
+        .collect();
+    to_bcs(&named_bytes)
+}
+
+// NOTE: serializing lists of strings, integers and bytes cannot fail,
+// hence the unwraps in the helpers below.
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+fn to_bcs<T: Serialize>(value: &T) -> Vec<u8> {
+    bcs::to_bytes(value).unwrap()
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, IndexerError> {
+    serde_json::from_str(json).map_err(|e| {
+        IndexerError::SerdeError(format!(
+            "Failed to parse JSON column {:?} with error: {:?}",
+            json, e
+        ))
+    })
+}
+
+/// Returns the snake_case name of a DB enum such as `OwnerType` or `ObjectStatus`,
+/// which is what the Postgres enums store as well.
+fn enum_to_text<T: Serialize>(value: &T) -> String {
+    to_json(value).trim_matches('"').to_string()
+}
+
+fn enum_from_text<T: DeserializeOwned>(text: &str) -> Result<T, IndexerError> {
+    serde_json::from_value(serde_json::Value::String(text.to_string())).map_err(|e| {
+        IndexerError::SerdeError(format!(
+            "Failed to parse enum column {:?} with error: {:?}",
+            text, e
+        ))
+    })
+}
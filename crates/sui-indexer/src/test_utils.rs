@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::path::Path;
+
 use anyhow::anyhow;
 use prometheus::Registry;
 use tokio::task::JoinHandle;
@@ -8,10 +10,10 @@ use tokio::task::JoinHandle;
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 
 use crate::errors::IndexerError;
-use crate::store::PgIndexerStore;
-use crate::utils::reset_database;
+use crate::store::{PgIndexerStore, SqliteIndexerStore};
+use crate::utils::{reset_database, reset_sqlite_database, run_sqlite_migrations};
 use crate::IndexerMetrics;
-use crate::{new_pg_connection_pool, Indexer, IndexerConfig};
+use crate::{new_pg_connection_pool, new_sqlite_connection_pool, Indexer, IndexerConfig};
 
 /// Spawns an indexer thread with provided Postgres DB url
 pub async fn start_test_indexer(
@@ -40,6 +42,50 @@ pub async fn start_test_indexer(
     Ok((store, handle))
 }
 
+/// Spawns an indexer thread with the SQLite database file at the provided DB url,
+/// so that test clusters can run an indexer without Postgres.
+pub async fn start_test_sqlite_indexer(
+    config: IndexerConfig,
+) -> Result<(SqliteIndexerStore, JoinHandle<Result<(), IndexerError>>), anyhow::Error> {
+    let db_url = config.get_db_url()?;
+    let blocking_pool = new_sqlite_connection_pool(&db_url)
+        .map_err(|e| anyhow!("unable to open SQLite database {db_url}: {e}"))?;
+    let mut conn = blocking_pool
+        .get()
+        .map_err(|e| anyhow!("Fail to get sqlite_connection_pool {e}"))?;
+    if config.reset_db {
+        reset_sqlite_database(&mut conn, true)?;
+    } else {
+        run_sqlite_migrations(&mut conn)?;
+    }
+    drop(conn);
+
+    let registry = Registry::default();
+    let indexer_metrics = IndexerMetrics::new(&registry);
+
+    let store = SqliteIndexerStore::new(blocking_pool, indexer_metrics.clone());
+    let store_clone = store.clone();
+    let handle = tokio::spawn(async move {
+        Indexer::start(&config, &registry, store_clone, indexer_metrics, None).await
+    });
+    Ok((store, handle))
+}
+
+/// Opens the SQLite database file at `db_path` and runs the migrations on it, so that tests
+/// can read and write a [`SqliteIndexerStore`] without spawning an indexer.
+pub fn new_test_sqlite_store(db_path: &Path) -> Result<SqliteIndexerStore, anyhow::Error> {
+    let db_url = db_path.to_string_lossy();
+    let blocking_pool = new_sqlite_connection_pool(&db_url)
+        .map_err(|e| anyhow!("unable to open SQLite database {db_url}: {e}"))?;
+    run_sqlite_migrations(
+        &mut blocking_pool
+            .get()
+            .map_err(|e| anyhow!("Fail to get sqlite_connection_pool {e}"))?,
+    )?;
+    let indexer_metrics = IndexerMetrics::new(&Registry::default());
+    Ok(SqliteIndexerStore::new(blocking_pool, indexer_metrics))
+}
+
 #[derive(Clone)]
 pub struct SuiTransactionBlockResponseBuilder<'a> {
     response: SuiTransactionBlockResponse,
@@ -5,7 +5,7 @@ use std::ops::Neg;
 
 use anyhow::anyhow;
 use diesel::migration::MigrationSource;
-use diesel::{PgConnection, RunQueryDsl};
+use diesel::{PgConnection, QueryableByName, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use jsonrpsee::http_client::HttpClient;
 use sui_types::digests::ObjectDigest;
@@ -27,9 +27,10 @@ use sui_types::storage::{DeleteKind, WriteKind};
 
 use crate::errors::IndexerError;
 use crate::types::CheckpointTransactionBlockResponse;
-use crate::PgPoolConnection;
+use crate::{PgPoolConnection, SqlitePoolConnection};
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations_sqlite");
 
 /// Resets the database by reverting all migrations and reapplying them.
 ///
@@ -80,6 +81,60 @@ pub fn drop_all_tables(conn: &mut PgConnection) -> Result<(), diesel::result::Er
     Ok(())
 }
 
+/// Same as [`reset_database`], for the SQLite backend.
+pub fn reset_sqlite_database(
+    conn: &mut SqlitePoolConnection,
+    drop_all: bool,
+) -> Result<(), anyhow::Error> {
+    info!("Resetting SQLite database ...");
+    if drop_all {
+        drop_all_sqlite_tables(conn)
+            .map_err(|e| anyhow!("Encountering error when dropping all tables {e}"))?;
+    } else {
+        conn.revert_all_migrations(SQLITE_MIGRATIONS)
+            .map_err(|e| anyhow!("Error reverting all migrations {e}"))?;
+    }
+    run_sqlite_migrations(conn)?;
+    info!("Reset SQLite database complete.");
+    Ok(())
+}
+
+/// Applies the pending migrations of the SQLite backend, which creates the schema
+/// when the database file is new.
+pub fn run_sqlite_migrations(conn: &mut SqlitePoolConnection) -> Result<(), anyhow::Error> {
+    conn.run_pending_migrations(SQLITE_MIGRATIONS)
+        .map_err(|e| anyhow!("Failed to run migrations {e}"))?;
+    Ok(())
+}
+
+pub fn drop_all_sqlite_tables(conn: &mut SqliteConnection) -> Result<(), diesel::result::Error> {
+    info!("Dropping all tables in the SQLite database");
+    #[derive(QueryableByName)]
+    struct SchemaObject {
+        #[diesel(sql_type = diesel::sql_types::Text)]
+        kind: String,
+        #[diesel(sql_type = diesel::sql_types::Text)]
+        name: String,
+    }
+
+    // Views have to be dropped as well, since they would fail to resolve their tables.
+    let schema_objects: Vec<SchemaObject> = diesel::sql_query(
+        "
+        SELECT type AS kind, name FROM sqlite_master
+        WHERE type IN ('view', 'table') AND name NOT LIKE 'sqlite_%'
+        ORDER BY type DESC
+    ",
+    )
+    .load(conn)?;
+
+    for SchemaObject { kind, name } in schema_objects {
+        let drop_query = format!("DROP {} IF EXISTS {}", kind.to_uppercase(), name);
+        diesel::sql_query(drop_query).execute(conn)?;
+    }
+    info!("Dropped all tables in the SQLite database");
+    Ok(())
+}
+
 pub async fn multi_get_full_transactions(
     http_client: HttpClient,
     digests: Vec<TransactionDigest>,
@@ -1381,3 +1381,71 @@ pub mod pg_integration_test {
         EventFilter::MoveEventType(StructTag::from_str(event_type).unwrap())
     }
 }
+
+// integration test with a SQLite database file, runs without any external database
+pub mod sqlite_integration_test {
+    use jsonrpsee::http_client::HttpClientBuilder;
+    use ntest::timeout;
+
+    use sui_indexer::store::{IndexerStore, SqliteIndexerStore};
+    use sui_indexer::test_utils::start_test_sqlite_indexer;
+    use sui_indexer::{DbBackend, IndexerConfig};
+    use sui_json_rpc::api::ReadApiClient;
+    use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+    use test_cluster::TestClusterBuilder;
+
+    const WAIT_UNTIL_TIME_LIMIT: u64 = 60;
+
+    #[tokio::test]
+    #[timeout(60000)]
+    async fn test_genesis_sync() {
+        let db_dir = tempfile::tempdir().unwrap();
+        let test_cluster = TestClusterBuilder::new().build().await;
+        let config = IndexerConfig {
+            db_backend: DbBackend::Sqlite,
+            db_url: Some(db_dir.path().join("indexer.db").display().to_string()),
+            rpc_client_url: test_cluster.rpc_url().to_string(),
+            migrated_methods: IndexerConfig::all_implemented_methods(),
+            reset_db: true,
+            ..Default::default()
+        };
+        let http_addr_port = format!(
+            "http://{}:{}",
+            config.rpc_server_url, config.rpc_server_port
+        );
+        let indexer_rpc_client = HttpClientBuilder::default().build(http_addr_port).unwrap();
+        let (store, handle) = start_test_sqlite_indexer(config).await.unwrap();
+        // Allow indexer to sync
+        wait_for_checkpoint(&store, 1).await;
+
+        let checkpoint = store.get_checkpoint(0.into()).await.unwrap();
+        for tx_digest in checkpoint.transactions {
+            let transaction = store
+                .get_transaction_by_digest(&tx_digest.base58_encode())
+                .await;
+            assert!(transaction.is_ok());
+            let indexer_rpc_tx = indexer_rpc_client
+                .get_transaction_block(tx_digest, Some(SuiTransactionBlockResponseOptions::new()))
+                .await
+                .unwrap();
+            assert_eq!(indexer_rpc_tx.digest, tx_digest);
+        }
+        drop(handle);
+    }
+
+    async fn wait_for_checkpoint(store: &SqliteIndexerStore, target: i64) {
+        let since = std::time::Instant::now();
+        loop {
+            if let Ok(cp) = store.get_latest_checkpoint_sequence_number().await {
+                if cp >= target {
+                    break;
+                }
+            }
+            let now = std::time::Instant::now();
+            if now.duration_since(since).as_secs() > WAIT_UNTIL_TIME_LIMIT {
+                panic!("wait_for_checkpoint timed out!");
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+}
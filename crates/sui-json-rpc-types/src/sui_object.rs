@@ -35,7 +35,7 @@ use sui_types::sui_serde::BigInt;
 use sui_types::sui_serde::SequenceNumber as AsSequenceNumber;
 use sui_types::sui_serde::SuiStructTag;
 
-use crate::{Page, SuiMoveStruct, SuiMoveValue};
+use crate::{Filter, Page, SuiMoveStruct, SuiMoveValue};
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
 pub struct SuiObjectResponse {
@@ -228,6 +228,32 @@ impl SuiObjectData {
             None => false,
         }
     }
+
+    /// Drop the fields which are not requested by `options`.
+    pub fn with_options(mut self, options: &SuiObjectDataOptions) -> Self {
+        if !options.show_type {
+            self.type_ = None;
+        }
+        if !options.show_owner {
+            self.owner = None;
+        }
+        if !options.show_previous_transaction {
+            self.previous_transaction = None;
+        }
+        if !options.show_storage_rebate {
+            self.storage_rebate = None;
+        }
+        if !options.show_display {
+            self.display = None;
+        }
+        if !options.show_content {
+            self.content = None;
+        }
+        if !options.show_bcs {
+            self.bcs = None;
+        }
+        self
+    }
 }
 
 impl Display for SuiObjectData {
@@ -1192,6 +1218,26 @@ impl SuiObjectDataFilter {
     }
 }
 
+/// Used to filter object subscriptions, objects without type, owner or previous transaction never
+/// match.
+impl Filter<SuiObjectData> for SuiObjectDataFilter {
+    fn matches(&self, item: &SuiObjectData) -> bool {
+        let (Some(type_), Some(owner), Some(previous_transaction)) =
+            (item.type_.clone(), item.owner, item.previous_transaction) else {
+            return false;
+        };
+        let info = ObjectInfo {
+            object_id: item.object_id,
+            version: item.version,
+            digest: item.digest,
+            type_,
+            owner,
+            previous_transaction,
+        };
+        SuiObjectDataFilter::matches(self, &info)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Default)]
 #[serde(rename_all = "camelCase", rename = "ObjectResponseQuery", default)]
 pub struct SuiObjectResponseQuery {
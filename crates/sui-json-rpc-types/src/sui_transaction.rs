@@ -26,7 +26,9 @@ use sui_types::digests::{ObjectDigest, TransactionEventsDigest};
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use sui_types::error::{ExecutionError, SuiError, SuiResult};
 use sui_types::execution_status::ExecutionStatus;
+use sui_types::execution_trace::ExecutionTrace;
 use sui_types::gas::GasCostSummary;
+use sui_types::gas_model::profile::GasProfile;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::object::Owner;
 use sui_types::parse_sui_type_tag;
@@ -692,6 +694,18 @@ pub struct DryRunTransactionBlockResponse {
     pub object_changes: Vec<ObjectChange>,
     pub balance_changes: Vec<BalanceChange>,
     pub input: SuiTransactionBlockData,
+    /// Gas profile of the execution in the speedscope format, attributing computation to every
+    /// command, Move function, native function and instruction, and storage fees, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_profile: Option<GasProfile>,
+}
+
+/// Additional arguments supplied to dry run beyond what is allowed in today's API.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase", rename = "DryRunArgs", default)]
+pub struct DryRunArgs {
+    /// Whether to return a gas profile of the execution. Default to be False
+    pub show_gas_profile: bool,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -736,6 +750,18 @@ pub struct DevInspectResults {
     /// Execution error from executing the transactions
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Trace of the Move calls, object reads and events of every command, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<ExecutionTrace>,
+}
+
+/// Additional arguments supplied to dev inspect beyond what is allowed in today's API.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, Eq, PartialEq, Default)]
+#[serde(rename_all = "camelCase", rename = "DevInspectArgs", default)]
+pub struct DevInspectArgs {
+    /// Whether to return a trace of the Move calls, object reads and events of every command.
+    /// Default to be False
+    pub show_trace: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -760,6 +786,7 @@ impl DevInspectResults {
         effects: TransactionEffects,
         events: TransactionEvents,
         return_values: Result<Vec<ExecutionResult>, ExecutionError>,
+        trace: Option<ExecutionTrace>,
         resolver: &impl GetModule,
     ) -> SuiResult<Self> {
         let tx_digest = *effects.transaction_digest();
@@ -794,6 +821,7 @@ impl DevInspectResults {
             events: SuiTransactionBlockEvents::try_from(events, tx_digest, None, resolver)?,
             results,
             error,
+            trace,
         })
     }
 }
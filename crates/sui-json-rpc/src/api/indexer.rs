@@ -6,7 +6,8 @@ use jsonrpsee_proc_macros::rpc;
 
 use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent, SuiObjectResponse,
+    Checkpoint, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent,
+    SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
     SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
     TransactionFilter,
 };
@@ -15,6 +16,7 @@ use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
 use sui_types::event::EventID;
+use sui_types::sui_serde::BigInt;
 
 #[open_rpc(namespace = "suix", tag = "Extended API")]
 #[rpc(server, client, namespace = "suix")]
@@ -71,11 +73,47 @@ pub trait IndexerApi {
         &self,
         /// The filter criteria of the event stream. See [Event filter](https://docs.sui.io/build/event_api#event-filters) documentation for examples.
         filter: EventFilter,
+        /// An optional resume cursor. If provided, events emitted after the specified event are replayed before new events are streamed. Default to only stream new events if not specified.
+        cursor: Option<EventID>,
     );
 
     /// Subscribe to a stream of Sui transaction effects
     #[subscription(name = "subscribeTransaction", item = SuiTransactionBlockEffects)]
-    fn subscribe_transaction(&self, filter: TransactionFilter);
+    fn subscribe_transaction(
+        &self,
+        /// The filter criteria of the transaction stream.
+        filter: TransactionFilter,
+        /// An optional resume cursor. If provided, transactions executed after the specified transaction are replayed before new transactions are streamed. Default to only stream new transactions if not specified.
+        cursor: Option<TransactionDigest>,
+    );
+
+    /// Subscribe to a stream of executed checkpoints
+    #[subscription(name = "subscribeCheckpoint", item = Checkpoint)]
+    fn subscribe_checkpoint(
+        &self,
+        /// An optional resume cursor. If provided, checkpoints after the specified checkpoint sequence number are replayed before new checkpoints are streamed. Default to only stream new checkpoints if not specified.
+        cursor: Option<BigInt<u64>>,
+    );
+
+    /// Subscribe to a stream of new object versions
+    #[subscription(name = "subscribeObject", item = SuiObjectData)]
+    fn subscribe_object(
+        &self,
+        /// The filter criteria of the object stream. Only objects created, mutated or unwrapped after the subscription are streamed.
+        filter: SuiObjectDataFilter,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    );
+
+    /// Subscribe to a stream of new versions of the objects owned by an address
+    #[subscription(name = "subscribeOwnedObjects", item = SuiObjectData)]
+    fn subscribe_owned_objects(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    );
 
     /// Return the list of dynamic field objects owned by an object.
     #[method(name = "getDynamicFields")]
@@ -74,6 +74,22 @@ pub trait ReadApi {
         options: Option<SuiObjectDataOptions>,
     ) -> RpcResult<SuiPastObjectResponse>;
 
+    /// Note there is no software-level guarantee/SLA that objects with past versions
+    /// can be retrieved by this API, even if the object and version exists/existed.
+    /// The result may vary across nodes depending on their pruning policies.
+    /// Return the object information for the latest version of an object at or before a specified
+    /// version
+    #[method(name = "tryGetPastObjectLtOrEqVersion")]
+    async fn try_get_past_object_lt_or_eq_version(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// the highest version of the queried object to return
+        version: SequenceNumber,
+        /// options for specifying the content to be returned
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse>;
+
     /// Note there is no software-level guarantee/SLA that objects with past versions
     /// can be retrieved by this API, even if the object and version exists/existed.
     /// The result may vary across nodes depending on their pruning policies.
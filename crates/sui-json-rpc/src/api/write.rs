@@ -6,8 +6,8 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 
 use sui_json_rpc_types::{
-    DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DevInspectArgs, DevInspectResults, DryRunArgs, DryRunTransactionBlockResponse,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::SuiAddress;
@@ -53,6 +53,8 @@ pub trait WriteApi {
         gas_price: Option<BigInt<u64>>,
         /// The epoch to perform the call. Will be set from the system state object if not provided
         epoch: Option<BigInt<u64>>,
+        /// Additional arguments, e.g. to return a trace of the execution
+        additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults>;
 
     /// Return transaction execution effects including the gas cost summary,
@@ -61,5 +63,7 @@ pub trait WriteApi {
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
+        /// Additional arguments, e.g. to return a gas profile of the execution
+        additional_args: Option<DryRunArgs>,
     ) -> RpcResult<DryRunTransactionBlockResponse>;
 }
@@ -1,11 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::HashSet;
+use std::future::Future;
+use std::hash::Hash;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use futures::Stream;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
 use jsonrpsee::core::error::SubscriptionClosed;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::types::SubscriptionResult;
@@ -21,22 +25,26 @@ use move_core_types::ident_str;
 use move_core_types::identifier::IdentStr;
 use move_core_types::language_storage::{StructTag, TypeTag};
 use mysten_metrics::spawn_monitored_task;
-use sui_core::authority::AuthorityState;
+use sui_core::authority::{AuthorityState, EventQueryResult};
 use sui_json_rpc_types::{
-    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiMoveValue,
-    SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery, SuiParsedMoveObject,
-    SuiTransactionBlockResponse, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
-    TransactionFilter,
+    Checkpoint, DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiEvent,
+    SuiMoveValue, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiParsedMoveObject, SuiTransactionBlockEffects,
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseQuery,
+    TransactionBlocksPage, TransactionFilter,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::dynamic_field::DynamicFieldName;
+use sui_types::error::SuiError;
 use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::sui_serde::BigInt;
 
 use crate::api::{
     cap_page_limit, validate_limit, IndexerApiServer, JsonRpcMetrics, ReadApiServer,
-    QUERY_MAX_RESULT_LIMIT,
+    QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
 };
 use crate::error::{Error, SuiRpcInputError};
 use crate::name_service::Domain;
@@ -76,6 +84,136 @@ where
         };
     });
 }
+
+enum ResumeState<T, C, K, P, L> {
+    Backfill {
+        cursor: Option<C>,
+        fetch_page: P,
+        subscribe: Option<L>,
+        live: Option<BoxStream<'static, T>>,
+        replayed: HashSet<K>,
+    },
+    Live {
+        live: BoxStream<'static, T>,
+        replayed: HashSet<K>,
+    },
+}
+
+/// Create a subscription stream that can be resumed after a disconnect.
+/// If `cursor` is provided, items after the cursor are first replayed page by page using
+/// `fetch_page`. Once the backfill has caught up, the live stream is subscribed to and one more
+/// page is fetched to cover items that arrived in between; items of the last pages that are also
+/// delivered by the live stream are dropped, so subscribers see no gaps and no duplicates.
+/// Without a cursor this is the live stream.
+pub fn resume_subscription<T, C, K, P, Fut, L, S>(
+    cursor: Option<C>,
+    fetch_page: P,
+    subscribe: L,
+    key: fn(&T) -> K,
+) -> BoxStream<'static, T>
+where
+    T: Send + 'static,
+    C: Clone + Send + 'static,
+    K: Eq + Hash + Send + 'static,
+    P: FnMut(Option<C>) -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<Page<T, C>>> + Send,
+    L: FnOnce() -> S + Send + 'static,
+    S: Stream<Item = T> + Send + 'static,
+{
+    if cursor.is_none() {
+        return subscribe().boxed();
+    }
+    let initial_state = ResumeState::Backfill {
+        cursor,
+        fetch_page,
+        subscribe: Some(subscribe),
+        live: None,
+        replayed: HashSet::new(),
+    };
+    futures::stream::unfold(initial_state, move |state| async move {
+        match state {
+            ResumeState::Backfill {
+                cursor,
+                mut fetch_page,
+                mut subscribe,
+                mut live,
+                mut replayed,
+            } => {
+                let page = match fetch_page(cursor.clone()).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        warn!("Failed to backfill subscription, closing stream: {e}");
+                        return None;
+                    }
+                };
+                let keys = page.data.iter().map(key);
+                if live.is_none() {
+                    // Only the page fetched right before subscribing can overlap with the live stream.
+                    replayed = keys.collect();
+                } else {
+                    replayed.extend(keys);
+                }
+                let cursor = page.next_cursor.or(cursor);
+                let next_state = if page.has_next_page {
+                    ResumeState::Backfill {
+                        cursor,
+                        fetch_page,
+                        subscribe,
+                        live,
+                        replayed,
+                    }
+                } else if let Some(subscribe) = subscribe.take() {
+                    ResumeState::Backfill {
+                        cursor,
+                        fetch_page,
+                        subscribe: None,
+                        live: Some(subscribe().boxed()),
+                        replayed,
+                    }
+                } else {
+                    ResumeState::Live {
+                        live: live.take()?,
+                        replayed,
+                    }
+                };
+                Some((futures::stream::iter(page.data), next_state))
+            }
+            ResumeState::Live {
+                mut live,
+                mut replayed,
+            } => loop {
+                let item = live.next().await?;
+                if !replayed.is_empty() && replayed.remove(&key(&item)) {
+                    continue;
+                }
+                break Some((
+                    futures::stream::iter(vec![item]),
+                    ResumeState::Live { live, replayed },
+                ));
+            },
+        }
+    })
+    .flatten()
+    .boxed()
+}
+
+/// Turns the result of an event query for `limit + 1` events into a page. A query stopped by
+/// its scan budget has a next page, starting after the last scanned event.
+fn event_page(result: EventQueryResult, cursor: Option<EventID>, limit: usize) -> EventPage {
+    let EventQueryResult {
+        events: mut data,
+        scan_cursor,
+    } = result;
+    let has_next_page = data.len() > limit || scan_cursor.is_some();
+    data.truncate(limit);
+    let next_cursor = scan_cursor.or_else(|| data.last().map_or(cursor, |e| Some(e.id.clone())));
+    EventPage {
+        data,
+        next_cursor,
+        has_next_page,
+    }
+}
+
 pub struct IndexerApi<R> {
     state: Arc<AuthorityState>,
     read_api: R,
@@ -224,13 +362,15 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
             let limit = cap_page_limit(limit);
             self.metrics.query_events_limit.report(limit as u64);
             // Retrieve 1 extra item for next cursor
-            let mut data = self
+            let result = self
                 .state
                 .query_events(query, cursor.clone(), limit + 1, descending)
                 .map_err(Error::from)?;
-            let has_next_page = data.len() > limit;
-            data.truncate(limit);
-            let next_cursor = data.last().map_or(cursor, |e| Some(e.id.clone()));
+            let EventPage {
+                data,
+                next_cursor,
+                has_next_page,
+            } = event_page(result, cursor, limit);
             self.metrics
                 .query_events_result_size
                 .report(data.len() as u64);
@@ -246,11 +386,30 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
     }
 
     #[instrument(skip(self))]
-    fn subscribe_event(&self, sink: SubscriptionSink, filter: EventFilter) -> SubscriptionResult {
-        spawn_subscription(
-            sink,
-            self.state.subscription_handler.subscribe_events(filter),
+    fn subscribe_event(
+        &self,
+        sink: SubscriptionSink,
+        filter: EventFilter,
+        cursor: Option<EventID>,
+    ) -> SubscriptionResult {
+        let state = self.state.clone();
+        let subscription_handler = self.state.subscription_handler.clone();
+        let live_filter = filter.clone();
+        let stream = resume_subscription(
+            cursor,
+            move |cursor: Option<EventID>| {
+                let state = state.clone();
+                let filter = filter.clone();
+                async move {
+                    let limit = *QUERY_MAX_RESULT_LIMIT;
+                    let result = state.query_events(filter, cursor.clone(), limit + 1, false)?;
+                    Ok::<_, anyhow::Error>(event_page(result, cursor, limit))
+                }
+            },
+            move || subscription_handler.subscribe_events(live_filter),
+            |event: &SuiEvent| event.id.clone(),
         );
+        spawn_subscription(sink, stream);
         Ok(())
     }
 
@@ -258,16 +417,114 @@ impl<R: ReadApiServer> IndexerApiServer for IndexerApi<R> {
         &self,
         sink: SubscriptionSink,
         filter: TransactionFilter,
+        cursor: Option<TransactionDigest>,
+    ) -> SubscriptionResult {
+        let state = self.state.clone();
+        let subscription_handler = self.state.subscription_handler.clone();
+        let live_filter = filter.clone();
+        let stream = resume_subscription(
+            cursor,
+            move |cursor: Option<TransactionDigest>| {
+                let state = state.clone();
+                let filter = filter.clone();
+                async move {
+                    let limit = *QUERY_MAX_RESULT_LIMIT;
+                    let mut digests =
+                        state.get_transactions(Some(filter), cursor, Some(limit + 1), false)?;
+                    let has_next_page = digests.len() > limit;
+                    digests.truncate(limit);
+                    let next_cursor = digests.last().cloned().map_or(cursor, Some);
+                    let data = state
+                        .multi_get_executed_effects(&digests)?
+                        .into_iter()
+                        .zip(digests)
+                        .map(|(effects, digest)| {
+                            effects
+                                .ok_or(SuiError::TransactionNotFound { digest })?
+                                .try_into()
+                        })
+                        .collect::<Result<Vec<SuiTransactionBlockEffects>, SuiError>>()?;
+                    Ok::<_, anyhow::Error>(Page {
+                        data,
+                        next_cursor,
+                        has_next_page,
+                    })
+                }
+            },
+            move || subscription_handler.subscribe_transactions(live_filter),
+            |effects: &SuiTransactionBlockEffects| *effects.transaction_digest(),
+        );
+        spawn_subscription(sink, stream);
+        Ok(())
+    }
+
+    fn subscribe_checkpoint(
+        &self,
+        sink: SubscriptionSink,
+        cursor: Option<BigInt<u64>>,
+    ) -> SubscriptionResult {
+        let state = self.state.clone();
+        let subscription_handler = self.state.subscription_handler.clone();
+        let stream = resume_subscription(
+            cursor.map(|c| *c),
+            move |cursor: Option<CheckpointSequenceNumber>| {
+                let state = state.clone();
+                async move {
+                    let limit = QUERY_MAX_RESULT_LIMIT_CHECKPOINTS;
+                    let latest = state.get_latest_checkpoint_sequence_number()?;
+                    // `get_checkpoints` clamps the range to the latest checkpoint, so a cursor
+                    // at the tip would return the cursor itself again.
+                    if cursor.map_or(false, |c| c >= latest) {
+                        return Ok(Page {
+                            data: vec![],
+                            next_cursor: cursor,
+                            has_next_page: false,
+                        });
+                    }
+                    let mut data = state.get_checkpoints(cursor, limit as u64 + 1, false)?;
+                    let has_next_page = data.len() > limit;
+                    data.truncate(limit);
+                    let next_cursor = data.last().map_or(cursor, |c| Some(c.sequence_number));
+                    Ok::<_, anyhow::Error>(Page {
+                        data,
+                        next_cursor,
+                        has_next_page,
+                    })
+                }
+            },
+            move || subscription_handler.subscribe_checkpoints(),
+            |checkpoint: &Checkpoint| checkpoint.sequence_number,
+        );
+        spawn_subscription(sink, stream);
+        Ok(())
+    }
+
+    fn subscribe_object(
+        &self,
+        sink: SubscriptionSink,
+        filter: SuiObjectDataFilter,
+        options: Option<SuiObjectDataOptions>,
     ) -> SubscriptionResult {
+        let options = options.unwrap_or_default();
         spawn_subscription(
             sink,
             self.state
                 .subscription_handler
-                .subscribe_transactions(filter),
+                .subscribe_objects(filter)
+                .map(move |object| object.with_options(&options)),
         );
         Ok(())
     }
 
+    fn subscribe_owned_objects(
+        &self,
+        sink: SubscriptionSink,
+        address: SuiAddress,
+        options: Option<SuiObjectDataOptions>,
+    ) -> SubscriptionResult {
+        self.subscribe_object(sink, SuiObjectDataFilter::AddressOwner(address), options)
+    }
+
     #[instrument(skip(self))]
     async fn get_dynamic_fields(
         &self,
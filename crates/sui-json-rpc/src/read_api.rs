@@ -402,6 +402,42 @@ impl ReadApi {
             .inc_by(converted_tx_block_resps.len() as u64);
         Ok(converted_tx_block_resps)
     }
+
+    fn past_object_response(
+        &self,
+        past_read: PastObjectRead,
+        options: SuiObjectDataOptions,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        match past_read {
+            PastObjectRead::ObjectNotExists(id) => Ok(SuiPastObjectResponse::ObjectNotExists(id)),
+            PastObjectRead::VersionFound(object_ref, o, layout) => {
+                let display_fields = if options.show_display {
+                    // TODO (jian): api breaking change to also modify past objects.
+                    Some(get_display_fields(self, &o, &layout)?)
+                } else {
+                    None
+                };
+                Ok(SuiPastObjectResponse::VersionFound(
+                    (object_ref, o, layout, options, display_fields).try_into()?,
+                ))
+            }
+            PastObjectRead::ObjectDeleted(oref) => {
+                Ok(SuiPastObjectResponse::ObjectDeleted(oref.into()))
+            }
+            PastObjectRead::VersionNotFound(id, seq_num) => {
+                Ok(SuiPastObjectResponse::VersionNotFound(id, seq_num))
+            }
+            PastObjectRead::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            } => Ok(SuiPastObjectResponse::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -521,38 +557,26 @@ impl ReadApiServer for ReadApi {
                 error!("Failed to call try_get_past_object for object: {object_id:?} version: {version:?} with error: {e:?}");
                 Error::from(e)
             })}).await.map_err(Error::from)??;
-            let options = options.unwrap_or_default();
-            match past_read {
-                PastObjectRead::ObjectNotExists(id) => {
-                    Ok(SuiPastObjectResponse::ObjectNotExists(id))
-                }
-                PastObjectRead::VersionFound(object_ref, o, layout) => {
-                    let display_fields = if options.show_display {
-                        // TODO (jian): api breaking change to also modify past objects.
-                        Some(get_display_fields(self, &o, &layout)?)
-                    } else {
-                        None
-                    };
-                    Ok(SuiPastObjectResponse::VersionFound(
-                        (object_ref, o, layout, options, display_fields).try_into()?,
-                    ))
-                }
-                PastObjectRead::ObjectDeleted(oref) => {
-                    Ok(SuiPastObjectResponse::ObjectDeleted(oref.into()))
-                }
-                PastObjectRead::VersionNotFound(id, seq_num) => {
-                    Ok(SuiPastObjectResponse::VersionNotFound(id, seq_num))
-                }
-                PastObjectRead::VersionTooHigh {
-                    object_id,
-                    asked_version,
-                    latest_version,
-                } => Ok(SuiPastObjectResponse::VersionTooHigh {
-                    object_id,
-                    asked_version,
-                    latest_version,
-                }),
-            }
+            self.past_object_response(past_read, options.unwrap_or_default())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn try_get_past_object_lt_or_eq_version(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        with_tracing!(async move {
+            let state = self.state.clone();
+            let past_read = spawn_monitored_task!(async move {
+            state.get_past_object_read_lt_or_eq_version(&object_id, version)
+            .map_err(|e| {
+                error!("Failed to call try_get_past_object_lt_or_eq_version for object: {object_id:?} version: {version:?} with error: {e:?}");
+                Error::from(e)
+            })}).await.map_err(Error::from)??;
+            self.past_object_response(past_read, options.unwrap_or_default())
         })
     }
 
@@ -1019,12 +1043,15 @@ fn get_display_object_by_type(
     object_type: &StructTag,
     // TODO: add query version support
 ) -> Result<Option<DisplayVersionUpdatedEvent>, Error> {
-    let mut events = fullnode_api.state.query_events(
-        EventFilter::MoveEventType(DisplayVersionUpdatedEvent::type_(object_type)),
-        None,
-        1,
-        true,
-    )?;
+    let mut events = fullnode_api
+        .state
+        .query_events(
+            EventFilter::MoveEventType(DisplayVersionUpdatedEvent::type_(object_type)),
+            None,
+            1,
+            true,
+        )?
+        .events;
 
     // If there's any recent version of Display, give it to the client.
     // TODO: add support for version query.
@@ -11,16 +11,17 @@ use move_core_types::language_storage::StructTag;
 
 use sui_core::authority::AuthorityState;
 use sui_json::SuiJsonValue;
-use sui_json_rpc_types::{RPCTransactionRequestParams, SuiObjectDataFilter};
 use sui_json_rpc_types::{
-    SuiObjectDataOptions, SuiObjectResponse, SuiTransactionBlockBuilderMode, SuiTypeTag,
-    TransactionBlockBytes,
+    DryRunTransactionBlockResponse, SuiObjectDataOptions, SuiObjectResponse,
+    SuiTransactionBlockBuilderMode, SuiTypeTag, TransactionBlockBytes,
 };
+use sui_json_rpc_types::{RPCTransactionRequestParams, SuiObjectDataFilter};
 use sui_open_rpc::Module;
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 use sui_types::base_types::ObjectInfo;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::sui_serde::BigInt;
+use sui_types::transaction::TransactionData;
 
 use crate::api::TransactionBuilderServer;
 use crate::SuiRpcModule;
@@ -73,6 +74,20 @@ impl DataReader for AuthorityStateDataReader {
         let epoch_store = self.0.load_epoch_store_one_call_per_task();
         Ok(epoch_store.reference_gas_price())
     }
+
+    async fn get_max_tx_gas(&self) -> Result<u64, anyhow::Error> {
+        let epoch_store = self.0.load_epoch_store_one_call_per_task();
+        Ok(epoch_store.protocol_config().max_tx_gas())
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx: TransactionData,
+    ) -> Result<DryRunTransactionBlockResponse, anyhow::Error> {
+        let digest = tx.digest();
+        let (response, _, _, _) = self.0.dry_exec_transaction(tx, digest, false).await?;
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -16,8 +16,9 @@ use sui_core::authority::AuthorityState;
 use sui_core::authority_client::NetworkAuthorityClient;
 use sui_core::transaction_orchestrator::TransactiondOrchestrator;
 use sui_json_rpc_types::{
-    DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlock,
-    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    DevInspectArgs, DevInspectResults, DryRunArgs, DryRunTransactionBlockResponse,
+    SuiTransactionBlock, SuiTransactionBlockEvents, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
 };
 use sui_open_rpc::Module;
 use sui_types::base_types::SuiAddress;
@@ -172,13 +173,14 @@ impl TransactionExecutionApi {
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
+        show_gas_profile: bool,
     ) -> Result<DryRunTransactionBlockResponse, Error> {
         let (txn_data, txn_digest) = get_transaction_data_and_digest(tx_bytes)?;
         let input_objs = txn_data.input_objects()?;
         let sender = txn_data.sender();
         let (resp, written_objects, transaction_effects, mock_gas) = self
             .state
-            .dry_exec_transaction(txn_data.clone(), txn_digest)
+            .dry_exec_transaction(txn_data.clone(), txn_digest, show_gas_profile)
             .await?;
         let object_cache = ObjectProviderCache::new_with_cache(self.state.clone(), written_objects);
         let balance_changes = get_balance_changes_from_effect(
@@ -203,6 +205,7 @@ impl TransactionExecutionApi {
             object_changes,
             balance_changes,
             input: resp.input,
+            gas_profile: resp.gas_profile,
         })
     }
 }
@@ -231,13 +234,19 @@ impl WriteApiServer for TransactionExecutionApi {
         tx_bytes: Base64,
         gas_price: Option<BigInt<u64>>,
         _epoch: Option<BigInt<u64>>,
+        additional_args: Option<DevInspectArgs>,
     ) -> RpcResult<DevInspectResults> {
         with_tracing!(async move {
             let tx_kind: TransactionKind =
                 bcs::from_bytes(&tx_bytes.to_vec().map_err(Error::from)?).map_err(Error::from)?;
             Ok(self
                 .state
-                .dev_inspect_transaction_block(sender_address, tx_kind, gas_price.map(|i| *i))
+                .dev_inspect_transaction_block(
+                    sender_address,
+                    tx_kind,
+                    gas_price.map(|i| *i),
+                    additional_args.unwrap_or_default().show_trace,
+                )
                 .await
                 .map_err(Error::from)?)
         })
@@ -247,8 +256,14 @@ impl WriteApiServer for TransactionExecutionApi {
     async fn dry_run_transaction_block(
         &self,
         tx_bytes: Base64,
+        additional_args: Option<DryRunArgs>,
     ) -> RpcResult<DryRunTransactionBlockResponse> {
-        with_tracing!(async move { Ok(self.dry_run_transaction_block(tx_bytes).await?) })
+        with_tracing!(async move {
+            let show_gas_profile = additional_args.unwrap_or_default().show_gas_profile;
+            Ok(self
+                .dry_run_transaction_block(tx_bytes, show_gas_profile)
+                .await?)
+        })
     }
 }
 
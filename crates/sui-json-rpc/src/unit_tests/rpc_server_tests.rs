@@ -118,7 +118,9 @@ async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
         .sign_transaction(&transaction_bytes.to_data()?);
     let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
     let tx_bytes1 = tx_bytes.clone();
-    let dryrun_response = http_client.dry_run_transaction_block(tx_bytes).await?;
+    let dryrun_response = http_client
+        .dry_run_transaction_block(tx_bytes, None)
+        .await?;
 
     let tx_response: SuiTransactionBlockResponse = http_client
         .execute_transaction_block(
@@ -898,7 +900,7 @@ async fn test_staking_multiple_coins() -> Result<(), anyhow::Error> {
     let (tx_bytes, signatures) = tx.to_tx_bytes_and_signatures();
 
     let dryrun_response = http_client
-        .dry_run_transaction_block(tx_bytes.clone())
+        .dry_run_transaction_block(tx_bytes.clone(), None)
         .await?;
 
     let executed_response = http_client
@@ -1,17 +1,22 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::fmt::Debug;
 use std::time::Duration;
 
 use jsonrpsee::core::client::{Subscription, SubscriptionClientT};
 use jsonrpsee::rpc_params;
+use serde::de::DeserializeOwned;
 use sui_test_transaction_builder::{create_devnet_nft, publish_nfts_package};
 use tokio::time::timeout;
 
 use sui_core::test_utils::wait_for_tx;
 use sui_json_rpc_types::{
+    Checkpoint, EventFilter, SuiEvent, SuiObjectData, SuiObjectDataOptions,
     SuiTransactionBlockEffects, SuiTransactionBlockEffectsAPI, TransactionFilter,
 };
+use sui_types::object::Owner;
+use sui_types::sui_serde::BigInt;
 use test_cluster::TestClusterBuilder;
 
 #[tokio::test]
@@ -46,3 +51,188 @@ async fn test_subscribe_transaction() -> Result<(), anyhow::Error> {
     assert_eq!(&digest, effects.transaction_digest());
     Ok(())
 }
+
+#[tokio::test]
+async fn test_subscribe_checkpoint_with_cursor() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+    let ws_client = cluster.fullnode_handle.ws_client;
+
+    // Resuming after genesis replays the checkpoints that were executed in the meantime,
+    // in order and without gaps, before streaming new ones.
+    let mut sub: Subscription<Checkpoint> = ws_client
+        .subscribe(
+            "suix_subscribeCheckpoint",
+            rpc_params![BigInt::<u64>::from(0)],
+            "suix_unsubscribeCheckpoint",
+        )
+        .await
+        .unwrap();
+
+    for expected in 1..=3 {
+        let checkpoint = match timeout(Duration::from_secs(30), sub.next()).await {
+            Ok(Some(Ok(checkpoint))) => checkpoint,
+            _ => panic!("Failed to get checkpoint"),
+        };
+        assert_eq!(expected, checkpoint.sequence_number);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_transaction_with_cursor() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+
+    let address = &cluster.get_address_0();
+    let wallet = cluster.wallet;
+    let state = cluster.fullnode_handle.sui_node.state();
+    let ws_client = cluster.fullnode_handle.ws_client;
+
+    let package_id = publish_nfts_package(&wallet).await.0;
+    let filter = TransactionFilter::FromAddress(*address);
+
+    let mut sub: Subscription<SuiTransactionBlockEffects> = ws_client
+        .subscribe(
+            "suix_subscribeTransaction",
+            rpc_params![filter.clone()],
+            "suix_unsubscribeTransaction",
+        )
+        .await
+        .unwrap();
+    let (_, _, cursor) = create_devnet_nft(&wallet, package_id).await;
+    wait_for_tx(cursor, state.clone()).await;
+    assert_eq!(&cursor, next_item(&mut sub).await.transaction_digest());
+    drop(sub);
+
+    // Transactions executed while disconnected are replayed on reconnect, then new ones are
+    // streamed, each exactly once.
+    let mut missed = vec![];
+    for _ in 0..2 {
+        let (_, _, digest) = create_devnet_nft(&wallet, package_id).await;
+        wait_for_tx(digest, state.clone()).await;
+        missed.push(digest);
+    }
+    let mut sub: Subscription<SuiTransactionBlockEffects> = ws_client
+        .subscribe(
+            "suix_subscribeTransaction",
+            rpc_params![filter, cursor],
+            "suix_unsubscribeTransaction",
+        )
+        .await
+        .unwrap();
+    for digest in &missed {
+        assert_eq!(digest, next_item(&mut sub).await.transaction_digest());
+    }
+    let (_, _, digest) = create_devnet_nft(&wallet, package_id).await;
+    wait_for_tx(digest, state).await;
+    assert_eq!(&digest, next_item(&mut sub).await.transaction_digest());
+    assert_no_more_items(&mut sub).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_event_with_cursor() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+
+    let address = &cluster.get_address_0();
+    let wallet = cluster.wallet;
+    let state = cluster.fullnode_handle.sui_node.state();
+    let ws_client = cluster.fullnode_handle.ws_client;
+
+    let package_id = publish_nfts_package(&wallet).await.0;
+    let filter = EventFilter::Sender(*address);
+
+    // Each NFT mint emits one event.
+    let mut sub: Subscription<SuiEvent> = ws_client
+        .subscribe(
+            "suix_subscribeEvent",
+            rpc_params![filter.clone()],
+            "suix_unsubscribeEvent",
+        )
+        .await
+        .unwrap();
+    let (_, _, digest) = create_devnet_nft(&wallet, package_id).await;
+    wait_for_tx(digest, state.clone()).await;
+    let event = next_item(&mut sub).await;
+    assert_eq!(digest, event.id.tx_digest);
+    let cursor = event.id;
+    drop(sub);
+
+    // Events emitted while disconnected are replayed on reconnect, then new ones are streamed,
+    // each exactly once.
+    let mut missed = vec![];
+    for _ in 0..2 {
+        let (_, _, digest) = create_devnet_nft(&wallet, package_id).await;
+        wait_for_tx(digest, state.clone()).await;
+        missed.push(digest);
+    }
+    let mut sub: Subscription<SuiEvent> = ws_client
+        .subscribe(
+            "suix_subscribeEvent",
+            rpc_params![filter, cursor],
+            "suix_unsubscribeEvent",
+        )
+        .await
+        .unwrap();
+    for digest in missed {
+        assert_eq!(digest, next_item(&mut sub).await.id.tx_digest);
+    }
+    let (_, _, digest) = create_devnet_nft(&wallet, package_id).await;
+    wait_for_tx(digest, state).await;
+    assert_eq!(digest, next_item(&mut sub).await.id.tx_digest);
+    assert_no_more_items(&mut sub).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_owned_objects() -> Result<(), anyhow::Error> {
+    let cluster = TestClusterBuilder::new().build().await;
+
+    let address = cluster.get_address_0();
+    let wallet = cluster.wallet;
+
+    let ws_client = cluster.fullnode_handle.ws_client;
+
+    let package_id = publish_nfts_package(&wallet).await.0;
+
+    let mut sub: Subscription<SuiObjectData> = ws_client
+        .subscribe(
+            "suix_subscribeOwnedObjects",
+            rpc_params![
+                address,
+                SuiObjectDataOptions::new()
+                    .with_owner()
+                    .with_previous_transaction()
+            ],
+            "suix_unsubscribeOwnedObjects",
+        )
+        .await
+        .unwrap();
+
+    let (_, _, digest) = create_devnet_nft(&wallet, package_id).await;
+    wait_for_tx(digest, cluster.fullnode_handle.sui_node.state()).await;
+
+    // Both the gas coin and the new NFT are streamed, with the requested fields only.
+    for _ in 0..2 {
+        let object = match timeout(Duration::from_secs(5), sub.next()).await {
+            Ok(Some(Ok(object))) => object,
+            _ => panic!("Failed to get object"),
+        };
+        assert_eq!(Some(Owner::AddressOwner(address)), object.owner);
+        assert_eq!(Some(digest), object.previous_transaction);
+        assert!(object.content.is_none());
+    }
+    Ok(())
+}
+
+async fn next_item<T: DeserializeOwned>(sub: &mut Subscription<T>) -> T {
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Ok(Some(Ok(item))) => item,
+        _ => panic!("Failed to get subscription item"),
+    }
+}
+
+async fn assert_no_more_items<T: DeserializeOwned + Debug>(sub: &mut Subscription<T>) {
+    if let Ok(item) = timeout(Duration::from_secs(2), sub.next()).await {
+        panic!("Unexpected subscription item {item:?}");
+    }
+}
@@ -821,14 +821,18 @@ fn resolve_call_arg(
 fn resolve_call_args(
     view: &BinaryIndexedView,
     type_args: &[TypeTag],
-    json_args: &[SuiJsonValue],
+    json_args: &[Option<SuiJsonValue>],
     parameter_types: &[SignatureToken],
-) -> Result<Vec<ResolvedCallArg>, anyhow::Error> {
+) -> Result<Vec<Option<ResolvedCallArg>>, anyhow::Error> {
     json_args
         .iter()
         .zip(parameter_types)
         .enumerate()
-        .map(|(idx, (arg, param))| resolve_call_arg(view, type_args, idx, arg, param))
+        .map(|(idx, (arg, param))| {
+            arg.as_ref()
+                .map(|arg| resolve_call_arg(view, type_args, idx, arg, param))
+                .transpose()
+        })
         .collect()
 }
 
@@ -841,6 +845,28 @@ pub fn resolve_move_function_args(
     type_args: &[TypeTag],
     combined_args_json: Vec<SuiJsonValue>,
 ) -> Result<Vec<(ResolvedCallArg, SignatureToken)>, anyhow::Error> {
+    Ok(resolve_move_function_args_partial(
+        package,
+        module_ident,
+        function,
+        type_args,
+        combined_args_json.into_iter().map(Some).collect(),
+    )?
+    .into_iter()
+    .filter_map(|(arg, expected_type)| Some((arg?, expected_type)))
+    .collect())
+}
+
+/// Like `resolve_move_function_args`, but args that are `None` are left unresolved, e.g. because
+/// they are the results of earlier commands of a programmable transaction. Their expected types
+/// are still returned.
+pub fn resolve_move_function_args_partial(
+    package: &MovePackage,
+    module_ident: Identifier,
+    function: Identifier,
+    type_args: &[TypeTag],
+    combined_args_json: Vec<Option<SuiJsonValue>>,
+) -> Result<Vec<(Option<ResolvedCallArg>, SignatureToken)>, anyhow::Error> {
     // Extract the expected function signature
     let module = package.deserialize_module(&module_ident, VERSION_MAX, true)?;
     let function_str = function.as_ident_str();
@@ -0,0 +1,329 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keystore whose private keys are held by an external signer, e.g. a hardware security module or
+//! a signing service, which signs on behalf of the keystore without ever exposing them.
+//!
+//! The keystore talks to the signer with newline-delimited JSON, every request being answered by
+//! a single response line, either on the stdin and stdout of a command run for every request or
+//! on a Unix socket the signer listens on. The requests are
+//!
+//! - `{"method":"keys"}`, answered with `{"keys":["<base64 flag || public key>",...]}`,
+//! - `{"method":"sign","address":"0x...","digest":"<base64 digest>"}`, answered with
+//!   `{"signature":"<base64 flag || signature || public key>"}`, where the signer must sign the
+//!   digest as is with the key of `address`. The digest is the Blake2b256 hash of the intent
+//!   message when signing transactions and personal messages,
+//!
+//! and the signer answers `{"error":"<message>"}` to any request it cannot serve, within
+//! [SIGNER_TIMEOUT].
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::{mpsc, OnceLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+
+use crate::keystore::AccountKeystore;
+
+/// How long to wait for the signer to answer a request, which may include a user confirming it
+/// on a device.
+pub const SIGNER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How to reach an external signer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerTransport {
+    /// Run `program` with `args` for every request, writing the request to its stdin and reading
+    /// the response from its stdout.
+    Command {
+        program: PathBuf,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    /// Connect to a signer listening on a Unix socket for every request.
+    Socket(PathBuf),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    Keys,
+    Sign { address: SuiAddress, digest: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerResponse {
+    Keys(Vec<String>),
+    Signature(String),
+    Error(String),
+}
+
+impl SignerTransport {
+    /// Send `request` to the signer and wait for its response, for at most [SIGNER_TIMEOUT].
+    pub fn request(&self, request: &SignerRequest) -> Result<SignerResponse, anyhow::Error> {
+        self.request_with_timeout(request, SIGNER_TIMEOUT)
+    }
+
+    /// Send `request` to the signer and wait for its response, for at most `timeout`.
+    pub fn request_with_timeout(
+        &self,
+        request: &SignerRequest,
+        timeout: Duration,
+    ) -> Result<SignerResponse, anyhow::Error> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        let response = match self {
+            SignerTransport::Command { program, args } => {
+                Self::request_command(program, args, &line, timeout)?
+            }
+            SignerTransport::Socket(path) => Self::request_socket(path, &line, timeout)?,
+        };
+        serde_json::from_str(&response)
+            .map_err(|e| anyhow!("Invalid response from external signer {response:?}: {e}"))
+    }
+
+    fn request_command(
+        program: &Path,
+        args: &[String],
+        line: &str,
+        timeout: Duration,
+    ) -> Result<String, anyhow::Error> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("Can't run external signer {:?}: {e}", program))?;
+        let mut stdout = child.stdout.take().expect("stdout of the signer is piped");
+        // Read the output from another thread, so that a signer which never answers is killed
+        // once the timeout expires instead of blocking forever.
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut output = vec![];
+            let _ = sender.send(stdout.read_to_end(&mut output).map(|_| output));
+        });
+        let written = child
+            .stdin
+            .take()
+            .expect("stdin of the signer is piped")
+            .write_all(line.as_bytes());
+        let output = match receiver.recv_timeout(timeout) {
+            Ok(output) => output,
+            Err(_) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "External signer {:?} did not answer within {:?}",
+                    program,
+                    timeout
+                );
+            }
+        };
+        let status = child.wait()?;
+        if !status.success() {
+            bail!("External signer {:?} exited with {}", program, status);
+        }
+        written?;
+        let output = String::from_utf8(output?)?;
+        Ok(output.lines().next().unwrap_or_default().to_string())
+    }
+
+    #[cfg(unix)]
+    fn request_socket(path: &Path, line: &str, timeout: Duration) -> Result<String, anyhow::Error> {
+        let mut stream = std::os::unix::net::UnixStream::connect(path)
+            .map_err(|e| anyhow!("Can't connect to external signer at {:?}: {e}", path))?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.write_all(line.as_bytes())?;
+        let mut response = String::new();
+        BufReader::new(stream)
+            .read_line(&mut response)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => anyhow!(
+                    "External signer at {:?} did not answer within {:?}",
+                    path,
+                    timeout
+                ),
+                _ => e.into(),
+            })?;
+        Ok(response)
+    }
+
+    #[cfg(not(unix))]
+    fn request_socket(
+        path: &Path,
+        _line: &str,
+        _timeout: Duration,
+    ) -> Result<String, anyhow::Error> {
+        bail!(
+            "Can't connect to external signer at {:?}: Unix sockets are not supported",
+            path
+        )
+    }
+}
+
+pub struct ExternalKeystore {
+    transport: SignerTransport,
+    // Loaded from the signer when first needed
+    keys: OnceLock<BTreeMap<SuiAddress, PublicKey>>,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.transport.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // Don't contact the signer until the keystore is used, configs are loaded by every
+        // command including the ones which don't sign anything.
+        Ok(ExternalKeystore {
+            transport: SignerTransport::deserialize(deserializer)?,
+            keys: OnceLock::new(),
+        })
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        self.sign_digest(address, msg)
+    }
+
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let mut hasher = DefaultHash::default();
+        hasher.update(
+            &bcs::to_bytes(&IntentMessage::new(intent, msg))
+                .map_err(signature::Error::from_source)?,
+        );
+        self.sign_digest(address, &hasher.finalize().digest)
+    }
+
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Keys can't be added to an external signer from here, add them to the signer instead"
+        ))
+    }
+
+    /// The keys held by the signer, or none if it can't be reached.
+    fn keys(&self) -> Vec<PublicKey> {
+        self.load_keys()
+            .map(|keys| keys.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        Err(anyhow!(
+            "The private key of [{address}] is held by an external signer and can't be read"
+        ))
+    }
+}
+
+impl ExternalKeystore {
+    /// Connect to the signer reached through `transport` and load the public keys it holds.
+    pub fn new(transport: SignerTransport) -> Result<Self, anyhow::Error> {
+        let keystore = Self {
+            transport,
+            keys: OnceLock::new(),
+        };
+        keystore.load_keys()?;
+        Ok(keystore)
+    }
+
+    pub fn transport(&self) -> &SignerTransport {
+        &self.transport
+    }
+
+    /// The public keys held by the signer, requested from it the first time.
+    fn load_keys(&self) -> Result<&BTreeMap<SuiAddress, PublicKey>, anyhow::Error> {
+        if let Some(keys) = self.keys.get() {
+            return Ok(keys);
+        }
+        let keys = match self.transport.request(&SignerRequest::Keys)? {
+            SignerResponse::Keys(keys) => keys
+                .iter()
+                .map(|key| {
+                    let key = PublicKey::decode_base64(key).map_err(|e| {
+                        anyhow!("Invalid public key {key:?} from external signer: {e}")
+                    })?;
+                    Ok((SuiAddress::from(&key), key))
+                })
+                .collect::<Result<BTreeMap<_, _>, anyhow::Error>>()?,
+            SignerResponse::Error(e) => bail!("External signer failed to list its keys: {e}"),
+            response => bail!("Unexpected response from external signer: {response:?}"),
+        };
+        Ok(self.keys.get_or_init(|| keys))
+    }
+
+    fn sign_digest(
+        &self,
+        address: &SuiAddress,
+        digest: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        let keys = self.load_keys().map_err(signature::Error::from_source)?;
+        if !keys.contains_key(address) {
+            return Err(signature::Error::from_source(format!(
+                "Cannot find key for address: [{address}]"
+            )));
+        }
+        let request = SignerRequest::Sign {
+            address: *address,
+            digest: Base64::encode(digest),
+        };
+        let signature = match self
+            .transport
+            .request(&request)
+            .map_err(signature::Error::from_source)?
+        {
+            SignerResponse::Signature(signature) => Signature::from_str(&signature)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+            SignerResponse::Error(e) => {
+                return Err(signature::Error::from_source(format!(
+                    "External signer failed to sign for [{address}]: {e}"
+                )))
+            }
+            response => {
+                return Err(signature::Error::from_source(format!(
+                    "Unexpected response from external signer: {response:?}"
+                )))
+            }
+        };
+        // The signature carries the public key it is verified against, make sure it is the one
+        // of the address so that a misbehaving signer is caught before submitting anything.
+        let signer = signature
+            .to_public_key()
+            .map(|key| SuiAddress::from(&key))
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        if signer != *address {
+            return Err(signature::Error::from_source(format!(
+                "External signer signed for [{signer}] instead of [{address}]"
+            )));
+        }
+        Ok(signature)
+    }
+}
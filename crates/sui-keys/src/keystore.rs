@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::external_signer::ExternalKeystore;
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
+use crate::keystore_cipher::KeystoreCipher;
 use anyhow::anyhow;
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
@@ -12,20 +14,21 @@ use std::collections::BTreeMap;
 use std::fmt::Write;
 use std::fmt::{Display, Formatter};
 use std::fs;
-use std::fs::File;
-use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::get_key_pair_from_rng;
 use sui_types::crypto::{
     enum_dispatch, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme, SuiKeyPair,
 };
+use zeroize::Zeroizing;
 
 #[derive(Serialize, Deserialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    External(ExternalKeystore),
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -91,6 +94,11 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External")?;
+                write!(writer, "Keystore Signer : {:?}", external.transport())?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -99,6 +107,8 @@ impl Display for Keystore {
 pub struct FileBasedKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
     path: Option<PathBuf>,
+    /// Set if the keystore is encrypted at rest, see `keystore_cipher`.
+    cipher: Option<KeystoreCipher>,
 }
 
 impl Serialize for FileBasedKeystore {
@@ -173,29 +183,46 @@ impl AccountKeystore for FileBasedKeystore {
 }
 
 impl FileBasedKeystore {
+    /// Load the keystore at `path`. If it is encrypted, it is decrypted with the passphrase from
+    /// the environment or with the key of an unlocked session, see `keystore_cipher`.
     pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
-        let keys = if path.exists() {
-            let reader = BufReader::new(
-                File::open(path)
-                    .map_err(|e| anyhow!("Can't open FileBasedKeystore from {:?}: {e}", path))?,
-            );
-            let kp_strings: Vec<String> = serde_json::from_reader(reader)
+        Self::load(path, None)
+    }
+
+    /// Load the keystore at `path`, decrypting it with `passphrase` if it is encrypted.
+    pub fn new_with_passphrase(path: &PathBuf, passphrase: &str) -> Result<Self, anyhow::Error> {
+        Self::load(path, Some(passphrase))
+    }
+
+    fn load(path: &PathBuf, passphrase: Option<&str>) -> Result<Self, anyhow::Error> {
+        let (keys, cipher) = if path.exists() {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| anyhow!("Can't open FileBasedKeystore from {:?}: {e}", path))?;
+            let (contents, cipher) = if KeystoreCipher::is_encrypted(&contents) {
+                let (plaintext, cipher) = KeystoreCipher::decrypt(path, &contents, passphrase)?;
+                (plaintext, Some(cipher))
+            } else {
+                (Zeroizing::new(contents.into_bytes()), None)
+            };
+            let kp_strings: Vec<String> = serde_json::from_slice(&contents)
                 .map_err(|e| anyhow!("Can't deserialize FileBasedKeystore from {:?}: {e}", path))?;
-            kp_strings
+            let keys = kp_strings
                 .iter()
                 .map(|kpstr| {
                     let key = SuiKeyPair::decode_base64(kpstr);
                     key.map(|k| (Into::<SuiAddress>::into(&k.public()), k))
                 })
                 .collect::<Result<BTreeMap<_, _>, _>>()
-                .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?
+                .map_err(|e| anyhow::anyhow!("Invalid Keypair file {:#?} {:?}", e, path))?;
+            (keys, cipher)
         } else {
-            BTreeMap::new()
+            (BTreeMap::new(), None)
         };
 
         Ok(Self {
             keys,
             path: Some(path.to_path_buf()),
+            cipher,
         })
     }
 
@@ -205,19 +232,65 @@ impl FileBasedKeystore {
 
     pub fn save(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
-            let store = serde_json::to_string_pretty(
-                &self
-                    .keys
-                    .values()
-                    .map(EncodeDecodeBase64::encode_base64)
-                    .collect::<Vec<_>>(),
-            )
-            .unwrap();
-            fs::write(path, store)?
+            let store = Zeroizing::new(
+                serde_json::to_string_pretty(
+                    &self
+                        .keys
+                        .values()
+                        .map(EncodeDecodeBase64::encode_base64)
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap(),
+            );
+            match &self.cipher {
+                Some(cipher) => fs::write(path, cipher.encrypt(store.as_bytes())?)?,
+                None => fs::write(path, store.as_bytes())?,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    /// Whether the keystore file at `path` is encrypted, without loading it.
+    pub fn is_encrypted_file(path: &Path) -> Result<bool, anyhow::Error> {
+        Ok(path.exists() && KeystoreCipher::is_encrypted(&fs::read_to_string(path)?))
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Encrypt the keystore with a key derived from `passphrase` and save it. This both migrates
+    /// a plaintext keystore and changes the passphrase of an encrypted one, in which case any
+    /// session unlocking it with the previous passphrase ends.
+    pub fn encrypt(&mut self, passphrase: &str) -> Result<(), anyhow::Error> {
+        self.cipher = Some(KeystoreCipher::new(passphrase)?);
+        self.save()?;
+        if let Some(path) = &self.path {
+            KeystoreCipher::end_session(path)?;
         }
         Ok(())
     }
 
+    /// Keep the keystore unlocked for `duration`, so that it can be loaded without passphrase.
+    pub fn unlock(&self, duration: Duration) -> Result<(), anyhow::Error> {
+        match (&self.cipher, &self.path) {
+            (Some(cipher), Some(path)) => cipher.start_session(path, duration),
+            (None, _) => Err(anyhow!(
+                "Keystore is not encrypted, there is nothing to unlock"
+            )),
+            (_, None) => Err(anyhow!("Keystore has no path to unlock")),
+        }
+    }
+
+    /// Lock the keystore at `path` again, ending the session started by `unlock`.
+    pub fn lock(path: &Path) -> Result<(), anyhow::Error> {
+        KeystoreCipher::end_session(path)
+    }
+
     pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
         self.keys.values().collect()
     }
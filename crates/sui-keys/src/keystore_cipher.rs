@@ -0,0 +1,236 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encryption at rest of file keystores.
+//!
+//! An encrypted keystore is a JSON object holding the Argon2id parameters used to derive a key
+//! from the passphrase, and the plaintext keystore (the JSON array of Base64 encoded keypairs)
+//! encrypted with XChaCha20-Poly1305 under that key.
+//!
+//! The passphrase is read from the `SUI_KEYSTORE_PASSPHRASE` environment variable when set.
+//! Otherwise the keystore must have been unlocked with `sui keytool unlock`, which keeps the
+//! derived key, never the passphrase, in a session file next to the keystore until it expires or
+//! the keystore is locked again.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+pub const PASSPHRASE_ENV_VAR: &str = "SUI_KEYSTORE_PASSPHRASE";
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 24;
+const SALT_LENGTH: usize = 16;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct KdfParams {
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedKeystore {
+    version: u8,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Key of an unlocked keystore, kept until `expires_at_ms`.
+#[derive(Serialize, Deserialize)]
+struct Session {
+    kdf: KdfParams,
+    key: String,
+    expires_at_ms: u64,
+}
+
+/// Key derived from the passphrase of a keystore, used to decrypt it and to encrypt it again when
+/// it is saved.
+pub struct KeystoreCipher {
+    kdf: KdfParams,
+    key: [u8; KEY_LENGTH],
+}
+
+impl KeystoreCipher {
+    const VERSION: u8 = 1;
+
+    /// Derive a key from `passphrase` with a fresh salt, to encrypt a keystore with.
+    pub fn new(passphrase: &str) -> Result<Self, anyhow::Error> {
+        let mut salt = [0u8; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let kdf = KdfParams {
+            salt: Base64::encode(salt),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        };
+        Self::derive(passphrase, kdf)
+    }
+
+    fn derive(passphrase: &str, kdf: KdfParams) -> Result<Self, anyhow::Error> {
+        let params = Params::new(kdf.m_cost, kdf.t_cost, kdf.p_cost, Some(KEY_LENGTH))
+            .map_err(|e| anyhow!("Invalid keystore key derivation parameters: {e}"))?;
+        let salt = Base64::decode(&kdf.salt).map_err(|e| anyhow!("Invalid keystore salt: {e}"))?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+            .map_err(|e| anyhow!("Can't derive keystore key from passphrase: {e}"))?;
+        Ok(Self { kdf, key: *key })
+    }
+
+    /// Whether `contents` of a keystore file are encrypted, as opposed to the plaintext array of
+    /// keypairs.
+    pub fn is_encrypted(contents: &str) -> bool {
+        contents.trim_start().starts_with('{')
+    }
+
+    /// Encrypt the plaintext keystore `contents` into the contents of an encrypted keystore file.
+    pub fn encrypt(&self, contents: &[u8]) -> Result<String, anyhow::Error> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = XChaCha20Poly1305::new(&self.key.into())
+            .encrypt(XNonce::from_slice(&nonce), contents)
+            .map_err(|e| anyhow!("Can't encrypt keystore: {e}"))?;
+        Ok(serde_json::to_string_pretty(&EncryptedKeystore {
+            version: Self::VERSION,
+            kdf: self.kdf.clone(),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })?)
+    }
+
+    /// Decrypt the encrypted keystore `contents` read from `path`, with `passphrase` if provided,
+    /// or else with the passphrase from the environment or the key of an unlocked session.
+    pub fn decrypt(
+        path: &Path,
+        contents: &str,
+        passphrase: Option<&str>,
+    ) -> Result<(Zeroizing<Vec<u8>>, Self), anyhow::Error> {
+        let encrypted: EncryptedKeystore = serde_json::from_str(contents)
+            .map_err(|e| anyhow!("Can't deserialize encrypted keystore from {:?}: {e}", path))?;
+        if encrypted.version != Self::VERSION {
+            bail!(
+                "Unsupported encrypted keystore version {} in {:?}",
+                encrypted.version,
+                path
+            );
+        }
+        let env_passphrase = std::env::var(PASSPHRASE_ENV_VAR).ok().map(Zeroizing::new);
+        let cipher = match passphrase.or(env_passphrase.as_ref().map(|p| p.as_str())) {
+            Some(passphrase) => Self::derive(passphrase, encrypted.kdf.clone())?,
+            None => Self::from_session(path, &encrypted.kdf)?.ok_or_else(|| {
+                anyhow!(
+                    "Keystore {:?} is encrypted, unlock it with `sui keytool unlock` or set {}",
+                    path,
+                    PASSPHRASE_ENV_VAR
+                )
+            })?,
+        };
+        let nonce = Base64::decode(&encrypted.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        if nonce.len() != NONCE_LENGTH {
+            bail!("Invalid nonce length {} in {:?}", nonce.len(), path);
+        }
+        let ciphertext = Base64::decode(&encrypted.ciphertext)
+            .map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+        let plaintext = XChaCha20Poly1305::new(&cipher.key.into())
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("Wrong passphrase for keystore {:?}", path))?;
+        Ok((Zeroizing::new(plaintext), cipher))
+    }
+
+    /// Keep the keystore at `path` unlocked with this key for `duration`.
+    pub fn start_session(&self, path: &Path, duration: Duration) -> Result<(), anyhow::Error> {
+        let mut session = Session {
+            kdf: self.kdf.clone(),
+            key: Base64::encode(self.key),
+            expires_at_ms: now_ms().saturating_add(duration.as_millis() as u64),
+        };
+        let contents = Zeroizing::new(serde_json::to_string(&session)?);
+        session.key.zeroize();
+        write_private(&session_path(path), contents.as_bytes())
+    }
+
+    /// Lock the keystore at `path` again, if it was unlocked.
+    pub fn end_session(path: &Path) -> Result<(), anyhow::Error> {
+        let session_path = session_path(path);
+        if session_path.exists() {
+            fs::remove_file(session_path)?;
+        }
+        Ok(())
+    }
+
+    /// The key of the session unlocking the keystore at `path`, if it has not expired and was
+    /// derived with the parameters the keystore is currently encrypted with.
+    fn from_session(path: &Path, kdf: &KdfParams) -> Result<Option<Self>, anyhow::Error> {
+        let Ok(contents) = fs::read_to_string(session_path(path)).map(Zeroizing::new) else {
+            return Ok(None);
+        };
+        let mut session: Session = serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Invalid keystore session for {:?}: {e}", path))?;
+        let key = Zeroizing::new(std::mem::take(&mut session.key));
+        if session.expires_at_ms < now_ms() || &session.kdf != kdf {
+            return Ok(None);
+        }
+        let key = Base64::decode(&key)
+            .ok()
+            .map(Zeroizing::new)
+            .and_then(|key| <[u8; KEY_LENGTH]>::try_from(key.as_slice()).ok())
+            .ok_or_else(|| anyhow!("Invalid keystore session for {:?}", path))?;
+        Ok(Some(Self {
+            kdf: session.kdf,
+            key,
+        }))
+    }
+}
+
+impl Drop for KeystoreCipher {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+/// Write `contents` to the file at `path`, only readable and writable by its owner. A new file is
+/// created with these permissions, so that it is never readable by others, even briefly.
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // The mode only applies to new files, restrict an existing one before writing to it
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    Ok(())
+}
+
+fn session_path(path: &Path) -> PathBuf {
+    let mut session_path = path.as_os_str().to_owned();
+    session_path.push(".session");
+    PathBuf::from(session_path)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis() as u64)
+        .unwrap_or_default()
+}
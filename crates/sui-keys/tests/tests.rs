@@ -5,8 +5,10 @@ use std::str::FromStr;
 use fastcrypto::hash::HashFunction;
 use tempfile::TempDir;
 
-use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use shared_crypto::intent::{Intent, IntentScope, PersonalMessage};
+use sui_keys::external_signer::{ExternalKeystore, SignerRequest, SignerResponse, SignerTransport};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
+use sui_types::crypto::{DefaultHash, EncodeDecodeBase64, SignatureScheme, SuiSignatureInner};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +71,276 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+/// Signer answering requests with the keys of an in-memory keystore, standing in for an external
+/// signer.
+#[cfg(unix)]
+struct MockSigner {
+    keystore: InMemKeystore,
+}
+
+#[cfg(unix)]
+impl MockSigner {
+    fn new(keystore: InMemKeystore) -> Self {
+        Self { keystore }
+    }
+
+    fn handle(&self, request: SignerRequest) -> SignerResponse {
+        use fastcrypto::encoding::{Base64, Encoding};
+        match request {
+            SignerRequest::Keys => SignerResponse::Keys(
+                self.keystore
+                    .keys()
+                    .iter()
+                    .map(EncodeDecodeBase64::encode_base64)
+                    .collect(),
+            ),
+            SignerRequest::Sign { address, digest } => {
+                let signature =
+                    Base64::decode(&digest)
+                        .map_err(|e| e.to_string())
+                        .and_then(|digest| {
+                            self.keystore
+                                .sign_hashed(&address, &digest)
+                                .map_err(|e| e.to_string())
+                        });
+                match signature {
+                    Ok(signature) => SignerResponse::Signature(Base64::encode(signature)),
+                    Err(e) => SignerResponse::Error(e),
+                }
+            }
+        }
+    }
+
+    /// Serve requests on a Unix socket bound at `path`, from a background thread.
+    fn serve(self, path: &std::path::Path) -> Result<(), anyhow::Error> {
+        use std::io::{BufRead, BufReader, Write};
+        let listener = std::os::unix::net::UnixListener::bind(path)?;
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut line = String::new();
+                let Ok(reader) = stream.try_clone() else {
+                    continue;
+                };
+                if BufReader::new(reader).read_line(&mut line).is_err() {
+                    continue;
+                }
+                let response = match serde_json::from_str(&line) {
+                    Ok(request) => self.handle(request),
+                    Err(e) => SignerResponse::Error(format!("Invalid request: {e}")),
+                };
+                let mut response = serde_json::to_string(&response).expect("Responses serialize");
+                response.push('\n');
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_signs_with_mock_signer() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let socket = temp_dir.path().join("signer.sock");
+    MockSigner::new(InMemKeystore::new_insecure_for_tests(2)).serve(&socket)?;
+    // Same keys as the signer, to compare signatures with
+    let local = InMemKeystore::new_insecure_for_tests(2);
+
+    let mut keystore = Keystore::from(ExternalKeystore::new(SignerTransport::Socket(
+        socket.clone(),
+    ))?);
+    assert_eq!(keystore.addresses(), local.addresses());
+
+    let address = local.addresses()[0];
+    let msg = PersonalMessage {
+        message: b"hello".to_vec(),
+    };
+    assert_eq!(
+        keystore.sign_secure(
+            &address,
+            &msg,
+            Intent::sui_app(IntentScope::PersonalMessage)
+        )?,
+        local.sign_secure(
+            &address,
+            &msg,
+            Intent::sui_app(IntentScope::PersonalMessage)
+        )?
+    );
+    assert!(keystore
+        .sign_secure(
+            &SuiAddress::random_for_testing_only(),
+            &msg,
+            Intent::sui_app(IntentScope::PersonalMessage)
+        )
+        .is_err());
+    assert!(keystore.get_key(&address).is_err());
+    assert!(keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None)
+        .is_err());
+
+    // Only how to reach the signer is saved, keys are loaded from it again
+    let serialized = serde_json::to_string(&keystore)?;
+    assert_eq!(
+        serialized,
+        format!(r#"{{"External":{{"socket":{:?}}}}}"#, socket)
+    );
+    let keystore: Keystore = serde_json::from_str(&serialized)?;
+    assert_eq!(keystore.addresses(), local.addresses());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_reports_signer_errors() {
+    let transport = SignerTransport::Command {
+        program: "sh".into(),
+        args: vec![
+            "-c".to_string(),
+            r#"read request; echo '{"error":"device locked"}'"#.to_string(),
+        ],
+    };
+    let err = ExternalKeystore::new(transport).err().unwrap();
+    assert!(err.to_string().contains("device locked"));
+
+    let transport = SignerTransport::Command {
+        program: "sh".into(),
+        args: vec!["-c".to_string(), "exit 1".to_string()],
+    };
+    assert!(ExternalKeystore::new(transport).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_times_out() {
+    let transport = SignerTransport::Command {
+        program: "sh".into(),
+        args: vec!["-c".to_string(), "sleep 10".to_string()],
+    };
+    let start = std::time::Instant::now();
+    let err = transport
+        .request_with_timeout(&SignerRequest::Keys, std::time::Duration::from_millis(200))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("did not answer"));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+    // A socket signer which accepts the connection but never answers
+    let temp_dir = TempDir::new().unwrap();
+    let socket = temp_dir.path().join("signer.sock");
+    let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+    let err = SignerTransport::Socket(socket)
+        .request_with_timeout(&SignerRequest::Keys, std::time::Duration::from_millis(200))
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("did not answer"));
+}
+
+#[test]
+fn external_keystore_loads_keys_when_used() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let socket = temp_dir.path().join("signer.sock");
+    let serialized = format!(r#"{{"External":{{"socket":{:?}}}}}"#, socket);
+
+    // Loading the keystore doesn't need the signer to be up
+    let keystore: Keystore = serde_json::from_str(&serialized)?;
+    assert!(keystore.addresses().is_empty());
+    let msg = PersonalMessage {
+        message: b"hello".to_vec(),
+    };
+    let local = InMemKeystore::new_insecure_for_tests(1);
+    let address = local.addresses()[0];
+    assert!(keystore
+        .sign_secure(
+            &address,
+            &msg,
+            Intent::sui_app(IntentScope::PersonalMessage)
+        )
+        .is_err());
+
+    #[cfg(unix)]
+    {
+        MockSigner::new(InMemKeystore::new_insecure_for_tests(1)).serve(&socket)?;
+        let keystore: Keystore = serde_json::from_str(&serialized)?;
+        assert_eq!(keystore.addresses(), vec![address]);
+        keystore.sign_secure(
+            &address,
+            &msg,
+            Intent::sui_app(IntentScope::PersonalMessage),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_requires_passphrase() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new()?;
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    let key = keystore.get_key(&address)?.encode_base64();
+    assert!(std::fs::read_to_string(&keystore_path)?.contains(&key));
+    assert!(!keystore.is_encrypted());
+
+    keystore.encrypt("correct horse")?;
+    assert!(FileBasedKeystore::is_encrypted_file(&keystore_path)?);
+    assert!(!std::fs::read_to_string(&keystore_path)?.contains(&key));
+
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    assert!(FileBasedKeystore::new_with_passphrase(&keystore_path, "wrong horse").is_err());
+    let mut keystore = FileBasedKeystore::new_with_passphrase(&keystore_path, "correct horse")?;
+    assert!(keystore.is_encrypted());
+    assert_eq!(keystore.addresses(), vec![address]);
+
+    // Keys added to an encrypted keystore are saved encrypted
+    let (added, _, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    assert!(FileBasedKeystore::is_encrypted_file(&keystore_path)?);
+    let keystore = FileBasedKeystore::new_with_passphrase(&keystore_path, "correct horse")?;
+    assert!(keystore.addresses().contains(&added));
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_session() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new()?;
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new(&keystore_path)?;
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    keystore.encrypt("correct horse")?;
+
+    keystore.unlock(std::time::Duration::from_secs(60))?;
+    assert_eq!(
+        FileBasedKeystore::new(&keystore_path)?.addresses(),
+        vec![address]
+    );
+    // The session holds the key of the keystore, it is only readable by its owner
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let session_path = temp_dir.path().join("sui.keystore.session");
+        let mode = std::fs::metadata(session_path)?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    FileBasedKeystore::lock(&keystore_path)?;
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+
+    // Changing the passphrase ends the session unlocked with the previous one
+    keystore.unlock(std::time::Duration::from_secs(60))?;
+    keystore.encrypt("battery staple")?;
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    assert!(FileBasedKeystore::new_with_passphrase(&keystore_path, "correct horse").is_err());
+    FileBasedKeystore::new_with_passphrase(&keystore_path, "battery staple")?;
+
+    // An expired session does not unlock the keystore
+    keystore.unlock(std::time::Duration::ZERO)?;
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    assert!(FileBasedKeystore::new(&keystore_path).is_err());
+    Ok(())
+}
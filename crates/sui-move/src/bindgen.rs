@@ -0,0 +1,498 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of typed Rust bindings for Move packages.
+//!
+//! The bindings are generated from the normalized modules of a package, either built locally or
+//! fetched from chain with `sui_getNormalizedMoveModulesByPackage`. Each Move module becomes a
+//! Rust module holding:
+//! - a struct deriving serde's `Serialize` and `Deserialize` for each Move struct, with the same
+//!   BCS layout, to read objects and events of the package,
+//! - a function for each public or entry Move function, adding a call to it to a
+//!   `ProgrammableTransactionBuilder`. Arguments of primitive types are taken as Rust values and
+//!   added as pure inputs, other arguments are taken as `Argument`s.
+//!
+//! The generated code depends on the `anyhow`, `move-core-types`, `serde` and `sui-types` crates.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail};
+use clap::Parser;
+use move_binary_format::normalized::Module as NormalizedModule;
+use move_cli::base;
+use move_package::BuildConfig as MoveBuildConfig;
+use sui_json_rpc_types::{
+    SuiMoveNormalizedFunction, SuiMoveNormalizedModule, SuiMoveNormalizedStruct,
+    SuiMoveNormalizedType, SuiMoveVisibility,
+};
+use sui_move_build::BuildConfig;
+use sui_types::base_types::ObjectID;
+use sui_types::{MOVE_STDLIB_PACKAGE_ID, SUI_FRAMEWORK_PACKAGE_ID};
+
+#[derive(Parser)]
+pub struct Bindgen {
+    /// Generate the bindings from a JSON file of normalized modules, as returned by
+    /// `sui_getNormalizedMoveModulesByPackage`, instead of building the package
+    #[clap(long)]
+    pub normalized_modules: Option<PathBuf>,
+    /// ID of the package the generated functions call. Defaults to the address of the modules,
+    /// which is the ID of the first version of the package: the ID of the latest version must be
+    /// given for upgraded packages
+    #[clap(long)]
+    pub package_id: Option<ObjectID>,
+    /// File to write the bindings to. They are printed if not provided
+    #[clap(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+impl Bindgen {
+    pub fn execute(
+        self,
+        path: Option<PathBuf>,
+        build_config: MoveBuildConfig,
+    ) -> anyhow::Result<()> {
+        let modules: BTreeMap<String, SuiMoveNormalizedModule> = match &self.normalized_modules {
+            Some(file) => {
+                let contents = fs::read_to_string(file)
+                    .map_err(|e| anyhow!("Can't read normalized modules from {:?}: {e}", file))?;
+                serde_json::from_str(&contents).map_err(|e| {
+                    anyhow!("Can't deserialize normalized modules from {:?}: {e}", file)
+                })?
+            }
+            None => {
+                let rerooted_path = base::reroot_path(path)?;
+                let pkg = BuildConfig {
+                    config: build_config,
+                    run_bytecode_verifier: true,
+                    print_diags_to_stderr: true,
+                    lint: false,
+                }
+                .build(rerooted_path)?;
+                pkg.get_modules()
+                    .map(|module| {
+                        let module = NormalizedModule::new(module);
+                        (module.name.to_string(), module.into())
+                    })
+                    .collect()
+            }
+        };
+
+        let package_id = match self.package_id {
+            Some(package_id) => package_id,
+            None => {
+                let module = modules
+                    .values()
+                    .next()
+                    .ok_or_else(|| anyhow!("The package has no modules"))?;
+                let address = ObjectID::from_hex_literal(&module.address)?;
+                if address == ObjectID::ZERO {
+                    bail!("The package is not published, its ID must be given with --package-id");
+                }
+                address
+            }
+        };
+
+        let bindings = generate_bindings(package_id, &modules)?;
+        match self.output {
+            Some(output) => fs::write(&output, bindings)
+                .map_err(|e| anyhow!("Can't write bindings to {:?}: {e}", output))?,
+            None => print!("{bindings}"),
+        }
+        Ok(())
+    }
+}
+
+/// Generate the Rust bindings of `modules`, whose functions are called in the package
+/// `package_id`. Can also be used from build scripts, to `include!` the bindings in a crate.
+pub fn generate_bindings(
+    package_id: ObjectID,
+    modules: &BTreeMap<String, SuiMoveNormalizedModule>,
+) -> anyhow::Result<String> {
+    let generator = Generator { modules };
+    let mut out = String::new();
+    writeln!(out, "// Generated by `sui move bindgen`, do not edit.")?;
+    writeln!(out)?;
+    writeln!(out, "use move_core_types::u256::U256;")?;
+    writeln!(out, "use serde::{{Deserialize, Serialize}};")?;
+    writeln!(out, "use sui_types::balance::{{Balance, Supply}};")?;
+    writeln!(out, "use sui_types::base_types::{{ObjectID, SuiAddress}};")?;
+    writeln!(out, "use sui_types::coin::{{Coin, TreasuryCap}};")?;
+    writeln!(out, "use sui_types::id::{{ID, UID}};")?;
+    writeln!(
+        out,
+        "use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;"
+    )?;
+    writeln!(out, "use sui_types::transaction::Argument;")?;
+    writeln!(out, "use sui_types::{{Identifier, TypeTag}};")?;
+    writeln!(out)?;
+    writeln!(out, "/// The package the generated functions call.")?;
+    writeln!(
+        out,
+        "pub const PACKAGE_ID: ObjectID = ObjectID::new({:?});",
+        package_id.into_bytes()
+    )?;
+    for (name, module) in modules {
+        generator.write_module(&mut out, name, module)?;
+    }
+    Ok(out)
+}
+
+struct Generator<'a> {
+    modules: &'a BTreeMap<String, SuiMoveNormalizedModule>,
+}
+
+impl Generator<'_> {
+    fn write_module(
+        &self,
+        out: &mut String,
+        name: &str,
+        module: &SuiMoveNormalizedModule,
+    ) -> anyhow::Result<()> {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "/// Bindings of the Move module `{}::{name}`.",
+            module.address
+        )?;
+        writeln!(
+            out,
+            "#[allow(dead_code, unused_imports, clippy::too_many_arguments)]"
+        )?;
+        writeln!(out, "pub mod {} {{", rust_ident(name))?;
+        writeln!(out, "    use super::*;")?;
+        for (struct_name, struct_) in &module.structs {
+            writeln!(out)?;
+            match self.struct_definition(struct_name, struct_) {
+                Ok(definition) => out.push_str(&definition),
+                // Structs embedding types of other packages can't be represented.
+                Err(e) => writeln!(out, "    // Skipped struct `{struct_name}`: {e}")?,
+            }
+        }
+        for (function_name, function) in &module.exposed_functions {
+            if !function.is_entry && !matches!(function.visibility, SuiMoveVisibility::Public) {
+                continue;
+            }
+            writeln!(out)?;
+            out.push_str(&self.function_definition(name, function_name, function));
+        }
+        writeln!(out, "}}")?;
+        Ok(())
+    }
+
+    fn struct_definition(
+        &self,
+        name: &str,
+        struct_: &SuiMoveNormalizedStruct,
+    ) -> anyhow::Result<String> {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]"
+        )?;
+        writeln!(
+            out,
+            "    pub struct {}{} {{",
+            rust_ident(name),
+            type_parameters(struct_)
+        )?;
+        for field in &struct_.fields {
+            writeln!(
+                out,
+                "        pub {}: {},",
+                rust_ident(&field.name),
+                self.field_type(&field.type_)?
+            )?;
+        }
+        writeln!(out, "    }}")?;
+        Ok(out)
+    }
+
+    fn package_struct(
+        &self,
+        address: ObjectID,
+        module: &str,
+        name: &str,
+    ) -> Option<&SuiMoveNormalizedStruct> {
+        let module = self.modules.get(module)?;
+        if ObjectID::from_hex_literal(&module.address).ok()? != address {
+            return None;
+        }
+        module.structs.get(name)
+    }
+
+    /// The Rust type with the same BCS layout as the Move type `type_`.
+    fn field_type(&self, type_: &SuiMoveNormalizedType) -> anyhow::Result<String> {
+        Ok(match type_ {
+            SuiMoveNormalizedType::Bool => "bool".to_string(),
+            SuiMoveNormalizedType::U8 => "u8".to_string(),
+            SuiMoveNormalizedType::U16 => "u16".to_string(),
+            SuiMoveNormalizedType::U32 => "u32".to_string(),
+            SuiMoveNormalizedType::U64 => "u64".to_string(),
+            SuiMoveNormalizedType::U128 => "u128".to_string(),
+            SuiMoveNormalizedType::U256 => "U256".to_string(),
+            SuiMoveNormalizedType::Address => "SuiAddress".to_string(),
+            SuiMoveNormalizedType::Vector(element) => format!("Vec<{}>", self.field_type(element)?),
+            SuiMoveNormalizedType::TypeParameter(index) => format!("T{index}"),
+            SuiMoveNormalizedType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => {
+                let address = ObjectID::from_hex_literal(address)?;
+                match (address, module.as_str(), name.as_str()) {
+                    (MOVE_STDLIB_PACKAGE_ID, "string" | "ascii", "String") => "String".to_string(),
+                    // A Move option is a vector of at most one element, which has the same BCS
+                    // layout as a Rust option.
+                    (MOVE_STDLIB_PACKAGE_ID, "option", "Option") => {
+                        format!("Option<{}>", self.field_type(&type_arguments[0])?)
+                    }
+                    (SUI_FRAMEWORK_PACKAGE_ID, "object", "UID" | "ID")
+                    | (SUI_FRAMEWORK_PACKAGE_ID, "balance", "Balance" | "Supply")
+                    | (SUI_FRAMEWORK_PACKAGE_ID, "coin", "Coin" | "TreasuryCap") => name.clone(),
+                    _ => {
+                        let struct_ =
+                            self.package_struct(address, module, name).ok_or_else(|| {
+                                anyhow!("type `{address}::{module}::{name}` is not in the package")
+                            })?;
+                        // Structs referencing types that can't be represented are skipped too.
+                        for field in &struct_.fields {
+                            self.field_type(&field.type_)?;
+                        }
+                        // Phantom type parameters don't change the layout and are left out.
+                        let mut arguments = vec![];
+                        for (parameter, argument) in
+                            struct_.type_parameters.iter().zip(type_arguments)
+                        {
+                            if !parameter.is_phantom {
+                                arguments.push(self.field_type(argument)?);
+                            }
+                        }
+                        let arguments = if arguments.is_empty() {
+                            String::new()
+                        } else {
+                            format!("<{}>", arguments.join(", "))
+                        };
+                        format!(
+                            "super::{}::{}{arguments}",
+                            rust_ident(module),
+                            rust_ident(name)
+                        )
+                    }
+                }
+            }
+            SuiMoveNormalizedType::Signer
+            | SuiMoveNormalizedType::Reference(_)
+            | SuiMoveNormalizedType::MutableReference(_) => {
+                bail!("unexpected field type {type_:?}")
+            }
+        })
+    }
+
+    fn function_definition(
+        &self,
+        module: &str,
+        name: &str,
+        function: &SuiMoveNormalizedFunction,
+    ) -> String {
+        let mut parameters = vec!["builder: &mut ProgrammableTransactionBuilder".to_string()];
+        if !function.type_parameters.is_empty() {
+            parameters.push(format!(
+                "type_args: [TypeTag; {}]",
+                function.type_parameters.len()
+            ));
+        }
+        let mut arguments = vec![];
+        // The transaction context is provided by the runtime.
+        for (index, parameter) in function
+            .parameters
+            .iter()
+            .enumerate()
+            .filter(|(_, parameter)| !is_tx_context(parameter))
+        {
+            match pure_type(parameter) {
+                Some(type_) => {
+                    parameters.push(format!("arg{index}: {type_}"));
+                    arguments.push(format!("builder.pure(arg{index})?"));
+                }
+                None => {
+                    parameters.push(format!("arg{index}: Argument"));
+                    arguments.push(format!("arg{index}"));
+                }
+            }
+        }
+        let type_args = if function.type_parameters.is_empty() {
+            "vec![]"
+        } else {
+            "type_args.to_vec()"
+        };
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "    /// Add a call to `{module}::{name}` to `builder`, returning its result."
+        );
+        let _ = writeln!(
+            out,
+            "    pub fn {}(\n        {},\n    ) -> anyhow::Result<Argument> {{",
+            rust_ident(name),
+            parameters.join(",\n        ")
+        );
+        let _ = writeln!(
+            out,
+            "        let arguments = vec![{}];",
+            arguments.join(", ")
+        );
+        let _ = writeln!(out, "        Ok(builder.programmable_move_call(");
+        let _ = writeln!(out, "            PACKAGE_ID,");
+        let _ = writeln!(out, "            Identifier::new({module:?})?,");
+        let _ = writeln!(out, "            Identifier::new({name:?})?,");
+        let _ = writeln!(out, "            {type_args},");
+        let _ = writeln!(out, "            arguments,");
+        let _ = writeln!(out, "        ))");
+        let _ = writeln!(out, "    }}");
+        out
+    }
+}
+
+fn type_parameters(struct_: &SuiMoveNormalizedStruct) -> String {
+    let parameters: Vec<_> = struct_
+        .type_parameters
+        .iter()
+        .enumerate()
+        .filter(|(_, parameter)| !parameter.is_phantom)
+        .map(|(index, _)| format!("T{index}"))
+        .collect();
+    if parameters.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", parameters.join(", "))
+    }
+}
+
+/// The Rust type of a parameter that can be passed as a pure input, if `type_` is one.
+fn pure_type(type_: &SuiMoveNormalizedType) -> Option<String> {
+    Some(match type_ {
+        SuiMoveNormalizedType::Bool => "bool".to_string(),
+        SuiMoveNormalizedType::U8 => "u8".to_string(),
+        SuiMoveNormalizedType::U16 => "u16".to_string(),
+        SuiMoveNormalizedType::U32 => "u32".to_string(),
+        SuiMoveNormalizedType::U64 => "u64".to_string(),
+        SuiMoveNormalizedType::U128 => "u128".to_string(),
+        SuiMoveNormalizedType::U256 => "U256".to_string(),
+        SuiMoveNormalizedType::Address => "SuiAddress".to_string(),
+        SuiMoveNormalizedType::Vector(element) => format!("Vec<{}>", pure_type(element)?),
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (
+            ObjectID::from_hex_literal(address).ok()?,
+            module.as_str(),
+            name.as_str(),
+        ) {
+            (MOVE_STDLIB_PACKAGE_ID, "string" | "ascii", "String") => "String".to_string(),
+            (MOVE_STDLIB_PACKAGE_ID, "option", "Option") => {
+                format!("Option<{}>", pure_type(&type_arguments[0])?)
+            }
+            (SUI_FRAMEWORK_PACKAGE_ID, "object", "ID") => "ObjectID".to_string(),
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+fn is_tx_context(type_: &SuiMoveNormalizedType) -> bool {
+    match type_ {
+        SuiMoveNormalizedType::Reference(inner)
+        | SuiMoveNormalizedType::MutableReference(inner) => {
+            matches!(
+                inner.as_ref(),
+                SuiMoveNormalizedType::Struct { address, module, name, .. }
+                    if ObjectID::from_hex_literal(address).ok() == Some(SUI_FRAMEWORK_PACKAGE_ID)
+                        && module == "tx_context"
+                        && name == "TxContext"
+            )
+        }
+        _ => false,
+    }
+}
+
+/// `name` as a Rust identifier, escaping keywords.
+fn rust_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type", "unsafe",
+        "use", "where", "while", "yield",
+    ];
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_command_line_common::testing::{
+        add_update_baseline_fix, format_diff, read_env_update_baseline, EXP_EXT,
+    };
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::transaction::Command;
+
+    // The expected bindings must compile too.
+    #[allow(dead_code, unused_imports)]
+    mod expected {
+        include!("../tests/data/bindgen/bindings.exp");
+    }
+
+    #[test]
+    fn bindings_match_expected() -> anyhow::Result<()> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/bindgen");
+        let exp_path = path.join("bindings").with_extension(EXP_EXT);
+        let modules = BuildConfig::new_for_testing()
+            .build(path)?
+            .get_modules()
+            .map(|module| {
+                let module = NormalizedModule::new(module);
+                (module.name.to_string(), module.into())
+            })
+            .collect();
+        let bindings = generate_bindings(ObjectID::from_hex_literal("0xb1d")?, &modules)?;
+
+        if read_env_update_baseline() {
+            fs::write(exp_path, bindings)?;
+            return Ok(());
+        }
+        let expected = fs::read_to_string(exp_path)?;
+        if bindings != expected {
+            bail!(add_update_baseline_fix(format!(
+                "Expected bindings differ from the generated ones:\n{}",
+                format_diff(expected, bindings),
+            )));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn expected_bindings_add_move_calls() -> anyhow::Result<()> {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        expected::counter::create(&mut builder, "counter".to_string())?;
+        let pt = builder.finish();
+
+        let [Command::MoveCall(call)] = &pt.commands[..] else {
+            panic!("Expected a single Move call, got {:?}", pt.commands);
+        };
+        assert_eq!(call.package, expected::PACKAGE_ID);
+        assert_eq!(call.module.as_str(), "counter");
+        assert_eq!(call.function.as_str(), "create");
+        assert_eq!(pt.inputs.len(), 1);
+        Ok(())
+    }
+}
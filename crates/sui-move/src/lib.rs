@@ -9,6 +9,8 @@ use move_package::BuildConfig;
 use move_unit_test::UnitTestingConfig;
 use std::path::PathBuf;
 
+#[cfg(feature = "bindgen")]
+pub mod bindgen;
 #[cfg(feature = "build")]
 pub mod build;
 #[cfg(feature = "coverage")]
@@ -23,6 +25,9 @@ pub mod unit_test;
 
 #[derive(Parser)]
 pub enum Command {
+    /// Generate typed Rust bindings for the structs and functions of a package
+    #[cfg(feature = "bindgen")]
+    Bindgen(bindgen::Bindgen),
     #[cfg(feature = "build")]
     Build(build::Build),
     #[cfg(feature = "coverage")]
@@ -49,6 +54,8 @@ pub fn execute_move_command(
     command: Command,
 ) -> anyhow::Result<()> {
     match command {
+        #[cfg(feature = "bindgen")]
+        Command::Bindgen(c) => c.execute(package_path, build_config),
         #[cfg(feature = "build")]
         Command::Build(c) => c.execute(package_path, build_config),
         #[cfg(feature = "coverage")]
@@ -0,0 +1,75 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module bindgen::counter {
+    use std::option::{Self, Option};
+    use std::string::String;
+    use sui::balance::Balance;
+    use sui::object::{Self, ID, UID};
+    use sui::sui::SUI;
+    use sui::transfer;
+    use sui::tx_context::{Self, TxContext};
+    use sui::url::Url;
+
+    struct Counter has key {
+        id: UID,
+        owner: address,
+        value: u64,
+        name: String,
+        tags: vector<u8>,
+        parent: Option<ID>,
+    }
+
+    /// Embeds a type of another package, which has no binding.
+    struct Link has store {
+        url: Url,
+    }
+
+    struct Vault has key {
+        id: UID,
+        funds: Balance<SUI>,
+        deposits: Wrapper<u64, SUI>,
+    }
+
+    struct Wrapper<T: store, phantom P> has store {
+        inner: T,
+        count: u16,
+    }
+
+    public fun new(name: String, ctx: &mut TxContext): Counter {
+        Counter {
+            id: object::new(ctx),
+            owner: tx_context::sender(ctx),
+            value: 0,
+            name,
+            tags: vector[],
+            parent: option::none(),
+        }
+    }
+
+    public entry fun create(name: String, ctx: &mut TxContext) {
+        transfer::transfer(new(name, ctx), tx_context::sender(ctx))
+    }
+
+    public fun increment(counter: &mut Counter, by: u64) {
+        counter.value = counter.value + by;
+    }
+
+    public fun set_parent(counter: &mut Counter, parent: Option<ID>, tags: vector<u8>) {
+        counter.parent = parent;
+        counter.tags = tags;
+    }
+
+    public fun value(counter: &Counter): u64 {
+        counter.value
+    }
+
+    public fun wrap<T: store>(inner: T): Wrapper<T, SUI> {
+        Wrapper { inner, count: 0 }
+    }
+
+    /// Friend functions can't be called from transactions and have no binding.
+    public(friend) fun reset(counter: &mut Counter) {
+        counter.value = 0;
+    }
+}
@@ -1121,6 +1121,10 @@ impl SuiNode {
             self.state.database.clone(),
             self.state.transaction_manager().clone(),
             self.accumulator.clone(),
+            self.state
+                .indexes
+                .is_some()
+                .then(|| self.state.subscription_handler.clone()),
             self.config.checkpoint_executor_config.clone(),
             &self.registry_service.default_registry(),
         );
@@ -291,6 +291,7 @@ impl RpcExampleProvider {
             events: SuiTransactionBlockEvents { data: vec![] },
             results: None,
             error: None,
+            trace: None,
         };
 
         Examples::new(
@@ -1,17 +1,19 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::offline_fetcher::OfflineFetcher;
 use crate::types::ReplayEngineError;
 use crate::types::EPOCH_CHANGE_STRUCT_TAG;
 use async_trait::async_trait;
 use futures::future::join_all;
 use lru::LruCache;
 use move_core_types::parser::parse_struct_tag;
-use parking_lot::RwLock;
+use parking_lot::Mutex;
 use rand::Rng;
 use std::collections::BTreeMap;
 use std::num::NonZeroUsize;
 use std::str::FromStr;
+use std::sync::Arc;
 use sui_core::authority::NodeStateDump;
 use sui_json_rpc::api::QUERY_MAX_RESULT_LIMIT;
 use sui_json_rpc_types::EventFilter;
@@ -89,27 +91,35 @@ pub(crate) trait DataFetcher {
 pub enum Fetchers {
     Remote(RemoteFetcher),
     NodeStateDump(NodeStateDumpFetcher),
+    Offline(OfflineFetcher),
 }
 
 impl Fetchers {
     pub fn as_remote(&self) -> &RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            _ => panic!("not a remote fetcher"),
         }
     }
 
     pub fn into_remote(self) -> RemoteFetcher {
         match self {
             Fetchers::Remote(q) => q,
-            Fetchers::NodeStateDump(_) => panic!("not a remote fetcher"),
+            _ => panic!("not a remote fetcher"),
         }
     }
 
     pub fn as_node_state_dump(&self) -> &NodeStateDumpFetcher {
         match self {
-            Fetchers::Remote(_) => panic!("not a node state dump fetcher"),
             Fetchers::NodeStateDump(q) => q,
+            _ => panic!("not a node state dump fetcher"),
+        }
+    }
+
+    pub fn as_offline(&self) -> &OfflineFetcher {
+        match self {
+            Fetchers::Offline(q) => q,
+            _ => panic!("not an offline fetcher"),
         }
     }
 }
@@ -124,6 +134,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_versioned(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_versioned(objects).await,
+            Fetchers::Offline(q) => q.multi_get_versioned(objects).await,
         }
     }
 
@@ -134,6 +145,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.multi_get_latest(objects).await,
             Fetchers::NodeStateDump(q) => q.multi_get_latest(objects).await,
+            Fetchers::Offline(q) => q.multi_get_latest(objects).await,
         }
     }
 
@@ -144,6 +156,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_checkpoint_txs(id).await,
             Fetchers::NodeStateDump(q) => q.get_checkpoint_txs(id).await,
+            Fetchers::Offline(q) => q.get_checkpoint_txs(id).await,
         }
     }
 
@@ -154,6 +167,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_transaction(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_transaction(tx_digest).await,
+            Fetchers::Offline(q) => q.get_transaction(tx_digest).await,
         }
     }
 
@@ -164,6 +178,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_loaded_child_objects(tx_digest).await,
             Fetchers::NodeStateDump(q) => q.get_loaded_child_objects(tx_digest).await,
+            Fetchers::Offline(q) => q.get_loaded_child_objects(tx_digest).await,
         }
     }
 
@@ -171,6 +186,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_latest_checkpoint_sequence_number().await,
             Fetchers::NodeStateDump(q) => q.get_latest_checkpoint_sequence_number().await,
+            Fetchers::Offline(q) => q.get_latest_checkpoint_sequence_number().await,
         }
     }
 
@@ -188,6 +204,10 @@ impl DataFetcher for Fetchers {
                 q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
                     .await
             }
+            Fetchers::Offline(q) => {
+                q.fetch_random_transaction(checkpoint_id_start, checkpoint_id_end)
+                    .await
+            }
         }
     }
 
@@ -198,6 +218,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
+            Fetchers::Offline(q) => q.get_epoch_start_timestamp_and_rgp(epoch_id).await,
         }
     }
 
@@ -208,6 +229,7 @@ impl DataFetcher for Fetchers {
         match self {
             Fetchers::Remote(q) => q.get_epoch_change_events(reverse).await,
             Fetchers::NodeStateDump(q) => q.get_epoch_change_events(reverse).await,
+            Fetchers::Offline(q) => q.get_epoch_change_events(reverse).await,
         }
     }
 }
@@ -216,61 +238,32 @@ const VERSIONED_OBJECT_CACHE_CAPACITY: Option<NonZeroUsize> = NonZeroUsize::new(
 const LATEST_OBJECT_CACHE_CAPACITY: Option<NonZeroUsize> = NonZeroUsize::new(1_000);
 const EPOCH_INFO_CACHE_CAPACITY: Option<NonZeroUsize> = NonZeroUsize::new(10_000);
 
+/// Clones of a fetcher share its caches.
+#[derive(Clone)]
 pub struct RemoteFetcher {
     /// This is used to download items not in store
     pub rpc_client: SuiClient,
     /// Cache versioned objects
-    pub versioned_object_cache: RwLock<LruCache<(ObjectID, VersionNumber), Object>>,
+    pub versioned_object_cache: Arc<Mutex<LruCache<(ObjectID, VersionNumber), Object>>>,
     /// Cache non-versioned objects
-    pub latest_object_cache: RwLock<LruCache<ObjectID, Object>>,
+    pub latest_object_cache: Arc<Mutex<LruCache<ObjectID, Object>>>,
     /// Cache epoch info
-    pub epoch_info_cache: RwLock<LruCache<u64, (u64, u64)>>,
-}
-
-impl Clone for RemoteFetcher {
-    fn clone(&self) -> Self {
-        let mut latest =
-            LruCache::new(LATEST_OBJECT_CACHE_CAPACITY.expect("Cache size must be non zero"));
-        self.latest_object_cache.read().iter().for_each(|(k, v)| {
-            latest.put(*k, v.clone());
-        });
-
-        let mut versioned =
-            LruCache::new(VERSIONED_OBJECT_CACHE_CAPACITY.expect("Cache size must be non zero"));
-        self.versioned_object_cache
-            .read()
-            .iter()
-            .for_each(|(k, v)| {
-                versioned.put(*k, v.clone());
-            });
-
-        let mut ep = LruCache::new(EPOCH_INFO_CACHE_CAPACITY.expect("Cache size must be non zero"));
-        self.epoch_info_cache.read().iter().for_each(|(k, v)| {
-            ep.put(*k, *v);
-        });
-
-        Self {
-            rpc_client: self.rpc_client.clone(),
-            versioned_object_cache: RwLock::new(versioned),
-            latest_object_cache: RwLock::new(latest),
-            epoch_info_cache: RwLock::new(ep),
-        }
-    }
+    pub epoch_info_cache: Arc<Mutex<LruCache<u64, (u64, u64)>>>,
 }
 
 impl RemoteFetcher {
     pub fn new(rpc_client: SuiClient) -> Self {
         Self {
             rpc_client,
-            versioned_object_cache: RwLock::new(LruCache::new(
+            versioned_object_cache: Arc::new(Mutex::new(LruCache::new(
                 VERSIONED_OBJECT_CACHE_CAPACITY.expect("Cache size must be non zero"),
-            )),
-            latest_object_cache: RwLock::new(LruCache::new(
+            ))),
+            latest_object_cache: Arc::new(Mutex::new(LruCache::new(
                 LATEST_OBJECT_CACHE_CAPACITY.expect("Cache size must be non zero"),
-            )),
-            epoch_info_cache: RwLock::new(LruCache::new(
+            ))),
+            epoch_info_cache: Arc::new(Mutex::new(LruCache::new(
                 EPOCH_INFO_CACHE_CAPACITY.expect("Cache size must be non zero"),
-            )),
+            ))),
         }
     }
 
@@ -283,7 +276,7 @@ impl RemoteFetcher {
         for (object_id, version) in objects {
             if let Some(obj) = self
                 .versioned_object_cache
-                .read()
+                .lock()
                 .peek(&(*object_id, *version))
             {
                 cached.push(obj.clone());
@@ -295,11 +288,33 @@ impl RemoteFetcher {
         (cached, to_fetch)
     }
 
+    /// Fetch the latest version of an object at or before `version`, `None` if there is none
+    pub async fn get_lt_or_eq_version(
+        &self,
+        id: ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        let response = self
+            .rpc_client
+            .read_api()
+            .try_get_parsed_past_object_lt_or_eq_version(
+                id,
+                version,
+                SuiObjectDataOptions::bcs_lossless(),
+            )
+            .await?;
+        match convert_past_obj_response(response) {
+            Ok(object) => Ok(Some(object)),
+            Err(ReplayEngineError::ObjectVersionNotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
     pub fn check_latest_cache(&self, objects: &[ObjectID]) -> (Vec<Object>, Vec<ObjectID>) {
         let mut to_fetch = Vec::new();
         let mut cached = Vec::new();
         for object_id in objects {
-            if let Some(obj) = self.latest_object_cache.read().peek(object_id) {
+            if let Some(obj) = self.latest_object_cache.lock().peek(object_id) {
                 cached.push(obj.clone());
             } else {
                 to_fetch.push(*object_id);
@@ -352,7 +367,7 @@ impl DataFetcher for RemoteFetcher {
                 for obj in &x {
                     let r = obj.compute_object_reference();
                     self.versioned_object_cache
-                        .write()
+                        .lock()
                         .put((r.0, r.1), obj.clone());
                 }
                 x
@@ -388,7 +403,7 @@ impl DataFetcher for RemoteFetcher {
                 x.extend(cached);
                 // Backfill the cache
                 for obj in &x {
-                    self.latest_object_cache.write().put(obj.id(), obj.clone());
+                    self.latest_object_cache.lock().put(obj.id(), obj.clone());
                 }
                 x
             })
@@ -477,7 +492,7 @@ impl DataFetcher for RemoteFetcher {
         epoch_id: u64,
     ) -> Result<(u64, u64), ReplayEngineError> {
         // Check epoch info cache
-        if let Some((ts, rgp)) = self.epoch_info_cache.read().peek(&epoch_id) {
+        if let Some((ts, rgp)) = self.epoch_info_cache.lock().peek(&epoch_id) {
             return Ok((*ts, *rgp));
         }
 
@@ -509,7 +524,7 @@ impl DataFetcher for RemoteFetcher {
 
         if let TransactionKind::ChangeEpoch(change) = tx_kind_orig {
             // Backfill cache
-            self.epoch_info_cache.write().put(
+            self.epoch_info_cache.lock().put(
                 epoch_id,
                 (change.epoch_start_timestamp_ms, reference_gas_price),
             );
@@ -0,0 +1,750 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A local fork of a live network. The state of the network is pinned at a checkpoint, objects are
+//! fetched from the RPC node the first time they are read and cached, and new transactions are
+//! executed on top of that state and served through a JSON-RPC endpoint.
+//! An object is resolved at the fork checkpoint as its latest version written by a transaction
+//! checkpointed at or before the fork. Versions of an object only increase, so that version is
+//! binary searched from its latest one. Objects deleted or wrapped on the network since the fork
+//! can't be resolved this way and do not exist on the fork.
+//! Signatures are checked unless all signers of a transaction are impersonated, so transactions can
+//! be sent on behalf of any address.
+
+use crate::data_fetcher::{DataFetcher, RemoteFetcher};
+use crate::replay::{get_executor, LocalExec};
+use crate::types::ReplayEngineError;
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use fastcrypto::traits::ToFromBytes;
+use futures::future::join_all;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::server::ServerBuilder;
+use jsonrpsee::types::error::CallError;
+use jsonrpsee::RpcModule;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::ModuleId;
+use parking_lot::{Mutex, RwLock};
+use prometheus::Registry;
+use shared_crypto::intent::Intent;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use sui_config::node::ExpensiveSafetyCheckConfig;
+use sui_core::authority::TemporaryStore;
+use sui_execution::Executor;
+use sui_json_rpc::{
+    get_balance_changes_from_effect, get_object_changes, ObjectProvider, ObjectProviderCache,
+};
+use sui_json_rpc_types::{
+    DryRunArgs, DryRunTransactionBlockResponse, SuiObjectDataOptions, SuiObjectResponse,
+    SuiTransactionBlock, SuiTransactionBlockData, SuiTransactionBlockEffects,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_protocol_config::ProtocolConfig;
+use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, VersionNumber};
+use sui_types::committee::EpochId;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::error::{SuiError, SuiResult, UserInputError};
+use sui_types::gas::{GasCharger, SuiGasStatus};
+use sui_types::gas_model::profile::GasProfile;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::metrics::LimitsMetrics;
+use sui_types::object::{Object, ObjectFormatOptions, ObjectRead, Owner};
+use sui_types::signature::GenericSignature;
+use sui_types::storage::{
+    get_module_by_id, BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync,
+};
+use sui_types::sui_serde::BigInt;
+use sui_types::temporary_store::{InnerTemporaryStore, TemporaryModuleResolver};
+use sui_types::transaction::{
+    InputObjectKind, InputObjects, Transaction, TransactionData, TransactionDataAPI,
+};
+use tokio::runtime::Handle;
+use tracing::info;
+
+/// Objects of the network at the fork checkpoint, overlaid with the outputs of the transactions
+/// executed on the fork. Must be read from a blocking thread, objects are downloaded on the fly.
+#[derive(Clone)]
+struct ForkStore {
+    fetcher: Arc<RemoteFetcher>,
+    checkpoint: CheckpointSequenceNumber,
+    /// Objects resolved or written so far, `None` if the object does not exist on the fork
+    objects: Arc<RwLock<BTreeMap<ObjectID, Option<Object>>>>,
+}
+
+impl ForkStore {
+    /// Find the version of an object at the fork checkpoint
+    async fn resolve(&self, id: ObjectID) -> Result<Option<Object>, ReplayEngineError> {
+        let latest = match self.fetcher.multi_get_latest(&[id]).await {
+            Ok(mut objects) => objects
+                .pop()
+                .ok_or(ReplayEngineError::ObjectNotExist { id })?,
+            Err(ReplayEngineError::ObjectNotExist { .. })
+            | Err(ReplayEngineError::ObjectDeleted { .. }) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if self.written_before_fork(&latest).await? {
+            return Ok(Some(latest));
+        }
+        // The version at the fork is in [low, high), binary searched with lookups of the latest
+        // version at or before a bound
+        let (mut low, mut high) = (0, latest.version().value());
+        let mut found = None;
+        while low < high {
+            let bound = low + (high - low) / 2;
+            match self
+                .fetcher
+                .get_lt_or_eq_version(id, SequenceNumber::from_u64(bound))
+                .await?
+            {
+                Some(object) if self.written_before_fork(&object).await? => {
+                    low = bound + 1;
+                    found = Some(object);
+                }
+                Some(object) => high = object.version().value(),
+                None => low = bound + 1,
+            }
+        }
+        Ok(found)
+    }
+
+    /// Whether the transaction which wrote `object` was checkpointed at or before the fork
+    async fn written_before_fork(&self, object: &Object) -> Result<bool, ReplayEngineError> {
+        let tx = self
+            .fetcher
+            .get_transaction(&object.previous_transaction)
+            .await?;
+        Ok(matches!(tx.checkpoint, Some(checkpoint) if checkpoint <= self.checkpoint))
+    }
+
+    /// Resolve the objects not read so far
+    async fn load_objects(&self, ids: &[ObjectID]) -> Result<(), ReplayEngineError> {
+        let missing: Vec<_> = {
+            let objects = self.objects.read();
+            ids.iter()
+                .filter(|id| !objects.contains_key(id))
+                .copied()
+                .collect()
+        };
+        let resolved = join_all(missing.iter().map(|id| self.resolve(*id))).await;
+        let mut objects = self.objects.write();
+        for (id, object) in missing.into_iter().zip(resolved) {
+            // A transaction may have written the object in the meantime
+            objects.entry(id).or_insert(object?);
+        }
+        Ok(())
+    }
+
+    fn get_or_load(&self, id: &ObjectID) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.objects.read().get(id) {
+            return Ok(object.clone());
+        }
+        Handle::current().block_on(self.load_objects(&[*id]))?;
+        Ok(self.objects.read().get(id).cloned().flatten())
+    }
+
+    fn apply(&self, outputs: &InnerTemporaryStore) {
+        let mut objects = self.objects.write();
+        for (id, (_, object, _)) in &outputs.written {
+            objects.insert(*id, Some(object.clone()));
+        }
+        for id in outputs.deleted.keys() {
+            objects.insert(*id, None);
+        }
+    }
+}
+
+/// Objects of the fork at past versions are read from the network
+#[async_trait]
+impl ObjectProvider for ForkStore {
+    type Error = ReplayEngineError;
+
+    async fn get_object(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Object, Self::Error> {
+        self.load_objects(&[*id]).await?;
+        let object = self.objects.read().get(id).cloned().flatten();
+        match object {
+            Some(object) if object.version() == *version => Ok(object),
+            _ => self
+                .fetcher
+                .multi_get_versioned(&[(*id, *version)])
+                .await?
+                .pop()
+                .ok_or(ReplayEngineError::ObjectVersionNotFound {
+                    id: *id,
+                    version: *version,
+                }),
+        }
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        self.load_objects(&[*id]).await?;
+        let object = self.objects.read().get(id).cloned().flatten();
+        match object {
+            Some(object) if object.version() <= *version => Ok(Some(object)),
+            _ => self.fetcher.get_lt_or_eq_version(*id, *version).await,
+        }
+    }
+}
+
+impl BackingPackageStore for ForkStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.get_or_load(package_id)
+    }
+}
+
+impl ChildObjectResolver for ForkStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        let Some(child_object) = self.get_or_load(child)? else {
+            return Ok(None);
+        };
+        let child_version = child_object.version();
+        if child_version > child_version_upper_bound {
+            return Err(SuiError::Unknown(format!(
+                "Invariant Violation. Fork loaded child_object {child} at version \
+                {child_version} but expected the version to be <= {child_version_upper_bound}"
+            )));
+        }
+        if child_object.owner != Owner::ObjectOwner((*parent).into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: *parent,
+                actual_owner: child_object.owner,
+            });
+        }
+        Ok(Some(child_object))
+    }
+}
+
+impl ParentSync for ForkStore {
+    fn get_latest_parent_entry_ref(&self, object_id: ObjectID) -> SuiResult<Option<ObjectRef>> {
+        Ok(self
+            .get_or_load(&object_id)?
+            .map(|object| object.compute_object_reference()))
+    }
+}
+
+impl ObjectStore for ForkStore {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.get_or_load(object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> SuiResult<Option<Object>> {
+        Ok(self
+            .get_or_load(object_id)?
+            .filter(|object| object.version() == version))
+    }
+}
+
+impl GetModule for ForkStore {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> SuiResult<Option<Self::Item>> {
+        get_module_by_id(self, id)
+    }
+}
+
+/// Modules behind an `Arc`, as `TemporaryModuleResolver` falls back to them
+struct SharedModules<'a>(&'a ForkStore);
+
+impl GetModule for SharedModules<'_> {
+    type Error = anyhow::Error;
+    type Item = Arc<CompiledModule>;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> anyhow::Result<Option<Self::Item>> {
+        Ok(self.0.get_module_by_id(id)?.map(Arc::new))
+    }
+}
+
+pub struct ForkNode {
+    store: ForkStore,
+    epoch: EpochId,
+    epoch_start_timestamp_ms: u64,
+    reference_gas_price: u64,
+    protocol_config: ProtocolConfig,
+    chain_identifier: String,
+    executor: Arc<dyn Executor + Send + Sync>,
+    metrics: Arc<LimitsMetrics>,
+    /// Execute all transactions without checking signatures
+    auto_impersonate: bool,
+    impersonated: RwLock<BTreeSet<SuiAddress>>,
+    /// Transactions are executed one at a time, each on top of the outputs of the previous ones
+    execution_lock: Mutex<()>,
+    transactions: RwLock<BTreeMap<TransactionDigest, SuiTransactionBlockResponse>>,
+}
+
+impl ForkNode {
+    /// Fork the network of `rpc_url` at `checkpoint`, or at its latest checkpoint
+    pub async fn new(
+        rpc_url: &str,
+        checkpoint: Option<CheckpointSequenceNumber>,
+        auto_impersonate: bool,
+    ) -> Result<Self, ReplayEngineError> {
+        let lx = LocalExec::new_from_fn_url(rpc_url)
+            .await?
+            .init_for_execution()
+            .await?;
+        let fetcher = lx.fetcher.as_remote().clone();
+        let read_api = fetcher.rpc_client.read_api();
+        let checkpoint = match checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => fetcher.get_latest_checkpoint_sequence_number().await?,
+        };
+        let epoch = read_api.get_checkpoint(checkpoint.into()).await?.epoch;
+        let protocol_config = lx.get_protocol_config(epoch).await?;
+        let (epoch_start_timestamp_ms, reference_gas_price) =
+            lx.get_epoch_start_timestamp_and_rgp(epoch).await?;
+        let chain_identifier = read_api.get_chain_identifier().await?;
+        let executor = get_executor(
+            None,
+            &protocol_config,
+            ExpensiveSafetyCheckConfig::default(),
+            None,
+        );
+        info!(
+            "Forked {} at checkpoint {} in epoch {} with protocol version {}",
+            rpc_url,
+            checkpoint,
+            epoch,
+            protocol_config.version.as_u64()
+        );
+
+        Ok(Self {
+            store: ForkStore {
+                fetcher: Arc::new(fetcher),
+                checkpoint,
+                objects: Default::default(),
+            },
+            epoch,
+            epoch_start_timestamp_ms,
+            reference_gas_price,
+            protocol_config,
+            chain_identifier,
+            executor,
+            metrics: Arc::new(LimitsMetrics::new(&Registry::new())),
+            auto_impersonate,
+            impersonated: Default::default(),
+            execution_lock: Mutex::new(()),
+            transactions: Default::default(),
+        })
+    }
+
+    fn is_impersonated(&self, signers: &[SuiAddress]) -> bool {
+        let impersonated = self.impersonated.read();
+        self.auto_impersonate || signers.iter().all(|signer| impersonated.contains(signer))
+    }
+
+    /// Execute a transaction on top of the fork. Its outputs are only applied and its signatures
+    /// only checked if `commit` is set, otherwise this is a dry run. A gas profile of the
+    /// execution is returned if `show_gas_profile` is set
+    fn execute(
+        &self,
+        tx: &Transaction,
+        commit: bool,
+        show_gas_profile: bool,
+    ) -> Result<(TransactionEffects, InnerTemporaryStore, Option<GasProfile>), ReplayEngineError>
+    {
+        let data = tx.data().transaction_data();
+        data.validity_check(&self.protocol_config)?;
+        let signers = data.signers();
+        if commit && !self.is_impersonated(&signers) {
+            tx.verify_signature()?;
+        }
+        let input_kinds = data.input_objects()?;
+        let ids: Vec<_> = input_kinds.iter().map(|kind| kind.object_id()).collect();
+        Handle::current().block_on(self.store.load_objects(&ids))?;
+
+        let _guard = self.execution_lock.lock();
+        let mut input_objects = vec![];
+        let mut shared_object_refs = vec![];
+        for kind in input_kinds {
+            let id = kind.object_id();
+            let object = self
+                .store
+                .get_or_load(&id)?
+                .ok_or(ReplayEngineError::ObjectNotExist { id })?;
+            match kind {
+                InputObjectKind::ImmOrOwnedMoveObject(object_ref) => {
+                    if object_ref.1 != object.version() {
+                        return Err(UserInputError::ObjectVersionUnavailableForConsumption {
+                            provided_obj_ref: object_ref,
+                            current_version: object.version(),
+                        }
+                        .into());
+                    }
+                    if let Owner::AddressOwner(owner) = object.owner {
+                        if !signers.contains(&owner) {
+                            return Err(UserInputError::IncorrectUserSignature {
+                                error: format!("Object {id} is owned by {owner}, not a signer"),
+                            }
+                            .into());
+                        }
+                    }
+                }
+                InputObjectKind::SharedMoveObject { .. } => {
+                    shared_object_refs.push(object.compute_object_reference())
+                }
+                InputObjectKind::MovePackage(_) => {}
+            }
+            input_objects.push((kind, object));
+        }
+        let dependencies = input_objects
+            .iter()
+            .map(|(_, object)| object.previous_transaction)
+            .collect();
+
+        let digest = *tx.digest();
+        let gas_status = SuiGasStatus::new(
+            data.gas_budget(),
+            data.gas_price(),
+            self.reference_gas_price,
+            &self.protocol_config,
+        )?;
+        let mut gas_charger = GasCharger::new(
+            digest,
+            data.gas().to_vec(),
+            gas_status,
+            &self.protocol_config,
+        );
+        if show_gas_profile {
+            gas_charger.enable_gas_profile();
+        }
+        let temporary_store = TemporaryStore::new(
+            Arc::new(self.store.clone()),
+            InputObjects::new(input_objects),
+            digest,
+            &self.protocol_config,
+        );
+        let (outputs, effects, _) = self.executor.execute_transaction_to_effects(
+            &self.protocol_config,
+            self.metrics.clone(),
+            true,
+            &HashSet::new(),
+            &self.epoch,
+            self.epoch_start_timestamp_ms,
+            temporary_store,
+            shared_object_refs,
+            &mut gas_charger,
+            data.kind().clone(),
+            data.sender(),
+            digest,
+            dependencies,
+        );
+        if commit {
+            self.store.apply(&outputs);
+        }
+        Ok((effects, outputs, gas_charger.take_gas_profile()))
+    }
+
+    fn execute_transaction_block(
+        &self,
+        tx: &Transaction,
+    ) -> Result<SuiTransactionBlockResponse, ReplayEngineError> {
+        // Executing a transaction again returns the effects of its first execution
+        if let Some(response) = self.transactions.read().get(tx.digest()) {
+            return Ok(response.clone());
+        }
+        let (effects, outputs, _) = self.execute(tx, true, false)?;
+        let resolver = TemporaryModuleResolver::new(&outputs, SharedModules(&self.store));
+        let mut response = SuiTransactionBlockResponse::new(*tx.digest());
+        response.transaction = Some(SuiTransactionBlock::try_from(tx.data().clone(), &resolver)?);
+        response.effects = Some(SuiTransactionBlockEffects::try_from(effects)?);
+        response.events = Some(SuiTransactionBlockEvents::try_from(
+            outputs.events.clone(),
+            *tx.digest(),
+            None,
+            &resolver,
+        )?);
+        response.confirmed_local_execution = Some(true);
+        self.transactions
+            .write()
+            .insert(*tx.digest(), response.clone());
+        Ok(response)
+    }
+
+    fn dry_run_transaction_block(
+        &self,
+        tx: &Transaction,
+        args: DryRunArgs,
+    ) -> Result<DryRunTransactionBlockResponse, ReplayEngineError> {
+        let data = tx.data().transaction_data();
+        let (effects, outputs, gas_profile) = self.execute(tx, false, args.show_gas_profile)?;
+        let objects =
+            ObjectProviderCache::new_with_cache(self.store.clone(), outputs.written.clone());
+        let (balance_changes, object_changes) = Handle::current().block_on(async {
+            let balance_changes =
+                get_balance_changes_from_effect(&objects, &effects, data.input_objects()?, None)
+                    .await?;
+            let object_changes = get_object_changes(
+                &objects,
+                data.sender(),
+                effects.modified_at_versions(),
+                effects.all_changed_objects(),
+                effects.all_deleted(),
+            )
+            .await?;
+            Ok::<_, ReplayEngineError>((balance_changes, object_changes))
+        })?;
+        let resolver = TemporaryModuleResolver::new(&outputs, SharedModules(&self.store));
+        Ok(DryRunTransactionBlockResponse {
+            effects: SuiTransactionBlockEffects::try_from(effects)?,
+            events: SuiTransactionBlockEvents::try_from(
+                outputs.events.clone(),
+                *tx.digest(),
+                None,
+                &resolver,
+            )?,
+            object_changes,
+            balance_changes,
+            input: SuiTransactionBlockData::try_from(data.clone(), &resolver)?,
+            gas_profile,
+        })
+    }
+
+    fn get_object(
+        &self,
+        id: ObjectID,
+        options: SuiObjectDataOptions,
+    ) -> Result<SuiObjectResponse, ReplayEngineError> {
+        let object_read = match self.store.get_or_load(&id)? {
+            Some(object) => {
+                let layout = object.get_layout(ObjectFormatOptions::default(), &self.store)?;
+                ObjectRead::Exists(object.compute_object_reference(), object, layout)
+            }
+            None => ObjectRead::NotExists(id),
+        };
+        Ok(SuiObjectResponse::try_from((object_read, options))?)
+    }
+
+    /// Serve the JSON-RPC API of the fork until the server is stopped
+    pub async fn serve(self, listen_address: SocketAddr) -> anyhow::Result<()> {
+        let checkpoint = self.store.checkpoint;
+        let module = Arc::new(self).rpc_module()?;
+        let methods_names = module.method_names().collect::<Vec<_>>();
+        let server = ServerBuilder::default().build(listen_address).await?;
+        let addr = server.local_addr()?;
+        let handle = server.start(module)?;
+        info!("Fork of checkpoint {checkpoint} listening on {addr}");
+        info!("Available JSON-RPC methods : {:?}", methods_names);
+        handle.stopped().await;
+        Ok(())
+    }
+
+    fn rpc_module(self: Arc<Self>) -> Result<RpcModule<Arc<Self>>, RpcError> {
+        let mut module = RpcModule::new(self);
+        module.register_async_method("sui_getObject", |params, node| async move {
+            let mut params = params.sequence();
+            let id: ObjectID = params.next()?;
+            let options = params.optional_next()?.unwrap_or_default();
+            blocking(node, move |node| node.get_object(id, options)).await
+        })?;
+        module.register_async_method("sui_multiGetObjects", |params, node| async move {
+            let mut params = params.sequence();
+            let ids: Vec<ObjectID> = params.next()?;
+            let options: SuiObjectDataOptions = params.optional_next()?.unwrap_or_default();
+            blocking(node, move |node| {
+                ids.into_iter()
+                    .map(|id| node.get_object(id, options.clone()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await
+        })?;
+        module.register_async_method("sui_executeTransactionBlock", |params, node| async move {
+            let mut params = params.sequence();
+            let tx = decode_transaction(params.next()?, params.next()?).map_err(rpc_error)?;
+            blocking(node, move |node| node.execute_transaction_block(&tx)).await
+        })?;
+        module.register_async_method("sui_dryRunTransactionBlock", |params, node| async move {
+            let mut params = params.sequence();
+            let tx = decode_transaction(params.next()?, vec![]).map_err(rpc_error)?;
+            let args: DryRunArgs = params.optional_next()?.unwrap_or_default();
+            blocking(node, move |node| node.dry_run_transaction_block(&tx, args)).await
+        })?;
+        module.register_async_method("sui_getTransactionBlock", |params, node| async move {
+            let digest: TransactionDigest = params.sequence().next()?;
+            let executed = node.transactions.read().get(&digest).cloned();
+            if let Some(response) = executed {
+                return Ok(response);
+            }
+            // Transactions before the fork are read from the network
+            node.store
+                .fetcher
+                .rpc_client
+                .read_api()
+                .get_transaction_with_options(
+                    digest,
+                    SuiTransactionBlockResponseOptions::full_content(),
+                )
+                .await
+                .map_err(rpc_error)
+        })?;
+        module.register_method("suix_getReferenceGasPrice", |_, node| {
+            Ok(BigInt::from(node.reference_gas_price))
+        })?;
+        module.register_method("sui_getLatestCheckpointSequenceNumber", |_, node| {
+            Ok(BigInt::from(node.store.checkpoint))
+        })?;
+        module.register_method("sui_getChainIdentifier", |_, node| {
+            Ok(node.chain_identifier.clone())
+        })?;
+        module.register_method("fork_impersonateAccount", |params, node| {
+            let address: SuiAddress = params.one()?;
+            node.impersonated.write().insert(address);
+            Ok(())
+        })?;
+        module.register_method("fork_stopImpersonatingAccount", |params, node| {
+            let address: SuiAddress = params.one()?;
+            node.impersonated.write().remove(&address);
+            Ok(())
+        })?;
+        Ok(module)
+    }
+}
+
+fn rpc_error(e: impl Into<anyhow::Error>) -> RpcError {
+    RpcError::Call(CallError::Failed(e.into()))
+}
+
+/// Run `f` on a blocking thread, the fork store downloads objects synchronously
+async fn blocking<T, F>(node: Arc<ForkNode>, f: F) -> Result<T, RpcError>
+where
+    T: Send + 'static,
+    F: FnOnce(&ForkNode) -> Result<T, ReplayEngineError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&node))
+        .await
+        .map_err(rpc_error)?
+        .map_err(rpc_error)
+}
+
+fn decode_transaction(tx_bytes: Base64, signatures: Vec<Base64>) -> anyhow::Result<Transaction> {
+    let data: TransactionData = bcs::from_bytes(&tx_bytes.to_vec()?)?;
+    let signatures = signatures
+        .iter()
+        .map(|signature| Ok(GenericSignature::from_bytes(&signature.to_vec()?)?))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(Transaction::from_generic_sig_data(
+        data,
+        Intent::sui_transaction(),
+        signatures,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use sui_json_rpc_types::{ObjectChange, SuiTransactionBlockEffectsAPI};
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use test_cluster::{TestCluster, TestClusterBuilder};
+
+    /// Wait for `digest` to be checkpointed, returning its checkpoint
+    async fn checkpoint_of(
+        test_cluster: &TestCluster,
+        digest: TransactionDigest,
+    ) -> CheckpointSequenceNumber {
+        loop {
+            let response = test_cluster
+                .sui_client()
+                .read_api()
+                .get_transaction_with_options(digest, SuiTransactionBlockResponseOptions::new())
+                .await
+                .unwrap();
+            if let Some(checkpoint) = response.checkpoint {
+                return checkpoint;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[cfg_attr(msim, ignore)]
+    async fn test_fork_at_checkpoint() {
+        let test_cluster = TestClusterBuilder::new()
+            .enable_fullnode_events()
+            .build()
+            .await;
+        let rgp = test_cluster.get_reference_gas_price().await;
+        let sender = test_cluster.get_address_0();
+        let recipient = test_cluster.get_address_1();
+        let gas = test_cluster
+            .wallet
+            .get_one_gas_object_owned_by_address(sender)
+            .await
+            .unwrap()
+            .unwrap();
+
+        // The gas coin is mutated once before the fork and once after it
+        let data = TestTransactionBuilder::new(sender, gas, rgp)
+            .transfer_sui(Some(1), recipient)
+            .build();
+        let response = test_cluster.sign_and_execute_transaction(&data).await;
+        let checkpoint = checkpoint_of(&test_cluster, response.digest).await;
+        let gas_at_fork = response
+            .effects
+            .unwrap()
+            .gas_object()
+            .reference
+            .to_object_ref();
+        let data = TestTransactionBuilder::new(sender, gas_at_fork, rgp)
+            .transfer_sui(Some(1), recipient)
+            .build();
+        let response = test_cluster.sign_and_execute_transaction(&data).await;
+        let created_after_fork = response.effects.unwrap().created()[0].object_id();
+
+        let node = ForkNode::new(test_cluster.rpc_url(), Some(checkpoint), true)
+            .await
+            .unwrap();
+        let object = node.store.resolve(gas.0).await.unwrap().unwrap();
+        assert_eq!(object.compute_object_reference(), gas_at_fork);
+        assert!(node
+            .store
+            .resolve(created_after_fork)
+            .await
+            .unwrap()
+            .is_none());
+
+        // Dry runs on the fork report their object and balance changes
+        let data = TestTransactionBuilder::new(sender, gas_at_fork, rgp)
+            .transfer_sui(Some(1), recipient)
+            .build();
+        let tx = test_cluster.wallet.sign_transaction(&data);
+        let response = blocking(Arc::new(node), move |node| {
+            node.dry_run_transaction_block(&tx, DryRunArgs::default())
+        })
+        .await
+        .unwrap();
+        assert!(response.object_changes.iter().any(|change| matches!(
+            change,
+            ObjectChange::Mutated { object_id, .. } if *object_id == gas.0
+        )));
+        assert!(response
+            .object_changes
+            .iter()
+            .any(|change| matches!(change, ObjectChange::Created { .. })));
+        assert!(response
+            .balance_changes
+            .iter()
+            .any(|change| change.owner == Owner::AddressOwner(recipient) && change.amount == 1));
+    }
+}
@@ -13,6 +13,9 @@ use transaction_provider::{FuzzStartPoint, TransactionSource};
 
 use crate::replay::LocalExec;
 use crate::replay::ProtocolVersionSummary;
+use crate::report::ReplayReport;
+use fork::ForkNode;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use sui_config::node::ExpensiveSafetyCheckConfig;
@@ -21,9 +24,12 @@ use tracing::{error, info};
 pub mod config;
 mod data_fetcher;
 mod db_rider;
+pub mod fork;
 pub mod fuzz;
 pub mod fuzz_mutations;
+mod offline_fetcher;
 mod replay;
+pub mod report;
 pub mod transaction_provider;
 pub mod types;
 
@@ -45,6 +51,10 @@ pub enum ReplayToolCommand {
         diag: bool,
         #[clap(long, short, allow_hyphen_values = true)]
         executor_version_override: Option<i64>,
+        /// Write a gas profile of the execution to this directory, in the speedscope format.
+        /// Only available in debug builds
+        #[clap(long)]
+        profile_output: Option<PathBuf>,
     },
 
     /// Replay a transaction from a node state dump
@@ -69,6 +79,46 @@ pub enum ReplayToolCommand {
         max_tasks: u64,
     },
 
+    /// Replay all transactions in a range of checkpoints with object caches shared between tasks,
+    /// continuing past failures and writing a JSON report of every effects mismatch
+    #[clap(name = "batch")]
+    ReplayCheckpointsBatch {
+        #[clap(long, short)]
+        start: u64,
+        #[clap(long, short)]
+        end: u64,
+        #[clap(long, short, default_value = "16")]
+        max_tasks: u64,
+        /// Path of the JSON report
+        #[clap(long, short, default_value = "replay_report.json")]
+        report: PathBuf,
+    },
+
+    /// Replay all transactions in a range of checkpoints without any RPC node, from a formal
+    /// snapshot and a checkpoint archive on local disk. All transactions between the end of the
+    /// snapshot epoch and the range are executed first to reconstruct object versions
+    #[clap(name = "offline")]
+    ReplayOffline {
+        /// Local directory of formal snapshots, containing `epoch_<N>` directories
+        #[clap(long)]
+        snapshot_dir: PathBuf,
+        /// Epoch of the snapshot to start from
+        #[clap(long)]
+        snapshot_epoch: u64,
+        /// Local DB to restore the snapshot into, reused if the snapshot was already restored
+        #[clap(long)]
+        db_path: PathBuf,
+        /// Local directory of the checkpoint archive
+        #[clap(long)]
+        archive_dir: PathBuf,
+        #[clap(long, short)]
+        start: u64,
+        #[clap(long, short)]
+        end: u64,
+        #[clap(long, short)]
+        terminate_early: bool,
+    },
+
     /// Replay all transactions in an epoch
     #[clap(name = "ep")]
     ReplayEpoch {
@@ -91,6 +141,22 @@ pub enum ReplayToolCommand {
         num_base_transactions: u64,
     },
 
+    /// Fork the state of the network at a checkpoint and serve a JSON-RPC endpoint executing new
+    /// transactions on top of it. Objects are fetched from the RPC node when first read
+    #[clap(name = "fork")]
+    Fork {
+        /// Checkpoint to fork from, the latest checkpoint if not set
+        #[clap(long, short)]
+        checkpoint: Option<u64>,
+        /// Address the JSON-RPC server listens on
+        #[clap(long, default_value = "127.0.0.1:9000")]
+        listen_address: SocketAddr,
+        /// Do not check the signatures of any transaction, as if every sender was impersonated.
+        /// Addresses can also be impersonated one at a time with `fork_impersonateAccount`
+        #[clap(long)]
+        auto_impersonate: bool,
+    },
+
     #[clap(name = "report")]
     Report,
 }
@@ -160,8 +226,12 @@ pub async fn execute_replay_command(
             show_effects,
             diag,
             executor_version_override,
+            profile_output,
         } => {
             let tx_digest = TransactionDigest::from_str(&tx_digest)?;
+            if profile_output.is_some() && (use_authority || !cfg!(debug_assertions)) {
+                warn!("Gas profiles are only recorded by debug builds, without --authority");
+            }
             info!("Executing tx: {}", tx_digest);
             let sandbox_state = LocalExec::replay_with_network_config(
                 rpc_url,
@@ -170,6 +240,7 @@ pub async fn execute_replay_command(
                 safety,
                 use_authority,
                 executor_version_override,
+                profile_output,
             )
             .await?;
 
@@ -186,6 +257,21 @@ pub async fn execute_replay_command(
             Some((1u64, 1u64))
         }
 
+        ReplayToolCommand::Fork {
+            checkpoint,
+            listen_address,
+            auto_impersonate,
+        } => {
+            let node = ForkNode::new(
+                &rpc_url.expect("Url must be provided"),
+                checkpoint,
+                auto_impersonate,
+            )
+            .await?;
+            node.serve(listen_address).await?;
+            None
+        }
+
         ReplayToolCommand::Report => {
             let mut lx =
                 LocalExec::new_from_fn_url(&rpc_url.expect("Url must be provided")).await?;
@@ -297,6 +383,88 @@ pub async fn execute_replay_command(
             );
             Some((total_succeeded, total_tx))
         }
+        ReplayToolCommand::ReplayCheckpointsBatch {
+            start,
+            end,
+            max_tasks,
+            report: report_path,
+        } => {
+            assert!(start <= end, "Start checkpoint must be <= end checkpoint");
+            assert!(max_tasks > 0, "Max tasks must be > 0");
+            let checkpoints_per_task = ((end - start + max_tasks) / max_tasks) as usize;
+            info!(
+                "Batch executing checkpoints {} to {} with at most {} tasks and at most {} checkpoints per task",
+                start, end, max_tasks, checkpoints_per_task
+            );
+            // Protocol version tables and object caches are populated once and shared by all tasks
+            let lx = LocalExec::new_from_fn_url(&rpc_url.expect("Url must be provided"))
+                .await?
+                .init_for_execution()
+                .await?;
+
+            let mut handles = vec![];
+            let range: Vec<_> = (start..=end).collect();
+            for (task_count, checkpoints) in range.chunks(checkpoints_per_task).enumerate() {
+                let checkpoints = checkpoints.to_vec();
+                let safety = safety.clone();
+                let mut task_lx = lx.new_sharing_caches();
+                handles.push(tokio::spawn(async move {
+                    info!("Spawning task {task_count} for checkpoints {checkpoints:?}");
+                    let report = task_lx
+                        .replay_checkpoints_for_report(&checkpoints, &safety, use_authority)
+                        .await;
+                    info!(
+                        "Task {task_count}: executed checkpoints {:?} @ {} total transactions, {} succeeded, {} mismatched",
+                        checkpoints, report.total_transactions, report.succeeded, report.mismatches.len()
+                    );
+                    report
+                }));
+            }
+
+            let mut report = ReplayReport::default();
+            for handle in futures::future::join_all(handles).await {
+                match handle {
+                    Ok(task_report) => report.merge(task_report),
+                    Err(e) => error!("Task failed: {:?}", e),
+                }
+            }
+            report.save(&report_path)?;
+            info!(
+                "Executed {} checkpoints @ {}/{} total TXs succeeded, {} effects mismatches, {} failures. Report written to {}",
+                end - start + 1,
+                report.succeeded,
+                report.total_transactions,
+                report.mismatches.len(),
+                report.failures.len(),
+                report_path.display()
+            );
+            Some((report.succeeded, report.total_transactions))
+        }
+        ReplayToolCommand::ReplayOffline {
+            snapshot_dir,
+            snapshot_epoch,
+            db_path,
+            archive_dir,
+            start,
+            end,
+            terminate_early,
+        } => {
+            assert!(start <= end, "Start checkpoint must be <= end checkpoint");
+            let mut lx =
+                LocalExec::new_for_offline(&snapshot_dir, snapshot_epoch, &db_path, &archive_dir)
+                    .await?;
+            let checkpoints: Vec<_> = (start..=end).collect();
+            let (succeeded, total) = lx
+                .execute_all_in_checkpoints(&checkpoints, &safety, terminate_early, use_authority)
+                .await?;
+            info!(
+                "Executed {} checkpoints offline @ {}/{} total TXs succeeded",
+                end - start + 1,
+                succeeded,
+                total
+            );
+            Some((succeeded, total))
+        }
         ReplayToolCommand::ReplayEpoch {
             epoch,
             terminate_early,
@@ -0,0 +1,648 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A fetcher which needs no RPC node. The live object set at the end of an epoch is restored from
+//! a local formal snapshot, and transactions since then are read from a local checkpoint archive.
+//! Since the archive holds no output objects, object versions after the snapshot are reconstructed
+//! by executing every transaction in checkpoint order and applying its outputs, which must match
+//! the on-chain effects.
+
+use crate::data_fetcher::DataFetcher;
+use crate::types::ReplayEngineError;
+use async_trait::async_trait;
+use futures::future::AbortHandle;
+use parking_lot::RwLock;
+use prometheus::Registry;
+use std::collections::{BTreeMap, HashMap};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
+use sui_config::node::ArchiveReaderConfig;
+use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_json_rpc_types::SuiEvent;
+use sui_json_rpc_types::SuiTransactionBlockResponse;
+use sui_snapshot::reader::StateSnapshotReaderV1;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::committee::EpochId;
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::error::SuiResult;
+use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
+use sui_types::object::Object;
+use sui_types::storage::{DeleteKind, ObjectStore};
+use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
+use sui_types::temporary_store::WrittenObjects;
+use sui_types::transaction::SenderSignedData;
+use tracing::info;
+
+const ARCHIVE_DOWNLOAD_CONCURRENCY: Option<NonZeroUsize> = NonZeroUsize::new(5);
+const SNAPSHOT_DOWNLOAD_CONCURRENCY: Option<NonZeroUsize> = NonZeroUsize::new(16);
+
+#[derive(Clone, Debug)]
+pub struct ArchivedTransaction {
+    pub data: SenderSignedData,
+    pub effects: TransactionEffects,
+    pub checkpoint: CheckpointSequenceNumber,
+    /// Index of the transaction in execution order since the snapshot
+    pub position: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EpochInfo {
+    pub epoch_start_timestamp_ms: u64,
+    pub reference_gas_price: u64,
+    pub protocol_version: u64,
+}
+
+#[derive(Default)]
+struct OfflineState {
+    /// Summaries and transactions of the checkpoints loaded from the archive so far
+    checkpoints:
+        BTreeMap<CheckpointSequenceNumber, (CertifiedCheckpointSummary, Vec<TransactionDigest>)>,
+    transactions: HashMap<TransactionDigest, ArchivedTransaction>,
+    /// All loaded transactions since the snapshot, in execution order
+    tx_order: Vec<TransactionDigest>,
+    /// Index in `tx_order` of the next transaction whose outputs must be applied
+    next_to_apply: usize,
+    /// Object versions written by the transactions applied since the snapshot
+    versioned_objects: BTreeMap<(ObjectID, SequenceNumber), Object>,
+    /// Latest versions written since the snapshot, `None` if the object was deleted or wrapped
+    latest_objects: BTreeMap<ObjectID, Option<Object>>,
+    epochs: BTreeMap<EpochId, EpochInfo>,
+}
+
+#[derive(Clone)]
+pub struct OfflineFetcher {
+    /// Live object set restored from the formal snapshot
+    perpetual_db: Arc<AuthorityPerpetualTables>,
+    archive: ArchiveReader,
+    /// First checkpoint after the snapshot, the roll forward starts here
+    first_checkpoint: CheckpointSequenceNumber,
+    state: Arc<RwLock<OfflineState>>,
+}
+
+fn local_store_config(directory: PathBuf) -> ObjectStoreConfig {
+    ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(directory),
+        ..Default::default()
+    }
+}
+
+impl OfflineFetcher {
+    /// Restore the formal snapshot of `snapshot_epoch` found in `snapshot_dir` into `db_path`, or
+    /// reuse `db_path` if a snapshot was already restored there, and read checkpoints from the
+    /// archive in `archive_dir`.
+    pub async fn new(
+        snapshot_dir: &Path,
+        snapshot_epoch: EpochId,
+        db_path: &Path,
+        archive_dir: &Path,
+    ) -> Result<Self, ReplayEngineError> {
+        let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path.join("store"), None));
+        if perpetual_db.database_is_empty()? {
+            info!(
+                "Restoring snapshot of epoch {} into {}",
+                snapshot_epoch,
+                db_path.display()
+            );
+            let mut reader = StateSnapshotReaderV1::new(
+                snapshot_epoch,
+                &local_store_config(snapshot_dir.to_path_buf()),
+                &local_store_config(db_path.join("snapshot")),
+                usize::MAX,
+                SNAPSHOT_DOWNLOAD_CONCURRENCY.expect("Concurrency must be non zero"),
+            )
+            .await?;
+            let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+            reader.read(&perpetual_db, abort_registration).await?;
+        }
+
+        // The snapshot holds the state after the last transaction of its epoch
+        let system_state = get_sui_system_state(perpetual_db.as_ref())?;
+        let epoch = system_state.epoch();
+        if epoch != snapshot_epoch + 1 {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "DB at {} holds the state of epoch {}, not of epoch {}",
+                    db_path.display(),
+                    epoch,
+                    snapshot_epoch + 1
+                ),
+            });
+        }
+
+        let archive = ArchiveReader::new(
+            ArchiveReaderConfig {
+                remote_store_config: local_store_config(archive_dir.to_path_buf()),
+                download_concurrency: ARCHIVE_DOWNLOAD_CONCURRENCY
+                    .expect("Concurrency must be non zero"),
+                use_for_pruning_watermark: false,
+            },
+            &ArchiveReaderMetrics::new(&Registry::new()),
+        )?;
+        archive.sync_manifest_once().await?;
+        let first_checkpoint = Self::first_checkpoint_of_epoch(&archive, epoch).await?;
+        info!(
+            "Restored snapshot of epoch {}, rolling forward from checkpoint {}",
+            snapshot_epoch, first_checkpoint
+        );
+
+        let mut state = OfflineState::default();
+        state.epochs.insert(
+            epoch,
+            EpochInfo {
+                epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+                reference_gas_price: system_state.reference_gas_price(),
+                protocol_version: system_state.protocol_version(),
+            },
+        );
+        Ok(Self {
+            perpetual_db,
+            archive,
+            first_checkpoint,
+            state: Arc::new(RwLock::new(state)),
+        })
+    }
+
+    async fn read_summary(
+        archive: &ArchiveReader,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CertifiedCheckpointSummary, ReplayEngineError> {
+        let mut summary = None;
+        archive
+            .for_each_checkpoint(sequence_number..sequence_number + 1, |s, _| {
+                summary = Some(s);
+                Ok(())
+            })
+            .await?;
+        summary.ok_or_else(|| ReplayEngineError::GeneralError {
+            err: format!("Checkpoint {sequence_number} not found in archive"),
+        })
+    }
+
+    /// Binary search the archive for the first checkpoint of the epoch
+    async fn first_checkpoint_of_epoch(
+        archive: &ArchiveReader,
+        epoch: EpochId,
+    ) -> Result<CheckpointSequenceNumber, ReplayEngineError> {
+        let latest = archive.latest_available_checkpoint().await?;
+        let (mut low, mut high) = (0, latest + 1);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if Self::read_summary(archive, mid).await?.epoch < epoch {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low > latest {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!("Archive has no checkpoint of epoch {epoch}"),
+            });
+        }
+        Ok(low)
+    }
+
+    /// Load all checkpoints up to `sequence_number` from the archive if not loaded yet
+    async fn load_checkpoints(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<(), ReplayEngineError> {
+        let next = self
+            .state
+            .read()
+            .checkpoints
+            .keys()
+            .next_back()
+            .map_or(self.first_checkpoint, |last| last + 1);
+        if sequence_number < next {
+            return Ok(());
+        }
+
+        let mut loaded = vec![];
+        self.archive
+            .for_each_checkpoint(next..sequence_number + 1, |summary, contents| {
+                loaded.push((summary, contents));
+                Ok(())
+            })
+            .await?;
+        if loaded.last().map(|(summary, _)| summary.sequence_number) != Some(sequence_number) {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!("Checkpoint {sequence_number} not found in archive"),
+            });
+        }
+
+        let mut state = self.state.write();
+        for (summary, contents) in loaded {
+            let checkpoint = summary.sequence_number;
+            let mut digests = vec![];
+            for execution_data in contents.iter() {
+                let digest = *execution_data.effects.transaction_digest();
+                digests.push(digest);
+                let position = state.tx_order.len();
+                state.tx_order.push(digest);
+                state.transactions.insert(
+                    digest,
+                    ArchivedTransaction {
+                        data: execution_data.transaction.data().clone(),
+                        effects: execution_data.effects.clone(),
+                        checkpoint,
+                        position,
+                    },
+                );
+            }
+            state.checkpoints.insert(checkpoint, (summary, digests));
+        }
+        Ok(())
+    }
+
+    pub fn transaction(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<ArchivedTransaction, ReplayEngineError> {
+        self.state
+            .read()
+            .transactions
+            .get(tx_digest)
+            .cloned()
+            .ok_or_else(|| ReplayEngineError::GeneralError {
+                err: format!("Transaction {tx_digest} not found in loaded checkpoints"),
+            })
+    }
+
+    pub fn epoch_info(&self, epoch: EpochId) -> Result<EpochInfo, ReplayEngineError> {
+        self.state
+            .read()
+            .epochs
+            .get(&epoch)
+            .copied()
+            .ok_or(ReplayEngineError::EpochNotSupported { epoch })
+    }
+
+    /// The transactions whose outputs must be applied before `tx_digest` can be executed
+    pub fn transactions_to_roll_forward(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<Vec<TransactionDigest>, ReplayEngineError> {
+        let state = self.state.read();
+        let position = state
+            .transactions
+            .get(tx_digest)
+            .map(|tx| tx.position)
+            .ok_or_else(|| ReplayEngineError::GeneralError {
+                err: format!("Transaction {tx_digest} not found in loaded checkpoints"),
+            })?;
+        if position < state.next_to_apply {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "Transaction {tx_digest} was already rolled forward past, replay it with a new executor"
+                ),
+            });
+        }
+        Ok(state.tx_order[state.next_to_apply..position].to_vec())
+    }
+
+    /// Record the outputs of the next transaction to roll forward. The caller must have checked
+    /// that its local effects match the on-chain effects.
+    pub fn apply_outputs(
+        &self,
+        tx_digest: &TransactionDigest,
+        written: &WrittenObjects,
+        deleted: &BTreeMap<ObjectID, (SequenceNumber, DeleteKind)>,
+    ) -> Result<(), ReplayEngineError> {
+        let is_end_of_epoch = {
+            let mut state = self.state.write();
+            if state.tx_order.get(state.next_to_apply) != Some(tx_digest) {
+                return Err(ReplayEngineError::GeneralError {
+                    err: format!("Transaction {tx_digest} is not the next one to roll forward"),
+                });
+            }
+            state.next_to_apply += 1;
+            for (id, (_, object, _)) in written {
+                state
+                    .versioned_objects
+                    .insert((*id, object.version()), object.clone());
+                state.latest_objects.insert(*id, Some(object.clone()));
+            }
+            for id in deleted.keys() {
+                state.latest_objects.insert(*id, None);
+            }
+            let checkpoint = state.transactions[tx_digest].checkpoint;
+            // The epoch change transaction is the last one of the last checkpoint of an epoch
+            let (summary, digests) = &state.checkpoints[&checkpoint];
+            summary.end_of_epoch_data.is_some() && digests.last() == Some(tx_digest)
+        };
+
+        if is_end_of_epoch {
+            let system_state = get_sui_system_state(self)?;
+            info!("Rolled forward into epoch {}", system_state.epoch());
+            self.state.write().epochs.insert(
+                system_state.epoch(),
+                EpochInfo {
+                    epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+                    reference_gas_price: system_state.reference_gas_price(),
+                    protocol_version: system_state.protocol_version(),
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Objects as of the last applied transaction
+impl ObjectStore for OfflineFetcher {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        if let Some(object) = self.state.read().latest_objects.get(object_id) {
+            return Ok(object.clone());
+        }
+        self.perpetual_db.get_object(object_id)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        if let Some(object) = self
+            .state
+            .read()
+            .versioned_objects
+            .get(&(*object_id, version))
+        {
+            return Ok(Some(object.clone()));
+        }
+        self.perpetual_db.get_object_by_key(object_id, version)
+    }
+}
+
+#[async_trait]
+impl DataFetcher for OfflineFetcher {
+    async fn multi_get_versioned(
+        &self,
+        objects: &[(ObjectID, SequenceNumber)],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|(id, version)| {
+                self.get_object_by_key(id, *version)?.ok_or(
+                    ReplayEngineError::ObjectVersionNotFound {
+                        id: *id,
+                        version: *version,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    async fn multi_get_latest(
+        &self,
+        objects: &[ObjectID],
+    ) -> Result<Vec<Object>, ReplayEngineError> {
+        objects
+            .iter()
+            .map(|id| {
+                self.get_object(id)?
+                    .ok_or(ReplayEngineError::ObjectNotExist { id: *id })
+            })
+            .collect()
+    }
+
+    async fn get_checkpoint_txs(
+        &self,
+        id: u64,
+    ) -> Result<Vec<TransactionDigest>, ReplayEngineError> {
+        if id < self.first_checkpoint {
+            return Err(ReplayEngineError::GeneralError {
+                err: format!(
+                    "Checkpoint {} is before the snapshot, the first replayable checkpoint is {}",
+                    id, self.first_checkpoint
+                ),
+            });
+        }
+        self.load_checkpoints(id).await?;
+        Ok(self.state.read().checkpoints[&id].1.clone())
+    }
+
+    async fn get_transaction(
+        &self,
+        _tx_digest: &TransactionDigest,
+    ) -> Result<SuiTransactionBlockResponse, ReplayEngineError> {
+        Err(ReplayEngineError::NotSupportedOffline {
+            operation: "Fetching transaction responses".to_string(),
+        })
+    }
+
+    async fn get_loaded_child_objects(
+        &self,
+        _tx_digest: &TransactionDigest,
+    ) -> Result<Vec<(ObjectID, SequenceNumber)>, ReplayEngineError> {
+        // Not recorded in the archive. Since transactions are rolled forward in order, child
+        // objects are read at their latest version instead.
+        Ok(vec![])
+    }
+
+    async fn get_latest_checkpoint_sequence_number(&self) -> Result<u64, ReplayEngineError> {
+        Ok(self.archive.latest_available_checkpoint().await?)
+    }
+
+    async fn fetch_random_transaction(
+        &self,
+        _checkpoint_id_start: Option<u64>,
+        _checkpoint_id_end: Option<u64>,
+    ) -> Result<TransactionDigest, ReplayEngineError> {
+        Err(ReplayEngineError::NotSupportedOffline {
+            operation: "Fetching a random transaction".to_string(),
+        })
+    }
+
+    async fn get_epoch_start_timestamp_and_rgp(
+        &self,
+        epoch_id: u64,
+    ) -> Result<(u64, u64), ReplayEngineError> {
+        let info = self.epoch_info(epoch_id)?;
+        Ok((info.epoch_start_timestamp_ms, info.reference_gas_price))
+    }
+
+    async fn get_epoch_change_events(
+        &self,
+        _reverse: bool,
+    ) -> Result<Vec<SuiEvent>, ReplayEngineError> {
+        Err(ReplayEngineError::NotSupportedOffline {
+            operation: "Querying epoch change events".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared_crypto::intent::Intent;
+    use sui_types::base_types::{random_object_ref, SuiAddress};
+    use sui_types::committee::Committee;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{CheckpointContents, CheckpointSummary};
+    use sui_types::storage::WriteKind;
+    use sui_types::transaction::TransactionData;
+    use tempfile::TempDir;
+
+    const CHECKPOINT: CheckpointSequenceNumber = 10;
+
+    /// A fetcher with `num_transactions` transactions loaded in `CHECKPOINT`, the first checkpoint
+    /// after an empty snapshot.
+    fn test_fetcher(num_transactions: usize) -> (OfflineFetcher, Vec<TransactionDigest>, TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_dir = dir.path().join("archive");
+        std::fs::create_dir_all(&archive_dir).unwrap();
+        let perpetual_db = Arc::new(AuthorityPerpetualTables::open(
+            &dir.path().join("store"),
+            None,
+        ));
+        let archive = ArchiveReader::new(
+            ArchiveReaderConfig {
+                remote_store_config: local_store_config(archive_dir),
+                download_concurrency: ARCHIVE_DOWNLOAD_CONCURRENCY.unwrap(),
+                use_for_pruning_watermark: false,
+            },
+            &ArchiveReaderMetrics::new(&Registry::new()),
+        )
+        .unwrap();
+
+        let sender = SuiAddress::random_for_testing_only();
+        let mut state = OfflineState::default();
+        let digests: Vec<_> = (0..num_transactions)
+            .map(|position| {
+                let data = TransactionData::new_transfer(
+                    sender,
+                    random_object_ref(),
+                    sender,
+                    random_object_ref(),
+                    1_000_000,
+                    1_000,
+                );
+                let digest = TransactionDigest::random();
+                state.tx_order.push(digest);
+                state.transactions.insert(
+                    digest,
+                    ArchivedTransaction {
+                        data: SenderSignedData::new(data, Intent::sui_transaction(), vec![]),
+                        effects: TransactionEffects::default(),
+                        checkpoint: CHECKPOINT,
+                        position,
+                    },
+                );
+                digest
+            })
+            .collect();
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(vec![]);
+        let summary = CheckpointSummary::new(
+            1,
+            CHECKPOINT,
+            num_transactions as u64,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            0,
+        );
+        state.checkpoints.insert(
+            CHECKPOINT,
+            (
+                CertifiedCheckpointSummary::new_from_keypairs_for_testing(
+                    summary, &keys, &committee,
+                ),
+                digests.clone(),
+            ),
+        );
+
+        let fetcher = OfflineFetcher {
+            perpetual_db,
+            archive,
+            first_checkpoint: CHECKPOINT,
+            state: Arc::new(RwLock::new(state)),
+        };
+        (fetcher, digests, dir)
+    }
+
+    #[tokio::test]
+    async fn test_roll_forward_in_order() {
+        let (fetcher, digests, _dir) = test_fetcher(3);
+        assert_eq!(
+            fetcher.transactions_to_roll_forward(&digests[2]).unwrap(),
+            digests[..2].to_vec()
+        );
+        assert!(fetcher
+            .transactions_to_roll_forward(&TransactionDigest::random())
+            .is_err());
+
+        // Outputs are only applied in execution order.
+        let object = Object::immutable_for_testing();
+        let id = object.id();
+        let written = BTreeMap::from([(
+            id,
+            (
+                object.compute_object_reference(),
+                object.clone(),
+                WriteKind::Create,
+            ),
+        )]);
+        assert!(fetcher
+            .apply_outputs(&digests[1], &written, &BTreeMap::new())
+            .is_err());
+        fetcher
+            .apply_outputs(&digests[0], &written, &BTreeMap::new())
+            .unwrap();
+        assert_eq!(fetcher.get_object(&id).unwrap(), Some(object.clone()));
+        assert_eq!(
+            fetcher.get_object_by_key(&id, object.version()).unwrap(),
+            Some(object.clone())
+        );
+        assert_eq!(
+            fetcher.transactions_to_roll_forward(&digests[2]).unwrap(),
+            vec![digests[1]]
+        );
+
+        let deleted = BTreeMap::from([(id, (object.version().next(), DeleteKind::Normal))]);
+        fetcher
+            .apply_outputs(&digests[1], &BTreeMap::new(), &deleted)
+            .unwrap();
+        assert_eq!(fetcher.get_object(&id).unwrap(), None);
+        // Older versions stay readable for transactions taking them as inputs.
+        assert_eq!(
+            fetcher.get_object_by_key(&id, object.version()).unwrap(),
+            Some(object)
+        );
+        assert!(fetcher
+            .transactions_to_roll_forward(&digests[2])
+            .unwrap()
+            .is_empty());
+        // Transactions already rolled forward past can't be replayed anymore.
+        assert!(fetcher.transactions_to_roll_forward(&digests[0]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_queries_return_errors() {
+        let (fetcher, digests, _dir) = test_fetcher(2);
+        assert_eq!(
+            fetcher.get_checkpoint_txs(CHECKPOINT).await.unwrap(),
+            digests
+        );
+        assert!(fetcher.get_checkpoint_txs(CHECKPOINT - 1).await.is_err());
+
+        assert!(matches!(
+            fetcher.get_transaction(&digests[0]).await,
+            Err(ReplayEngineError::NotSupportedOffline { .. })
+        ));
+        assert!(matches!(
+            fetcher.fetch_random_transaction(None, None).await,
+            Err(ReplayEngineError::NotSupportedOffline { .. })
+        ));
+        assert!(matches!(
+            fetcher.get_epoch_change_events(false).await,
+            Err(ReplayEngineError::NotSupportedOffline { .. })
+        ));
+    }
+}
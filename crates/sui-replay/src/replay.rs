@@ -7,6 +7,8 @@ use crate::data_fetcher::DataFetcher;
 use crate::data_fetcher::Fetchers;
 use crate::data_fetcher::NodeStateDumpFetcher;
 use crate::data_fetcher::RemoteFetcher;
+use crate::offline_fetcher::OfflineFetcher;
+use crate::report::ReplayReport;
 use crate::types::*;
 use futures::executor::block_on;
 use move_binary_format::CompiledModule;
@@ -17,7 +19,7 @@ use move_core_types::resolver::{ModuleResolver, ResourceResolver};
 use prometheus::Registry;
 use similar::{ChangeTag, TextDiff};
 use std::collections::{BTreeMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::Mutex;
 use sui_config::node::ExpensiveSafetyCheckConfig;
@@ -179,6 +181,16 @@ impl Storage {
         }
     }
 
+    /// A new store for another runner, with its own live objects but sharing the package and
+    /// object version caches of this store
+    pub fn share_caches(&self) -> Self {
+        Self {
+            live_objects_store: BTreeMap::new(),
+            package_cache: self.package_cache.clone(),
+            object_version_cache: self.object_version_cache.clone(),
+        }
+    }
+
     pub fn all_objects(&self) -> Vec<Object> {
         self.live_objects_store
             .values()
@@ -226,6 +238,8 @@ pub struct LocalExec {
     // Retry policies due to RPC errors
     pub num_retries_for_timeout: u32,
     pub sleep_period_for_timeout: std::time::Duration,
+    // Directory to write gas profiles of locally executed transactions to, if any
+    pub enable_profiler: Option<PathBuf>,
 }
 
 impl LocalExec {
@@ -305,6 +319,7 @@ impl LocalExec {
         expensive_safety_check_config: ExpensiveSafetyCheckConfig,
         use_authority: bool,
         executor_version_override: Option<i64>,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         async fn inner_exec(
             rpc_url: String,
@@ -312,18 +327,20 @@ impl LocalExec {
             expensive_safety_check_config: ExpensiveSafetyCheckConfig,
             use_authority: bool,
             executor_version_override: Option<i64>,
+            enable_profiler: Option<PathBuf>,
         ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-            LocalExec::new_from_fn_url(&rpc_url)
+            let mut lx = LocalExec::new_from_fn_url(&rpc_url)
                 .await?
                 .init_for_execution()
-                .await?
-                .execute_transaction(
-                    &tx_digest,
-                    expensive_safety_check_config,
-                    use_authority,
-                    executor_version_override,
-                )
-                .await
+                .await?;
+            lx.enable_profiler = enable_profiler;
+            lx.execute_transaction(
+                &tx_digest,
+                expensive_safety_check_config,
+                use_authority,
+                executor_version_override,
+            )
+            .await
         }
 
         if let Some(url) = rpc_url.clone() {
@@ -334,6 +351,7 @@ impl LocalExec {
                 expensive_safety_check_config.clone(),
                 use_authority,
                 executor_version_override,
+                enable_profiler.clone(),
             )
             .await
             {
@@ -354,6 +372,7 @@ impl LocalExec {
                 expensive_safety_check_config.clone(),
                 use_authority,
                 executor_version_override,
+                enable_profiler.clone(),
             )
             .await
             {
@@ -411,9 +430,33 @@ impl LocalExec {
             sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
             diag: Default::default(),
             executor_version_override: None,
+            enable_profiler: None,
         })
     }
 
+    /// A new executor for another task, sharing the remote client, protocol version tables and
+    /// object caches of this one. Must be called after `init_for_execution`
+    pub fn new_sharing_caches(&self) -> Self {
+        let registry = prometheus::Registry::new();
+        Self {
+            client: self.client.clone(),
+            protocol_version_epoch_table: self.protocol_version_epoch_table.clone(),
+            protocol_version_system_package_table: self
+                .protocol_version_system_package_table
+                .clone(),
+            current_protocol_version: self.current_protocol_version,
+            exec_store_events: Arc::new(Mutex::new(Vec::new())),
+            metrics: Arc::new(LimitsMetrics::new(&registry)),
+            storage: self.storage.share_caches(),
+            fetcher: self.fetcher.clone(),
+            num_retries_for_timeout: self.num_retries_for_timeout,
+            sleep_period_for_timeout: self.sleep_period_for_timeout,
+            diag: Default::default(),
+            executor_version_override: self.executor_version_override,
+            enable_profiler: self.enable_profiler.clone(),
+        }
+    }
+
     pub async fn new_for_state_dump(
         path: &str,
         backup_rpc_url: Option<String>,
@@ -452,6 +495,40 @@ impl LocalExec {
             sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
             diag: Default::default(),
             executor_version_override: None,
+            enable_profiler: None,
+        })
+    }
+
+    /// An executor which needs no RPC node, backed by a formal snapshot and a checkpoint archive
+    /// on local disk. See `OfflineFetcher::new`
+    pub async fn new_for_offline(
+        snapshot_dir: &Path,
+        snapshot_epoch: u64,
+        db_path: &Path,
+        archive_dir: &Path,
+    ) -> Result<Self, ReplayEngineError> {
+        // Use a throwaway metrics registry for local execution.
+        let registry = prometheus::Registry::new();
+        let metrics = Arc::new(LimitsMetrics::new(&registry));
+
+        let fetcher =
+            OfflineFetcher::new(snapshot_dir, snapshot_epoch, db_path, archive_dir).await?;
+        let current_protocol_version = fetcher.epoch_info(snapshot_epoch + 1)?.protocol_version;
+
+        Ok(Self {
+            client: None,
+            protocol_version_epoch_table: BTreeMap::new(),
+            protocol_version_system_package_table: BTreeMap::new(),
+            current_protocol_version,
+            exec_store_events: Arc::new(Mutex::new(Vec::new())),
+            metrics,
+            storage: Storage::default(),
+            fetcher: Fetchers::Offline(fetcher),
+            num_retries_for_timeout: RPC_TIMEOUT_ERR_NUM_RETRIES,
+            sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+            diag: Default::default(),
+            executor_version_override: None,
+            enable_profiler: None,
         })
     }
 
@@ -663,6 +740,52 @@ impl LocalExec {
         Ok((succeeded, num as u64))
     }
 
+    /// Replay all the TXs of the checkpoints, recording every success, effects mismatch and
+    /// failure in a report instead of stopping at the first error
+    pub async fn replay_checkpoints_for_report(
+        &mut self,
+        checkpoint_ids: &[u64],
+        expensive_safety_check_config: &ExpensiveSafetyCheckConfig,
+        use_authority: bool,
+    ) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        for checkpoint_id in checkpoint_ids {
+            let txs = match self.get_checkpoint_txs(*checkpoint_id).await {
+                Ok(txs) => txs,
+                Err(e) => {
+                    error!("Error fetching checkpoint {}: {:#?}", checkpoint_id, e);
+                    report.record_failure(*checkpoint_id, None, &e);
+                    continue;
+                }
+            };
+            for tx in txs {
+                match self
+                    .execute_transaction(
+                        &tx,
+                        expensive_safety_check_config.clone(),
+                        use_authority,
+                        None,
+                    )
+                    .await
+                    .and_then(|q| q.check_effects())
+                {
+                    Ok(()) => report.record_success(),
+                    Err(ReplayEngineError::EffectsForked {
+                        digest,
+                        on_chain,
+                        local,
+                        ..
+                    }) => report.record_mismatch(*checkpoint_id, digest, &on_chain, &local),
+                    Err(e) => {
+                        error!("Error executing tx: {},  {:#?}", tx, e);
+                        report.record_failure(*checkpoint_id, Some(tx), &e);
+                    }
+                }
+            }
+        }
+        report
+    }
+
     pub async fn execution_engine_execute_with_tx_info_impl(
         &mut self,
         tx_info: &OnChainTransactionInfo,
@@ -671,7 +794,11 @@ impl LocalExec {
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         let tx_digest = &tx_info.tx_digest;
         // A lot of the logic here isnt designed for genesis
-        if *tx_digest == TransactionDigest::genesis() || tx_info.sender == SuiAddress::ZERO {
+        // Offline replays must execute the other system transactions, their outputs are needed to
+        // roll forward
+        if *tx_digest == TransactionDigest::genesis()
+            || (tx_info.sender == SuiAddress::ZERO && !self.is_offline_replay())
+        {
             // Genesis.
             warn!(
                 "Genesis/system TX replay not supported: {}, skipping transaction",
@@ -711,14 +838,22 @@ impl LocalExec {
             self.to_temporary_store(tx_digest, InputObjects::new(input_objects), protocol_config);
 
         // We could probably cache the executor per protocol config
-        let executor = get_executor(ov, protocol_config, expensive_safety_check_config);
+        let executor = get_executor(
+            ov,
+            protocol_config,
+            expensive_safety_check_config,
+            self.enable_profiler.clone(),
+        );
 
         // All prep done
         let expensive_checks = true;
         let certificate_deny_set = HashSet::new();
-        let res = if let Ok(gas_status) =
+        let gas_status = if tx_info.kind.is_system_tx() {
+            Ok(SuiGasStatus::new_unmetered())
+        } else {
             SuiGasStatus::new(tx_info.gas_budget, tx_info.gas_price, rgp, protocol_config)
-        {
+        };
+        let res = if let Ok(gas_status) = gas_status {
             executor.execute_transaction_to_effects(
                 protocol_config,
                 metrics,
@@ -766,10 +901,14 @@ impl LocalExec {
         );
         }
 
-        let tx_info = if self.is_remote_replay() {
-            self.resolve_tx_components(tx_digest).await?
-        } else {
-            self.resolve_tx_components_from_dump(tx_digest).await?
+        let tx_info = match &self.fetcher {
+            Fetchers::Remote(_) => self.resolve_tx_components(tx_digest).await?,
+            Fetchers::NodeStateDump(_) => self.resolve_tx_components_from_dump(tx_digest).await?,
+            Fetchers::Offline(_) => {
+                return self
+                    .offline_execute_impl(tx_digest, expensive_safety_check_config)
+                    .await
+            }
         };
         self.execution_engine_execute_with_tx_info_impl(
             &tx_info,
@@ -779,6 +918,74 @@ impl LocalExec {
         .await
     }
 
+    /// Offline replays reconstruct object versions by executing every transaction since the
+    /// snapshot in order, so first roll forward all the transactions preceding this one
+    async fn offline_execute_impl(
+        &mut self,
+        tx_digest: &TransactionDigest,
+        expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    ) -> Result<ExecutionSandboxState, ReplayEngineError> {
+        let to_roll_forward = self
+            .fetcher
+            .as_offline()
+            .transactions_to_roll_forward(tx_digest)?;
+        if !to_roll_forward.is_empty() {
+            info!(
+                "Rolling forward {} transactions before {}",
+                to_roll_forward.len(),
+                tx_digest
+            );
+        }
+        for digest in to_roll_forward {
+            // Later transactions cannot be replayed if the state forked
+            self.offline_execute_and_apply(&digest, expensive_safety_check_config.clone())
+                .await?
+                .check_effects()?;
+        }
+        self.offline_execute_and_apply(tx_digest, expensive_safety_check_config)
+            .await
+    }
+
+    /// Execute a transaction of an offline replay and apply its outputs if its effects match the
+    /// on-chain effects
+    async fn offline_execute_and_apply(
+        &mut self,
+        tx_digest: &TransactionDigest,
+        expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    ) -> Result<ExecutionSandboxState, ReplayEngineError> {
+        let tx_info = self.resolve_tx_components_offline(tx_digest).await?;
+        let sandbox_state = self
+            .execution_engine_execute_with_tx_info_impl(
+                &tx_info,
+                None,
+                expensive_safety_check_config,
+            )
+            .await?;
+        if sandbox_state.transaction_info.effects != sandbox_state.local_exec_effects {
+            return Ok(sandbox_state);
+        }
+        if let Some(store) = &sandbox_state.local_exec_temporary_store {
+            self.fetcher
+                .as_offline()
+                .apply_outputs(tx_digest, &store.written, &store.deleted)?;
+            // Objects read by later transactions must be at their new versions
+            for (id, (_, object, _)) in store.written.iter() {
+                self.storage.live_objects_store.insert(*id, object.clone());
+                if object.is_package() {
+                    self.storage
+                        .package_cache
+                        .lock()
+                        .expect("Cannot lock")
+                        .insert(*id, object.clone());
+                }
+            }
+            for id in store.deleted.keys() {
+                self.storage.live_objects_store.remove(id);
+            }
+        }
+        Ok(sandbox_state)
+    }
+
     /// Executes a transaction with the state specified in `pre_run_sandbox`
     /// This is useful for executing a transaction with a specific state
     /// However if the state in invalid, the behavior is undefined. Use wisely
@@ -1028,6 +1235,10 @@ impl LocalExec {
         matches!(self.fetcher, Fetchers::Remote(_))
     }
 
+    pub fn is_offline_replay(&self) -> bool {
+        matches!(self.fetcher, Fetchers::Offline(_))
+    }
+
     /// Must be called after `populate_protocol_version_tables`
     pub fn system_package_versions_for_epoch(
         &self,
@@ -1049,6 +1260,15 @@ impl LocalExec {
                 .map(|w| w.compute_object_reference())
                 .map(|q| (q.0, q.1))
                 .collect()),
+
+            // Transactions are rolled forward in order, so the latest system packages are the
+            // ones of the epoch being replayed
+            Fetchers::Offline(f) => Self::system_package_ids(self.current_protocol_version)
+                .iter()
+                .filter_map(|id| f.get_object(id).transpose())
+                .map(|o| o.map(|o| (o.id(), o.version())))
+                .collect::<SuiResult<_>>()
+                .map_err(ReplayEngineError::from),
         }
     }
 
@@ -1465,6 +1685,56 @@ impl LocalExec {
         })
     }
 
+    async fn resolve_tx_components_offline(
+        &self,
+        tx_digest: &TransactionDigest,
+    ) -> Result<OnChainTransactionInfo, ReplayEngineError> {
+        assert!(self.is_offline_replay());
+
+        let offline = self.fetcher.as_offline();
+        let archived = offline.transaction(tx_digest)?;
+        let orig_tx = archived.data;
+        let sender = orig_tx.transaction_data().sender();
+        let effects = SuiTransactionBlockEffects::try_from(archived.effects)?;
+
+        let input_objs = orig_tx
+            .transaction_data()
+            .input_objects()
+            .map_err(|e| ReplayEngineError::UserInputError { err: e })?;
+        let tx_kind_orig = orig_tx.transaction_data().kind();
+
+        // Fetch the objects at the version right before the execution of this TX
+        let modified_at_versions: Vec<(ObjectID, SequenceNumber)> = effects.modified_at_versions();
+
+        let shared_obj_refs = effects.shared_objects();
+        let gas_data = orig_tx.transaction_data().gas_data();
+        let gas_object_refs: Vec<_> = gas_data.clone().payment.into_iter().collect();
+
+        let epoch_id = effects.executed_epoch();
+        let epoch_info = offline.epoch_info(epoch_id)?;
+        let protocol_config =
+            ProtocolConfig::get_for_version(epoch_info.protocol_version.into(), Chain::Unknown);
+
+        Ok(OnChainTransactionInfo {
+            kind: tx_kind_orig.clone(),
+            sender,
+            modified_at_versions,
+            input_objects: input_objs,
+            shared_object_refs: shared_obj_refs.iter().map(|r| r.to_object_ref()).collect(),
+            gas: gas_object_refs,
+            gas_budget: gas_data.budget,
+            gas_price: gas_data.price,
+            executed_epoch: epoch_id,
+            dependencies: effects.dependencies().to_vec(),
+            effects,
+            protocol_config,
+            tx_digest: *tx_digest,
+            epoch_start_timestamp: epoch_info.epoch_start_timestamp_ms,
+            sender_signed_data: orig_tx.clone(),
+            reference_gas_price: epoch_info.reference_gas_price,
+        })
+    }
+
     async fn resolve_download_input_objects(
         &mut self,
         tx_info: &OnChainTransactionInfo,
@@ -1591,8 +1861,11 @@ impl LocalExec {
         self.multi_download_and_store(&shared_refs).await?;
 
         // Download gas (although this should already be in cache from modified at versions?)
-        let gas_refs: Vec<_> = tx_info.gas.iter().map(|w| (w.0, w.1)).collect();
-        self.multi_download_and_store(&gas_refs).await?;
+        // System transactions have a placeholder gas object
+        if !tx_info.kind.is_system_tx() {
+            let gas_refs: Vec<_> = tx_info.gas.iter().map(|w| (w.0, w.1)).collect();
+            self.multi_download_and_store(&gas_refs).await?;
+        }
 
         // Fetch the input objects we know from the raw transaction
         let input_objs = self.resolve_download_input_objects(tx_info).await?;
@@ -1647,7 +1920,14 @@ impl ChildObjectResolver for LocalExec {
             child: &ObjectID,
             child_version_upper_bound: SequenceNumber,
         ) -> SuiResult<Option<Object>> {
-            let child_object = match self_.get_object(child)? {
+            // Offline replays do not know the loaded child objects up front, but roll forward in
+            // order so the latest version is the one this transaction read
+            let child_object = if self_.is_offline_replay() {
+                self_.fetcher.as_offline().get_object(child)?
+            } else {
+                self_.get_object(child)?
+            };
+            let child_object = match child_object {
                 None => return Ok(None),
                 Some(o) => o,
             };
@@ -1884,6 +2164,7 @@ pub fn get_executor(
     executor_version_override: Option<i64>,
     protocol_config: &ProtocolConfig,
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    enable_profiler: Option<PathBuf>,
 ) -> Arc<dyn Executor + Send + Sync> {
     let protocol_config = executor_version_override
         .map(|q| {
@@ -1904,6 +2185,7 @@ pub fn get_executor(
         &protocol_config,
         expensive_safety_check_config.enable_move_vm_paranoid_checks(),
         silent,
+        enable_profiler,
     )
     .expect("Creating an executor should not fail here")
 }
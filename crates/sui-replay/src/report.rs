@@ -0,0 +1,243 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::Path;
+use sui_json_rpc_types::SuiTransactionBlockEffects;
+use sui_types::digests::TransactionDigest;
+
+use crate::types::ReplayEngineError;
+
+/// Machine readable outcome of a batch replay of a checkpoint range
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayReport {
+    pub total_transactions: u64,
+    pub succeeded: u64,
+    /// Transactions whose local effects differ from the on-chain effects
+    pub mismatches: Vec<EffectsMismatch>,
+    /// Transactions or checkpoints which could not be replayed at all
+    pub failures: Vec<ReplayFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectsMismatch {
+    pub checkpoint: u64,
+    pub tx_digest: TransactionDigest,
+    pub diff: Vec<FieldDiff>,
+}
+
+/// A field of the JSON representation of the effects which differs.
+/// A missing value means the field is only present on the other side.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiff {
+    /// JSON pointer of the field, e.g. `/gasUsed/computationCost`
+    pub path: String,
+    pub on_chain: Option<Value>,
+    pub local: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayFailure {
+    pub checkpoint: u64,
+    /// Not set if the transactions of the checkpoint could not be fetched
+    pub tx_digest: Option<TransactionDigest>,
+    pub error: String,
+}
+
+impl ReplayReport {
+    pub fn record_success(&mut self) {
+        self.total_transactions += 1;
+        self.succeeded += 1;
+    }
+
+    pub fn record_mismatch(
+        &mut self,
+        checkpoint: u64,
+        tx_digest: TransactionDigest,
+        on_chain: &SuiTransactionBlockEffects,
+        local: &SuiTransactionBlockEffects,
+    ) {
+        self.total_transactions += 1;
+        let diff = match (serde_json::to_value(on_chain), serde_json::to_value(local)) {
+            (Ok(on_chain), Ok(local)) => diff_json(&on_chain, &local),
+            (on_chain, local) => vec![FieldDiff {
+                path: String::new(),
+                on_chain: on_chain.ok(),
+                local: local.ok(),
+            }],
+        };
+        self.mismatches.push(EffectsMismatch {
+            checkpoint,
+            tx_digest,
+            diff,
+        });
+    }
+
+    pub fn record_failure(
+        &mut self,
+        checkpoint: u64,
+        tx_digest: Option<TransactionDigest>,
+        error: &ReplayEngineError,
+    ) {
+        if tx_digest.is_some() {
+            self.total_transactions += 1;
+        }
+        self.failures.push(ReplayFailure {
+            checkpoint,
+            tx_digest,
+            error: error.to_string(),
+        });
+    }
+
+    /// Merge the report of another range into this one, keeping entries sorted by checkpoint
+    pub fn merge(&mut self, other: ReplayReport) {
+        self.total_transactions += other.total_transactions;
+        self.succeeded += other.succeeded;
+        self.mismatches.extend(other.mismatches);
+        self.mismatches.sort_by_key(|m| m.checkpoint);
+        self.failures.extend(other.failures);
+        self.failures.sort_by_key(|f| f.checkpoint);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayEngineError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| ReplayEngineError::GeneralError { err: e.to_string() })?;
+        std::fs::write(path, json).map_err(|e| ReplayEngineError::GeneralError {
+            err: format!("Unable to write report to {}: {e}", path.display()),
+        })
+    }
+}
+
+/// Returns the leaf fields which differ between two JSON values
+pub fn diff_json(on_chain: &Value, local: &Value) -> Vec<FieldDiff> {
+    let mut diffs = vec![];
+    diff_json_at(String::new(), Some(on_chain), Some(local), &mut diffs);
+    diffs
+}
+
+fn diff_json_at(
+    path: String,
+    on_chain: Option<&Value>,
+    local: Option<&Value>,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    match (on_chain, local) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let keys: BTreeSet<_> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let path = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                diff_json_at(path, a.get(key), b.get(key), diffs);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_json_at(format!("{path}/{i}"), a.get(i), b.get(i), diffs);
+            }
+        }
+        (a, b) if a != b => diffs.push(FieldDiff {
+            path,
+            on_chain: a.cloned(),
+            local: b.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(diffs: &[FieldDiff]) -> Vec<&str> {
+        diffs.iter().map(|diff| diff.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_diff_json_equal_values() {
+        let value = json!({"status": {"status": "success"}, "created": [{"version": 1}]});
+        assert!(diff_json(&value, &value.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_json_reports_leaf_fields() {
+        let on_chain = json!({
+            "gasUsed": {"computationCost": "100", "storageCost": "10"},
+            "status": {"status": "success"},
+            "sharedObjects": [],
+        });
+        let local = json!({
+            "gasUsed": {"computationCost": "120", "storageCost": "10"},
+            "status": {"status": "failure", "error": "InsufficientGas"},
+        });
+        let diffs = diff_json(&on_chain, &local);
+        assert_eq!(
+            paths(&diffs),
+            vec![
+                "/gasUsed/computationCost",
+                "/sharedObjects",
+                "/status/error",
+                "/status/status"
+            ]
+        );
+        assert_eq!(diffs[0].on_chain, Some(json!("100")));
+        assert_eq!(diffs[0].local, Some(json!("120")));
+        // Fields present on one side only have no value on the other.
+        assert_eq!(diffs[1].local, None);
+        assert_eq!(diffs[2].on_chain, None);
+    }
+
+    #[test]
+    fn test_diff_json_arrays_and_escaping() {
+        let on_chain = json!({"a/b": [1, 2], "c~d": [{"x": 1}]});
+        let local = json!({"a/b": [1, 3, 4], "c~d": [{"x": 2}]});
+        let diffs = diff_json(&on_chain, &local);
+        assert_eq!(paths(&diffs), vec!["/a~1b/1", "/a~1b/2", "/c~0d/0/x"]);
+        assert_eq!(diffs[1].on_chain, None);
+        assert_eq!(diffs[1].local, Some(json!(4)));
+
+        // Values of different types are reported at the parent path.
+        let diffs = diff_json(&json!({"a": [1]}), &json!({"a": {"0": 1}}));
+        assert_eq!(paths(&diffs), vec!["/a"]);
+        let diffs = diff_json(&json!(1), &json!(2));
+        assert_eq!(paths(&diffs), vec![""]);
+    }
+
+    #[test]
+    fn test_merge_keeps_entries_sorted() {
+        let mut report = ReplayReport::default();
+        report.record_success();
+        report.record_failure(
+            5,
+            Some(TransactionDigest::random()),
+            &ReplayEngineError::GeneralError {
+                err: "failed".to_string(),
+            },
+        );
+        let mut other = ReplayReport::default();
+        other.record_success();
+        other.record_failure(
+            2,
+            None,
+            &ReplayEngineError::GeneralError {
+                err: "failed".to_string(),
+            },
+        );
+        report.merge(other);
+        assert_eq!(report.total_transactions, 3);
+        assert_eq!(report.succeeded, 2);
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|f| f.checkpoint)
+                .collect::<Vec<_>>(),
+            vec![2, 5]
+        );
+    }
+}
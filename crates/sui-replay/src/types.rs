@@ -178,6 +178,9 @@ pub enum ReplayEngineError {
         cfgs
     )]
     UnableToExecuteWithNetworkConfigs { cfgs: ReplayableNetworkConfigSet },
+
+    #[error("{operation} is not supported by offline replay, it needs an RPC node")]
+    NotSupportedOffline { operation: String },
 }
 
 impl From<SuiObjectResponseError> for ReplayEngineError {
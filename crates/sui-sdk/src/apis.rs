@@ -4,7 +4,7 @@
 use std::collections::BTreeMap;
 use std::future;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use fastcrypto::encoding::Base64;
 use futures::stream;
@@ -19,16 +19,17 @@ use sui_json_rpc::api::{
     CoinReadApiClient, IndexerApiClient, MoveUtilsClient, ReadApiClient, WriteApiClient,
 };
 use sui_json_rpc_types::{
-    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DevInspectResults,
-    DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter, EventPage, ObjectsPage,
-    ProtocolConfigResponse, SuiCoinMetadata, SuiCommittee, SuiEvent, SuiGetPastObjectRequest,
-    SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiPastObjectResponse, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
-    SuiTransactionBlockResponseQuery, TransactionBlocksPage,
+    Balance, Checkpoint, CheckpointId, Coin, CoinPage, DelegatedStake, DevInspectArgs,
+    DevInspectResults, DryRunArgs, DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter,
+    EventPage, ObjectsPage, ProtocolConfigResponse, SuiCoinMetadata, SuiCommittee, SuiEvent,
+    SuiGetPastObjectRequest, SuiMoveNormalizedModule, SuiObjectDataOptions, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiPastObjectResponse, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
 };
 use sui_json_rpc_types::{CheckpointPage, SuiLoadedChildObjectsResponse};
 use sui_types::balance::Supply;
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::error::SuiObjectResponseError;
 use sui_types::event::EventID;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
@@ -37,6 +38,7 @@ use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary
 use sui_types::transaction::{Transaction, TransactionData, TransactionKind};
 
 const WAIT_FOR_LOCAL_EXECUTION_RETRY_COUNT: u8 = 3;
+const WAIT_FOR_FINALITY_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Debug)]
 pub struct ReadApi {
@@ -88,6 +90,20 @@ impl ReadApi {
             .await?)
     }
 
+    /// Return the latest version of an object at or before `version`
+    pub async fn try_get_parsed_past_object_lt_or_eq_version(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: SuiObjectDataOptions,
+    ) -> SuiRpcResult<SuiPastObjectResponse> {
+        Ok(self
+            .api
+            .http
+            .try_get_past_object_lt_or_eq_version(object_id, version, Some(options))
+            .await?)
+    }
+
     pub async fn try_multi_get_parsed_past_object(
         &self,
         past_objects: Vec<SuiGetPastObjectRequest>,
@@ -136,6 +152,66 @@ impl ReadApi {
             .await?)
     }
 
+    /// Wait until a transaction is final, i.e. included in a checkpoint, and return it. The
+    /// transaction does not need to be known by this fullnode yet, e.g. if it was executed
+    /// through another one.
+    pub async fn wait_for_transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+        options: SuiTransactionBlockResponseOptions,
+        timeout: Duration,
+    ) -> SuiRpcResult<SuiTransactionBlockResponse> {
+        let start = Instant::now();
+        loop {
+            if let Ok(response) = self
+                .get_transaction_with_options(digest, options.clone())
+                .await
+            {
+                if response.checkpoint.is_some() {
+                    return Ok(response);
+                }
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::FailToConfirmTransactionStatus(
+                    digest,
+                    start.elapsed().as_secs(),
+                ));
+            }
+            tokio::time::sleep(WAIT_FOR_FINALITY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Wait until an object is modified past `version`, or deleted, and return it. An object
+    /// that does not exist yet is waited for until it is created.
+    pub async fn wait_for_object_change(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: SuiObjectDataOptions,
+        timeout: Duration,
+    ) -> SuiRpcResult<SuiObjectResponse> {
+        let start = Instant::now();
+        loop {
+            let response = self
+                .get_object_with_options(object_id, options.clone())
+                .await?;
+            let changed = match &response.data {
+                Some(data) => data.version > version,
+                None => matches!(response.error, Some(SuiObjectResponseError::Deleted { .. })),
+            };
+            if changed {
+                return Ok(response);
+            }
+            if start.elapsed() >= timeout {
+                return Err(Error::ObjectNotModified(
+                    object_id,
+                    start.elapsed().as_secs(),
+                ));
+            }
+            tokio::time::sleep(WAIT_FOR_FINALITY_POLL_INTERVAL).await;
+        }
+    }
+
     pub async fn multi_get_transactions_with_options(
         &self,
         digests: Vec<TransactionDigest>,
@@ -258,7 +334,24 @@ impl ReadApi {
         Ok(self
             .api
             .http
-            .dry_run_transaction_block(Base64::from_bytes(&bcs::to_bytes(&tx)?))
+            .dry_run_transaction_block(Base64::from_bytes(&bcs::to_bytes(&tx)?), None)
+            .await?)
+    }
+
+    /// Same as `dry_run_transaction_block`, with additional arguments, e.g. to return a gas
+    /// profile of the execution
+    pub async fn dry_run_transaction_block_with_args(
+        &self,
+        tx: TransactionData,
+        additional_args: DryRunArgs,
+    ) -> SuiRpcResult<DryRunTransactionBlockResponse> {
+        Ok(self
+            .api
+            .http
+            .dry_run_transaction_block(
+                Base64::from_bytes(&bcs::to_bytes(&tx)?),
+                Some(additional_args),
+            )
             .await?)
     }
 
@@ -277,6 +370,30 @@ impl ReadApi {
                 Base64::from_bytes(&bcs::to_bytes(&tx)?),
                 gas_price,
                 epoch,
+                None,
+            )
+            .await?)
+    }
+
+    /// Same as `dev_inspect_transaction_block`, with additional arguments, e.g. to return a
+    /// trace of the execution
+    pub async fn dev_inspect_transaction_block_with_args(
+        &self,
+        sender_address: SuiAddress,
+        tx: TransactionKind,
+        gas_price: Option<BigInt<u64>>,
+        epoch: Option<BigInt<u64>>,
+        additional_args: DevInspectArgs,
+    ) -> SuiRpcResult<DevInspectResults> {
+        Ok(self
+            .api
+            .http
+            .dev_inspect_transaction_block(
+                sender_address,
+                Base64::from_bytes(&bcs::to_bytes(&tx)?),
+                gas_price,
+                epoch,
+                Some(additional_args),
             )
             .await?)
     }
@@ -429,7 +546,7 @@ impl EventApi {
     ) -> SuiRpcResult<impl Stream<Item = SuiRpcResult<SuiEvent>>> {
         match &self.api.ws {
             Some(c) => {
-                let subscription: Subscription<SuiEvent> = c.subscribe_event(filter).await?;
+                let subscription: Subscription<SuiEvent> = c.subscribe_event(filter, None).await?;
                 Ok(subscription.map(|item| Ok(item?)))
             }
             _ => Err(Error::Subscription(
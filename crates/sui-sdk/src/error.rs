@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use sui_types::base_types::{SuiAddress, TransactionDigest};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::error::UserInputError;
 use thiserror::Error;
 
@@ -21,6 +21,8 @@ pub enum Error {
     TransactionConfirmationError(TransactionDigest, jsonrpsee::core::Error),
     #[error("Failed to confirm tx status for {0:?} within {1} seconds.")]
     FailToConfirmTransactionStatus(TransactionDigest, u64),
+    #[error("Object {0} was not modified within {1} seconds.")]
+    ObjectNotModified(ObjectID, u64),
     #[error("Data error: {0}")]
     DataError(String),
     #[error("Client/Server api version mismatch, client api version : {client_version}, server api version : {server_version}")]
@@ -0,0 +1,277 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Failover across multiple fullnode RPC endpoints.
+//!
+//! `FailoverClient` is a JSON-RPC client over the HTTP clients of several fullnodes, so all the
+//! APIs of `SuiClient` use it unchanged. Endpoints are health-checked in the background: an
+//! endpoint which can't be reached, or whose latest checkpoint lags behind the most up to date
+//! endpoint by more than the configured lag, is unhealthy. Reads are load balanced across the
+//! healthy endpoints, writes are routed to the endpoint with the latest checkpoint, and requests
+//! failing with a transport error fail over to the next endpoint, unhealthy endpoints being tried
+//! last. Reads are retried with exponential backoff once all endpoints failed. Batches, which may
+//! hold writes, fail over like writes and are not retried.
+
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use jsonrpsee::core::client::{BatchResponse, ClientT};
+use jsonrpsee::core::params::BatchRequestBuilder;
+use jsonrpsee::core::traits::ToRpcParams;
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::http_client::HttpClient;
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use sui_json_rpc::api::ReadApiClient;
+
+/// Methods which are not idempotent reads. Executing a signed transaction again returns the
+/// effects of its first execution, so they can fail over, but they are not retried.
+const WRITE_METHODS: &[&str] = &["sui_executeTransactionBlock"];
+
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub healthy: bool,
+    pub latest_checkpoint: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FailoverConfig {
+    pub max_retries: usize,
+    pub retry_backoff: Duration,
+    pub health_check_interval: Duration,
+    pub max_checkpoint_lag: u64,
+}
+
+struct Endpoint {
+    url: String,
+    http: HttpClient,
+    healthy: AtomicBool,
+    latest_checkpoint: AtomicU64,
+}
+
+pub(crate) struct FailoverClient {
+    endpoints: Arc<Vec<Endpoint>>,
+    next_read: AtomicUsize,
+    config: FailoverConfig,
+    health_check: Option<JoinHandle<()>>,
+}
+
+impl FailoverClient {
+    pub async fn new(endpoints: Vec<(String, HttpClient)>, config: FailoverConfig) -> Self {
+        let endpoints = Arc::new(
+            endpoints
+                .into_iter()
+                .map(|(url, http)| Endpoint {
+                    url,
+                    http,
+                    healthy: AtomicBool::new(true),
+                    latest_checkpoint: AtomicU64::new(0),
+                })
+                .collect::<Vec<_>>(),
+        );
+        // A single endpoint is always used, whatever its health.
+        let health_check = if endpoints.len() > 1 {
+            check_health(&endpoints, config.max_checkpoint_lag).await;
+            Some(tokio::spawn(health_check_loop(
+                Arc::downgrade(&endpoints),
+                config,
+            )))
+        } else {
+            None
+        };
+        Self {
+            endpoints,
+            next_read: AtomicUsize::new(0),
+            config,
+            health_check,
+        }
+    }
+
+    pub fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| EndpointStatus {
+                url: endpoint.url.clone(),
+                healthy: endpoint.healthy.load(Ordering::Relaxed),
+                latest_checkpoint: endpoint.latest_checkpoint.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// The order in which endpoints are tried for a request.
+    fn candidates(&self, write: bool) -> Vec<&Endpoint> {
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = self
+            .endpoints
+            .iter()
+            .partition(|endpoint| endpoint.healthy.load(Ordering::Relaxed));
+        if write {
+            healthy.sort_by_key(|endpoint| {
+                std::cmp::Reverse(endpoint.latest_checkpoint.load(Ordering::Relaxed))
+            });
+        } else if !healthy.is_empty() {
+            let first = self.next_read.fetch_add(1, Ordering::Relaxed) % healthy.len();
+            healthy.rotate_left(first);
+        }
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    async fn call<'a, T, F, Fut>(
+        &'a self,
+        method: &str,
+        write: bool,
+        request: F,
+    ) -> Result<T, RpcError>
+    where
+        F: Fn(&'a HttpClient) -> Fut,
+        Fut: Future<Output = Result<T, RpcError>>,
+    {
+        let retries = if write { 0 } else { self.config.max_retries };
+        let mut backoff = self.config.retry_backoff;
+        let mut last_error = None;
+        for attempt in 0..=retries {
+            if attempt > 0 {
+                debug!("Retrying {method} in {backoff:?}, attempt {attempt}");
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            for endpoint in self.candidates(write) {
+                match request(&endpoint.http).await {
+                    Err(e) if is_transport_error(&e) => {
+                        warn!("Request {method} to {} failed: {e}", endpoint.url);
+                        if self.endpoints.len() > 1 {
+                            endpoint.healthy.store(false, Ordering::Relaxed);
+                        }
+                        last_error = Some(e);
+                    }
+                    result => return result,
+                }
+            }
+        }
+        Err(last_error.expect("There is at least one endpoint"))
+    }
+}
+
+impl Drop for FailoverClient {
+    fn drop(&mut self) {
+        if let Some(health_check) = &self.health_check {
+            health_check.abort();
+        }
+    }
+}
+
+impl Debug for FailoverClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.endpoints.iter().map(|endpoint| &endpoint.url))
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ClientT for FailoverClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), RpcError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams(params.to_rpc_params()?);
+        let write = WRITE_METHODS.contains(&method);
+        self.call(method, write, |http| {
+            http.notification(method, params.clone())
+        })
+        .await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, RpcError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams(params.to_rpc_params()?);
+        let write = WRITE_METHODS.contains(&method);
+        self.call(method, write, |http| http.request(method, params.clone()))
+            .await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, RpcError>
+    where
+        R: DeserializeOwned + Debug + 'a,
+    {
+        // The methods of a batch can't be inspected, it's handled as a write.
+        self.call("batch", true, |http| http.batch_request(batch.clone()))
+            .await
+    }
+}
+
+/// Parameters already serialized, to be sent again to each endpoint tried.
+#[derive(Clone)]
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, serde_json::Error> {
+        Ok(self.0)
+    }
+}
+
+fn is_transport_error(error: &RpcError) -> bool {
+    matches!(
+        error,
+        RpcError::Transport(_) | RpcError::RequestTimeout | RpcError::RestartNeeded(_)
+    )
+}
+
+async fn health_check_loop(endpoints: Weak<Vec<Endpoint>>, config: FailoverConfig) {
+    let mut interval = tokio::time::interval(config.health_check_interval);
+    // The first tick completes immediately, and endpoints were just checked.
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let Some(endpoints) = endpoints.upgrade() else {
+            return;
+        };
+        check_health(&endpoints, config.max_checkpoint_lag).await;
+    }
+}
+
+async fn check_health(endpoints: &[Endpoint], max_checkpoint_lag: u64) {
+    let checkpoints = join_all(
+        endpoints
+            .iter()
+            .map(|endpoint| endpoint.http.get_latest_checkpoint_sequence_number()),
+    )
+    .await;
+    let latest = checkpoints
+        .iter()
+        .filter_map(|checkpoint| checkpoint.as_ref().ok().map(|c| **c))
+        .max()
+        .unwrap_or_default();
+    for (endpoint, checkpoint) in endpoints.iter().zip(checkpoints) {
+        let healthy = match checkpoint {
+            Ok(checkpoint) => {
+                endpoint
+                    .latest_checkpoint
+                    .store(*checkpoint, Ordering::Relaxed);
+                latest - *checkpoint <= max_checkpoint_lag
+            }
+            Err(e) => {
+                warn!("Health check of {} failed: {e}", endpoint.url);
+                false
+            }
+        };
+        if healthy != endpoint.healthy.swap(healthy, Ordering::Relaxed) {
+            debug!("Endpoint {} is now healthy: {healthy}", endpoint.url);
+        }
+    }
+}
@@ -8,7 +8,7 @@ use std::time::Duration;
 
 use async_trait::async_trait;
 use jsonrpsee::core::client::ClientT;
-use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
+use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClientBuilder};
 use jsonrpsee::rpc_params;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 use serde_json::Value;
@@ -20,26 +20,33 @@ use sui_json_rpc::{
 };
 pub use sui_json_rpc_types as rpc_types;
 use sui_json_rpc_types::{
-    ObjectsPage, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
-    SuiObjectResponseQuery,
+    DryRunTransactionBlockResponse, ObjectsPage, SuiObjectDataFilter, SuiObjectDataOptions,
+    SuiObjectResponse, SuiObjectResponseQuery, SuiProtocolConfigValue,
 };
+pub use sui_transaction_builder as transaction_builder;
 use sui_transaction_builder::{DataReader, TransactionBuilder};
 pub use sui_types as types;
 use sui_types::base_types::{ObjectID, ObjectInfo, SuiAddress};
+use sui_types::transaction::TransactionData;
 
 use crate::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriverApi, ReadApi};
 use crate::error::{Error, SuiRpcResult};
+use crate::failover::{EndpointStatus, FailoverClient, FailoverConfig};
 
 pub mod apis;
 pub mod error;
+pub mod failover;
 pub mod sui_client_config;
 pub mod wallet_context;
 pub const SUI_COIN_TYPE: &str = "0x2::sui::SUI";
+const DEFAULT_FAILOVER_MAX_RETRIES: usize = 3;
 
 pub struct SuiClientBuilder {
     request_timeout: Duration,
     max_concurrent_requests: usize,
     ws_url: Option<String>,
+    max_retries: Option<usize>,
+    failover: FailoverConfig,
 }
 
 impl Default for SuiClientBuilder {
@@ -48,6 +55,13 @@ impl Default for SuiClientBuilder {
             request_timeout: Duration::from_secs(60),
             max_concurrent_requests: 256,
             ws_url: None,
+            max_retries: None,
+            failover: FailoverConfig {
+                max_retries: 0,
+                retry_backoff: Duration::from_millis(100),
+                health_check_interval: Duration::from_secs(10),
+                max_checkpoint_lag: 100,
+            },
         }
     }
 }
@@ -68,7 +82,50 @@ impl SuiClientBuilder {
         self
     }
 
+    /// Number of times a read failing on all endpoints is retried, with exponential backoff.
+    /// Defaults to 3 for clients built with [Self::build_with_failover], and 0 otherwise.
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    /// Delay before the first retry of a read, doubled at each retry.
+    pub fn retry_backoff(mut self, retry_backoff: Duration) -> Self {
+        self.failover.retry_backoff = retry_backoff;
+        self
+    }
+
+    /// Interval between health checks of the endpoints, when there are several.
+    pub fn health_check_interval(mut self, health_check_interval: Duration) -> Self {
+        self.failover.health_check_interval = health_check_interval;
+        self
+    }
+
+    /// Number of checkpoints an endpoint can lag behind the most up to date one and be healthy.
+    pub fn max_checkpoint_lag(mut self, max_checkpoint_lag: u64) -> Self {
+        self.failover.max_checkpoint_lag = max_checkpoint_lag;
+        self
+    }
+
     pub async fn build(self, http: impl AsRef<str>) -> SuiRpcResult<SuiClient> {
+        let max_retries = self.max_retries.unwrap_or(0);
+        self.build_client([http], max_retries).await
+    }
+
+    /// Build a client failing over between the fullnodes at `http_urls`, see [failover].
+    pub async fn build_with_failover(
+        self,
+        http_urls: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> SuiRpcResult<SuiClient> {
+        let max_retries = self.max_retries.unwrap_or(DEFAULT_FAILOVER_MAX_RETRIES);
+        self.build_client(http_urls, max_retries).await
+    }
+
+    async fn build_client(
+        self,
+        http_urls: impl IntoIterator<Item = impl AsRef<str>>,
+        max_retries: usize,
+    ) -> SuiRpcResult<SuiClient> {
         let client_version = env!("CARGO_PKG_VERSION");
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -96,12 +153,24 @@ impl SuiClientBuilder {
             None
         };
 
-        let http = HttpClientBuilder::default()
-            .max_request_body_size(2 << 30)
-            .max_concurrent_requests(self.max_concurrent_requests)
-            .set_headers(headers.clone())
-            .request_timeout(self.request_timeout)
-            .build(http)?;
+        let mut endpoints = vec![];
+        for url in http_urls {
+            let http = HttpClientBuilder::default()
+                .max_request_body_size(2 << 30)
+                .max_concurrent_requests(self.max_concurrent_requests)
+                .set_headers(headers.clone())
+                .request_timeout(self.request_timeout)
+                .build(url.as_ref())?;
+            endpoints.push((url.as_ref().to_string(), http));
+        }
+        if endpoints.is_empty() {
+            return Err(Error::DataError("At least one RPC URL is required.".into()));
+        }
+        let failover = FailoverConfig {
+            max_retries,
+            ..self.failover
+        };
+        let http = FailoverClient::new(endpoints, failover).await;
 
         let info = Self::get_server_info(&http, &ws).await?;
 
@@ -126,7 +195,7 @@ impl SuiClientBuilder {
     }
 
     async fn get_server_info(
-        http: &HttpClient,
+        http: &FailoverClient,
         ws: &Option<WsClient>,
     ) -> Result<ServerInfo, Error> {
         let rpc_spec: Value = http.request("rpc.discover", rpc_params![]).await?;
@@ -182,7 +251,7 @@ pub struct SuiClient {
 }
 
 pub(crate) struct RpcClient {
-    http: HttpClient,
+    http: FailoverClient,
     ws: Option<WsClient>,
     info: ServerInfo,
 }
@@ -216,6 +285,11 @@ impl SuiClient {
         &self.api.info.version
     }
 
+    /// The health of the RPC endpoints the client fails over between.
+    pub fn endpoint_status(&self) -> Vec<EndpointStatus> {
+        self.api.http.endpoint_status()
+    }
+
     pub fn check_api_version(&self) -> SuiRpcResult<()> {
         let server_version = self.api_version();
         let client_version = env!("CARGO_PKG_VERSION");
@@ -301,4 +375,22 @@ impl DataReader for ReadApi {
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error> {
         Ok(self.get_reference_gas_price().await?)
     }
+
+    async fn get_max_tx_gas(&self) -> Result<u64, anyhow::Error> {
+        let config = self.get_protocol_config(None).await?;
+        match config.attributes.get("max_tx_gas") {
+            Some(Some(SuiProtocolConfigValue::U64(max_tx_gas))) => Ok(*max_tx_gas),
+            _ => Err(anyhow::anyhow!(
+                "Protocol version {:?} has no max_tx_gas",
+                config.protocol_version
+            )),
+        }
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx: TransactionData,
+    ) -> Result<DryRunTransactionBlockResponse, anyhow::Error> {
+        Ok(self.dry_run_transaction_block(tx).await?)
+    }
 }
@@ -20,6 +20,10 @@ pub struct SuiClientConfig {
     pub envs: Vec<SuiEnv>,
     pub active_env: Option<String>,
     pub active_address: Option<SuiAddress>,
+    /// Margin, in percent, added to the gas used by a dry run of a transaction to get its budget
+    /// when no gas budget is given. Defaults to `GasBudget::DEFAULT_MARGIN_PERCENT`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_budget_margin: Option<u64>,
 }
 
 impl SuiClientConfig {
@@ -29,6 +33,7 @@ impl SuiClientConfig {
             envs: vec![],
             active_env: None,
             active_address: None,
+            gas_budget_margin: None,
         }
     }
 
@@ -2,30 +2,52 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sui_client_config::SuiClientConfig;
-use crate::SuiClient;
+use crate::{SuiClient, SuiClientBuilder};
 use anyhow::anyhow;
 use colored::Colorize;
 use shared_crypto::intent::Intent;
 use std::collections::BTreeSet;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use sui_config::{Config, PersistedConfig};
 use sui_json_rpc_types::{
     SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
     SuiObjectResponseQuery, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_keys::keystore::AccountKeystore;
-use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_transaction_builder::offline::OfflineState;
+use sui_transaction_builder::{GasBudget, TransactionBuilder};
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest};
+use sui_types::crypto::Signature;
 use sui_types::gas_coin::GasCoin;
 use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
 use tokio::sync::RwLock;
 use tracing::warn;
 
+const WAIT_FOR_FINALITY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// When the execution of a transaction is done.
+#[derive(Clone, Debug, Default)]
+pub enum Finality {
+    /// The fullnode executed the transaction.
+    #[default]
+    LocalExecution,
+    /// The transaction is included in a checkpoint.
+    Checkpoint,
+    /// The transaction is included in a checkpoint and can be read from the fullnode at this
+    /// URL, e.g. the one an indexer reads from.
+    IndexedBy(String),
+}
+
 pub struct WalletContext {
     pub config: PersistedConfig<SuiClientConfig>,
     request_timeout: Option<std::time::Duration>,
     client: Arc<RwLock<Option<SuiClient>>>,
     max_concurrent_requests: Option<u64>,
+    offline: Option<Arc<OfflineState>>,
+    sponsor: Option<SuiAddress>,
+    finality: Finality,
 }
 
 impl WalletContext {
@@ -47,15 +69,43 @@ impl WalletContext {
             request_timeout,
             client: Default::default(),
             max_concurrent_requests,
+            offline: None,
+            sponsor: None,
+            finality: Finality::default(),
         };
         Ok(context)
     }
 
+    /// Work offline, building transactions from `state` instead of reading objects from the
+    /// network, which is not accessed anymore.
+    pub fn set_offline_state(&mut self, state: OfflineState) {
+        self.offline = Some(Arc::new(state));
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline.is_some()
+    }
+
+    /// Build sponsored transactions, whose gas is paid by `sponsor`.
+    pub fn set_sponsor(&mut self, sponsor: SuiAddress) {
+        self.sponsor = Some(sponsor);
+    }
+
+    /// Wait until executed transactions reach `finality` before returning their effects.
+    pub fn set_finality(&mut self, finality: Finality) {
+        self.finality = finality;
+    }
+
     pub fn get_addresses(&self) -> Vec<SuiAddress> {
         self.config.keystore.addresses()
     }
 
     pub async fn get_client(&self) -> clap::Result<SuiClient, anyhow::Error> {
+        if self.is_offline() {
+            return Err(anyhow!(
+                "The network can't be accessed offline, only transactions can be built"
+            ));
+        }
         let read = self.client.read().await;
 
         Ok(if let Some(client) = read.as_ref() {
@@ -75,6 +125,19 @@ impl WalletContext {
         })
     }
 
+    /// Builder of transactions reading objects from the network, or from the offline state when
+    /// offline.
+    pub async fn transaction_builder(&self) -> clap::Result<TransactionBuilder, anyhow::Error> {
+        let builder = match &self.offline {
+            Some(state) => TransactionBuilder::new(state.clone()),
+            None => self.get_client().await?.transaction_builder().clone(),
+        };
+        Ok(match self.sponsor {
+            Some(sponsor) => builder.with_sponsor(sponsor),
+            None => builder,
+        })
+    }
+
     // TODO: Ger rid of mut
     pub fn active_address(&mut self) -> clap::Result<SuiAddress, anyhow::Error> {
         if self.config.keystore.addresses().is_empty() {
@@ -155,12 +218,19 @@ impl WalletContext {
     }
 
     pub async fn get_object_owner(&self, id: &ObjectID) -> clap::Result<SuiAddress, anyhow::Error> {
-        let client = self.get_client().await?;
-        let object = client
-            .read_api()
-            .get_object_with_options(*id, SuiObjectDataOptions::new().with_owner())
-            .await?
-            .into_object()?;
+        let object = match &self.offline {
+            Some(state) => state
+                .get_object(id)
+                .cloned()
+                .ok_or_else(|| anyhow!("Object {id} is not in the offline state"))?,
+            None => self
+                .get_client()
+                .await?
+                .read_api()
+                .get_object_with_options(*id, SuiObjectDataOptions::new().with_owner())
+                .await?
+                .into_object()?,
+        };
         Ok(object
             .owner
             .ok_or_else(|| anyhow!("Owner field is None"))?
@@ -281,15 +351,51 @@ impl WalletContext {
         Ok(gas_price)
     }
 
-    /// Sign a transaction with a key currently managed by the WalletContext
+    /// The gas budget `gas_budget` if given, or else a budget estimated by dry running the
+    /// transaction with the margin of the client config.
+    pub fn gas_budget(&self, gas_budget: Option<u64>) -> GasBudget {
+        gas_budget.map_or_else(
+            || GasBudget::Auto {
+                margin_percent: self
+                    .config
+                    .gas_budget_margin
+                    .unwrap_or(GasBudget::DEFAULT_MARGIN_PERCENT),
+            },
+            GasBudget::Fixed,
+        )
+    }
+
+    /// Sign a transaction with keys currently managed by the WalletContext
     pub fn sign_transaction(&self, data: &TransactionData) -> Transaction {
-        let sig = self
-            .config
-            .keystore
-            .sign_secure(&data.sender(), data, Intent::sui_transaction())
-            .unwrap();
-        // TODO: To support sponsored transaction, we should also look at the gas owner.
-        Transaction::from_data(data.clone(), Intent::sui_transaction(), vec![sig])
+        let sigs = self.sign_transaction_data(data).unwrap();
+        Transaction::from_data(data.clone(), Intent::sui_transaction(), sigs)
+    }
+
+    /// Sign `data` for each of its signers, the sender and the sponsor of sponsored transactions,
+    /// failing if the key of a signer is not managed by the WalletContext.
+    pub fn sign_transaction_data(&self, data: &TransactionData) -> anyhow::Result<Vec<Signature>> {
+        let keystore = &self.config.keystore;
+        let addresses = keystore.addresses();
+        let (signers, missing): (Vec<_>, Vec<_>) = data
+            .signers()
+            .into_iter()
+            .partition(|signer| addresses.contains(signer));
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "The keys of signers {missing:?} are not managed by the wallet. Serialize the \
+                transaction with --serialize-unsigned-transaction, sign it with \
+                `sui keytool sign` for each signer and execute it with \
+                `sui client execute-signed-tx`"
+            ));
+        }
+        signers
+            .iter()
+            .map(|signer| {
+                keystore
+                    .sign_secure(signer, data, Intent::sui_transaction())
+                    .map_err(|e| anyhow!(e))
+            })
+            .collect()
     }
 
     /// Execute a transaction and wait for it to be locally executed on the fullnode.
@@ -311,7 +417,7 @@ impl WalletContext {
         tx: Transaction,
     ) -> anyhow::Result<SuiTransactionBlockResponse> {
         let client = self.get_client().await?;
-        Ok(client
+        let mut response = client
             .quorum_driver_api()
             .execute_transaction_block(
                 tx,
@@ -324,6 +430,40 @@ impl WalletContext {
                     .with_balance_changes(),
                 Some(sui_types::quorum_driver_types::ExecuteTransactionRequestType::WaitForLocalExecution),
             )
+            .await?;
+        let indexed_by = match &self.finality {
+            Finality::LocalExecution => return Ok(response),
+            Finality::Checkpoint => None,
+            Finality::IndexedBy(url) => Some(url.as_str()),
+        };
+        let checkpointed = self
+            .wait_for_transaction_checkpoint(
+                response.digest,
+                indexed_by,
+                SuiTransactionBlockResponseOptions::new(),
+                WAIT_FOR_FINALITY_TIMEOUT,
+            )
+            .await?;
+        response.checkpoint = checkpointed.checkpoint;
+        Ok(response)
+    }
+
+    /// Wait until a transaction is included in a checkpoint, as read from the fullnode at
+    /// `indexed_by` if set, from the active env otherwise.
+    pub async fn wait_for_transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+        indexed_by: Option<&str>,
+        options: SuiTransactionBlockResponseOptions,
+        timeout: Duration,
+    ) -> anyhow::Result<SuiTransactionBlockResponse> {
+        let client = match indexed_by {
+            Some(url) => SuiClientBuilder::default().build(url).await?,
+            None => self.get_client().await?,
+        };
+        Ok(client
+            .read_api()
+            .wait_for_transaction_checkpoint(digest, options, timeout)
             .await?)
     }
 }
@@ -0,0 +1,37 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_sdk::{SuiClientBuilder, SUI_COIN_TYPE};
+use test_cluster::TestClusterBuilder;
+
+#[tokio::test]
+async fn test_failover_to_healthy_endpoint() -> Result<(), anyhow::Error> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    // Nothing listens on the first endpoint.
+    let unreachable_url = "http://127.0.0.1:1";
+    let rpc_url = test_cluster.rpc_url();
+
+    let client = SuiClientBuilder::default()
+        .retry_backoff(Duration::from_millis(10))
+        .build_with_failover([unreachable_url, rpc_url])
+        .await?;
+
+    let status = client.endpoint_status();
+    assert_eq!(2, status.len());
+    assert!(!status[0].healthy);
+    assert!(status[1].healthy);
+
+    // Reads succeed whichever endpoint they are balanced to first.
+    for _ in 0..4 {
+        let coins = client
+            .coin_read_api()
+            .get_coins(address, Some(SUI_COIN_TYPE.to_string()), None, None)
+            .await?;
+        assert!(!coins.data.is_empty());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Query planning for event queries against the [`IndexStore`](crate::IndexStore).
+//!
+//! An arbitrary [`EventFilter`] is turned into an [`EventQueryPlan`]: a set of index scans whose
+//! union contains every matching event, plus an optional residual filter which is evaluated on
+//! each candidate event. For conjunctions the most selective indexable operand drives the scan,
+//! disjunctions are answered with a merge of the scans of their operands, and filters without an
+//! index (`Package`, `MoveEventField`) fall back to scanning all events.
+
+use move_core_types::language_storage::{ModuleId, StructTag};
+use sui_json_rpc_types::EventFilter;
+use sui_types::base_types::{SuiAddress, TransactionDigest};
+
+/// A scan over one of the event indexes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventIndexScan {
+    /// All events, in order of execution.
+    All,
+    Transaction(TransactionDigest),
+    MoveModule(ModuleId),
+    MoveEventType(StructTag),
+    MoveEventModule(ModuleId),
+    Sender(SuiAddress),
+    /// Events in the inclusive time range. Unlike the other scans, this scan is ordered by
+    /// timestamp first, so it is never merged with other scans.
+    TimeRange {
+        start_time: u64,
+        end_time: u64,
+    },
+}
+
+impl EventIndexScan {
+    /// Estimated selectivity of the scan, lower is more selective.
+    /// The index statistics are not tracked, so this is a static ranking based on how many
+    /// events a single key typically covers.
+    pub fn selectivity(&self) -> u8 {
+        match self {
+            EventIndexScan::Transaction(_) => 0,
+            EventIndexScan::MoveEventType(_) => 1,
+            EventIndexScan::Sender(_) => 2,
+            EventIndexScan::MoveEventModule(_) | EventIndexScan::MoveModule(_) => 3,
+            EventIndexScan::TimeRange { .. } => 4,
+            EventIndexScan::All => 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EventQueryPlan {
+    /// Index scans whose union contains every event matching the filter.
+    /// An empty list means no event can match.
+    pub scans: Vec<EventIndexScan>,
+    /// Filter to evaluate on every candidate event, `None` if the scans return exactly the
+    /// matching events.
+    pub residual: Option<EventFilter>,
+}
+
+/// Intermediate result of planning a (sub-)filter.
+struct PartialPlan {
+    scans: Vec<EventIndexScan>,
+    /// Whether the scans return exactly the events matching the sub-filter.
+    exact: bool,
+}
+
+impl PartialPlan {
+    fn scan(scan: EventIndexScan) -> Self {
+        Self {
+            scans: vec![scan],
+            exact: true,
+        }
+    }
+
+    fn scan_all(exact: bool) -> Self {
+        Self {
+            scans: vec![EventIndexScan::All],
+            exact,
+        }
+    }
+
+    fn is_match_all(&self) -> bool {
+        self.exact && self.scans == [EventIndexScan::All]
+    }
+
+    /// Cost of the plan, the selectivity of its least selective scan.
+    fn cost(&self) -> u8 {
+        self.scans
+            .iter()
+            .map(EventIndexScan::selectivity)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl EventQueryPlan {
+    pub fn new(filter: &EventFilter) -> Self {
+        let PartialPlan { scans, exact } = Self::plan(filter);
+        Self {
+            scans,
+            residual: (!exact).then(|| filter.clone()),
+        }
+    }
+
+    fn plan(filter: &EventFilter) -> PartialPlan {
+        match filter {
+            EventFilter::Transaction(digest) => {
+                PartialPlan::scan(EventIndexScan::Transaction(*digest))
+            }
+            EventFilter::MoveModule { package, module } => PartialPlan::scan(
+                EventIndexScan::MoveModule(ModuleId::new((*package).into(), module.clone())),
+            ),
+            EventFilter::MoveEventType(struct_tag) => {
+                PartialPlan::scan(EventIndexScan::MoveEventType(struct_tag.clone()))
+            }
+            EventFilter::MoveEventModule { package, module } => PartialPlan::scan(
+                EventIndexScan::MoveEventModule(ModuleId::new((*package).into(), module.clone())),
+            ),
+            EventFilter::Sender(sender) => PartialPlan::scan(EventIndexScan::Sender(*sender)),
+            EventFilter::TimeRange {
+                start_time,
+                end_time,
+            } => PartialPlan::scan(EventIndexScan::TimeRange {
+                start_time: *start_time,
+                end_time: *end_time,
+            }),
+            // not using "_ =>" because we want to make sure we remember to add new variants here
+            EventFilter::Package(_) | EventFilter::MoveEventField { .. } => {
+                PartialPlan::scan_all(false)
+            }
+            EventFilter::All(filters) => Self::plan_all(filters.iter()),
+            EventFilter::And(f1, f2) => Self::plan_all([f1.as_ref(), f2.as_ref()].into_iter()),
+            EventFilter::Any(filters) => Self::plan_any(filters.iter()),
+            EventFilter::Or(f1, f2) => Self::plan_any([f1.as_ref(), f2.as_ref()].into_iter()),
+        }
+    }
+
+    /// A conjunction is driven by the cheapest plan of its operands, all other operands are
+    /// checked on the candidate events.
+    fn plan_all<'a>(filters: impl Iterator<Item = &'a EventFilter>) -> PartialPlan {
+        let mut plans: Vec<_> = filters
+            .map(Self::plan)
+            .filter(|plan| !plan.is_match_all())
+            .collect();
+        match plans.len() {
+            0 => PartialPlan::scan_all(true),
+            1 => plans.remove(0),
+            _ => {
+                let best = plans
+                    .into_iter()
+                    .min_by_key(PartialPlan::cost)
+                    .expect("plans is not empty");
+                PartialPlan {
+                    scans: best.scans,
+                    exact: false,
+                }
+            }
+        }
+    }
+
+    /// A disjunction is the union of the scans of its operands. Time range scans are ordered
+    /// differently than the other indexes and can't be merged, so they fall back to a full scan.
+    fn plan_any<'a>(filters: impl Iterator<Item = &'a EventFilter>) -> PartialPlan {
+        let plans: Vec<_> = filters.map(Self::plan).collect();
+        let exact = plans.iter().all(|plan| plan.exact);
+        let mut scans: Vec<EventIndexScan> = vec![];
+        for scan in plans.into_iter().flat_map(|plan| plan.scans) {
+            match scan {
+                EventIndexScan::All => return PartialPlan::scan_all(exact),
+                EventIndexScan::TimeRange { .. } => return PartialPlan::scan_all(false),
+                scan if !scans.contains(&scan) => scans.push(scan),
+                _ => {}
+            }
+        }
+        PartialPlan { scans, exact }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::ident_str;
+    use serde_json::json;
+    use sui_types::base_types::ObjectID;
+
+    fn event_type() -> StructTag {
+        StructTag {
+            address: AccountAddress::random(),
+            module: ident_str!("pool").to_owned(),
+            name: ident_str!("SwapEvent").to_owned(),
+            type_params: vec![],
+        }
+    }
+
+    #[test]
+    fn test_single_index_is_exact() {
+        let sender = SuiAddress::random_for_testing_only();
+        let plan = EventQueryPlan::new(&EventFilter::Sender(sender));
+        assert_eq!(plan.scans, vec![EventIndexScan::Sender(sender)]);
+        assert!(plan.residual.is_none());
+    }
+
+    #[test]
+    fn test_unindexed_filter_scans_all() {
+        let filter = EventFilter::MoveEventField {
+            path: "/amount".to_string(),
+            value: json!("100"),
+        };
+        let plan = EventQueryPlan::new(&filter);
+        assert_eq!(plan.scans, vec![EventIndexScan::All]);
+        assert!(plan.residual.is_some());
+    }
+
+    #[test]
+    fn test_conjunction_uses_most_selective_index() {
+        let sender = SuiAddress::random_for_testing_only();
+        let event_type = event_type();
+        let filter = EventFilter::All(vec![
+            EventFilter::TimeRange {
+                start_time: 0,
+                end_time: 100,
+            },
+            EventFilter::Sender(sender),
+            EventFilter::MoveEventType(event_type.clone()),
+        ]);
+        let plan = EventQueryPlan::new(&filter);
+        assert_eq!(plan.scans, vec![EventIndexScan::MoveEventType(event_type)]);
+        assert!(plan.residual.is_some());
+
+        // Operands matching every event don't constrain the plan.
+        let filter = EventFilter::And(
+            Box::new(EventFilter::All(vec![])),
+            Box::new(EventFilter::Sender(sender)),
+        );
+        let plan = EventQueryPlan::new(&filter);
+        assert_eq!(plan.scans, vec![EventIndexScan::Sender(sender)]);
+        assert!(plan.residual.is_none());
+    }
+
+    #[test]
+    fn test_disjunction_merges_index_scans() {
+        let sender = SuiAddress::random_for_testing_only();
+        let event_type = event_type();
+        let filter = EventFilter::Or(
+            Box::new(EventFilter::Sender(sender)),
+            Box::new(EventFilter::MoveEventType(event_type.clone())),
+        );
+        let plan = EventQueryPlan::new(&filter);
+        assert_eq!(
+            plan.scans,
+            vec![
+                EventIndexScan::Sender(sender),
+                EventIndexScan::MoveEventType(event_type.clone())
+            ]
+        );
+        assert!(plan.residual.is_none());
+
+        // A nested conjunction keeps its scan but needs the residual check.
+        let filter = EventFilter::Any(vec![
+            EventFilter::Sender(sender),
+            EventFilter::All(vec![
+                EventFilter::MoveEventType(event_type.clone()),
+                EventFilter::Package(ObjectID::random()),
+            ]),
+        ]);
+        let plan = EventQueryPlan::new(&filter);
+        assert_eq!(
+            plan.scans,
+            vec![
+                EventIndexScan::Sender(sender),
+                EventIndexScan::MoveEventType(event_type)
+            ]
+        );
+        assert!(plan.residual.is_some());
+    }
+
+    #[test]
+    fn test_disjunction_with_time_range_scans_all() {
+        let filter = EventFilter::Any(vec![
+            EventFilter::Sender(SuiAddress::random_for_testing_only()),
+            EventFilter::TimeRange {
+                start_time: 0,
+                end_time: 100,
+            },
+        ]);
+        let plan = EventQueryPlan::new(&filter);
+        assert_eq!(plan.scans, vec![EventIndexScan::All]);
+        assert!(plan.residual.is_some());
+    }
+
+    #[test]
+    fn test_empty_disjunction_matches_nothing() {
+        let plan = EventQueryPlan::new(&EventFilter::Any(vec![]));
+        assert!(plan.scans.is_empty());
+        assert!(plan.residual.is_none());
+    }
+}
@@ -18,6 +18,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::BTreeMap;
 use tokio::sync::OwnedMutexGuard;
 
+use crate::event_query::{EventIndexScan, EventQueryPlan};
 use crate::mutex_table::MutexTable;
 use crate::sharded_lru::ShardedLruCache;
 use sui_json_rpc_types::{SuiObjectDataFilter, TransactionFilter};
@@ -1125,6 +1126,206 @@ impl IndexStore {
         })
     }
 
+    /// Return up to `limit` index entries of the candidate events of `plan`, i.e. the union of
+    /// its scans, in event order. If `after` is provided the scans start strictly after that
+    /// event. The residual filter of the plan is not applied.
+    pub fn scan_events(
+        &self,
+        plan: &EventQueryPlan,
+        after: Option<EventId>,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(EventId, EventIndex)>> {
+        let mut candidates = vec![];
+        for scan in &plan.scans {
+            candidates.extend(self.scan_event_index(scan, after, limit, descending)?);
+        }
+        if plan.scans.len() > 1 {
+            // The first `limit` events of the union are among the first `limit` events of each scan.
+            candidates.sort_by_key(|(id, _)| *id);
+            candidates.dedup_by_key(|(id, _)| *id);
+            if descending {
+                candidates.reverse();
+            }
+            candidates.truncate(limit);
+        }
+        Ok(candidates)
+    }
+
+    fn scan_event_index(
+        &self,
+        scan: &EventIndexScan,
+        after: Option<EventId>,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(EventId, EventIndex)>> {
+        let start = after.unwrap_or(if descending {
+            (TxSequenceNumber::MAX, usize::MAX)
+        } else {
+            (0, 0)
+        });
+        match scan {
+            EventIndexScan::All => self.scan_event_order(None, start, after, limit, descending),
+            EventIndexScan::Transaction(digest) => {
+                let seq = self
+                    .get_transaction_seq(digest)?
+                    .ok_or(SuiError::TransactionNotFound { digest: *digest })?;
+                let start = if descending {
+                    min(start, (seq, usize::MAX))
+                } else {
+                    max(start, (seq, 0))
+                };
+                self.scan_event_order(Some(seq), start, after, limit, descending)
+            }
+            EventIndexScan::MoveModule(module_id) => Self::scan_keyed_event_index(
+                &self.tables.event_by_move_module,
+                module_id,
+                start,
+                after,
+                limit,
+                descending,
+            ),
+            EventIndexScan::MoveEventType(struct_tag) => Self::scan_keyed_event_index(
+                &self.tables.event_by_move_event,
+                struct_tag,
+                start,
+                after,
+                limit,
+                descending,
+            ),
+            EventIndexScan::MoveEventModule(module_id) => Self::scan_keyed_event_index(
+                &self.tables.event_by_event_module,
+                module_id,
+                start,
+                after,
+                limit,
+                descending,
+            ),
+            EventIndexScan::Sender(sender) => Self::scan_keyed_event_index(
+                &self.tables.event_by_sender,
+                sender,
+                start,
+                after,
+                limit,
+                descending,
+            ),
+            EventIndexScan::TimeRange {
+                start_time,
+                end_time,
+            } => {
+                self.scan_event_time_range(*start_time, *end_time, start, after, limit, descending)
+            }
+        }
+    }
+
+    fn scan_event_order(
+        &self,
+        tx_seq: Option<TxSequenceNumber>,
+        start: EventId,
+        after: Option<EventId>,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(EventId, EventIndex)>> {
+        let in_scope = |(tx, _): &EventId| tx_seq.map_or(true, |seq| *tx == seq);
+        Ok(if descending {
+            self.tables
+                .event_order
+                .unbounded_iter()
+                .skip_prior_to(&start)?
+                .reverse()
+                .take_while(|(id, _)| in_scope(id))
+                .filter(|(id, _)| Some(*id) != after)
+                .take(limit)
+                .collect()
+        } else {
+            self.tables
+                .event_order
+                .unbounded_iter()
+                .skip_to(&start)?
+                .take_while(|(id, _)| in_scope(id))
+                .filter(|(id, _)| Some(*id) != after)
+                .take(limit)
+                .collect()
+        })
+    }
+
+    fn scan_keyed_event_index<KeyT: Clone + PartialEq + Serialize + DeserializeOwned>(
+        index: &DBMap<(KeyT, EventId), EventIndex>,
+        key: &KeyT,
+        start: EventId,
+        after: Option<EventId>,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(EventId, EventIndex)>> {
+        Ok(if descending {
+            index
+                .unbounded_iter()
+                .skip_prior_to(&(key.clone(), start))?
+                .reverse()
+                .take_while(|((k, _), _)| k == key)
+                .filter(|((_, id), _)| Some(*id) != after)
+                .take(limit)
+                .map(|((_, id), index)| (id, index))
+                .collect()
+        } else {
+            index
+                .unbounded_iter()
+                .skip_to(&(key.clone(), start))?
+                .take_while(|((k, _), _)| k == key)
+                .filter(|((_, id), _)| Some(*id) != after)
+                .take(limit)
+                .map(|((_, id), index)| (id, index))
+                .collect()
+        })
+    }
+
+    /// The time index is ordered by timestamp first, so the scan resumes from the timestamp of
+    /// the `after` event.
+    fn scan_event_time_range(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        start: EventId,
+        after: Option<EventId>,
+        limit: usize,
+        descending: bool,
+    ) -> SuiResult<Vec<(EventId, EventIndex)>> {
+        let after_time = match after {
+            Some(id) => self.tables.event_order.get(&id)?.map(|(_, _, time)| time),
+            None => None,
+        };
+        Ok(if descending {
+            let (time, start) = match after_time {
+                Some(time) if time <= end_time => (time, start),
+                _ => (end_time, (TxSequenceNumber::MAX, usize::MAX)),
+            };
+            self.tables
+                .event_by_time
+                .unbounded_iter()
+                .skip_prior_to(&(time, start))?
+                .reverse()
+                .take_while(|((time, _), _)| time >= &start_time)
+                .filter(|((_, id), _)| Some(*id) != after)
+                .take(limit)
+                .map(|((_, id), index)| (id, index))
+                .collect()
+        } else {
+            let (time, start) = match after_time {
+                Some(time) if time >= start_time => (time, start),
+                _ => (start_time, (0, 0)),
+            };
+            self.tables
+                .event_by_time
+                .unbounded_iter()
+                .skip_to(&(time, start))?
+                .take_while(|((time, _), _)| time <= &end_time)
+                .filter(|((_, id), _)| Some(*id) != after)
+                .take(limit)
+                .map(|((_, id), index)| (id, index))
+                .collect()
+        })
+    }
+
     pub fn get_dynamic_fields_iterator(
         &self,
         object: ObjectID,
@@ -1579,15 +1780,20 @@ impl IndexStore {
 
 #[cfg(test)]
 mod tests {
+    use crate::event_query::EventQueryPlan;
     use crate::indexes::ObjectIndexChanges;
     use crate::IndexStore;
     use move_core_types::account_address::AccountAddress;
+    use move_core_types::ident_str;
+    use move_core_types::language_storage::StructTag;
     use prometheus::Registry;
     use std::collections::BTreeMap;
     use std::env::temp_dir;
+    use sui_json_rpc_types::EventFilter;
     use sui_types::base_types::{ObjectInfo, ObjectType, SuiAddress};
     use sui_types::digests::TransactionDigest;
     use sui_types::effects::TransactionEvents;
+    use sui_types::event::Event;
     use sui_types::gas_coin::GAS;
     use sui_types::object;
     use sui_types::object::Owner;
@@ -1729,4 +1935,131 @@ mod tests {
 
         Ok(())
     }
+
+    fn event_type(name: &str) -> StructTag {
+        StructTag {
+            address: AccountAddress::ONE,
+            module: ident_str!("pool").to_owned(),
+            name: name.parse().unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scan_events() -> anyhow::Result<()> {
+        // Four transactions of alternating senders, each emitting a `Swap` and a `Deposit` event.
+        let dir = tempfile::tempdir()?;
+        let index_store = IndexStore::new(dir.path().to_path_buf(), &Registry::default(), None);
+        let senders = [
+            SuiAddress::random_for_testing_only(),
+            SuiAddress::random_for_testing_only(),
+        ];
+        let (swap, deposit) = (event_type("Swap"), event_type("Deposit"));
+        let mut digests = vec![];
+        for i in 0..4 {
+            let sender = senders[i % 2];
+            let events = TransactionEvents {
+                data: [&swap, &deposit]
+                    .into_iter()
+                    .map(|type_| {
+                        Event::new(
+                            &AccountAddress::ONE,
+                            ident_str!("pool"),
+                            sender,
+                            type_.clone(),
+                            vec![],
+                        )
+                    })
+                    .collect(),
+            };
+            let digest = TransactionDigest::random();
+            index_store
+                .index_tx(
+                    sender,
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    vec![].into_iter(),
+                    &events,
+                    ObjectIndexChanges {
+                        deleted_owners: vec![],
+                        deleted_dynamic_fields: vec![],
+                        new_owners: vec![],
+                        new_dynamic_fields: vec![],
+                    },
+                    &digest,
+                    1000 * i as u64,
+                    None,
+                    &BTreeMap::new(),
+                )
+                .await?;
+            digests.push(digest);
+        }
+        let scan = |filter: EventFilter, after, limit, descending: bool| {
+            let plan = EventQueryPlan::new(&filter);
+            index_store
+                .scan_events(&plan, after, limit, descending)
+                .unwrap()
+        };
+        let events_of = |scanned: &[((u64, usize), (_, TransactionDigest, u64))]| {
+            scanned
+                .iter()
+                .map(|((_, event_seq), (_, digest, _))| (*digest, *event_seq))
+                .collect::<Vec<_>>()
+        };
+
+        // Single index scan, in both directions.
+        let scanned = scan(EventFilter::Sender(senders[0]), None, 10, false);
+        assert_eq!(
+            events_of(&scanned),
+            vec![
+                (digests[0], 0),
+                (digests[0], 1),
+                (digests[2], 0),
+                (digests[2], 1)
+            ]
+        );
+        let scanned = scan(EventFilter::Sender(senders[1]), None, 1, true);
+        assert_eq!(events_of(&scanned), vec![(digests[3], 1)]);
+
+        // A disjunction is a merge of the index scans, paged with the last scanned event.
+        let filter = EventFilter::Any(vec![
+            EventFilter::Sender(senders[0]),
+            EventFilter::MoveEventType(deposit.clone()),
+        ]);
+        let first_page = scan(filter.clone(), None, 3, false);
+        assert_eq!(
+            events_of(&first_page),
+            vec![(digests[0], 0), (digests[0], 1), (digests[1], 1)]
+        );
+        let after = first_page.last().map(|(id, _)| *id);
+        let second_page = scan(filter, after, 3, false);
+        assert_eq!(
+            events_of(&second_page),
+            vec![(digests[2], 0), (digests[2], 1), (digests[3], 1)]
+        );
+
+        // Filters without an index scan all events, the residual filter is left to the caller.
+        let filter = EventFilter::MoveEventField {
+            path: "/amount".to_string(),
+            value: serde_json::json!(1),
+        };
+        assert_eq!(scan(filter, None, 100, false).len(), 8);
+
+        // Time range scans resume from the timestamp of the last scanned event.
+        let filter = EventFilter::TimeRange {
+            start_time: 1000,
+            end_time: 2000,
+        };
+        let first_page = scan(filter.clone(), None, 3, false);
+        assert_eq!(
+            events_of(&first_page),
+            vec![(digests[1], 0), (digests[1], 1), (digests[2], 0)]
+        );
+        let after = first_page.last().map(|(id, _)| *id);
+        assert_eq!(
+            events_of(&scan(filter, after, 3, false)),
+            vec![(digests[2], 1)]
+        );
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+pub mod event_query;
 pub mod indexes;
 
 use crate::blob::BlobIter;
@@ -3,13 +3,18 @@
 
 use crate::{
     db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand},
+    export::{export, ExportFormat, ExportSource},
     get_object, get_transaction_block, make_clients, restore_from_db_checkpoint,
     state_sync_from_archive, verify_archive, ConciseObjectOutput, GroupedObjectOutput,
     VerboseObjectOutput,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use prometheus::Registry;
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
 use sui_config::genesis::Genesis;
+use sui_config::node::ArchiveReaderConfig;
 use sui_core::authority_client::AuthorityAPI;
 use sui_replay::{execute_replay_command, ReplayToolCommand};
 
@@ -142,6 +147,40 @@ pub enum ToolCommand {
         download_concurrency: usize,
     },
 
+    /// Export checkpoints, transactions, effects, events, move calls and object versions
+    /// into Parquet or CSV tables partitioned by epoch. Reads from the archive store, or from
+    /// a local node DB if `--db-path` is set. Events are only exported from a DB.
+    #[clap(name = "export")]
+    Export {
+        /// Path of the node DB to read from instead of the archive store
+        #[clap(long = "db-path")]
+        db_path: Option<PathBuf>,
+        #[clap(flatten)]
+        object_store_config: ObjectStoreConfig,
+        #[clap(long = "download-concurrency", default_value_t = 5)]
+        download_concurrency: usize,
+        /// Directory the tables are written to. Exporting again into the same directory
+        /// resumes from where the previous export stopped.
+        #[clap(long = "output-dir")]
+        output_dir: PathBuf,
+        #[clap(
+            value_enum,
+            long = "format",
+            default_value = "parquet",
+            ignore_case = true
+        )]
+        format: ExportFormat,
+        /// First checkpoint to export
+        #[clap(long = "start", default_value_t = 0)]
+        start: CheckpointSequenceNumber,
+        /// Last checkpoint to export, defaults to the latest available checkpoint
+        #[clap(long = "end")]
+        end: Option<CheckpointSequenceNumber>,
+        /// Maximum number of checkpoints per file
+        #[clap(long = "checkpoints-per-file", default_value_t = 10_000)]
+        checkpoints_per_file: u64,
+    },
+
     #[clap(name = "dump-validators")]
     DumpValidators {
         #[clap(long = "genesis")]
@@ -408,6 +447,39 @@ impl ToolCommand {
             } => {
                 verify_archive(&genesis, object_store_config, download_concurrency, true).await?;
             }
+            ToolCommand::Export {
+                db_path,
+                object_store_config,
+                download_concurrency,
+                output_dir,
+                format,
+                start,
+                end,
+                checkpoints_per_file,
+            } => {
+                let source = match db_path {
+                    Some(db_path) => ExportSource::open_db(&db_path),
+                    None => {
+                        let archive_reader_config = ArchiveReaderConfig {
+                            remote_store_config: object_store_config,
+                            download_concurrency: NonZeroUsize::new(download_concurrency)
+                                .ok_or_else(|| anyhow!("Download concurrency must be positive"))?,
+                            use_for_pruning_watermark: false,
+                        };
+                        let metrics = ArchiveReaderMetrics::new(&Registry::default());
+                        ExportSource::Archive(ArchiveReader::new(archive_reader_config, &metrics)?)
+                    }
+                };
+                export(
+                    source,
+                    &output_dir,
+                    format,
+                    start,
+                    end,
+                    checkpoints_per_file,
+                )
+                .await?;
+            }
             ToolCommand::SignTransaction {
                 genesis,
                 sender_signed_data,
@@ -0,0 +1,377 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of checkpoints, transactions, effects, events, move calls and object versions into
+//! columnar tables, read either from an archive store or from a local node DB.
+
+mod tables;
+mod writer;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use strum::IntoEnumIterator;
+use sui_archival::reader::ArchiveReader;
+use sui_core::authority::authority_store_tables::{
+    AuthorityPerpetualTables, AuthorityPerpetualTablesReadOnly,
+};
+use sui_core::checkpoints::{CheckpointStore, CheckpointStoreReadOnly, CheckpointWatermark};
+use sui_types::effects::{TransactionEffectsAPI, TransactionEvents};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
+use tracing::{info, warn};
+use typed_store::traits::Map;
+
+use tables::{CheckpointData, CheckpointTransaction, Row, Table};
+pub use writer::ExportFormat;
+
+pub enum ExportSource {
+    Archive(ArchiveReader),
+    Db {
+        checkpoint_store: CheckpointStoreReadOnly,
+        perpetual_db: AuthorityPerpetualTablesReadOnly,
+    },
+}
+
+impl ExportSource {
+    /// Open the DB of a node as a secondary instance, so that exporting never writes to the DB
+    /// and can run while the node is running.
+    pub fn open_db(db_path: &Path) -> Self {
+        ExportSource::Db {
+            checkpoint_store: CheckpointStore::open_readonly(&db_path.join("checkpoints")),
+            perpetual_db: AuthorityPerpetualTables::open_readonly(&db_path.join("store")),
+        }
+    }
+
+    async fn latest_checkpoint(&self) -> Result<CheckpointSequenceNumber> {
+        match self {
+            ExportSource::Archive(reader) => {
+                reader.sync_manifest_once().await?;
+                reader.latest_available_checkpoint().await
+            }
+            ExportSource::Db {
+                checkpoint_store,
+                perpetual_db,
+            } => {
+                // Catch up the checkpoints before the transactions, so that all transactions of
+                // the executed checkpoints are visible.
+                checkpoint_store.watermarks.try_catch_up_with_primary()?;
+                perpetual_db.transactions.try_catch_up_with_primary()?;
+                checkpoint_store
+                    .watermarks
+                    .get(&CheckpointWatermark::HighestExecuted)?
+                    .map(|(sequence_number, _)| sequence_number)
+                    .ok_or_else(|| anyhow!("No executed checkpoint in DB"))
+            }
+        }
+    }
+
+    fn read_db_checkpoint(
+        checkpoint_store: &CheckpointStoreReadOnly,
+        perpetual_db: &AuthorityPerpetualTablesReadOnly,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Result<CheckpointData> {
+        let checkpoint: VerifiedCheckpoint = checkpoint_store
+            .certified_checkpoints
+            .get(&sequence_number)?
+            .ok_or_else(|| anyhow!("Checkpoint {sequence_number} not found"))?
+            .into();
+        let contents = checkpoint_store
+            .checkpoint_content
+            .get(&checkpoint.content_digest)?
+            .ok_or_else(|| anyhow!("Contents of checkpoint {sequence_number} not found"))?;
+        let transactions = contents
+            .iter()
+            .map(|digests| {
+                let transaction = perpetual_db
+                    .transactions
+                    .get(&digests.transaction)?
+                    .ok_or_else(|| anyhow!("Transaction {} not found", digests.transaction))?;
+                let effects = match perpetual_db.executed_effects.get(&digests.transaction)? {
+                    Some(effects_digest) => perpetual_db.effects.get(&effects_digest)?,
+                    None => None,
+                }
+                .ok_or_else(|| anyhow!("Effects of {} not found", digests.transaction))?;
+                let events = effects.events_digest().and_then(|digest| {
+                    let data: Vec<_> = perpetual_db
+                        .events
+                        .range_iter((*digest, 0)..=(*digest, usize::MAX))
+                        .map(|(_, event)| event)
+                        .collect();
+                    (!data.is_empty()).then_some(TransactionEvents { data })
+                });
+                Ok(CheckpointTransaction {
+                    data: transaction.into_inner().data().transaction_data().clone(),
+                    effects,
+                    events,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(CheckpointData {
+            summary: checkpoint.into_inner(),
+            transactions,
+        })
+    }
+}
+
+/// Rows of a range of checkpoints of one epoch which are written to the same files.
+struct Batch {
+    epoch: u64,
+    first: CheckpointSequenceNumber,
+    last: CheckpointSequenceNumber,
+    rows: BTreeMap<Table, Vec<Row>>,
+}
+
+struct Exporter {
+    output_dir: PathBuf,
+    format: ExportFormat,
+    checkpoints_per_file: u64,
+    batch: Option<Batch>,
+}
+
+impl Exporter {
+    fn process(&mut self, checkpoint: CheckpointData) -> Result<()> {
+        let epoch = checkpoint.summary.epoch;
+        let sequence_number = checkpoint.summary.sequence_number;
+        // Files never span epochs, so that tables can be partitioned by epoch.
+        if let Some(batch) = &self.batch {
+            if batch.epoch != epoch || sequence_number - batch.first >= self.checkpoints_per_file {
+                self.flush()?;
+            }
+        }
+        let batch = self.batch.get_or_insert_with(|| Batch {
+            epoch,
+            first: sequence_number,
+            last: sequence_number,
+            rows: BTreeMap::new(),
+        });
+        batch.last = sequence_number;
+        for table in Table::iter() {
+            batch
+                .rows
+                .entry(table)
+                .or_default()
+                .extend(table.rows(&checkpoint));
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let Some(batch) = self.batch.take() else {
+            return Ok(());
+        };
+        for (table, rows) in &batch.rows {
+            if rows.is_empty() {
+                continue;
+            }
+            writer::write_table_file(
+                &self.output_dir,
+                self.format,
+                *table,
+                batch.epoch,
+                (batch.first, batch.last),
+                rows,
+            )?;
+        }
+        writer::write_watermark(&self.output_dir, batch.last + 1)?;
+        info!(
+            "Exported checkpoints {} to {} of epoch {}",
+            batch.first, batch.last, batch.epoch
+        );
+        Ok(())
+    }
+}
+
+/// Export the checkpoints from `start` to `end` (inclusive, defaults to the latest available
+/// checkpoint) into `output_dir`. If a previous export into the same directory was interrupted,
+/// the export resumes from its watermark.
+pub async fn export(
+    source: ExportSource,
+    output_dir: &Path,
+    format: ExportFormat,
+    start: CheckpointSequenceNumber,
+    end: Option<CheckpointSequenceNumber>,
+    checkpoints_per_file: u64,
+) -> Result<()> {
+    std::fs::create_dir_all(output_dir)?;
+    let start = match writer::read_watermark(output_dir)? {
+        Some(watermark) => {
+            info!("Resuming export from watermark {watermark}");
+            watermark.max(start)
+        }
+        None => start,
+    };
+    writer::remove_files_from(output_dir, start)?;
+
+    let latest = source.latest_checkpoint().await?;
+    let end = end.map_or(latest, |end| end.min(latest));
+    if start > end {
+        info!("Nothing to export, next checkpoint {start} is past checkpoint {end}");
+        return Ok(());
+    }
+    info!(
+        "Exporting checkpoints {start} to {end} into {}",
+        output_dir.display()
+    );
+
+    let mut exporter = Exporter {
+        output_dir: output_dir.to_path_buf(),
+        format,
+        checkpoints_per_file: checkpoints_per_file.max(1),
+        batch: None,
+    };
+    match source {
+        ExportSource::Archive(reader) => {
+            warn!("Events are not stored in archives, the events table will not be exported");
+            reader
+                .for_each_checkpoint(start..end + 1, |summary, contents| {
+                    let transactions = contents
+                        .iter()
+                        .map(|execution_data| CheckpointTransaction {
+                            data: execution_data.transaction.data().transaction_data().clone(),
+                            effects: execution_data.effects.clone(),
+                            events: None,
+                        })
+                        .collect();
+                    exporter.process(CheckpointData {
+                        summary,
+                        transactions,
+                    })
+                })
+                .await?;
+        }
+        ExportSource::Db {
+            checkpoint_store,
+            perpetual_db,
+        } => {
+            for sequence_number in start..=end {
+                let checkpoint = ExportSource::read_db_checkpoint(
+                    &checkpoint_store,
+                    &perpetual_db,
+                    sequence_number,
+                )?;
+                exporter.process(checkpoint)?;
+            }
+        }
+    }
+    exporter.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use sui_types::committee::Committee;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSummary,
+    };
+
+    use super::tables::{CheckpointData, Table};
+    use super::writer::{self, ExportFormat};
+    use super::Exporter;
+
+    fn checkpoint(epoch: u64, sequence_number: u64) -> CheckpointData {
+        let (committee, keys) = Committee::new_simple_test_committee();
+        let contents = CheckpointContents::new_with_causally_ordered_transactions(vec![]);
+        let summary = CheckpointSummary::new(
+            epoch,
+            sequence_number,
+            0,
+            &contents,
+            None,
+            GasCostSummary::default(),
+            None,
+            sequence_number * 1000,
+        );
+        CheckpointData {
+            summary: CertifiedCheckpointSummary::new_from_keypairs_for_testing(
+                summary, &keys, &committee,
+            ),
+            transactions: vec![],
+        }
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return vec![];
+        };
+        let mut names: Vec<_> = entries
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_export_files_by_epoch_and_range() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut exporter = Exporter {
+            output_dir: dir.path().to_path_buf(),
+            format: ExportFormat::Csv,
+            checkpoints_per_file: 2,
+            batch: None,
+        };
+        for (epoch, sequence_number) in [(0, 0), (0, 1), (0, 2), (1, 3)] {
+            exporter.process(checkpoint(epoch, sequence_number))?;
+        }
+        exporter.flush()?;
+
+        let checkpoints_dir = dir.path().join("checkpoints");
+        assert_eq!(
+            file_names(&checkpoints_dir.join("epoch=0")),
+            vec!["0_1.csv", "2_2.csv"]
+        );
+        assert_eq!(
+            file_names(&checkpoints_dir.join("epoch=1")),
+            vec!["3_3.csv"]
+        );
+        // Tables without rows are not written.
+        assert!(!dir.path().join("transactions").exists());
+        assert_eq!(writer::read_watermark(dir.path())?, Some(4));
+
+        let csv = fs::read_to_string(checkpoints_dir.join("epoch=0").join("0_1.csv"))?;
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("epoch,sequence_number,digest,previous_digest"));
+        assert!(lines[2].starts_with("0,1,"));
+
+        // Files past the watermark of an interrupted export are removed before resuming.
+        writer::remove_files_from(dir.path(), 2)?;
+        assert_eq!(
+            file_names(&checkpoints_dir.join("epoch=0")),
+            vec!["0_1.csv"]
+        );
+        assert!(file_names(&checkpoints_dir.join("epoch=1")).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_parquet_table_file() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let rows: Vec<_> = (0..3)
+            .flat_map(|sequence_number| Table::Checkpoints.rows(&checkpoint(0, sequence_number)))
+            .collect();
+        writer::write_table_file(
+            dir.path(),
+            ExportFormat::Parquet,
+            Table::Checkpoints,
+            0,
+            (0, 2),
+            &rows,
+        )?;
+
+        let partition_dir = dir.path().join("checkpoints").join("epoch=0");
+        assert_eq!(file_names(&partition_dir), vec!["0_2.parquet"]);
+        let reader = SerializedFileReader::new(fs::File::open(partition_dir.join("0_2.parquet"))?)?;
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 3);
+        assert_eq!(
+            metadata.schema_descr().num_columns(),
+            Table::Checkpoints.columns().len()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Row layout of the exported tables.
+
+use strum_macros::{Display, EnumIter};
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::execution_status::ExecutionStatus;
+use sui_types::messages_checkpoint::CertifiedCheckpointSummary;
+use sui_types::object::Owner;
+use sui_types::transaction::{TransactionData, TransactionDataAPI, TransactionKind};
+
+/// A checkpoint with everything needed to produce the rows of all tables.
+pub struct CheckpointData {
+    pub summary: CertifiedCheckpointSummary,
+    pub transactions: Vec<CheckpointTransaction>,
+}
+
+pub struct CheckpointTransaction {
+    pub data: TransactionData,
+    pub effects: TransactionEffects,
+    /// Events are not part of the archive, they are only exported from a local DB.
+    pub events: Option<TransactionEvents>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Display, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum Table {
+    Checkpoints,
+    Transactions,
+    Effects,
+    Events,
+    MoveCalls,
+    Objects,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum ColumnType {
+    U64,
+    Str,
+    Bool,
+}
+
+pub struct Column {
+    pub name: &'static str,
+    pub ty: ColumnType,
+    pub nullable: bool,
+}
+
+const fn col(name: &'static str, ty: ColumnType) -> Column {
+    Column {
+        name,
+        ty,
+        nullable: false,
+    }
+}
+
+const fn nullable(name: &'static str, ty: ColumnType) -> Column {
+    Column {
+        name,
+        ty,
+        nullable: true,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    U64(Option<u64>),
+    Str(Option<String>),
+    Bool(Option<bool>),
+}
+
+impl Value {
+    fn u64(value: u64) -> Self {
+        Value::U64(Some(value))
+    }
+
+    fn str(value: impl ToString) -> Self {
+        Value::Str(Some(value.to_string()))
+    }
+
+    fn opt_str(value: Option<impl ToString>) -> Self {
+        Value::Str(value.map(|v| v.to_string()))
+    }
+
+    /// CSV representation, nulls are written as empty fields.
+    pub fn to_csv_field(&self) -> String {
+        match self {
+            Value::U64(v) => v.map(|v| v.to_string()).unwrap_or_default(),
+            Value::Str(v) => v.clone().unwrap_or_default(),
+            Value::Bool(v) => v.map(|v| v.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+pub type Row = Vec<Value>;
+
+const CHECKPOINT_COLUMNS: &[Column] = &[
+    col("epoch", ColumnType::U64),
+    col("sequence_number", ColumnType::U64),
+    col("digest", ColumnType::Str),
+    nullable("previous_digest", ColumnType::Str),
+    col("timestamp_ms", ColumnType::U64),
+    col("network_total_transactions", ColumnType::U64),
+    col("num_transactions", ColumnType::U64),
+    col("epoch_computation_cost", ColumnType::U64),
+    col("epoch_storage_cost", ColumnType::U64),
+    col("epoch_storage_rebate", ColumnType::U64),
+    col("epoch_non_refundable_storage_fee", ColumnType::U64),
+    col("end_of_epoch", ColumnType::Bool),
+];
+
+const TRANSACTION_COLUMNS: &[Column] = &[
+    col("checkpoint", ColumnType::U64),
+    col("epoch", ColumnType::U64),
+    col("timestamp_ms", ColumnType::U64),
+    col("digest", ColumnType::Str),
+    col("kind", ColumnType::Str),
+    col("sender", ColumnType::Str),
+    col("gas_owner", ColumnType::Str),
+    col("gas_budget", ColumnType::U64),
+    col("gas_price", ColumnType::U64),
+    col("num_commands", ColumnType::U64),
+];
+
+const EFFECTS_COLUMNS: &[Column] = &[
+    col("checkpoint", ColumnType::U64),
+    col("transaction_digest", ColumnType::Str),
+    col("success", ColumnType::Bool),
+    nullable("error", ColumnType::Str),
+    col("computation_cost", ColumnType::U64),
+    col("storage_cost", ColumnType::U64),
+    col("storage_rebate", ColumnType::U64),
+    col("non_refundable_storage_fee", ColumnType::U64),
+    col("num_created", ColumnType::U64),
+    col("num_mutated", ColumnType::U64),
+    col("num_deleted", ColumnType::U64),
+    nullable("events_digest", ColumnType::Str),
+];
+
+const EVENT_COLUMNS: &[Column] = &[
+    col("checkpoint", ColumnType::U64),
+    col("transaction_digest", ColumnType::Str),
+    col("event_sequence", ColumnType::U64),
+    col("package", ColumnType::Str),
+    col("module", ColumnType::Str),
+    col("sender", ColumnType::Str),
+    col("event_type", ColumnType::Str),
+    col("bcs", ColumnType::Str),
+];
+
+const MOVE_CALL_COLUMNS: &[Column] = &[
+    col("checkpoint", ColumnType::U64),
+    col("transaction_digest", ColumnType::Str),
+    col("call_index", ColumnType::U64),
+    col("package", ColumnType::Str),
+    col("module", ColumnType::Str),
+    col("function", ColumnType::Str),
+];
+
+const OBJECT_COLUMNS: &[Column] = &[
+    col("checkpoint", ColumnType::U64),
+    col("transaction_digest", ColumnType::Str),
+    col("object_id", ColumnType::Str),
+    col("version", ColumnType::U64),
+    col("digest", ColumnType::Str),
+    col("status", ColumnType::Str),
+    nullable("owner", ColumnType::Str),
+];
+
+impl Table {
+    pub fn columns(&self) -> &'static [Column] {
+        match self {
+            Table::Checkpoints => CHECKPOINT_COLUMNS,
+            Table::Transactions => TRANSACTION_COLUMNS,
+            Table::Effects => EFFECTS_COLUMNS,
+            Table::Events => EVENT_COLUMNS,
+            Table::MoveCalls => MOVE_CALL_COLUMNS,
+            Table::Objects => OBJECT_COLUMNS,
+        }
+    }
+
+    /// Rows of this table for the given checkpoint, in the order of [Table::columns].
+    pub fn rows(&self, checkpoint: &CheckpointData) -> Vec<Row> {
+        match self {
+            Table::Checkpoints => vec![checkpoint_row(checkpoint)],
+            Table::Transactions => checkpoint
+                .transactions
+                .iter()
+                .map(|tx| transaction_row(&checkpoint.summary, tx))
+                .collect(),
+            Table::Effects => checkpoint
+                .transactions
+                .iter()
+                .map(|tx| effects_row(&checkpoint.summary, &tx.effects))
+                .collect(),
+            Table::Events => checkpoint
+                .transactions
+                .iter()
+                .flat_map(|tx| event_rows(&checkpoint.summary, tx))
+                .collect(),
+            Table::MoveCalls => checkpoint
+                .transactions
+                .iter()
+                .flat_map(|tx| move_call_rows(&checkpoint.summary, tx))
+                .collect(),
+            Table::Objects => checkpoint
+                .transactions
+                .iter()
+                .flat_map(|tx| object_rows(&checkpoint.summary, &tx.effects))
+                .collect(),
+        }
+    }
+}
+
+fn checkpoint_row(checkpoint: &CheckpointData) -> Row {
+    let summary = &checkpoint.summary;
+    let gas = &summary.epoch_rolling_gas_cost_summary;
+    vec![
+        Value::u64(summary.epoch),
+        Value::u64(summary.sequence_number),
+        Value::str(summary.digest()),
+        Value::opt_str(summary.previous_digest),
+        Value::u64(summary.timestamp_ms),
+        Value::u64(summary.network_total_transactions),
+        Value::u64(checkpoint.transactions.len() as u64),
+        Value::u64(gas.computation_cost),
+        Value::u64(gas.storage_cost),
+        Value::u64(gas.storage_rebate),
+        Value::u64(gas.non_refundable_storage_fee),
+        Value::Bool(Some(summary.end_of_epoch_data.is_some())),
+    ]
+}
+
+fn transaction_row(summary: &CertifiedCheckpointSummary, tx: &CheckpointTransaction) -> Row {
+    let (kind, num_commands) = match tx.data.kind() {
+        TransactionKind::ProgrammableTransaction(pt) => {
+            ("ProgrammableTransaction", pt.commands.len() as u64)
+        }
+        TransactionKind::ChangeEpoch(_) => ("ChangeEpoch", 0),
+        TransactionKind::Genesis(_) => ("Genesis", 0),
+        TransactionKind::ConsensusCommitPrologue(_) => ("ConsensusCommitPrologue", 0),
+    };
+    vec![
+        Value::u64(summary.sequence_number),
+        Value::u64(summary.epoch),
+        Value::u64(summary.timestamp_ms),
+        Value::str(tx.effects.transaction_digest()),
+        Value::str(kind),
+        Value::str(tx.data.sender()),
+        Value::str(tx.data.gas_owner()),
+        Value::u64(tx.data.gas_budget()),
+        Value::u64(tx.data.gas_price()),
+        Value::u64(num_commands),
+    ]
+}
+
+fn effects_row(summary: &CertifiedCheckpointSummary, effects: &TransactionEffects) -> Row {
+    let (success, error) = match effects.status() {
+        ExecutionStatus::Success => (true, None),
+        ExecutionStatus::Failure { error, command } => (
+            false,
+            Some(match command {
+                Some(command) => format!("{error:?} in command {command}"),
+                None => format!("{error:?}"),
+            }),
+        ),
+    };
+    let gas = effects.gas_cost_summary();
+    vec![
+        Value::u64(summary.sequence_number),
+        Value::str(effects.transaction_digest()),
+        Value::Bool(Some(success)),
+        Value::Str(error),
+        Value::u64(gas.computation_cost),
+        Value::u64(gas.storage_cost),
+        Value::u64(gas.storage_rebate),
+        Value::u64(gas.non_refundable_storage_fee),
+        Value::u64(effects.created().len() as u64),
+        Value::u64(effects.mutated().len() as u64),
+        Value::u64(effects.deleted().len() as u64),
+        Value::opt_str(effects.events_digest()),
+    ]
+}
+
+fn event_rows(summary: &CertifiedCheckpointSummary, tx: &CheckpointTransaction) -> Vec<Row> {
+    let Some(events) = &tx.events else {
+        return vec![];
+    };
+    events
+        .data
+        .iter()
+        .enumerate()
+        .map(|(seq, event)| {
+            vec![
+                Value::u64(summary.sequence_number),
+                Value::str(tx.effects.transaction_digest()),
+                Value::u64(seq as u64),
+                Value::str(event.package_id),
+                Value::str(&event.transaction_module),
+                Value::str(event.sender),
+                Value::str(&event.type_),
+                Value::str(hex::encode(&event.contents)),
+            ]
+        })
+        .collect()
+}
+
+fn move_call_rows(summary: &CertifiedCheckpointSummary, tx: &CheckpointTransaction) -> Vec<Row> {
+    tx.data
+        .move_calls()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (package, module, function))| {
+            vec![
+                Value::u64(summary.sequence_number),
+                Value::str(tx.effects.transaction_digest()),
+                Value::u64(index as u64),
+                Value::str(package),
+                Value::str(module),
+                Value::str(function),
+            ]
+        })
+        .collect()
+}
+
+fn object_rows(summary: &CertifiedCheckpointSummary, effects: &TransactionEffects) -> Vec<Row> {
+    let written = [
+        ("created", effects.created()),
+        ("mutated", effects.mutated()),
+        ("unwrapped", effects.unwrapped()),
+    ]
+    .into_iter()
+    .flat_map(|(status, objects)| {
+        objects
+            .iter()
+            .map(move |(object_ref, owner)| (status, object_ref, Some(owner)))
+    });
+    let removed = [
+        ("deleted", effects.deleted()),
+        ("wrapped", effects.wrapped()),
+        ("unwrapped_then_deleted", effects.unwrapped_then_deleted()),
+    ]
+    .into_iter()
+    .flat_map(|(status, objects)| {
+        objects
+            .iter()
+            .map(move |object_ref| (status, object_ref, None::<&Owner>))
+    });
+    written
+        .chain(removed)
+        .map(|(status, (object_id, version, digest), owner)| {
+            vec![
+                Value::u64(summary.sequence_number),
+                Value::str(effects.transaction_digest()),
+                Value::str(object_id),
+                Value::u64(version.value()),
+                Value::str(digest),
+                Value::str(status),
+                Value::opt_str(owner),
+            ]
+        })
+        .collect()
+}
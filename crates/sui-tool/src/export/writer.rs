@@ -0,0 +1,196 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Writers for the exported table files and the export watermark.
+//!
+//! Files are laid out as `<output-dir>/<table>/epoch=<epoch>/<first>_<last>.<ext>` where `first`
+//! and `last` are the inclusive checkpoint range covered by the file. The watermark file records
+//! the next checkpoint to export and is only advanced once all tables of a range are written.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow_array::{ArrayRef, BooleanArray, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use clap::ValueEnum;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::info;
+
+use super::tables::{ColumnType, Row, Table, Value};
+
+const WATERMARK_FILE: &str = "watermark.json";
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+pub enum ExportFormat {
+    Parquet,
+    Csv,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Watermark {
+    next_checkpoint: CheckpointSequenceNumber,
+}
+
+pub fn read_watermark(output_dir: &Path) -> Result<Option<CheckpointSequenceNumber>> {
+    let path = output_dir.join(WATERMARK_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let watermark: Watermark = serde_json::from_slice(&fs::read(&path)?)
+        .with_context(|| format!("Failed to parse watermark file {}", path.display()))?;
+    Ok(Some(watermark.next_checkpoint))
+}
+
+pub fn write_watermark(output_dir: &Path, next_checkpoint: CheckpointSequenceNumber) -> Result<()> {
+    write_atomically(&output_dir.join(WATERMARK_FILE), |path| {
+        fs::write(path, serde_json::to_vec(&Watermark { next_checkpoint })?)?;
+        Ok(())
+    })
+}
+
+/// Remove files written past the watermark by an interrupted export, they will be written again.
+pub fn remove_files_from(output_dir: &Path, first: CheckpointSequenceNumber) -> Result<()> {
+    for table_dir in read_dirs(output_dir)? {
+        for partition_dir in read_dirs(&table_dir)? {
+            for entry in fs::read_dir(&partition_dir)? {
+                let path = entry?.path();
+                let file_first = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.split('_').next())
+                    .and_then(|first| first.parse::<CheckpointSequenceNumber>().ok());
+                if file_first.map_or(false, |file_first| file_first >= first) {
+                    info!("Removing stale export file {}", path.display());
+                    fs::remove_file(&path)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_dirs(path: &Path) -> Result<Vec<PathBuf>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut dirs = vec![];
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    Ok(dirs)
+}
+
+/// Write the rows of one table for an inclusive checkpoint range of an epoch.
+pub fn write_table_file(
+    output_dir: &Path,
+    format: ExportFormat,
+    table: Table,
+    epoch: u64,
+    (first, last): (CheckpointSequenceNumber, CheckpointSequenceNumber),
+    rows: &[Row],
+) -> Result<()> {
+    let dir = output_dir
+        .join(table.to_string())
+        .join(format!("epoch={epoch}"));
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{first}_{last}.{}", format.extension()));
+    write_atomically(&path, |path| match format {
+        ExportFormat::Parquet => write_parquet(path, table, rows),
+        ExportFormat::Csv => write_csv(path, table, rows),
+    })
+}
+
+fn write_atomically(path: &Path, write: impl FnOnce(&Path) -> Result<()>) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    write(&tmp_path)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn write_csv(path: &Path, table: Table, rows: &[Row]) -> Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(table.columns().iter().map(|column| column.name))?;
+    for row in rows {
+        writer.write_record(row.iter().map(Value::to_csv_field))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_parquet(path: &Path, table: Table, rows: &[Row]) -> Result<()> {
+    let columns = table.columns();
+    let schema = Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|column| {
+                let data_type = match column.ty {
+                    ColumnType::U64 => DataType::UInt64,
+                    ColumnType::Str => DataType::Utf8,
+                    ColumnType::Bool => DataType::Boolean,
+                };
+                Field::new(column.name, data_type, column.nullable)
+            })
+            .collect::<Vec<_>>(),
+    ));
+    let arrays = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let values = rows.iter().map(|row| &row[index]);
+            let array: ArrayRef = match column.ty {
+                ColumnType::U64 => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::U64(v) => *v,
+                            _ => None,
+                        })
+                        .collect::<UInt64Array>(),
+                ),
+                ColumnType::Str => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Str(v) => v.clone(),
+                            _ => None,
+                        })
+                        .collect::<StringArray>(),
+                ),
+                ColumnType::Bool => Arc::new(
+                    values
+                        .map(|value| match value {
+                            Value::Bool(v) => *v,
+                            _ => None,
+                        })
+                        .collect::<BooleanArray>(),
+                ),
+            };
+            array
+        })
+        .collect::<Vec<_>>();
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(fs::File::create(path)?, schema, Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
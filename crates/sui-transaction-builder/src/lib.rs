@@ -13,13 +13,15 @@ use move_binary_format::file_format::SignatureToken;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
 
-use sui_json::{resolve_move_function_args, ResolvedCallArg, SuiJsonValue};
+use sui_json::{resolve_move_function_args_partial, ResolvedCallArg, SuiJsonValue};
 use sui_json_rpc_types::{
-    RPCTransactionRequestParams, SuiData, SuiObjectDataOptions, SuiObjectResponse, SuiRawData,
-    SuiTypeTag,
+    DryRunTransactionBlockResponse, RPCTransactionRequestParams, SuiData, SuiExecutionStatus,
+    SuiObjectDataOptions, SuiObjectResponse, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTypeTag,
 };
 use sui_protocol_config::ProtocolConfig;
-use sui_types::base_types::{ObjectID, ObjectInfo, ObjectRef, ObjectType, SuiAddress};
+use sui_types::base_types::{
+    random_object_ref, ObjectID, ObjectInfo, ObjectRef, ObjectType, SuiAddress,
+};
 use sui_types::error::UserInputError;
 use sui_types::gas_coin::GasCoin;
 use sui_types::governance::{ADD_STAKE_MUL_COIN_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
@@ -28,10 +30,13 @@ use sui_types::object::{Object, Owner};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{
-    Argument, CallArg, Command, InputObjectKind, ObjectArg, TransactionData, TransactionKind,
+    Argument, CallArg, Command, InputObjectKind, ObjectArg, ProgrammableTransaction,
+    TransactionData, TransactionDataAPI, TransactionKind,
 };
 use sui_types::{coin, fp_ensure, SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
 
+pub mod offline;
+
 #[async_trait]
 pub trait DataReader {
     async fn get_owned_objects(
@@ -47,14 +52,71 @@ pub trait DataReader {
     ) -> Result<SuiObjectResponse, anyhow::Error>;
 
     async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error>;
+
+    /// Maximum gas budget of a transaction in the current epoch.
+    async fn get_max_tx_gas(&self) -> Result<u64, anyhow::Error>;
+
+    async fn dry_run_transaction_block(
+        &self,
+        tx: TransactionData,
+    ) -> Result<DryRunTransactionBlockResponse, anyhow::Error>;
+}
+
+/// Gas budget of a transaction built by the `TransactionBuilder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasBudget {
+    /// Budget given by the caller, in MIST.
+    Fixed(u64),
+    /// Budget estimated by dry running the transaction: the gas it uses, computation and storage,
+    /// increased by `margin_percent` percent. The gas coin is then selected to cover it.
+    Auto { margin_percent: u64 },
+}
+
+impl GasBudget {
+    pub const DEFAULT_MARGIN_PERCENT: u64 = 20;
+
+    pub fn auto() -> Self {
+        Self::Auto {
+            margin_percent: Self::DEFAULT_MARGIN_PERCENT,
+        }
+    }
+}
+
+impl From<u64> for GasBudget {
+    fn from(budget: u64) -> Self {
+        Self::Fixed(budget)
+    }
+}
+
+/// Argument of a Move call added to a programmable transaction, either a JSON value resolved
+/// against the type of the parameter, or an argument already in the transaction, e.g. the result
+/// of an earlier command.
+#[derive(Debug, Clone)]
+pub enum MoveCallArg {
+    Json(SuiJsonValue),
+    Argument(Argument),
 }
 
 #[derive(Clone)]
-pub struct TransactionBuilder(Arc<dyn DataReader + Sync + Send>);
+pub struct TransactionBuilder {
+    data_reader: Arc<dyn DataReader + Sync + Send>,
+    sponsor: Option<SuiAddress>,
+}
 
 impl TransactionBuilder {
     pub fn new(data_reader: Arc<dyn DataReader + Sync + Send>) -> Self {
-        Self(data_reader)
+        Self {
+            data_reader,
+            sponsor: None,
+        }
+    }
+
+    /// Build sponsored transactions, whose gas is paid by `sponsor` rather than by the sender:
+    /// gas coins are selected among the coins of `sponsor`, and given gas coins must be owned by
+    /// it. Transactions paying gas with the coins they send, e.g. `pay_sui`, are not sponsored.
+    pub fn with_sponsor(mut self, sponsor: SuiAddress) -> Self {
+        self.sponsor = Some(sponsor);
+        self
     }
 
     async fn select_gas(
@@ -71,11 +133,14 @@ impl TransactionBuilder {
         if let Some(gas) = input_gas {
             self.get_object_ref(gas).await
         } else {
-            let gas_objs = self.0.get_owned_objects(signer, GasCoin::type_()).await?;
+            let gas_objs = self
+                .data_reader
+                .get_owned_objects(signer, GasCoin::type_())
+                .await?;
 
             for obj in gas_objs {
                 let response = self
-                    .0
+                    .data_reader
                     .get_object_with_options(obj.object_id, SuiObjectDataOptions::new().with_bcs())
                     .await?;
                 let obj = response.object()?;
@@ -95,29 +160,100 @@ impl TransactionBuilder {
         }
     }
 
+    /// Resolve `gas_budget` to the budget of the transaction `build` makes out of a budget. An
+    /// automatic budget is estimated from the transaction built with the maximum budget.
+    async fn resolve_gas_budget(
+        &self,
+        gas_budget: GasBudget,
+        build: impl FnOnce(u64) -> anyhow::Result<TransactionData>,
+    ) -> anyhow::Result<u64> {
+        let margin_percent = match gas_budget {
+            GasBudget::Fixed(budget) => return Ok(budget),
+            GasBudget::Auto { margin_percent } => margin_percent,
+        };
+        let tx = build(self.data_reader.get_max_tx_gas().await?)?;
+        let (signer, gas_price) = (tx.sender(), tx.gas_price());
+        self.estimate_gas_budget(signer, tx.into_kind(), gas_price, margin_percent)
+            .await
+    }
+
+    /// Build the transaction `build` makes out of its gas payment and budget, paying gas with
+    /// `gas` or else with a coin of `signer`, or of the sponsor, covering the budget that is not
+    /// one of `input_objects`.
+    async fn finish_transaction(
+        &self,
+        signer: SuiAddress,
+        gas: Option<ObjectID>,
+        gas_budget: GasBudget,
+        input_objects: Vec<ObjectID>,
+        gas_price: u64,
+        build: impl Fn(ObjectRef, u64) -> anyhow::Result<TransactionData>,
+    ) -> anyhow::Result<TransactionData> {
+        // The dry run estimating an automatic budget pays gas with a mock coin, so the payment of
+        // the transaction it is given does not matter.
+        let gas_budget = self
+            .resolve_gas_budget(gas_budget, |budget| build(random_object_ref(), budget))
+            .await?;
+        let gas_owner = self.sponsor.unwrap_or(signer);
+        let gas = self
+            .select_gas(gas_owner, gas, gas_budget, input_objects, gas_price)
+            .await?;
+        let mut tx = build(gas, gas_budget)?;
+        tx.gas_data_mut().owner = gas_owner;
+        Ok(tx)
+    }
+
+    /// Estimate the gas budget of a transaction of `kind` sent by `signer`, by dry running it with
+    /// a mock gas coin and adding `margin_percent` percent to the gas it uses.
+    pub async fn estimate_gas_budget(
+        &self,
+        signer: SuiAddress,
+        kind: TransactionKind,
+        gas_price: u64,
+        margin_percent: u64,
+    ) -> anyhow::Result<u64> {
+        let max_budget = self.data_reader.get_max_tx_gas().await?;
+        let tx = TransactionData::new_with_gas_coins(kind, signer, vec![], max_budget, gas_price);
+        let response = self.data_reader.dry_run_transaction_block(tx).await?;
+        if let SuiExecutionStatus::Failure { error } = response.effects.status() {
+            bail!("Cannot estimate the gas budget, the transaction fails when dry run: {error}");
+        }
+        let summary = response.effects.gas_cost_summary();
+        // Storage is charged in full before the rebate is credited, so the budget covers both.
+        let used = summary.computation_cost + summary.storage_cost;
+        Ok((used.saturating_mul(100 + margin_percent) / 100).min(max_budget))
+    }
+
     pub async fn transfer_object(
         &self,
         signer: SuiAddress,
         object_id: ObjectID,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
         recipient: SuiAddress,
     ) -> anyhow::Result<TransactionData> {
         let mut builder = ProgrammableTransactionBuilder::new();
         self.single_transfer_object(&mut builder, object_id, recipient)
             .await?;
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, vec![object_id], gas_price)
-            .await?;
-
-        Ok(TransactionData::new(
-            TransactionKind::programmable(builder.finish()),
+        let pt = builder.finish();
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
             signer,
             gas,
-            gas_budget,
+            gas_budget.into(),
+            vec![object_id],
             gas_price,
-        ))
+            |gas, gas_budget| {
+                Ok(TransactionData::new(
+                    TransactionKind::programmable(pt.clone()),
+                    signer,
+                    gas,
+                    gas_budget,
+                    gas_price,
+                ))
+            },
+        )
+        .await
     }
 
     async fn single_transfer_object(
@@ -134,15 +270,21 @@ impl TransactionBuilder {
         &self,
         signer: SuiAddress,
         sui_object_id: ObjectID,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
         recipient: SuiAddress,
         amount: Option<u64>,
     ) -> anyhow::Result<TransactionData> {
         let object = self.get_object_ref(sui_object_id).await?;
-        let gas_price = self.0.get_reference_gas_price().await?;
-        Ok(TransactionData::new_transfer_sui(
-            recipient, signer, amount, object, gas_budget, gas_price,
-        ))
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        let build = |gas_budget| {
+            TransactionData::new_transfer_sui(
+                recipient, signer, amount, object, gas_budget, gas_price,
+            )
+        };
+        let gas_budget = self
+            .resolve_gas_budget(gas_budget.into(), |gas_budget| Ok(build(gas_budget)))
+            .await?;
+        Ok(build(gas_budget))
     }
 
     pub async fn pay(
@@ -152,7 +294,7 @@ impl TransactionBuilder {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         if let Some(gas) = gas {
             if input_coins.contains(&gas) {
@@ -168,14 +310,26 @@ impl TransactionBuilder {
             .await
             .into_iter()
             .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, input_coins, gas_price)
-            .await?;
-
-        TransactionData::new_pay(
-            signer, coin_refs, recipients, amounts, gas, gas_budget, gas_price,
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
+            signer,
+            gas,
+            gas_budget.into(),
+            input_coins,
+            gas_price,
+            |gas, gas_budget| {
+                TransactionData::new_pay(
+                    signer,
+                    coin_refs.clone(),
+                    recipients.clone(),
+                    amounts.clone(),
+                    gas,
+                    gas_budget,
+                    gas_price,
+                )
+            },
         )
+        .await
     }
 
     pub async fn pay_sui(
@@ -184,7 +338,7 @@ impl TransactionBuilder {
         input_coins: Vec<ObjectID>,
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         fp_ensure!(
             !input_coins.is_empty(),
@@ -201,16 +355,20 @@ impl TransactionBuilder {
             .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
         // [0] is safe because input_coins is non-empty and coins are of same length as input_coins.
         let gas_object_ref = coin_refs.remove(0);
-        let gas_price = self.0.get_reference_gas_price().await?;
-        TransactionData::new_pay_sui(
-            signer,
-            coin_refs,
-            recipients,
-            amounts,
-            gas_object_ref,
-            gas_budget,
-            gas_price,
-        )
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        let build = |gas_budget| {
+            TransactionData::new_pay_sui(
+                signer,
+                coin_refs.clone(),
+                recipients.clone(),
+                amounts.clone(),
+                gas_object_ref,
+                gas_budget,
+                gas_price,
+            )
+        };
+        let gas_budget = self.resolve_gas_budget(gas_budget.into(), &build).await?;
+        build(gas_budget)
     }
 
     pub async fn pay_all_sui(
@@ -218,7 +376,7 @@ impl TransactionBuilder {
         signer: SuiAddress,
         input_coins: Vec<ObjectID>,
         recipient: SuiAddress,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         fp_ensure!(
             !input_coins.is_empty(),
@@ -236,15 +394,21 @@ impl TransactionBuilder {
             .collect::<anyhow::Result<Vec<ObjectRef>>>()?;
         // [0] is safe because input_coins is non-empty and coins are of same length as input_coins.
         let gas_object_ref = coin_refs.remove(0);
-        let gas_price = self.0.get_reference_gas_price().await?;
-        Ok(TransactionData::new_pay_all_sui(
-            signer,
-            coin_refs,
-            recipient,
-            gas_object_ref,
-            gas_budget,
-            gas_price,
-        ))
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        let build = |gas_budget| {
+            TransactionData::new_pay_all_sui(
+                signer,
+                coin_refs.clone(),
+                recipient,
+                gas_object_ref,
+                gas_budget,
+                gas_price,
+            )
+        };
+        let gas_budget = self
+            .resolve_gas_budget(gas_budget.into(), |gas_budget| Ok(build(gas_budget)))
+            .await?;
+        Ok(build(gas_budget))
     }
 
     pub async fn move_call(
@@ -256,7 +420,7 @@ impl TransactionBuilder {
         type_args: Vec<SuiTypeTag>,
         call_args: Vec<SuiJsonValue>,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         let mut builder = ProgrammableTransactionBuilder::new();
         self.single_move_call(
@@ -268,7 +432,19 @@ impl TransactionBuilder {
             call_args,
         )
         .await?;
-        let pt = builder.finish();
+        self.programmable(signer, builder.finish(), gas, gas_budget)
+            .await
+    }
+
+    /// Make a transaction out of the programmable transaction `pt`, paying gas with `gas` or else
+    /// with a gas coin of `signer` that is not an input of `pt`.
+    pub async fn programmable(
+        &self,
+        signer: SuiAddress,
+        pt: ProgrammableTransaction,
+        gas: Option<ObjectID>,
+        gas_budget: impl Into<GasBudget>,
+    ) -> anyhow::Result<TransactionData> {
         let input_objects = pt
             .input_objects()?
             .iter()
@@ -277,18 +453,24 @@ impl TransactionBuilder {
                 _ => None,
             })
             .collect();
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, input_objects, gas_price)
-            .await?;
-
-        Ok(TransactionData::new(
-            TransactionKind::programmable(pt),
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
             signer,
             gas,
-            gas_budget,
+            gas_budget.into(),
+            input_objects,
             gas_price,
-        ))
+            |gas, gas_budget| {
+                Ok(TransactionData::new(
+                    TransactionKind::programmable(pt.clone()),
+                    signer,
+                    gas,
+                    gas_budget,
+                    gas_price,
+                ))
+            },
+        )
+        .await
     }
 
     pub async fn single_move_call(
@@ -300,6 +482,29 @@ impl TransactionBuilder {
         type_args: Vec<SuiTypeTag>,
         call_args: Vec<SuiJsonValue>,
     ) -> anyhow::Result<()> {
+        self.programmable_move_call(
+            builder,
+            package,
+            module,
+            function,
+            type_args,
+            call_args.into_iter().map(MoveCallArg::Json).collect(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Add a Move call to `builder`, resolving its JSON arguments against the signature of the
+    /// function. Returns the result of the call.
+    pub async fn programmable_move_call(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        package: ObjectID,
+        module: &str,
+        function: &str,
+        type_args: Vec<SuiTypeTag>,
+        call_args: Vec<MoveCallArg>,
+    ) -> anyhow::Result<Argument> {
         let module = Identifier::from_str(module)?;
         let function = Identifier::from_str(function)?;
 
@@ -314,10 +519,23 @@ impl TransactionBuilder {
             )
             .await?;
 
-        builder.command(Command::move_call(
+        Ok(builder.command(Command::move_call(
             package, module, function, type_args, call_args,
-        ));
-        Ok(())
+        )))
+    }
+
+    /// Add object `id` as an input of `builder`, used by mutable reference if `is_mutable_ref`
+    /// when it is shared.
+    pub async fn object_input(
+        &self,
+        builder: &mut ProgrammableTransactionBuilder,
+        id: ObjectID,
+        is_mutable_ref: bool,
+    ) -> anyhow::Result<Argument> {
+        let obj_arg = self
+            .get_object_arg(id, &mut BTreeMap::new(), is_mutable_ref)
+            .await?;
+        builder.obj(obj_arg)
     }
 
     async fn get_object_arg(
@@ -327,7 +545,7 @@ impl TransactionBuilder {
         is_mutable_ref: bool,
     ) -> Result<ObjectArg, anyhow::Error> {
         let response = self
-            .0
+            .data_reader
             .get_object_with_options(id, SuiObjectDataOptions::bcs_lossless())
             .await?;
 
@@ -356,10 +574,10 @@ impl TransactionBuilder {
         module: &Identifier,
         function: &Identifier,
         type_args: &[TypeTag],
-        json_args: Vec<SuiJsonValue>,
+        call_args: Vec<MoveCallArg>,
     ) -> Result<Vec<Argument>, anyhow::Error> {
         let object = self
-            .0
+            .data_reader
             .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
             .await?
             .into_object()?;
@@ -375,7 +593,14 @@ impl TransactionBuilder {
             package.linkage_table,
         )?;
 
-        let json_args_and_tokens = resolve_move_function_args(
+        let json_args = call_args
+            .iter()
+            .map(|arg| match arg {
+                MoveCallArg::Json(json) => Some(json.clone()),
+                MoveCallArg::Argument(_) => None,
+            })
+            .collect();
+        let json_args_and_tokens = resolve_move_function_args_partial(
             &package,
             module.clone(),
             function.clone(),
@@ -385,7 +610,15 @@ impl TransactionBuilder {
 
         let mut args = Vec::new();
         let mut objects = BTreeMap::new();
-        for (arg, expected_type) in json_args_and_tokens {
+        for ((arg, expected_type), call_arg) in json_args_and_tokens.into_iter().zip(call_args) {
+            // Only JSON arguments are resolved, the others are already in the transaction
+            let Some(arg) = arg else {
+                match call_arg {
+                    MoveCallArg::Argument(arg) => args.push(arg),
+                    MoveCallArg::Json(json) => bail!("Unresolved JSON argument {:?}", json),
+                }
+                continue;
+            };
             args.push(match arg {
                 ResolvedCallArg::Pure(p) => builder.input(CallArg::Pure(p)),
 
@@ -420,20 +653,27 @@ impl TransactionBuilder {
         compiled_modules: Vec<Vec<u8>>,
         dep_ids: Vec<ObjectID>,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(sender, gas, gas_budget, vec![], gas_price)
-            .await?;
-        Ok(TransactionData::new_module(
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
             sender,
             gas,
-            compiled_modules,
-            dep_ids,
-            gas_budget,
+            gas_budget.into(),
+            vec![],
             gas_price,
-        ))
+            |gas, gas_budget| {
+                Ok(TransactionData::new_module(
+                    sender,
+                    gas,
+                    compiled_modules.clone(),
+                    dep_ids.clone(),
+                    gas_budget,
+                    gas_price,
+                ))
+            },
+        )
+        .await
     }
 
     pub async fn upgrade(
@@ -446,32 +686,39 @@ impl TransactionBuilder {
         upgrade_policy: u8,
         digest: Vec<u8>,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(sender, gas, gas_budget, vec![], gas_price)
-            .await?;
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
         let upgrade_cap = self
-            .0
+            .data_reader
             .get_object_with_options(upgrade_capability, SuiObjectDataOptions::new().with_owner())
             .await?
             .into_object()?;
         let cap_owner = upgrade_cap
             .owner
             .ok_or_else(|| anyhow!("Unable to determine ownership of upgrade capability"))?;
-        TransactionData::new_upgrade(
+        self.finish_transaction(
             sender,
             gas,
-            package_id,
-            compiled_modules,
-            dep_ids,
-            (upgrade_cap.object_ref(), cap_owner),
-            upgrade_policy,
-            digest,
-            gas_budget,
+            gas_budget.into(),
+            vec![],
             gas_price,
+            |gas, gas_budget| {
+                TransactionData::new_upgrade(
+                    sender,
+                    gas,
+                    package_id,
+                    compiled_modules.clone(),
+                    dep_ids.clone(),
+                    (upgrade_cap.object_ref(), cap_owner),
+                    upgrade_policy,
+                    digest.clone(),
+                    gas_budget,
+                    gas_price,
+                )
+            },
         )
+        .await
     }
 
     // TODO: consolidate this with Pay transactions
@@ -481,35 +728,42 @@ impl TransactionBuilder {
         coin_object_id: ObjectID,
         split_amounts: Vec<u64>,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         let coin = self
-            .0
+            .data_reader
             .get_object_with_options(coin_object_id, SuiObjectDataOptions::bcs_lossless())
             .await?
             .into_object()?;
         let coin_object_ref = coin.object_ref();
         let coin: Object = coin.try_into()?;
         let type_args = vec![coin.get_move_template_type()?];
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
-            .await?;
-
-        TransactionData::new_move_call(
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        let split_amounts = bcs::to_bytes(&split_amounts)?;
+        self.finish_transaction(
             signer,
-            SUI_FRAMEWORK_PACKAGE_ID,
-            coin::PAY_MODULE_NAME.to_owned(),
-            coin::PAY_SPLIT_VEC_FUNC_NAME.to_owned(),
-            type_args,
             gas,
-            vec![
-                CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_object_ref)),
-                CallArg::Pure(bcs::to_bytes(&split_amounts)?),
-            ],
-            gas_budget,
+            gas_budget.into(),
+            vec![coin_object_id],
             gas_price,
+            |gas, gas_budget| {
+                TransactionData::new_move_call(
+                    signer,
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    coin::PAY_MODULE_NAME.to_owned(),
+                    coin::PAY_SPLIT_VEC_FUNC_NAME.to_owned(),
+                    type_args.clone(),
+                    gas,
+                    vec![
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_object_ref)),
+                        CallArg::Pure(split_amounts.clone()),
+                    ],
+                    gas_budget,
+                    gas_price,
+                )
+            },
         )
+        .await
     }
 
     // TODO: consolidate this with Pay transactions
@@ -519,35 +773,42 @@ impl TransactionBuilder {
         coin_object_id: ObjectID,
         split_count: u64,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         let coin = self
-            .0
+            .data_reader
             .get_object_with_options(coin_object_id, SuiObjectDataOptions::bcs_lossless())
             .await?
             .into_object()?;
         let coin_object_ref = coin.object_ref();
         let coin: Object = coin.try_into()?;
         let type_args = vec![coin.get_move_template_type()?];
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, vec![coin_object_id], gas_price)
-            .await?;
-
-        TransactionData::new_move_call(
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        let split_count = bcs::to_bytes(&split_count)?;
+        self.finish_transaction(
             signer,
-            SUI_FRAMEWORK_PACKAGE_ID,
-            coin::PAY_MODULE_NAME.to_owned(),
-            coin::PAY_SPLIT_N_FUNC_NAME.to_owned(),
-            type_args,
             gas,
-            vec![
-                CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_object_ref)),
-                CallArg::Pure(bcs::to_bytes(&split_count)?),
-            ],
-            gas_budget,
+            gas_budget.into(),
+            vec![coin_object_id],
             gas_price,
+            |gas, gas_budget| {
+                TransactionData::new_move_call(
+                    signer,
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    coin::PAY_MODULE_NAME.to_owned(),
+                    coin::PAY_SPLIT_N_FUNC_NAME.to_owned(),
+                    type_args.clone(),
+                    gas,
+                    vec![
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_object_ref)),
+                        CallArg::Pure(split_count.clone()),
+                    ],
+                    gas_budget,
+                    gas_price,
+                )
+            },
         )
+        .await
     }
 
     // TODO: consolidate this with Pay transactions
@@ -557,10 +818,10 @@ impl TransactionBuilder {
         primary_coin: ObjectID,
         coin_to_merge: ObjectID,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         let coin = self
-            .0
+            .data_reader
             .get_object_with_options(primary_coin, SuiObjectDataOptions::bcs_lossless())
             .await?
             .into_object()?;
@@ -568,31 +829,31 @@ impl TransactionBuilder {
         let coin_to_merge_ref = self.get_object_ref(coin_to_merge).await?;
         let coin: Object = coin.try_into()?;
         let type_args = vec![coin.get_move_template_type()?];
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(
-                signer,
-                gas,
-                gas_budget,
-                vec![primary_coin, coin_to_merge],
-                gas_price,
-            )
-            .await?;
-
-        TransactionData::new_move_call(
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
             signer,
-            SUI_FRAMEWORK_PACKAGE_ID,
-            coin::PAY_MODULE_NAME.to_owned(),
-            coin::PAY_JOIN_FUNC_NAME.to_owned(),
-            type_args,
             gas,
-            vec![
-                CallArg::Object(ObjectArg::ImmOrOwnedObject(primary_coin_ref)),
-                CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_to_merge_ref)),
-            ],
-            gas_budget,
+            gas_budget.into(),
+            vec![primary_coin, coin_to_merge],
             gas_price,
+            |gas, gas_budget| {
+                TransactionData::new_move_call(
+                    signer,
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    coin::PAY_MODULE_NAME.to_owned(),
+                    coin::PAY_JOIN_FUNC_NAME.to_owned(),
+                    type_args.clone(),
+                    gas,
+                    vec![
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(primary_coin_ref)),
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_to_merge_ref)),
+                    ],
+                    gas_budget,
+                    gas_price,
+                )
+            },
         )
+        .await
     }
 
     pub async fn batch_transaction(
@@ -600,7 +861,7 @@ impl TransactionBuilder {
         signer: SuiAddress,
         single_transaction_params: Vec<RPCTransactionRequestParams>,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         fp_ensure!(
             !single_transaction_params.is_empty(),
@@ -629,27 +890,8 @@ impl TransactionBuilder {
                 }
             };
         }
-        let pt = builder.finish();
-        let all_inputs = pt.input_objects()?;
-        let inputs = all_inputs
-            .iter()
-            .flat_map(|obj| match obj {
-                InputObjectKind::ImmOrOwnedMoveObject((id, _, _)) => Some(*id),
-                _ => None,
-            })
-            .collect();
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, inputs, gas_price)
-            .await?;
-
-        Ok(TransactionData::new(
-            TransactionKind::programmable(pt),
-            signer,
-            gas,
-            gas_budget,
-            gas_price,
-        ))
+        self.programmable(signer, builder.finish(), gas, gas_budget)
+            .await
     }
 
     pub async fn request_add_stake(
@@ -659,13 +901,9 @@ impl TransactionBuilder {
         amount: Option<u64>,
         validator: SuiAddress,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, coins.clone(), gas_price)
-            .await?;
-
+        let input_coins = coins.clone();
         let mut obj_vec = vec![];
         let coin = coins
             .pop()
@@ -711,13 +949,24 @@ impl TransactionBuilder {
             ));
             builder.finish()
         };
-        Ok(TransactionData::new_programmable(
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
             signer,
-            vec![gas],
-            pt,
-            gas_budget,
+            gas,
+            gas_budget.into(),
+            input_coins,
             gas_price,
-        ))
+            |gas, gas_budget| {
+                Ok(TransactionData::new_programmable(
+                    signer,
+                    vec![gas],
+                    pt.clone(),
+                    gas_budget,
+                    gas_price,
+                ))
+            },
+        )
+        .await
     }
 
     pub async fn request_withdraw_stake(
@@ -725,27 +974,34 @@ impl TransactionBuilder {
         signer: SuiAddress,
         staked_sui: ObjectID,
         gas: Option<ObjectID>,
-        gas_budget: u64,
+        gas_budget: impl Into<GasBudget>,
     ) -> anyhow::Result<TransactionData> {
         let staked_sui = self.get_object_ref(staked_sui).await?;
-        let gas_price = self.0.get_reference_gas_price().await?;
-        let gas = self
-            .select_gas(signer, gas, gas_budget, vec![], gas_price)
-            .await?;
-        TransactionData::new_move_call(
+        let gas_price = self.data_reader.get_reference_gas_price().await?;
+        self.finish_transaction(
             signer,
-            SUI_SYSTEM_PACKAGE_ID,
-            SUI_SYSTEM_MODULE_NAME.to_owned(),
-            WITHDRAW_STAKE_FUN_NAME.to_owned(),
-            vec![],
             gas,
-            vec![
-                CallArg::SUI_SYSTEM_MUT,
-                CallArg::Object(ObjectArg::ImmOrOwnedObject(staked_sui)),
-            ],
-            gas_budget,
+            gas_budget.into(),
+            vec![],
             gas_price,
+            |gas, gas_budget| {
+                TransactionData::new_move_call(
+                    signer,
+                    SUI_SYSTEM_PACKAGE_ID,
+                    SUI_SYSTEM_MODULE_NAME.to_owned(),
+                    WITHDRAW_STAKE_FUN_NAME.to_owned(),
+                    vec![],
+                    gas,
+                    vec![
+                        CallArg::SUI_SYSTEM_MUT,
+                        CallArg::Object(ObjectArg::ImmOrOwnedObject(staked_sui)),
+                    ],
+                    gas_budget,
+                    gas_price,
+                )
+            },
         )
+        .await
     }
 
     // TODO: we should add retrial to reduce the transaction building error rate
@@ -760,7 +1016,7 @@ impl TransactionBuilder {
         object_id: ObjectID,
     ) -> anyhow::Result<(ObjectRef, ObjectType)> {
         let object = self
-            .0
+            .data_reader
             .get_object_with_options(object_id, SuiObjectDataOptions::new().with_type())
             .await?
             .into_object()?;
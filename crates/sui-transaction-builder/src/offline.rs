@@ -0,0 +1,136 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline transaction building, for cold-signing workflows where the machine holding the keys
+//! has no network access.
+//!
+//! An `OfflineState` is a snapshot of the objects a transaction may use, their references,
+//! owners, types and contents in BCS, which includes the modules of packages, and of the reference
+//! gas price. It is exported to a file from an online machine, e.g. with `sui client
+//! export-state`, and then serves as the `DataReader` of a `TransactionBuilder` on the offline
+//! machine. Transactions built from it are only valid as long as the objects they use are not
+//! modified on chain in the meantime.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use move_core_types::language_storage::StructTag;
+use serde::{Deserialize, Serialize};
+
+use sui_json_rpc_types::{
+    DryRunTransactionBlockResponse, SuiObjectData, SuiObjectDataOptions, SuiObjectResponse,
+};
+use sui_types::base_types::{ObjectID, ObjectInfo, ObjectType, SuiAddress};
+use sui_types::error::SuiObjectResponseError;
+use sui_types::object::Owner;
+use sui_types::transaction::TransactionData;
+
+use crate::DataReader;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct OfflineState {
+    pub reference_gas_price: u64,
+    objects: BTreeMap<ObjectID, SuiObjectData>,
+}
+
+impl OfflineState {
+    /// The options objects must be read with to be added to the state.
+    pub fn object_options() -> SuiObjectDataOptions {
+        SuiObjectDataOptions::bcs_lossless()
+    }
+
+    pub fn new(reference_gas_price: u64) -> Self {
+        Self {
+            reference_gas_price,
+            objects: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Can't read offline state from {:?}: {e}", path))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Can't deserialize offline state from {:?}: {e}", path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| anyhow!("Can't write offline state to {:?}: {e}", path))
+    }
+
+    /// Add `object`, read with `object_options`, to the state, replacing any older version.
+    pub fn insert_object(&mut self, object: SuiObjectData) -> Result<(), anyhow::Error> {
+        if object.type_.is_none() || object.owner.is_none() || object.bcs.is_none() {
+            return Err(anyhow!(
+                "Object {} must be read with its type, owner and BCS to be used offline",
+                object.object_id
+            ));
+        }
+        self.objects.insert(object.object_id, object);
+        Ok(())
+    }
+
+    pub fn get_object(&self, object_id: &ObjectID) -> Option<&SuiObjectData> {
+        self.objects.get(object_id)
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = &SuiObjectData> {
+        self.objects.values()
+    }
+}
+
+#[async_trait]
+impl DataReader for OfflineState {
+    async fn get_owned_objects(
+        &self,
+        address: SuiAddress,
+        object_type: StructTag,
+    ) -> Result<Vec<ObjectInfo>, anyhow::Error> {
+        self.objects
+            .values()
+            .filter(|object| match &object.type_ {
+                Some(ObjectType::Struct(type_)) => {
+                    type_.is(&object_type) && object.owner == Some(Owner::AddressOwner(address))
+                }
+                _ => false,
+            })
+            .map(|object| SuiObjectResponse::new_with_data(object.clone()).try_into())
+            .collect()
+    }
+
+    async fn get_object_with_options(
+        &self,
+        object_id: ObjectID,
+        _options: SuiObjectDataOptions,
+    ) -> Result<SuiObjectResponse, anyhow::Error> {
+        // Objects are returned with all the fields of the state, whatever the options.
+        Ok(match self.objects.get(&object_id) {
+            Some(object) => SuiObjectResponse::new_with_data(object.clone()),
+            None => {
+                SuiObjectResponse::new_with_error(SuiObjectResponseError::NotExists { object_id })
+            }
+        })
+    }
+
+    async fn get_reference_gas_price(&self) -> Result<u64, anyhow::Error> {
+        Ok(self.reference_gas_price)
+    }
+
+    async fn get_max_tx_gas(&self) -> Result<u64, anyhow::Error> {
+        Err(anyhow!(
+            "The protocol config is not part of the offline state, a gas budget must be given"
+        ))
+    }
+
+    async fn dry_run_transaction_block(
+        &self,
+        _tx: TransactionData,
+    ) -> Result<DryRunTransactionBlockResponse, anyhow::Error> {
+        Err(anyhow!(
+            "Transactions can't be dry run offline, a gas budget must be given"
+        ))
+    }
+}
@@ -1156,6 +1156,7 @@ impl<'a> SuiTestAdapter<'a> {
                         *QUERY_MAX_RESULT_LIMIT,
                         /* descending */ false,
                     )
+                    .map(|result| result.events)
                     .unwrap_or_default()
                     .into_iter()
                     .map(|sui_event| sui_event.into())
@@ -1195,7 +1196,7 @@ impl<'a> SuiTestAdapter<'a> {
     ) -> anyhow::Result<TxnSummary> {
         let results = self
             .fullnode
-            .dev_inspect_transaction_block(sender, transaction_kind, gas_price)
+            .dev_inspect_transaction_block(sender, transaction_kind, gas_price, false)
             .await?;
         let DevInspectResults {
             effects, events, ..
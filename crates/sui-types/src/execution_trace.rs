@@ -0,0 +1,293 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured trace of the execution of a programmable transaction, recorded on request by
+//! dev-inspect. For every command it records the Move calls made, with their arguments and return
+//! values, the objects read and the events emitted, in the order they happened.
+
+use std::fmt::Write;
+
+use fastcrypto::encoding::{Encoding, Hex};
+use move_core_types::{account_address::AccountAddress, language_storage::StructTag, u256::U256};
+use move_vm_types::views::{ValueView, ValueVisitor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::base_types::{ObjectID, SequenceNumber};
+use crate::sui_serde::{SequenceNumber as AsSequenceNumber, SuiStructTag};
+
+/// Trace of the execution of a transaction, command by command.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionTrace {
+    pub commands: Vec<CommandTrace>,
+    /// Objects written or deleted by the transaction, known once all commands have run.
+    pub object_changes: Vec<TracedObjectChange>,
+
+    /// Functions currently executing, innermost last.
+    #[serde(skip)]
+    call_stack: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTrace {
+    /// The command, prefixed with its index in the transaction.
+    pub command: String,
+    /// What happened while executing the command, in order.
+    pub events: Vec<TraceEvent>,
+    /// Set if the command failed, in which case it is the last one of the trace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TraceEvent {
+    /// A Move function was called, `depth` is 0 for the function called by the command.
+    #[serde(rename_all = "camelCase")]
+    CallStart {
+        depth: usize,
+        function: String,
+        arguments: Vec<String>,
+    },
+    /// A Move function returned.
+    #[serde(rename_all = "camelCase")]
+    CallEnd {
+        depth: usize,
+        function: String,
+        return_values: Vec<String>,
+    },
+    /// An object was used by the command, either as an input or as a child object.
+    #[serde(rename_all = "camelCase")]
+    ObjectRead {
+        object_id: ObjectID,
+        #[schemars(with = "AsSequenceNumber")]
+        #[serde_as(as = "AsSequenceNumber")]
+        version: SequenceNumber,
+        access: ObjectAccess,
+    },
+    /// An event was emitted.
+    #[serde(rename_all = "camelCase")]
+    Event {
+        #[schemars(with = "String")]
+        #[serde_as(as = "SuiStructTag")]
+        event_type: StructTag,
+        contents: String,
+    },
+}
+
+/// How an object was used by a command, `Child` if it was loaded as a child object, e.g. a
+/// dynamic field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ObjectAccess {
+    ByImmutableReference,
+    ByMutableReference,
+    ByValue,
+    Child,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TracedObjectChange {
+    pub object_id: ObjectID,
+    pub deleted: bool,
+}
+
+impl ExecutionTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn start_command(&mut self, command: impl ToString) {
+        self.call_stack.clear();
+        self.commands.push(CommandTrace {
+            command: command.to_string(),
+            events: vec![],
+            error: None,
+        });
+    }
+
+    pub fn fail_command(&mut self, error: impl ToString) {
+        if let Some(command) = self.commands.last_mut() {
+            command.error = Some(error.to_string());
+        }
+    }
+
+    pub fn call_start(&mut self, function: String, arguments: Vec<String>) {
+        let depth = self.call_stack.len();
+        self.call_stack.push(function.clone());
+        self.record(TraceEvent::CallStart {
+            depth,
+            function,
+            arguments,
+        });
+    }
+
+    pub fn call_end(&mut self, return_values: Vec<String>) {
+        let Some(function) = self.call_stack.pop() else {
+            return;
+        };
+        let depth = self.call_stack.len();
+        self.record(TraceEvent::CallEnd {
+            depth,
+            function,
+            return_values,
+        });
+    }
+
+    pub fn object_read(
+        &mut self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        access: ObjectAccess,
+    ) {
+        self.record(TraceEvent::ObjectRead {
+            object_id,
+            version,
+            access,
+        });
+    }
+
+    pub fn event(&mut self, event_type: StructTag, contents: impl ValueView) {
+        self.record(TraceEvent::Event {
+            event_type,
+            contents: render_value(contents),
+        });
+    }
+
+    pub fn object_change(&mut self, object_id: ObjectID, deleted: bool) {
+        self.object_changes
+            .push(TracedObjectChange { object_id, deleted });
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        if let Some(command) = self.commands.last_mut() {
+            command.events.push(event);
+        }
+    }
+}
+
+/// Render a Move value, e.g. `{ 0x2, [1, 2], &true }`. Byte vectors are rendered in hex.
+pub fn render_value(value: impl ValueView) -> String {
+    let mut renderer = ValueRenderer::default();
+    value.visit(&mut renderer);
+    renderer.out
+}
+
+struct Container {
+    close: &'static str,
+    remaining: usize,
+    first: bool,
+}
+
+#[derive(Default)]
+struct ValueRenderer {
+    out: String,
+    open: Vec<Container>,
+}
+
+impl ValueRenderer {
+    fn leaf(&mut self, val: impl std::fmt::Display) {
+        self.separate();
+        let _ = write!(self.out, "{val}");
+        self.complete();
+    }
+
+    fn container(&mut self, open: &'static str, close: &'static str, len: usize) {
+        self.separate();
+        if len == 0 {
+            self.out.push_str(open.trim_end());
+            self.out.push_str(close.trim_start());
+            self.complete();
+        } else {
+            self.out.push_str(open);
+            self.open.push(Container {
+                close,
+                remaining: len,
+                first: true,
+            });
+        }
+    }
+
+    fn separate(&mut self) {
+        if let Some(container) = self.open.last_mut() {
+            if !container.first {
+                self.out.push_str(", ");
+            }
+            container.first = false;
+        }
+    }
+
+    /// A value was fully rendered, close the containers it completes.
+    fn complete(&mut self) {
+        while let Some(container) = self.open.last_mut() {
+            container.remaining -= 1;
+            if container.remaining != 0 {
+                break;
+            }
+            self.out.push_str(container.close);
+            self.open.pop();
+        }
+    }
+}
+
+impl ValueVisitor for ValueRenderer {
+    fn visit_u8(&mut self, _depth: usize, val: u8) {
+        self.leaf(val);
+    }
+
+    fn visit_u16(&mut self, _depth: usize, val: u16) {
+        self.leaf(val);
+    }
+
+    fn visit_u32(&mut self, _depth: usize, val: u32) {
+        self.leaf(val);
+    }
+
+    fn visit_u64(&mut self, _depth: usize, val: u64) {
+        self.leaf(val);
+    }
+
+    fn visit_u128(&mut self, _depth: usize, val: u128) {
+        self.leaf(val);
+    }
+
+    fn visit_u256(&mut self, _depth: usize, val: U256) {
+        self.leaf(val);
+    }
+
+    fn visit_bool(&mut self, _depth: usize, val: bool) {
+        self.leaf(val);
+    }
+
+    fn visit_address(&mut self, _depth: usize, val: AccountAddress) {
+        self.leaf(val.to_hex_literal());
+    }
+
+    fn visit_struct(&mut self, _depth: usize, len: usize) -> bool {
+        self.container("{ ", " }", len);
+        true
+    }
+
+    fn visit_vec(&mut self, _depth: usize, len: usize) -> bool {
+        self.container("[", "]", len);
+        true
+    }
+
+    fn visit_vec_u8(&mut self, _depth: usize, vals: &[u8]) {
+        self.leaf(format_args!("0x{}", Hex::encode(vals)));
+    }
+
+    fn visit_ref(&mut self, _depth: usize, _is_global: bool) -> bool {
+        // The referent is visited next and closes the reference.
+        self.container("&", "", 1);
+        true
+    }
+}
+
+#[cfg(test)]
+#[path = "unit_tests/execution_trace_tests.rs"]
+mod execution_trace_tests;
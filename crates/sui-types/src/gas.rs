@@ -8,7 +8,7 @@ use crate::{
     digests::TransactionDigest,
     effects::{TransactionEffects, TransactionEffectsAPI},
     error::{ExecutionError, SuiResult, UserInputError, UserInputResult},
-    gas_model::{gas_v2::SuiGasStatus as SuiGasStatusV2, tables::GasStatus},
+    gas_model::{gas_v2::SuiGasStatus as SuiGasStatusV2, profile::GasProfile, tables::GasStatus},
     is_system_package,
     object::{Data, Object},
     storage::{DeleteKindWithOldVersion, WriteKind},
@@ -113,6 +113,22 @@ impl GasCharger {
         self.gas_status.summary()
     }
 
+    /// Record a gas profile of the execution of the transaction, to be retrieved with
+    /// `take_gas_profile` once gas has been charged.
+    pub fn enable_gas_profile(&mut self) {
+        let name = self.tx_digest.to_string();
+        self.move_gas_status_mut().enable_profile(name);
+    }
+
+    /// Return the gas profile of the execution, completed with the storage charges, if one was
+    /// being recorded.
+    pub fn take_gas_profile(&mut self) -> Option<GasProfile> {
+        let summary = self.summary();
+        let mut profile = self.move_gas_status_mut().take_profile()?;
+        profile.finish(&summary);
+        Some(profile)
+    }
+
     // This function is called when the transaction is about to be executed.
     // It will smash all gas coins into a single one and set the logical gas coin
     // to be the first one in the list.
@@ -205,7 +221,10 @@ impl GasCharger {
     }
 
     pub fn charge_publish_package(&mut self, size: usize) -> Result<(), ExecutionError> {
-        self.gas_status.charge_publish_package(size)
+        self.move_gas_status_mut().open_profile_frame("publish package");
+        let result = self.gas_status.charge_publish_package(size);
+        self.move_gas_status_mut().close_profile_frame();
+        result
     }
 
     pub fn charge_input_objects(
@@ -223,7 +242,10 @@ impl GasCharger {
             .filter(|(id, _)| !is_system_package(**id))
             .map(|(_, obj)| obj.object_size_for_gas_metering())
             .sum();
-        self.gas_status.charge_storage_read(total_size)
+        self.move_gas_status_mut().open_profile_frame("input objects");
+        let result = self.gas_status.charge_storage_read(total_size);
+        self.move_gas_status_mut().close_profile_frame();
+        result
     }
 
     /// Resets any mutations, deletions, and events recorded in the store, as well as any storage costs and
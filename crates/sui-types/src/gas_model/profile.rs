@@ -0,0 +1,258 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Gas profile of the execution of a transaction, in the speedscope file format
+//! (https://www.speedscope.app/file-format-schema.json) so that it can be viewed as a flamegraph.
+//!
+//! Computation is recorded as an evented profile where time is the computation gas consumed, in
+//! internal gas units (1000 per gas unit), with a frame for every command, Move function, native
+//! function and bytecode instruction. Storage cost and rebate are only known once execution is
+//! over and are recorded as a separate sampled profile, in MIST.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::gas::GasCostSummary;
+
+/// What opened a frame, which decides what closes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    /// Opened and closed by the execution layer, e.g. around a command. Closing it also closes
+    /// any frame left open above it by an aborted call.
+    Explicit,
+    /// Move function called from Move, closed when it returns.
+    Call,
+    /// Native function called from Move, closed once its cost is charged.
+    Native,
+    /// Single bytecode instruction, closed once it is charged.
+    Instruction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "SpeedscopeFrame")]
+struct Frame {
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "SpeedscopeShared")]
+struct Shared {
+    frames: Vec<Frame>,
+    #[serde(skip)]
+    frame_table: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "SpeedscopeEvent")]
+struct Event {
+    #[serde(rename = "type")]
+    ty: String,
+    frame: u64,
+    at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename = "SpeedscopeProfile", rename_all = "lowercase")]
+enum Profile {
+    #[serde(rename_all = "camelCase")]
+    Evented {
+        name: String,
+        unit: String,
+        start_value: u64,
+        end_value: u64,
+        events: Vec<Event>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Sampled {
+        name: String,
+        unit: String,
+        start_value: u64,
+        end_value: u64,
+        samples: Vec<Vec<u64>>,
+        weights: Vec<u64>,
+    },
+}
+
+/// Gas profile returned by dry runs when requested, to be saved as a `.json` file and opened at
+/// https://www.speedscope.app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfile {
+    exporter: String,
+    name: String,
+    active_profile_index: u64,
+    #[serde(rename = "$schema")]
+    schema: String,
+    shared: Shared,
+    profiles: Vec<Profile>,
+
+    /// Frames currently open, innermost last.
+    #[serde(skip)]
+    stack: Vec<(u64, FrameKind)>,
+    /// Computation gas consumed so far, in internal gas units.
+    #[serde(skip)]
+    consumed: u64,
+}
+
+impl GasProfile {
+    const OPEN_FRAME_IDENT: &'static str = "O";
+    const CLOSE_FRAME_IDENT: &'static str = "C";
+
+    /// Start a profile with a root frame named `name`, typically the transaction digest.
+    pub fn new(name: String) -> Self {
+        let mut profile = Self {
+            exporter: "speedscope@1.15.2".to_string(),
+            name: name.clone(),
+            active_profile_index: 0,
+            schema: "https://www.speedscope.app/file-format-schema.json".to_string(),
+            shared: Shared {
+                frames: vec![],
+                frame_table: HashMap::new(),
+            },
+            profiles: vec![Profile::Evented {
+                name: format!("computation of {name} (internal gas units)"),
+                unit: "none".to_string(),
+                start_value: 0,
+                end_value: 0,
+                events: vec![],
+            }],
+            stack: vec![],
+            consumed: 0,
+        };
+        profile.open_frame(name);
+        profile
+    }
+
+    pub(crate) fn record_gas(&mut self, amount: u64) {
+        self.consumed = self.consumed.saturating_add(amount);
+    }
+
+    /// Open a frame that stays open until the matching `close_frame`.
+    pub fn open_frame(&mut self, name: impl Display) {
+        self.push(name, FrameKind::Explicit);
+    }
+
+    /// Close the innermost frame opened with `open_frame`, along with the frames of any calls
+    /// that did not return because execution was aborted.
+    pub fn close_frame(&mut self) {
+        while let Some((_, kind)) = self.stack.last() {
+            let kind = *kind;
+            self.pop();
+            if kind == FrameKind::Explicit {
+                break;
+            }
+        }
+    }
+
+    pub(crate) fn open_call(&mut self, name: impl Display) {
+        self.push(name, FrameKind::Call);
+    }
+
+    /// The function whose call was just charged is native.
+    pub(crate) fn mark_native(&mut self) {
+        if let Some((_, kind @ FrameKind::Call)) = self.stack.last_mut() {
+            *kind = FrameKind::Native;
+        }
+    }
+
+    /// A Move function returned. The entry function of a command has no frame of its own, so
+    /// nothing is closed if the innermost frame was not opened by a call.
+    pub(crate) fn close_call(&mut self) {
+        if matches!(self.stack.last(), Some((_, FrameKind::Call))) {
+            self.pop();
+        }
+    }
+
+    pub(crate) fn close_native(&mut self) {
+        if matches!(self.stack.last(), Some((_, FrameKind::Native))) {
+            self.pop();
+        }
+    }
+
+    pub(crate) fn open_instruction(&mut self, name: impl Display) {
+        self.push(name, FrameKind::Instruction);
+    }
+
+    pub(crate) fn close_instruction(&mut self) {
+        if matches!(self.stack.last(), Some((_, FrameKind::Instruction))) {
+            self.pop();
+        }
+    }
+
+    /// Close all frames, including the root one, and record the storage charges of `summary`.
+    pub fn finish(&mut self, summary: &GasCostSummary) {
+        while !self.stack.is_empty() {
+            self.pop();
+        }
+
+        let mut samples = vec![];
+        let mut weights = vec![];
+        for (name, value) in [
+            ("storage cost", summary.storage_cost),
+            ("storage rebate", summary.storage_rebate),
+            (
+                "non-refundable storage fee",
+                summary.non_refundable_storage_fee,
+            ),
+        ] {
+            if value != 0 {
+                samples.push(vec![self.frame_index(name)]);
+                weights.push(value);
+            }
+        }
+        let total = weights.iter().sum();
+        self.profiles.push(Profile::Sampled {
+            name: format!("storage of {} (MIST)", self.name),
+            unit: "none".to_string(),
+            start_value: 0,
+            end_value: total,
+            samples,
+            weights,
+        });
+    }
+
+    fn frame_index(&mut self, name: impl Display) -> u64 {
+        let name = name.to_string();
+        if let Some(index) = self.shared.frame_table.get(&name) {
+            return *index;
+        }
+        let index = self.shared.frames.len() as u64;
+        self.shared.frames.push(Frame { name: name.clone() });
+        self.shared.frame_table.insert(name, index);
+        index
+    }
+
+    fn push(&mut self, name: impl Display, kind: FrameKind) {
+        let frame = self.frame_index(name);
+        self.event(Self::OPEN_FRAME_IDENT, frame);
+        self.stack.push((frame, kind));
+    }
+
+    fn pop(&mut self) {
+        if let Some((frame, _)) = self.stack.pop() {
+            self.event(Self::CLOSE_FRAME_IDENT, frame);
+        }
+    }
+
+    fn event(&mut self, ty: &str, frame: u64) {
+        let at = self.consumed;
+        if let Some(Profile::Evented {
+            events, end_value, ..
+        }) = self.profiles.first_mut()
+        {
+            events.push(Event {
+                ty: ty.to_string(),
+                frame,
+                at,
+            });
+            *end_value = at;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "../unit_tests/gas_profile_tests.rs"]
+mod gas_profile_tests;
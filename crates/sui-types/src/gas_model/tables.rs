@@ -2,13 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::BTreeMap;
+use std::fmt::Display;
 
 use move_binary_format::errors::{PartialVMError, PartialVMResult};
 
 use move_core_types::gas_algebra::{AbstractMemorySize, InternalGas, NumArgs, NumBytes};
 use move_core_types::language_storage::ModuleId;
 
+use crate::execution_trace::{render_value, ExecutionTrace};
 use crate::gas_model::gas_predicates::charge_input_as_memory;
+use crate::gas_model::profile::GasProfile;
 use move_core_types::vm_status::StatusCode;
 #[cfg(debug_assertions)]
 use move_vm_profiler::GasProfiler;
@@ -76,6 +79,12 @@ pub struct GasStatus {
 
     #[cfg(debug_assertions)]
     profiler: Option<GasProfiler>,
+
+    // Gas profile of the execution, only recorded when requested.
+    profile: Option<GasProfile>,
+
+    // Trace of the execution, only recorded when requested.
+    trace: Option<ExecutionTrace>,
 }
 
 impl GasStatus {
@@ -114,6 +123,8 @@ impl GasStatus {
             instructions_next_tier_start,
             #[cfg(debug_assertions)]
             profiler: None,
+            profile: None,
+            trace: None,
         }
     }
 
@@ -142,6 +153,8 @@ impl GasStatus {
             instructions_next_tier_start: None,
             #[cfg(debug_assertions)]
             profiler: None,
+            profile: None,
+            trace: None,
         }
     }
 
@@ -288,10 +301,12 @@ impl GasStatus {
 
         match self.gas_left.checked_sub(amount) {
             Some(gas_left) => {
+                self.record_profile_gas(amount);
                 self.gas_left = gas_left;
                 Ok(())
             }
             None => {
+                self.record_profile_gas(self.gas_left);
                 self.gas_left = InternalGas::new(0);
                 Err(PartialVMError::new(StatusCode::OUT_OF_GAS))
             }
@@ -327,6 +342,90 @@ impl GasStatus {
         };
         self.deduct_units(computation_cost)
     }
+
+    /// Start recording a gas profile of the execution, with a root frame named `name`.
+    pub fn enable_profile(&mut self, name: String) {
+        self.profile = Some(GasProfile::new(name));
+    }
+
+    /// Stop recording the gas profile and return it, if one was being recorded.
+    pub fn take_profile(&mut self) -> Option<GasProfile> {
+        self.profile.take()
+    }
+
+    /// Open a frame of the gas profile, e.g. around a command. Gas charged until the matching
+    /// `close_profile_frame` is attributed to it.
+    pub fn open_profile_frame(&mut self, name: impl Display) {
+        if let Some(profile) = &mut self.profile {
+            profile.open_frame(name);
+        }
+    }
+
+    pub fn close_profile_frame(&mut self) {
+        if let Some(profile) = &mut self.profile {
+            profile.close_frame();
+        }
+    }
+
+    /// Start recording a trace of the execution.
+    pub fn enable_trace(&mut self) {
+        self.trace = Some(ExecutionTrace::new());
+    }
+
+    /// Stop recording the execution trace and return it, if one was being recorded.
+    pub fn take_trace(&mut self) -> Option<ExecutionTrace> {
+        self.trace.take()
+    }
+
+    /// The execution trace being recorded, if any.
+    pub fn trace_mut(&mut self) -> Option<&mut ExecutionTrace> {
+        self.trace.as_mut()
+    }
+
+    fn record_profile_gas(&mut self, amount: InternalGas) {
+        if let Some(profile) = &mut self.profile {
+            profile.record_gas(amount.into());
+        }
+    }
+
+    /// Same as `charge`, attributing the cost to a frame for `instr` in the gas profile.
+    fn charge_instr(
+        &mut self,
+        instr: impl Display,
+        num_instructions: u64,
+        pushes: u64,
+        pops: u64,
+        incr_size: u64,
+        decr_size: u64,
+    ) -> PartialVMResult<()> {
+        if let Some(profile) = &mut self.profile {
+            profile.open_instruction(instr);
+        }
+        let result = self.charge(num_instructions, pushes, pops, incr_size, decr_size);
+        if let Some(profile) = &mut self.profile {
+            profile.close_instruction();
+        }
+        result
+    }
+
+    fn open_profile_call(&mut self, module_id: &ModuleId, func_name: &str) {
+        if let Some(profile) = &mut self.profile {
+            profile.open_call(format_args!(
+                "{}::{}",
+                module_id.short_str_lossless(),
+                func_name
+            ));
+        }
+    }
+
+    fn trace_call(&mut self, module_id: &ModuleId, func_name: &str, args: Option<Vec<String>>) {
+        if let (Some(trace), Some(args)) = (&mut self.trace, args) {
+            trace.call_start(
+                format!("{}::{}", module_id.short_str_lossless(), func_name),
+                args,
+            );
+        }
+    }
 }
 
 /// Returns a tuple of (<pops>, <pushes>, <stack_size_decrease>, <stack_size_increase>)
@@ -381,11 +480,25 @@ impl GasMeter for GasStatus {
     /// Charge an instruction and fail if not enough gas units are left.
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
         let (pops, pushes, pop_size, push_size) = get_simple_instruction_stack_change(instr);
-        self.charge(1, pushes, pops, push_size.into(), pop_size.into())
+        self.charge_instr(
+            format_args!("{:?}", instr),
+            1,
+            pushes,
+            pops,
+            push_size.into(),
+            pop_size.into(),
+        )
     }
 
     fn charge_pop(&mut self, popped_val: impl ValueView) -> PartialVMResult<()> {
-        self.charge(1, 0, 1, 0, popped_val.legacy_abstract_memory_size().into())
+        self.charge_instr(
+            "Pop",
+            1,
+            0,
+            1,
+            0,
+            popped_val.legacy_abstract_memory_size().into(),
+        )
     }
 
     fn charge_native_function(
@@ -400,9 +513,13 @@ impl GasMeter for GasStatus {
             .map(|ret_vals| ret_vals.len())
             .unwrap_or(0) as u64;
         // Calculate the number of bytes that are getting pushed onto the stack.
+        let mut rendered = self.trace.as_ref().map(|_| vec![]);
         let size_increase = ret_vals
             .map(|ret_vals| {
                 ret_vals.fold(AbstractMemorySize::zero(), |acc, elem| {
+                    if let Some(rendered) = &mut rendered {
+                        rendered.push(render_value(&elem));
+                    }
                     acc + elem.legacy_abstract_memory_size()
                 })
             })
@@ -410,9 +527,19 @@ impl GasMeter for GasStatus {
         // Charge for the stack operations. We don't count this as an "instruction" since we
         // already accounted for the `Call` instruction in the
         // `charge_native_function_before_execution` call.
-        self.charge(0, pushes, 0, size_increase.into(), 0)?;
-        // Now charge the gas that the native function told us to charge.
-        self.deduct_gas(amount)
+        let result = self
+            .charge(0, pushes, 0, size_increase.into(), 0)
+            .and_then(|()| {
+                // Now charge the gas that the native function told us to charge.
+                self.deduct_gas(amount)
+            });
+        if let Some(profile) = &mut self.profile {
+            profile.close_native();
+        }
+        if let (Some(trace), Some(rendered)) = (&mut self.trace, rendered) {
+            trace.call_end(rendered);
+        }
+        result
     }
 
     fn charge_native_function_before_execution(
@@ -430,13 +557,16 @@ impl GasMeter for GasStatus {
         // Track that this is going to be popping from the operand stack. We also increment the
         // instruction count as we need to account for the `Call` bytecode that initiated this
         // native call.
+        if let Some(profile) = &mut self.profile {
+            profile.mark_native();
+        }
         self.charge(1, 0, pops, 0, stack_reduction_size.into())
     }
 
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
     ) -> PartialVMResult<()> {
@@ -444,16 +574,23 @@ impl GasMeter for GasStatus {
         let pops = args.len() as u64;
         // Size stays the same -- we're just moving it from the operand stack to the locals. But
         // the size on the operand stack is reduced by sum_{args} arg.size().
+        let mut rendered = self.trace.as_ref().map(|_| vec![]);
         let stack_reduction_size = args.fold(AbstractMemorySize::new(0), |acc, elem| {
+            if let Some(rendered) = &mut rendered {
+                rendered.push(render_value(&elem));
+            }
             acc + elem.legacy_abstract_memory_size()
         });
-        self.charge(1, 0, pops, 0, stack_reduction_size.into())
+        self.charge_instr("Call", 1, 0, pops, 0, stack_reduction_size.into())?;
+        self.open_profile_call(module_id, func_name);
+        self.trace_call(module_id, func_name, rendered);
+        Ok(())
     }
 
     fn charge_call_generic(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         _ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         _num_locals: NumArgs,
@@ -461,17 +598,24 @@ impl GasMeter for GasStatus {
         // We have to perform this many pops from the operand stack for this function call.
         let pops = args.len() as u64;
         // Calculate the size reduction on the operand stack.
+        let mut rendered = self.trace.as_ref().map(|_| vec![]);
         let stack_reduction_size = args.fold(AbstractMemorySize::new(0), |acc, elem| {
+            if let Some(rendered) = &mut rendered {
+                rendered.push(render_value(&elem));
+            }
             acc + elem.legacy_abstract_memory_size()
         });
         // Charge for the pops, no pushes, and account for the stack size decrease. Also track the
         // `CallGeneric` instruction we must have encountered for this.
-        self.charge(1, 0, pops, 0, stack_reduction_size.into())
+        self.charge_instr("CallGeneric", 1, 0, pops, 0, stack_reduction_size.into())?;
+        self.open_profile_call(module_id, func_name);
+        self.trace_call(module_id, func_name, rendered);
+        Ok(())
     }
 
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
         // Charge for the load from the locals onto the stack.
-        self.charge(1, 1, 0, u64::from(size), 0)
+        self.charge_instr("LdConst", 1, 1, 0, u64::from(size), 0)
     }
 
     fn charge_ld_const_after_deserialization(
@@ -484,21 +628,42 @@ impl GasMeter for GasStatus {
 
     fn charge_copy_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
         // Charge for the copy of the local onto the stack.
-        self.charge(1, 1, 0, val.legacy_abstract_memory_size().into(), 0)
+        self.charge_instr(
+            "CopyLoc",
+            1,
+            1,
+            0,
+            val.legacy_abstract_memory_size().into(),
+            0,
+        )
     }
 
     fn charge_move_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
         // Charge for the move of the local on to the stack. Note that we charge here since we
         // aren't tracking the local size (at least not yet). If we were, this should be a net-zero
         // operation in terms of memory usage.
-        self.charge(1, 1, 0, val.legacy_abstract_memory_size().into(), 0)
+        self.charge_instr(
+            "MoveLoc",
+            1,
+            1,
+            0,
+            val.legacy_abstract_memory_size().into(),
+            0,
+        )
     }
 
     fn charge_store_loc(&mut self, val: impl ValueView) -> PartialVMResult<()> {
         // Charge for the storing of the value on the stack into a local. Note here that if we were
         // also accounting for the size of the locals that this would be a net-zero operation in
         // terms of memory.
-        self.charge(1, 0, 1, 0, val.legacy_abstract_memory_size().into())
+        self.charge_instr(
+            "StLoc",
+            1,
+            0,
+            1,
+            0,
+            val.legacy_abstract_memory_size().into(),
+        )
     }
 
     fn charge_pack(
@@ -510,7 +675,7 @@ impl GasMeter for GasStatus {
         let num_fields = args.len() as u64;
         // The actual amount of memory on the stack is staying the same with the addition of some
         // extra size for the struct, so the size doesn't really change much.
-        self.charge(1, 1, num_fields, STRUCT_SIZE.into(), 0)
+        self.charge_instr("Pack", 1, 1, num_fields, STRUCT_SIZE.into(), 0)
     }
 
     fn charge_unpack(
@@ -520,14 +685,15 @@ impl GasMeter for GasStatus {
     ) -> PartialVMResult<()> {
         // We perform `num_fields` number of pushes.
         let num_fields = args.len() as u64;
-        self.charge(1, num_fields, 1, 0, STRUCT_SIZE.into())
+        self.charge_instr("Unpack", 1, num_fields, 1, 0, STRUCT_SIZE.into())
     }
 
     fn charge_read_ref(&mut self, ref_val: impl ValueView) -> PartialVMResult<()> {
         // We read the the reference so we are decreasing the size of the stack by the size of the
         // reference, and adding to it the size of the value that has been read from that
         // reference.
-        self.charge(
+        self.charge_instr(
+            "ReadRef",
             1,
             1,
             1,
@@ -544,7 +710,8 @@ impl GasMeter for GasStatus {
         // TODO(tzakian): We should account for this elsewhere as the owner of data the the
         // reference points to won't be on the stack. For now though, we treat it as adding to the
         // stack size.
-        self.charge(
+        self.charge_instr(
+            "WriteRef",
             1,
             1,
             2,
@@ -555,7 +722,8 @@ impl GasMeter for GasStatus {
 
     fn charge_eq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
         let size_reduction = lhs.legacy_abstract_memory_size() + rhs.legacy_abstract_memory_size();
-        self.charge(
+        self.charge_instr(
+            "Eq",
             1,
             1,
             2,
@@ -566,7 +734,14 @@ impl GasMeter for GasStatus {
 
     fn charge_neq(&mut self, lhs: impl ValueView, rhs: impl ValueView) -> PartialVMResult<()> {
         let size_reduction = lhs.legacy_abstract_memory_size() + rhs.legacy_abstract_memory_size();
-        self.charge(1, 1, 2, Type::Bool.size().into(), size_reduction.into())
+        self.charge_instr(
+            "Neq",
+            1,
+            1,
+            2,
+            Type::Bool.size().into(),
+            size_reduction.into(),
+        )
     }
 
     fn charge_load_resource(
@@ -584,7 +759,14 @@ impl GasMeter for GasStatus {
         _ty: impl TypeView,
         _is_success: bool,
     ) -> PartialVMResult<()> {
-        self.charge(1, 1, 1, REFERENCE_SIZE.into(), Type::Address.size().into())
+        self.charge_instr(
+            "BorrowGlobal",
+            1,
+            1,
+            1,
+            REFERENCE_SIZE.into(),
+            Type::Address.size().into(),
+        )
     }
 
     fn charge_exists(
@@ -594,7 +776,8 @@ impl GasMeter for GasStatus {
         // TODO(Gas): see if we can get rid of this param
         _exists: bool,
     ) -> PartialVMResult<()> {
-        self.charge(
+        self.charge_instr(
+            "Exists",
             1,
             1,
             1,
@@ -612,7 +795,14 @@ impl GasMeter for GasStatus {
         let size = val
             .map(|val| val.legacy_abstract_memory_size())
             .unwrap_or_else(|| ty.to_type_tag().abstract_size_for_gas_metering());
-        self.charge(1, 1, 1, size.into(), Type::Address.size().into())
+        self.charge_instr(
+            "MoveFrom",
+            1,
+            1,
+            1,
+            size.into(),
+            Type::Address.size().into(),
+        )
     }
 
     fn charge_move_to(
@@ -622,7 +812,7 @@ impl GasMeter for GasStatus {
         _val: impl ValueView,
         _is_success: bool,
     ) -> PartialVMResult<()> {
-        self.charge(1, 0, 2, 0, Type::Address.size().into())
+        self.charge_instr("MoveTo", 1, 0, 2, 0, Type::Address.size().into())
     }
 
     fn charge_vec_pack<'a>(
@@ -634,11 +824,18 @@ impl GasMeter for GasStatus {
         let num_args = args.len() as u64;
         // The amount of data on the stack stays constant except we have some extra metadata for
         // the vector to hold the length of the vector.
-        self.charge(1, 1, num_args, VEC_SIZE.into(), 0)
+        self.charge_instr("VecPack", 1, 1, num_args, VEC_SIZE.into(), 0)
     }
 
     fn charge_vec_len(&mut self, _ty: impl TypeView) -> PartialVMResult<()> {
-        self.charge(1, 1, 1, Type::U64.size().into(), REFERENCE_SIZE.into())
+        self.charge_instr(
+            "VecLen",
+            1,
+            1,
+            1,
+            Type::U64.size().into(),
+            REFERENCE_SIZE.into(),
+        )
     }
 
     fn charge_vec_borrow(
@@ -647,7 +844,8 @@ impl GasMeter for GasStatus {
         _ty: impl TypeView,
         _is_success: bool,
     ) -> PartialVMResult<()> {
-        self.charge(
+        self.charge_instr(
+            "VecBorrow",
             1,
             1,
             2,
@@ -662,7 +860,7 @@ impl GasMeter for GasStatus {
         _val: impl ValueView,
     ) -> PartialVMResult<()> {
         // The value was already on the stack, so we aren't increasing the number of bytes on the stack.
-        self.charge(1, 0, 2, 0, REFERENCE_SIZE.into())
+        self.charge_instr("VecPushBack", 1, 0, 2, 0, REFERENCE_SIZE.into())
     }
 
     fn charge_vec_pop_back(
@@ -670,7 +868,7 @@ impl GasMeter for GasStatus {
         _ty: impl TypeView,
         _val: Option<impl ValueView>,
     ) -> PartialVMResult<()> {
-        self.charge(1, 1, 1, 0, REFERENCE_SIZE.into())
+        self.charge_instr("VecPopBack", 1, 1, 1, 0, REFERENCE_SIZE.into())
     }
 
     fn charge_vec_unpack(
@@ -682,21 +880,46 @@ impl GasMeter for GasStatus {
         // Charge for the pushes
         let pushes = u64::from(expect_num_elements);
         // The stack size stays pretty much the same modulo the additional vector size
-        self.charge(1, pushes, 1, 0, VEC_SIZE.into())
+        self.charge_instr("VecUnpack", 1, pushes, 1, 0, VEC_SIZE.into())
     }
 
     fn charge_vec_swap(&mut self, _ty: impl TypeView) -> PartialVMResult<()> {
         let size_decrease = REFERENCE_SIZE + Type::U64.size() + Type::U64.size();
-        self.charge(1, 1, 1, 0, size_decrease.into())
+        self.charge_instr("VecSwap", 1, 1, 1, 0, size_decrease.into())
     }
 
     fn charge_drop_frame(
         &mut self,
         _locals: impl Iterator<Item = impl ValueView>,
     ) -> PartialVMResult<()> {
+        if let Some(profile) = &mut self.profile {
+            profile.close_call();
+        }
         Ok(())
     }
 
+    fn is_tracing(&self) -> bool {
+        self.trace.is_some()
+    }
+
+    fn trace_entrypoint(
+        &mut self,
+        module_id: &ModuleId,
+        func_name: &str,
+        args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) {
+        if self.trace.is_some() {
+            let args = args.map(render_value).collect();
+            self.trace_call(module_id, func_name, Some(args));
+        }
+    }
+
+    fn trace_return(&mut self, ret_vals: impl ExactSizeIterator<Item = impl ValueView>) {
+        if let Some(trace) = &mut self.trace {
+            trace.call_end(ret_vals.map(render_value).collect());
+        }
+    }
+
     fn remaining_gas(&self) -> InternalGas {
         if !self.charge {
             return InternalGas::new(u64::MAX);
@@ -42,6 +42,7 @@ pub mod executable_transaction;
 pub mod execution;
 pub mod execution_mode;
 pub mod execution_status;
+pub mod execution_trace;
 pub mod gas;
 pub mod gas_coin;
 pub mod gas_model;
@@ -1398,6 +1398,12 @@ impl TransactionData {
             self.gas_data().payment.clone(),
         )
     }
+
+    /// Digest of the transaction signed with this data, the same as the digest of any
+    /// `Transaction` built out of it.
+    pub fn digest(&self) -> TransactionDigest {
+        TransactionDigest::new(default_hash(self))
+    }
 }
 
 #[enum_dispatch]
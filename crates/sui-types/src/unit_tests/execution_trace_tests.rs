@@ -0,0 +1,91 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::language_storage::StructTag;
+use move_vm_types::values::{Struct, Value};
+
+use super::*;
+
+#[test]
+fn test_render_value() {
+    assert_eq!(render_value(Value::u64(7)), "7");
+    assert_eq!(render_value(Value::vector_u8([0xab, 0x01])), "0xab01");
+    assert_eq!(render_value(Value::vector_u64([])), "[]");
+
+    let value = Value::struct_(Struct::pack([
+        Value::address(AccountAddress::TWO),
+        Value::vector_u64([1, 2]),
+        Value::struct_(Struct::pack([Value::bool(true)])),
+        Value::struct_(Struct::pack([])),
+    ]));
+    assert_eq!(render_value(value), "{ 0x2, [1, 2], { true }, {} }");
+}
+
+#[test]
+fn test_calls_follow_stack() {
+    let mut trace = ExecutionTrace::new();
+    trace.start_command("0: MoveCall");
+    trace.call_start("0x2::coin::split".to_string(), vec!["1".to_string()]);
+    trace.call_start("0x2::object::new".to_string(), vec![]);
+    trace.call_end(vec!["{ 0x5 }".to_string()]);
+    trace.call_end(vec![]);
+    // Nothing is open any more, so this does not record anything
+    trace.call_end(vec![]);
+
+    let depths: Vec<_> = trace.commands[0]
+        .events
+        .iter()
+        .map(|event| match event {
+            TraceEvent::CallStart {
+                depth, function, ..
+            } => format!("start {depth} {function}"),
+            TraceEvent::CallEnd {
+                depth, function, ..
+            } => format!("end {depth} {function}"),
+            _ => panic!("Only calls were recorded"),
+        })
+        .collect();
+    assert_eq!(
+        depths,
+        [
+            "start 0 0x2::coin::split",
+            "start 1 0x2::object::new",
+            "end 1 0x2::object::new",
+            "end 0 0x2::coin::split",
+        ]
+    );
+}
+
+#[test]
+fn test_aborted_command_resets_stack() {
+    let mut trace = ExecutionTrace::new();
+    trace.start_command("0: MoveCall");
+    trace.call_start("0x2::m::f".to_string(), vec![]);
+    trace.event(
+        StructTag {
+            address: AccountAddress::TWO,
+            module: "m".parse().unwrap(),
+            name: "E".parse().unwrap(),
+            type_params: vec![],
+        },
+        Value::struct_(Struct::pack([Value::u64(1)])),
+    );
+    trace.fail_command("MoveAbort in 0x2::m::f");
+
+    trace.start_command("1: TransferObjects");
+    trace.call_start("0x2::m::g".to_string(), vec![]);
+
+    assert_eq!(trace.commands.len(), 2);
+    assert_eq!(
+        trace.commands[0].error.as_deref(),
+        Some("MoveAbort in 0x2::m::f")
+    );
+    assert!(matches!(
+        &trace.commands[0].events[1],
+        TraceEvent::Event { contents, .. } if contents == "{ 1 }"
+    ));
+    assert!(matches!(
+        trace.commands[1].events[0],
+        TraceEvent::CallStart { depth: 0, .. }
+    ));
+}
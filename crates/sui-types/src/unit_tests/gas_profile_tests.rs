@@ -0,0 +1,121 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn frame_names(profile: &GasProfile, events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .map(|e| format!("{}:{}", e.ty, profile.shared.frames[e.frame as usize].name))
+        .collect()
+}
+
+fn events(profile: &GasProfile) -> &[Event] {
+    match &profile.profiles[0] {
+        Profile::Evented { events, .. } => events,
+        Profile::Sampled { .. } => panic!("Computation profile must be evented"),
+    }
+}
+
+#[test]
+fn test_frames_follow_calls() {
+    let mut profile = GasProfile::new("tx".to_string());
+    profile.open_frame("0: MoveCall");
+    profile.open_instruction("Call");
+    profile.record_gas(2000);
+    profile.close_instruction();
+    profile.open_call("0x2::coin::split");
+    // A native called from `split`
+    profile.open_call("0x2::object::new");
+    profile.mark_native();
+    profile.record_gas(1000);
+    profile.close_native();
+    profile.close_call();
+    // The entry function returning does not close the command frame
+    profile.close_call();
+    profile.close_frame();
+    profile.finish(&GasCostSummary::default());
+
+    assert_eq!(
+        frame_names(&profile, events(&profile)),
+        vec![
+            "O:tx",
+            "O:0: MoveCall",
+            "O:Call",
+            "C:Call",
+            "O:0x2::coin::split",
+            "O:0x2::object::new",
+            "C:0x2::object::new",
+            "C:0x2::coin::split",
+            "C:0: MoveCall",
+            "C:tx",
+        ]
+    );
+    let at: Vec<_> = events(&profile).iter().map(|e| e.at).collect();
+    assert_eq!(at, vec![0, 0, 0, 2000, 2000, 2000, 3000, 3000, 3000, 3000]);
+}
+
+#[test]
+fn test_abort_closes_open_calls() {
+    let mut profile = GasProfile::new("tx".to_string());
+    profile.open_frame("0: MoveCall");
+    profile.open_call("0x2::a::f");
+    profile.open_call("0x2::a::g");
+    profile.record_gas(5000);
+    // `g` aborts, so neither call returns
+    profile.close_frame();
+    profile.open_frame("1: TransferObjects");
+    profile.close_frame();
+    profile.finish(&GasCostSummary::default());
+
+    assert_eq!(
+        frame_names(&profile, events(&profile)),
+        vec![
+            "O:tx",
+            "O:0: MoveCall",
+            "O:0x2::a::f",
+            "O:0x2::a::g",
+            "C:0x2::a::g",
+            "C:0x2::a::f",
+            "C:0: MoveCall",
+            "O:1: TransferObjects",
+            "C:1: TransferObjects",
+            "C:tx",
+        ]
+    );
+}
+
+#[test]
+fn test_storage_profile() {
+    let mut profile = GasProfile::new("tx".to_string());
+    profile.finish(&GasCostSummary::new(1000, 2000, 500, 5));
+
+    let Profile::Sampled {
+        samples,
+        weights,
+        end_value,
+        ..
+    } = &profile.profiles[1]
+    else {
+        panic!("Storage profile must be sampled");
+    };
+    let names: Vec<_> = samples
+        .iter()
+        .map(|s| profile.shared.frames[s[0] as usize].name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "storage cost",
+            "storage rebate",
+            "non-refundable storage fee"
+        ]
+    );
+    assert_eq!(*weights, [2000, 500, 5]);
+    assert_eq!(*end_value, 2505);
+
+    let json = serde_json::to_value(&profile).unwrap();
+    assert_eq!(json["profiles"][0]["type"], "evented");
+    assert_eq!(json["profiles"][1]["type"], "sampled");
+    assert_eq!(json["profiles"][0]["events"][0]["type"], "O");
+}
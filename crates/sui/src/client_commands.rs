@@ -6,6 +6,7 @@ use std::{
     fmt::{Debug, Display, Formatter, Write},
     path::PathBuf,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{anyhow, ensure};
@@ -35,8 +36,9 @@ use sui_verifier::meter::SuiVerifierMeter;
 use shared_crypto::intent::Intent;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectResponse, SuiObjectResponseQuery, SuiRawData,
-    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+    DryRunTransactionBlockResponse, DynamicFieldPage, SuiData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
+    SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
 use sui_keys::keystore::AccountKeystore;
@@ -47,13 +49,15 @@ use sui_move_build::{
 use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
 use sui_sdk::wallet_context::WalletContext;
 use sui_sdk::SuiClient;
+use sui_transaction_builder::offline::OfflineState;
+use sui_transaction_builder::GasBudget;
 use sui_types::crypto::SignatureScheme;
 use sui_types::dynamic_field::DynamicFieldType;
 use sui_types::move_package::UpgradeCap;
 use sui_types::signature::GenericSignature;
 use sui_types::transaction::{SenderSignedData, TransactionData, TransactionDataAPI};
 use sui_types::{
-    base_types::{ObjectID, SuiAddress},
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
     gas_coin::GasCoin,
     object::Owner,
     parse_sui_type_tag,
@@ -61,24 +65,25 @@ use sui_types::{
 };
 use tracing::info;
 
+use crate::client_explain::{diff_explanations, explain_transaction, TransactionExplanation};
+use crate::client_ptb::{parse_ptb_commands, PtbBuilder};
+
 macro_rules! serialize_or_execute {
     ($tx_data:expr, $serialize_unsigned:expr, $serialize_signed:expr, $context:expr, $result_variant:ident) => {{
         assert!(
             !$serialize_unsigned || !$serialize_signed,
             "Cannot specify both --serialize-unsigned and --serialize-signed"
         );
-        if $serialize_unsigned {
+        // Transactions built offline can't be executed, they are serialized unsigned by default.
+        if $serialize_unsigned || ($context.is_offline() && !$serialize_signed) {
             SuiClientCommandResult::SerializedUnsignedTransaction($tx_data)
         } else {
-            let signature = $context.config.keystore.sign_secure(
-                &$tx_data.sender(),
-                &$tx_data,
-                Intent::sui_transaction(),
-            )?;
-            let sender_signed_data = SenderSignedData::new_from_sender_signature(
+            // Sponsored transactions are also signed by the sponsor.
+            let signatures = $context.sign_transaction_data(&$tx_data)?;
+            let sender_signed_data = SenderSignedData::new(
                 $tx_data,
                 Intent::sui_transaction(),
-                signature,
+                signatures.into_iter().map(Into::into).collect(),
             );
             if $serialize_signed {
                 SuiClientCommandResult::SerializedSignedTransaction(sender_signed_data)
@@ -178,8 +183,9 @@ pub enum SuiClientCommands {
         gas: Option<ObjectID>,
 
         /// Gas budget for running module initializers
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Publish the package without checking whether compiling dependencies from source results
         /// in bytecode matching the dependencies found on-chain.
@@ -248,8 +254,9 @@ pub enum SuiClientCommands {
         gas: Option<ObjectID>,
 
         /// Gas budget for running module initializers
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Publish the package without checking whether compiling dependencies from source results
         /// in bytecode matching the dependencies found on-chain.
@@ -339,8 +346,49 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
         /// Gas budget for this call
+        /// If not provided, it is estimated by dry running the transaction
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
+        /// (TransactionData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_unsigned_transaction: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the signed transaction data
+        /// (SenderSignedData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_signed_transaction: bool,
+    },
+
+    /// Run a programmable transaction block made of a sequence of commands, given as a JSON
+    /// array, e.g. `[{"SplitCoins": {"coin": "$gas", "amounts": [1000]}},
+    /// {"TransferObjects": {"objects": ["$0.0"], "recipient": "0x..."}}]`. Commands refer to
+    /// the results of earlier commands with `$N`, or `$N.M` for their M-th result, and to the
+    /// gas coin with `$gas`. See `sui::client_ptb` for the commands and their arguments.
+    #[clap(name = "ptb")]
+    Ptb {
+        /// The commands of the transaction block, as a JSON array
+        #[clap(long, required_unless_present = "file", conflicts_with = "file")]
+        commands: Option<String>,
+
+        /// Read the commands of the transaction block from a JSON file instead
+        #[clap(long)]
+        file: Option<PathBuf>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+
+        /// Dry run the transaction instead of executing it
+        #[clap(long, required = false)]
+        dry_run: bool,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -370,8 +418,9 @@ pub enum SuiClientCommands {
         gas: Option<ObjectID>,
 
         /// Gas budget for this transfer
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -397,8 +446,9 @@ pub enum SuiClientCommands {
         sui_coin_object_id: ObjectID,
 
         /// Gas budget for this transfer
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
@@ -436,8 +486,9 @@ pub enum SuiClientCommands {
         gas: Option<ObjectID>,
 
         /// Gas budget for this transaction
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -467,8 +518,9 @@ pub enum SuiClientCommands {
         amounts: Vec<u64>,
 
         /// Gas budget for this transaction
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -493,8 +545,9 @@ pub enum SuiClientCommands {
         recipient: SuiAddress,
 
         /// Gas budget for this transaction
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -570,8 +623,9 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
         /// Gas budget for this call
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -597,8 +651,9 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas: Option<ObjectID>,
         /// Gas budget for this call
+        /// If not provided, it is estimated by dry running the transaction
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
 
         /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
         /// (TransactionData) using base64 encoding, and print out the string.
@@ -622,9 +677,74 @@ pub enum SuiClientCommands {
         signatures: Vec<String>,
     },
 
+    /// Explain a transaction before signing it: resolve its inputs and the functions it calls,
+    /// and preview its balance and object changes with a dry run.
+    #[clap(name = "explain-tx")]
+    ExplainTx {
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        #[clap(long)]
+        tx_bytes: String,
+
+        /// Another transaction, as base-64 encoded string, to diff the explanation with
+        #[clap(long)]
+        diff: Option<String>,
+
+        /// Don't preview the effects of the transaction with a dry run
+        #[clap(long)]
+        no_dry_run: bool,
+    },
+
+    /// Wait until a transaction is final, i.e. included in a checkpoint, and print it.
+    #[clap(name = "wait-for-tx")]
+    WaitForTx {
+        /// Digest of the transaction
+        #[clap(name = "digest")]
+        digest: TransactionDigest,
+
+        /// Also wait until the transaction can be read from the fullnode at this URL, e.g. the
+        /// one an indexer reads from
+        #[clap(long)]
+        indexed_by: Option<String>,
+
+        /// Seconds to wait before giving up
+        #[clap(long, default_value = "60")]
+        timeout: u64,
+    },
+
+    /// Wait until an object is modified or deleted, and print its new version.
+    #[clap(name = "watch-object")]
+    WatchObject {
+        /// Object ID of the object to watch
+        #[clap(name = "object_id")]
+        id: ObjectID,
+
+        /// Version the object must be modified past, its current version if not set
+        #[clap(long)]
+        version: Option<u64>,
+
+        /// Seconds to wait before giving up
+        #[clap(long, default_value = "60")]
+        timeout: u64,
+    },
+
     /// Query the chain identifier from the rpc endpoint.
     #[clap(name = "chain-identifier")]
     ChainIdentifier,
+
+    /// Export the objects owned by the managed addresses, and the objects given, to a state file
+    /// that transactions are built from offline with `sui client --offline --state-file <FILE>`.
+    /// The packages called and the objects passed to Move calls that are not owned by a managed
+    /// address, e.g. shared objects, must be given.
+    #[clap(name = "export-state")]
+    ExportState {
+        /// File to write the state to
+        #[clap(long)]
+        state_file: PathBuf,
+
+        /// Objects to export besides the objects owned by the managed addresses
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        object_ids: Vec<ObjectID>,
+    },
 }
 
 impl SuiClientCommands {
@@ -646,8 +766,10 @@ impl SuiClientCommands {
                 serialize_signed_transaction,
                 lint,
             } => {
+                ensure_online(context, "upgrade")?;
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
+                let gas_budget = context.gas_budget(gas_budget);
 
                 let client = context.get_client().await?;
                 let (dependencies, compiled_modules, compiled_package, package_id) =
@@ -661,42 +783,8 @@ impl SuiClientCommands {
                     )
                     .await?;
 
-                let package_id = package_id.map_err(|e| match e {
-                    PublishedAtError::NotPresent => {
-                        anyhow!("No 'published-at' field in manifest for package to be upgraded.")
-                    }
-                    PublishedAtError::Invalid(v) => anyhow!(
-                        "Invalid 'published-at' field in manifest of package to be upgraded. \
-                         Expected an on-chain address, but found: {v:?}"
-                    ),
-                })?;
-
-                let resp = context
-                    .get_client()
-                    .await?
-                    .read_api()
-                    .get_object_with_options(
-                        upgrade_capability,
-                        SuiObjectDataOptions::default().with_bcs().with_owner(),
-                    )
-                    .await?;
-
-                let Some(data) = resp.data else {
-                    return Err(anyhow!("Could not find upgrade capability at {upgrade_capability}"))
-                };
-
-                let upgrade_cap: UpgradeCap = data
-                    .bcs
-                    .ok_or_else(|| {
-                        anyhow!("Fetch upgrade capability object but no data was returned")
-                    })?
-                    .try_as_move()
-                    .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
-                    .deserialize()?;
-                // We keep the existing policy -- no fancy policies or changing the upgrade
-                // policy at the moment. To change the policy you can call a Move function in the
-                // `package` module to change this policy.
-                let upgrade_policy = upgrade_cap.policy;
+                let package_id = published_at(package_id)?;
+                let upgrade_policy = get_upgrade_policy(&client, upgrade_capability).await?;
                 let package_digest = compiled_package
                     .get_package_digest(with_unpublished_dependencies, !legacy_digest);
 
@@ -733,8 +821,10 @@ impl SuiClientCommands {
                 serialize_signed_transaction,
                 lint,
             } => {
+                ensure_online(context, "publish")?;
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
+                let gas_budget = context.gas_budget(gas_budget);
 
                 let client = context.get_client().await?;
                 let (dependencies, compiled_modules, _, _) = compile_package(
@@ -862,6 +952,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let gas_budget = context.gas_budget(gas_budget);
                 let tx_data = construct_move_call_transaction(
                     package, &module, &function, type_args, gas, gas_budget, args, context,
                 )
@@ -875,6 +966,48 @@ impl SuiClientCommands {
                 )
             }
 
+            SuiClientCommands::Ptb {
+                commands,
+                file,
+                gas,
+                gas_budget,
+                dry_run,
+                serialize_unsigned_transaction,
+                serialize_signed_transaction,
+            } => {
+                ensure_online(context, "ptb")?;
+                let commands = match (commands, file) {
+                    (Some(commands), _) => commands,
+                    (None, Some(file)) => std::fs::read_to_string(&file)
+                        .map_err(|e| anyhow!("Cannot read PTB commands from {:?}: {e}", file))?,
+                    (None, None) => return Err(anyhow!("No PTB commands provided")),
+                };
+                let commands = parse_ptb_commands(&commands)?;
+
+                let gas_owner = context.try_get_object_owner(&gas).await?;
+                let sender = gas_owner.unwrap_or(context.active_address()?);
+                let client = context.get_client().await?;
+                let pt = PtbBuilder::new(&client).build(commands).await?;
+                let gas_budget = context.gas_budget(gas_budget);
+                let tx_data = client
+                    .transaction_builder()
+                    .programmable(sender, pt, gas, gas_budget)
+                    .await?;
+                if dry_run {
+                    SuiClientCommandResult::DryRun(
+                        client.read_api().dry_run_transaction_block(tx_data).await?,
+                    )
+                } else {
+                    serialize_or_execute!(
+                        tx_data,
+                        serialize_unsigned_transaction,
+                        serialize_signed_transaction,
+                        context,
+                        Ptb
+                    )
+                }
+            }
+
             SuiClientCommands::Transfer {
                 to,
                 object_id,
@@ -884,9 +1017,10 @@ impl SuiClientCommands {
                 serialize_signed_transaction,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
-                let client = context.get_client().await?;
-                let data = client
+                let gas_budget = context.gas_budget(gas_budget);
+                let data = context
                     .transaction_builder()
+                    .await?
                     .transfer_object(from, object_id, gas, gas_budget, to)
                     .await?;
                 serialize_or_execute!(
@@ -907,10 +1041,11 @@ impl SuiClientCommands {
                 serialize_signed_transaction,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
+                let gas_budget = context.gas_budget(gas_budget);
 
-                let client = context.get_client().await?;
-                let data = client
+                let data = context
                     .transaction_builder()
+                    .await?
                     .transfer_sui(from, object_id, gas_budget, to, amount)
                     .await?;
                 serialize_or_execute!(
@@ -948,9 +1083,10 @@ impl SuiClientCommands {
                     ),
                 );
                 let from = context.get_object_owner(&input_coins[0]).await?;
-                let client = context.get_client().await?;
-                let data = client
+                let gas_budget = context.gas_budget(gas_budget);
+                let data = context
                     .transaction_builder()
+                    .await?
                     .pay(from, input_coins, recipients, amounts, gas, gas_budget)
                     .await?;
                 serialize_or_execute!(
@@ -987,9 +1123,10 @@ impl SuiClientCommands {
                     ),
                 );
                 let signer = context.get_object_owner(&input_coins[0]).await?;
-                let client = context.get_client().await?;
-                let data = client
+                let gas_budget = context.gas_budget(gas_budget);
+                let data = context
                     .transaction_builder()
+                    .await?
                     .pay_sui(signer, input_coins, recipients, amounts, gas_budget)
                     .await?;
                 serialize_or_execute!(
@@ -1013,9 +1150,10 @@ impl SuiClientCommands {
                     "PayAllSui transaction requires a non-empty list of input coins"
                 );
                 let signer = context.get_object_owner(&input_coins[0]).await?;
-                let client = context.get_client().await?;
-                let data = client
+                let gas_budget = context.gas_budget(gas_budget);
+                let data = context
                     .transaction_builder()
+                    .await?
                     .pay_all_sui(signer, input_coins, recipient, gas_budget)
                     .await?;
 
@@ -1084,6 +1222,49 @@ impl SuiClientCommands {
                     .collect();
                 SuiClientCommandResult::Gas(coins)
             }
+            SuiClientCommands::WaitForTx {
+                digest,
+                indexed_by,
+                timeout,
+            } => {
+                let response = context
+                    .wait_for_transaction_checkpoint(
+                        digest,
+                        indexed_by.as_deref(),
+                        SuiTransactionBlockResponseOptions::full_content(),
+                        Duration::from_secs(timeout),
+                    )
+                    .await?;
+                SuiClientCommandResult::WaitForTx(response)
+            }
+            SuiClientCommands::WatchObject {
+                id,
+                version,
+                timeout,
+            } => {
+                let client = context.get_client().await?;
+                let version = match version {
+                    Some(version) => SequenceNumber::from_u64(version),
+                    None => {
+                        client
+                            .read_api()
+                            .get_object_with_options(id, SuiObjectDataOptions::new())
+                            .await?
+                            .object()?
+                            .version
+                    }
+                };
+                let response = client
+                    .read_api()
+                    .wait_for_object_change(
+                        id,
+                        version,
+                        SuiObjectDataOptions::full_content(),
+                        Duration::from_secs(timeout),
+                    )
+                    .await?;
+                SuiClientCommandResult::WatchObject(response)
+            }
             SuiClientCommands::ChainIdentifier => {
                 let ci = context
                     .get_client()
@@ -1093,6 +1274,50 @@ impl SuiClientCommands {
                     .await?;
                 SuiClientCommandResult::ChainIdentifier(ci)
             }
+            SuiClientCommands::ExportState {
+                state_file,
+                object_ids,
+            } => {
+                let client = context.get_client().await?;
+                let mut state =
+                    OfflineState::new(client.read_api().get_reference_gas_price().await?);
+                for address in context.get_addresses() {
+                    let mut cursor = None;
+                    loop {
+                        let response = client
+                            .read_api()
+                            .get_owned_objects(
+                                address,
+                                Some(SuiObjectResponseQuery::new_with_options(
+                                    OfflineState::object_options(),
+                                )),
+                                cursor,
+                                None,
+                            )
+                            .await?;
+                        for object in response.data {
+                            state.insert_object(object.into_object()?)?;
+                        }
+                        if response.has_next_page {
+                            cursor = response.next_cursor;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                for object in client
+                    .read_api()
+                    .multi_get_object_with_options(object_ids, OfflineState::object_options())
+                    .await?
+                {
+                    state.insert_object(object.into_object()?)?;
+                }
+                state.save(&state_file)?;
+                SuiClientCommandResult::ExportState {
+                    state_file,
+                    objects: state.objects().count(),
+                }
+            }
             SuiClientCommands::SplitCoin {
                 coin_id,
                 amounts,
@@ -1103,11 +1328,11 @@ impl SuiClientCommands {
                 serialize_signed_transaction,
             } => {
                 let signer = context.get_object_owner(&coin_id).await?;
-                let client = context.get_client().await?;
+                let gas_budget = context.gas_budget(gas_budget);
+                let builder = context.transaction_builder().await?;
                 let data = match (amounts, count) {
                     (Some(amounts), None) => {
-                        client
-                            .transaction_builder()
+                        builder
                             .split_coin(signer, coin_id, amounts, gas, gas_budget)
                             .await?
                     }
@@ -1115,8 +1340,7 @@ impl SuiClientCommands {
                         if count == 0 {
                             return Err(anyhow!("Coin split count must be greater than 0"));
                         }
-                        client
-                            .transaction_builder()
+                        builder
                             .split_coin_equal(signer, coin_id, count, gas, gas_budget)
                             .await?
                     }
@@ -1140,10 +1364,11 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
-                let client = context.get_client().await?;
                 let signer = context.get_object_owner(&primary_coin).await?;
-                let data = client
+                let gas_budget = context.gas_budget(gas_budget);
+                let data = context
                     .transaction_builder()
+                    .await?
                     .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
                     .await?;
                 serialize_or_execute!(
@@ -1178,12 +1403,7 @@ impl SuiClientCommands {
                 tx_bytes,
                 signatures,
             } => {
-                let data = bcs::from_bytes(
-                    &Base64::try_from(tx_bytes)
-                        .map_err(|e| anyhow!(e))?
-                        .to_vec()
-                        .map_err(|e| anyhow!(e))?,
-                )?;
+                let data = decode_tx_data(tx_bytes)?;
 
                 let mut sigs = Vec::new();
                 for sig in signatures {
@@ -1203,6 +1423,23 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
+            SuiClientCommands::ExplainTx {
+                tx_bytes,
+                diff,
+                no_dry_run,
+            } => {
+                let client = context.get_client().await?;
+                let tx_data = decode_tx_data(tx_bytes)?;
+                let explanation = explain_transaction(&client, &tx_data, !no_dry_run).await?;
+                match diff {
+                    Some(other) => {
+                        let other = decode_tx_data(other)?;
+                        let other = explain_transaction(&client, &other, !no_dry_run).await?;
+                        SuiClientCommandResult::DiffTx(diff_explanations(&explanation, &other))
+                    }
+                    None => SuiClientCommandResult::ExplainTx(explanation),
+                }
+            }
             SuiClientCommands::NewEnv { alias, rpc, ws } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
@@ -1296,7 +1533,7 @@ fn compile_package_simple(
     )?)
 }
 
-async fn compile_package(
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1366,6 +1603,50 @@ async fn compile_package(
     Ok((dependencies, compiled_modules, compiled_package, package_id))
 }
 
+/// The ID of the package to upgrade, from its `published-at` manifest field.
+pub(crate) fn published_at(
+    package_id: Result<ObjectID, PublishedAtError>,
+) -> Result<ObjectID, anyhow::Error> {
+    package_id.map_err(|e| match e {
+        PublishedAtError::NotPresent => {
+            anyhow!("No 'published-at' field in manifest for package to be upgraded.")
+        }
+        PublishedAtError::Invalid(v) => anyhow!(
+            "Invalid 'published-at' field in manifest of package to be upgraded. \
+             Expected an on-chain address, but found: {v:?}"
+        ),
+    })
+}
+
+/// The policy of the upgrade capability `upgrade_capability`.
+pub(crate) async fn get_upgrade_policy(
+    client: &SuiClient,
+    upgrade_capability: ObjectID,
+) -> Result<u8, anyhow::Error> {
+    let resp = client
+        .read_api()
+        .get_object_with_options(
+            upgrade_capability,
+            SuiObjectDataOptions::default().with_bcs().with_owner(),
+        )
+        .await?;
+
+    let Some(data) = resp.data else {
+        return Err(anyhow!("Could not find upgrade capability at {upgrade_capability}"))
+    };
+
+    let upgrade_cap: UpgradeCap = data
+        .bcs
+        .ok_or_else(|| anyhow!("Fetch upgrade capability object but no data was returned"))?
+        .try_as_move()
+        .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
+        .deserialize()?;
+    // We keep the existing policy -- no fancy policies or changing the upgrade
+    // policy at the moment. To change the policy you can call a Move function in the
+    // `package` module to change this policy.
+    Ok(upgrade_cap.policy)
+}
+
 impl Display for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut writer = String::new();
@@ -1402,9 +1683,12 @@ impl Display for SuiClientCommandResult {
                 };
                 writeln!(writer, "{}", raw_object)?;
             }
-            SuiClientCommandResult::Call(response) => {
+            SuiClientCommandResult::Call(response) | SuiClientCommandResult::Ptb(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::DryRun(response) => {
+                write!(writer, "{}", write_dry_run_response(response)?)?;
+            }
             SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) => {
                 writeln!(
                     writer,
@@ -1530,9 +1814,28 @@ impl Display for SuiClientCommandResult {
                     writeln!(writer, " {0: ^66} | {1: ^11}", gas.id(), gas.value())?;
                 }
             }
+            SuiClientCommandResult::ExplainTx(explanation) => {
+                writeln!(writer, "{explanation}")?;
+            }
+            SuiClientCommandResult::DiffTx(diff) => {
+                write!(writer, "{diff}")?;
+            }
+            SuiClientCommandResult::WaitForTx(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::WatchObject(object_read) => {
+                let object = unwrap_err_to_string(|| Ok(object_read.object()?));
+                writeln!(writer, "{}", object)?;
+            }
             SuiClientCommandResult::ChainIdentifier(ci) => {
                 writeln!(writer, "{}", ci)?;
             }
+            SuiClientCommandResult::ExportState {
+                state_file,
+                objects,
+            } => {
+                writeln!(writer, "Exported {objects} objects to {:?}", state_file)?;
+            }
             SuiClientCommandResult::SplitCoin(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
@@ -1605,13 +1908,24 @@ impl Display for SuiClientCommandResult {
     }
 }
 
+/// Commands resolving packages or objects beyond the offline state, e.g. to verify dependencies or
+/// resolve PTB arguments, need the network.
+fn ensure_online(context: &WalletContext, command: &str) -> Result<(), anyhow::Error> {
+    if context.is_offline() {
+        return Err(anyhow!(
+            "`sui client {command}` can't be used offline, it needs to access the network"
+        ));
+    }
+    Ok(())
+}
+
 async fn construct_move_call_transaction(
     package: ObjectID,
     module: &str,
     function: &str,
     type_args: Vec<TypeTag>,
     gas: Option<ObjectID>,
-    gas_budget: u64,
+    gas_budget: GasBudget,
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<TransactionData, anyhow::Error> {
@@ -1628,16 +1942,16 @@ async fn construct_move_call_transaction(
     let gas_owner = context.try_get_object_owner(&gas).await?;
     let sender = gas_owner.unwrap_or(context.active_address()?);
 
-    let client = context.get_client().await?;
-    client
+    context
         .transaction_builder()
+        .await?
         .move_call(
             sender, package, module, function, type_args, args, gas, gas_budget,
         )
         .await
 }
 
-fn convert_number_to_string(value: Value) -> Value {
+pub(crate) fn convert_number_to_string(value: Value) -> Value {
     match value {
         Value::Number(n) => Value::String(n.to_string()),
         Value::Array(a) => Value::Array(a.into_iter().map(convert_number_to_string).collect()),
@@ -1650,6 +1964,16 @@ fn convert_number_to_string(value: Value) -> Value {
     }
 }
 
+/// Decodes base-64 encoded BCS bytes of transaction data.
+fn decode_tx_data(tx_bytes: String) -> Result<TransactionData, anyhow::Error> {
+    Ok(bcs::from_bytes(
+        &Base64::try_from(tx_bytes)
+            .map_err(|e| anyhow!(e))?
+            .to_vec()
+            .map_err(|e| anyhow!(e))?,
+    )?)
+}
+
 // TODO(chris): only print out the full response when `--verbose` is provided
 pub fn write_transaction_response(
     response: &SuiTransactionBlockResponse,
@@ -1657,6 +1981,10 @@ pub fn write_transaction_response(
     let mut writer = String::new();
     writeln!(writer, "{}", "----- Transaction Digest ----".bold())?;
     writeln!(writer, "{}", response.digest)?;
+    if let Some(checkpoint) = response.checkpoint {
+        writeln!(writer, "{}", "----- Checkpoint ----".bold())?;
+        writeln!(writer, "{}", checkpoint)?;
+    }
     writeln!(writer, "{}", "----- Transaction Data ----".bold())?;
     if let Some(t) = &response.transaction {
         writeln!(writer, "{}", t)?;
@@ -1684,10 +2012,32 @@ pub fn write_transaction_response(
     Ok(writer)
 }
 
+pub fn write_dry_run_response(
+    response: &DryRunTransactionBlockResponse,
+) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "{}", "----- Dry Run Transaction Data ----".bold())?;
+    writeln!(writer, "{}", response.input)?;
+
+    writeln!(writer, "{}", "----- Transaction Effects ----".bold())?;
+    writeln!(writer, "{}", response.effects)?;
+
+    writeln!(writer, "{}", "----- Events ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.events))?;
+
+    writeln!(writer, "{}", "----- Object changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.object_changes))?;
+
+    writeln!(writer, "{}", "----- Balance changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.balance_changes))?;
+    Ok(writer)
+}
+
 impl Debug for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s = unwrap_err_to_string(|| match self {
-            SuiClientCommandResult::Object(object_read) => {
+            SuiClientCommandResult::Object(object_read)
+            | SuiClientCommandResult::WatchObject(object_read) => {
                 let object = object_read.object()?;
                 Ok(serde_json::to_string_pretty(&object)?)
             }
@@ -1726,9 +2076,9 @@ impl SuiClientCommandResult {
     pub fn tx_block_response(&self) -> Option<&SuiTransactionBlockResponse> {
         use SuiClientCommandResult::*;
         match self {
-            Upgrade(b) | Publish(b) | TransactionBlock(b) | Call(b) | Transfer(b)
+            Upgrade(b) | Publish(b) | TransactionBlock(b) | Call(b) | Ptb(b) | Transfer(b)
             | TransferSui(b) | Pay(b) | PaySui(b) | PayAllSui(b) | SplitCoin(b) | MergeCoin(b)
-            | ExecuteSignedTx(b) => Some(b),
+            | ExecuteSignedTx(b) | WaitForTx(b) => Some(b),
             _ => None,
         }
     }
@@ -1736,7 +2086,7 @@ impl SuiClientCommandResult {
     pub fn objects_response(&self) -> Option<Vec<SuiObjectResponse>> {
         use SuiClientCommandResult::*;
         match self {
-            Object(o) | RawObject(o) => Some(vec![o.clone()]),
+            Object(o) | RawObject(o) | WatchObject(o) => Some(vec![o.clone()]),
             Objects(o) => Some(o.clone()),
             _ => None,
         }
@@ -1759,6 +2109,8 @@ pub enum SuiClientCommandResult {
     RawObject(SuiObjectResponse),
     TransactionBlock(SuiTransactionBlockResponse),
     Call(SuiTransactionBlockResponse),
+    Ptb(SuiTransactionBlockResponse),
+    DryRun(DryRunTransactionBlockResponse),
     SerializedUnsignedTransaction(TransactionData),
     SerializedSignedTransaction(SenderSignedData),
     Transfer(SuiTransactionBlockResponse),
@@ -1772,7 +2124,15 @@ pub enum SuiClientCommandResult {
     SyncClientState,
     NewAddress((SuiAddress, String, SignatureScheme)),
     Gas(Vec<GasCoin>),
+    ExplainTx(TransactionExplanation),
+    DiffTx(String),
+    WaitForTx(SuiTransactionBlockResponse),
+    WatchObject(SuiObjectResponse),
     ChainIdentifier(String),
+    ExportState {
+        state_file: PathBuf,
+        objects: usize,
+    },
     SplitCoin(SuiTransactionBlockResponse),
     MergeCoin(SuiTransactionBlockResponse),
     Switch(SwitchResponse),
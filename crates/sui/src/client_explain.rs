@@ -0,0 +1,526 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human readable explanations of serialized transactions, with `sui client explain-tx`.
+//!
+//! The inputs of a programmable transaction are resolved: objects with their type, owner and
+//! version, and pure values decoded with the type of the parameter they are first passed to, e.g.
+//! the parameter of the Move function called, the amounts of `SplitCoins` or the recipient of
+//! `TransferObjects`. Move calls are shown with the signature of the function called. The effects
+//! of the transaction are previewed with a dry run, which reports the balance and object changes
+//! computed by the fullnode, and two transactions can be diffed, e.g. to check what differs between
+//! a transaction to sign and the one that was expected.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+
+use colored::Colorize;
+use fastcrypto::encoding::{Encoding, Hex};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::TypeTag;
+use move_core_types::value::{MoveTypeLayout, MoveValue};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use sui_json_rpc_types::{
+    BalanceChange, ObjectChange, SuiExecutionStatus, SuiMoveNormalizedFunction,
+    SuiMoveNormalizedModule, SuiMoveNormalizedType, SuiMoveVisibility, SuiObjectData,
+    SuiObjectDataOptions, SuiTransactionBlockEffectsAPI,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
+use sui_types::gas::GasCostSummary;
+use sui_types::transaction::{
+    Argument, CallArg, Command, ObjectArg, ProgrammableMoveCall, ProgrammableTransaction,
+    TransactionData, TransactionDataAPI, TransactionExpiration, TransactionKind,
+};
+use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS};
+
+#[derive(Serialize)]
+pub struct TransactionExplanation {
+    pub sender: SuiAddress,
+    pub gas_owner: SuiAddress,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    pub gas_payment: Vec<ObjectRef>,
+    pub expiration: TransactionExpiration,
+    pub inputs: Vec<String>,
+    pub commands: Vec<String>,
+    pub dry_run: Option<DryRunPreview>,
+}
+
+/// The effects the transaction would have if it was executed now.
+#[derive(Serialize)]
+pub enum DryRunPreview {
+    Executed {
+        status: SuiExecutionStatus,
+        gas_used: GasCostSummary,
+        balance_changes: Vec<BalanceChange>,
+        object_changes: Vec<ObjectChange>,
+    },
+    /// The dry run itself failed, e.g. because the gas coins were already spent.
+    Failed { error: String },
+}
+
+/// The type of a pure input, given by its first usage.
+enum PureType<'a> {
+    Move(&'a SuiMoveNormalizedType),
+    U64,
+    Address,
+}
+
+pub async fn explain_transaction(
+    client: &SuiClient,
+    tx_data: &TransactionData,
+    dry_run: bool,
+) -> anyhow::Result<TransactionExplanation> {
+    let (inputs, commands) = match tx_data.kind() {
+        TransactionKind::ProgrammableTransaction(pt) => explain_programmable(client, pt).await?,
+        kind => (vec![], vec![kind.to_string()]),
+    };
+
+    let dry_run = if dry_run {
+        Some(
+            match client
+                .read_api()
+                .dry_run_transaction_block(tx_data.clone())
+                .await
+            {
+                Ok(response) => DryRunPreview::Executed {
+                    status: response.effects.status().clone(),
+                    gas_used: response.effects.gas_cost_summary().clone(),
+                    balance_changes: response.balance_changes,
+                    object_changes: response.object_changes,
+                },
+                Err(e) => DryRunPreview::Failed {
+                    error: e.to_string(),
+                },
+            },
+        )
+    } else {
+        None
+    };
+
+    Ok(TransactionExplanation {
+        sender: tx_data.sender(),
+        gas_owner: tx_data.gas_owner(),
+        gas_budget: tx_data.gas_budget(),
+        gas_price: tx_data.gas_price(),
+        gas_payment: tx_data.gas().to_vec(),
+        expiration: *tx_data.expiration(),
+        inputs,
+        commands,
+        dry_run,
+    })
+}
+
+/// A line diff of the explanations of two transactions.
+pub fn diff_explanations(old: &TransactionExplanation, new: &TransactionExplanation) -> String {
+    let (old, new) = (old.to_string(), new.to_string());
+    let mut writer = String::new();
+    for change in TextDiff::from_lines(&old, &new).iter_all_changes() {
+        let line = change.value().trim_end_matches('\n');
+        let line = match change.tag() {
+            ChangeTag::Delete => format!("-{line}").red(),
+            ChangeTag::Insert => format!("+{line}").green(),
+            ChangeTag::Equal => format!(" {line}").normal(),
+        };
+        writeln!(writer, "{line}").unwrap();
+    }
+    writer
+}
+
+async fn explain_programmable(
+    client: &SuiClient,
+    pt: &ProgrammableTransaction,
+) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+    let mut packages: BTreeMap<ObjectID, BTreeMap<String, SuiMoveNormalizedModule>> =
+        BTreeMap::new();
+    for command in &pt.commands {
+        if let Command::MoveCall(call) = command {
+            if !packages.contains_key(&call.package) {
+                let modules = client
+                    .read_api()
+                    .get_normalized_move_modules_by_package(call.package)
+                    .await?;
+                packages.insert(call.package, modules);
+            }
+        }
+    }
+    let function = |call: &ProgrammableMoveCall| {
+        packages
+            .get(&call.package)
+            .and_then(|modules| modules.get(call.module.as_str()))
+            .and_then(|module| module.exposed_functions.get(call.function.as_str()))
+    };
+
+    // Pure inputs are decoded with the type of the first parameter they are passed to.
+    let mut pure_types = BTreeMap::new();
+    let mut use_input = |arg: &Argument, ty| {
+        if let Argument::Input(i) = arg {
+            pure_types.entry(*i as usize).or_insert(ty);
+        }
+    };
+    for command in &pt.commands {
+        match command {
+            Command::MoveCall(call) => {
+                if let Some(function) = function(call) {
+                    for (arg, ty) in call.arguments.iter().zip(&function.parameters) {
+                        use_input(arg, PureType::Move(ty));
+                    }
+                }
+            }
+            Command::SplitCoins(_, amounts) => {
+                for amount in amounts {
+                    use_input(amount, PureType::U64);
+                }
+            }
+            Command::TransferObjects(_, recipient) => use_input(recipient, PureType::Address),
+            _ => {}
+        }
+    }
+
+    let object_ids = pt
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            CallArg::Object(arg) => Some(arg.id()),
+            CallArg::Pure(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let objects = client
+        .read_api()
+        .multi_get_object_with_options(
+            object_ids,
+            SuiObjectDataOptions::new().with_type().with_owner(),
+        )
+        .await?
+        .into_iter()
+        .filter_map(|response| response.data)
+        .map(|object| (object.object_id, object))
+        .collect::<BTreeMap<_, _>>();
+
+    let inputs = pt
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| match input {
+            CallArg::Pure(bytes) => explain_pure(bytes, pure_types.get(&i)),
+            CallArg::Object(arg) => explain_object(arg, objects.get(&arg.id())),
+        })
+        .collect();
+
+    let commands = pt
+        .commands
+        .iter()
+        .map(|command| match command {
+            Command::MoveCall(call) => match function(call) {
+                Some(function) => format!("{command}\n    {}", signature(call, function)),
+                None => command.to_string(),
+            },
+            _ => command.to_string(),
+        })
+        .collect();
+
+    Ok((inputs, commands))
+}
+
+fn explain_pure(bytes: &[u8], ty: Option<&PureType>) -> String {
+    let decoded = match ty {
+        Some(PureType::Move(ty)) => decode_pure(bytes, ty).map(|v| (format_type(ty, &[]), v)),
+        Some(PureType::U64) => bcs::from_bytes::<u64>(bytes)
+            .ok()
+            .map(|v| ("u64".to_string(), v.to_string())),
+        Some(PureType::Address) => bcs::from_bytes::<SuiAddress>(bytes)
+            .ok()
+            .map(|v| ("address".to_string(), v.to_string())),
+        None => None,
+    };
+    match decoded {
+        Some((ty, value)) => format!("Pure {ty}: {value}"),
+        None => format!("Pure 0x{} ({} bytes)", Hex::encode(bytes), bytes.len()),
+    }
+}
+
+fn explain_object(arg: &ObjectArg, object: Option<&SuiObjectData>) -> String {
+    let mut explanation = match arg {
+        ObjectArg::ImmOrOwnedObject((id, version, _)) => {
+            format!("Object {id} at version {version}")
+        }
+        ObjectArg::SharedObject {
+            id,
+            initial_shared_version,
+            mutable,
+        } => format!(
+            "Shared object {id} (initial version {initial_shared_version}, {})",
+            if *mutable { "mutable" } else { "immutable" }
+        ),
+    };
+    let Some(object) = object else {
+        explanation.push_str(", not found");
+        return explanation;
+    };
+    if let Some(type_) = &object.type_ {
+        write!(explanation, " of type {type_}").unwrap();
+    }
+    if let Some(owner) = &object.owner {
+        write!(explanation, ", owned by {owner}").unwrap();
+    }
+    // The transaction can't be executed if the object was modified since it was built.
+    if let ObjectArg::ImmOrOwnedObject((_, version, _)) = arg {
+        if *version != object.version {
+            write!(explanation, ", current version {}", object.version).unwrap();
+        }
+    }
+    explanation
+}
+
+/// Decodes the BCS bytes of a pure value of type `ty`.
+fn decode_pure(bytes: &[u8], ty: &SuiMoveNormalizedType) -> Option<String> {
+    if is_string(ty) {
+        return bcs::from_bytes::<String>(bytes)
+            .ok()
+            .map(|s| format!("{s:?}"));
+    }
+    let layout = pure_layout(ty)?;
+    MoveValue::simple_deserialize(bytes, &layout)
+        .ok()
+        .map(|v| v.to_string())
+}
+
+fn pure_layout(ty: &SuiMoveNormalizedType) -> Option<MoveTypeLayout> {
+    Some(match ty {
+        SuiMoveNormalizedType::Bool => MoveTypeLayout::Bool,
+        SuiMoveNormalizedType::U8 => MoveTypeLayout::U8,
+        SuiMoveNormalizedType::U16 => MoveTypeLayout::U16,
+        SuiMoveNormalizedType::U32 => MoveTypeLayout::U32,
+        SuiMoveNormalizedType::U64 => MoveTypeLayout::U64,
+        SuiMoveNormalizedType::U128 => MoveTypeLayout::U128,
+        SuiMoveNormalizedType::U256 => MoveTypeLayout::U256,
+        SuiMoveNormalizedType::Address => MoveTypeLayout::Address,
+        SuiMoveNormalizedType::Vector(ty) => MoveTypeLayout::Vector(Box::new(pure_layout(ty)?)),
+        SuiMoveNormalizedType::Reference(ty) | SuiMoveNormalizedType::MutableReference(ty) => {
+            return pure_layout(ty)
+        }
+        // Strings are encoded as vectors of bytes, IDs as addresses and options as vectors of at
+        // most one element.
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            type_arguments,
+        } => match (parse_address(address)?, module.as_str(), name.as_str()) {
+            (MOVE_STDLIB_ADDRESS, "string" | "ascii", "String") => {
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8))
+            }
+            (MOVE_STDLIB_ADDRESS, "option", "Option") => {
+                MoveTypeLayout::Vector(Box::new(pure_layout(type_arguments.first()?)?))
+            }
+            (SUI_FRAMEWORK_ADDRESS, "object", "ID") => MoveTypeLayout::Address,
+            _ => return None,
+        },
+        SuiMoveNormalizedType::Signer | SuiMoveNormalizedType::TypeParameter(_) => return None,
+    })
+}
+
+fn is_string(ty: &SuiMoveNormalizedType) -> bool {
+    match ty {
+        SuiMoveNormalizedType::Reference(ty) | SuiMoveNormalizedType::MutableReference(ty) => {
+            is_string(ty)
+        }
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            ..
+        } => {
+            parse_address(address) == Some(MOVE_STDLIB_ADDRESS)
+                && (module == "string" || module == "ascii")
+                && name == "String"
+        }
+        _ => false,
+    }
+}
+
+fn parse_address(address: &str) -> Option<AccountAddress> {
+    AccountAddress::from_hex_literal(address).ok()
+}
+
+/// The signature of the function called, with the type arguments of the call substituted.
+fn signature(call: &ProgrammableMoveCall, function: &SuiMoveNormalizedFunction) -> String {
+    let visibility = match function.visibility {
+        SuiMoveVisibility::Private => "",
+        SuiMoveVisibility::Public => "public ",
+        SuiMoveVisibility::Friend => "public(friend) ",
+    };
+    let entry = if function.is_entry { "entry " } else { "" };
+    let format_types = |types: &[SuiMoveNormalizedType]| {
+        types
+            .iter()
+            .map(|ty| format_type(ty, &call.type_arguments))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut signature = format!(
+        "{visibility}{entry}fun {}({})",
+        call.function,
+        format_types(&function.parameters)
+    );
+    match function.return_.as_slice() {
+        [] => {}
+        [ty] => write!(signature, ": {}", format_type(ty, &call.type_arguments)).unwrap(),
+        types => write!(signature, ": ({})", format_types(types)).unwrap(),
+    }
+    signature
+}
+
+fn format_type(ty: &SuiMoveNormalizedType, type_arguments: &[TypeTag]) -> String {
+    match ty {
+        SuiMoveNormalizedType::Bool => "bool".to_string(),
+        SuiMoveNormalizedType::U8 => "u8".to_string(),
+        SuiMoveNormalizedType::U16 => "u16".to_string(),
+        SuiMoveNormalizedType::U32 => "u32".to_string(),
+        SuiMoveNormalizedType::U64 => "u64".to_string(),
+        SuiMoveNormalizedType::U128 => "u128".to_string(),
+        SuiMoveNormalizedType::U256 => "u256".to_string(),
+        SuiMoveNormalizedType::Address => "address".to_string(),
+        SuiMoveNormalizedType::Signer => "signer".to_string(),
+        SuiMoveNormalizedType::Struct {
+            address,
+            module,
+            name,
+            type_arguments: struct_type_arguments,
+        } => {
+            let mut s = format!("{address}::{module}::{name}");
+            if !struct_type_arguments.is_empty() {
+                let args = struct_type_arguments
+                    .iter()
+                    .map(|ty| format_type(ty, type_arguments))
+                    .collect::<Vec<_>>();
+                write!(s, "<{}>", args.join(", ")).unwrap();
+            }
+            s
+        }
+        SuiMoveNormalizedType::Vector(ty) => format!("vector<{}>", format_type(ty, type_arguments)),
+        SuiMoveNormalizedType::TypeParameter(i) => type_arguments
+            .get(*i as usize)
+            .map(|ty| ty.to_string())
+            .unwrap_or_else(|| format!("T{i}")),
+        SuiMoveNormalizedType::Reference(ty) => format!("&{}", format_type(ty, type_arguments)),
+        SuiMoveNormalizedType::MutableReference(ty) => {
+            format!("&mut {}", format_type(ty, type_arguments))
+        }
+    }
+}
+
+impl Display for TransactionExplanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "{}", "----- Transaction ----".bold())?;
+        writeln!(writer, "Sender: {}", self.sender)?;
+        writeln!(writer, "Gas owner: {}", self.gas_owner)?;
+        writeln!(
+            writer,
+            "Gas budget: {} MIST at gas price {}",
+            self.gas_budget, self.gas_price
+        )?;
+        writeln!(writer, "Gas payment:")?;
+        for (id, version, digest) in &self.gas_payment {
+            writeln!(writer, "  {id} at version {version} ({digest})")?;
+        }
+        match self.expiration {
+            TransactionExpiration::None => {}
+            TransactionExpiration::Epoch(epoch) => writeln!(writer, "Expires after epoch {epoch}")?,
+        }
+
+        if !self.inputs.is_empty() {
+            writeln!(writer, "{}", "----- Inputs ----".bold())?;
+            for (i, input) in self.inputs.iter().enumerate() {
+                writeln!(writer, "  Input({i}): {input}")?;
+            }
+        }
+
+        writeln!(writer, "{}", "----- Commands ----".bold())?;
+        for (i, command) in self.commands.iter().enumerate() {
+            writeln!(writer, "  {i}: {command}")?;
+        }
+
+        match &self.dry_run {
+            None => {}
+            Some(DryRunPreview::Failed { error }) => {
+                writeln!(writer, "{}", "----- Dry run ----".bold())?;
+                writeln!(writer, "{}", format!("Dry run failed: {error}").red())?;
+            }
+            Some(DryRunPreview::Executed {
+                status,
+                gas_used,
+                balance_changes,
+                object_changes,
+            }) => {
+                writeln!(writer, "{}", "----- Dry run ----".bold())?;
+                writeln!(writer, "Status: {status:?}")?;
+                writeln!(writer, "Gas used: {gas_used}")?;
+
+                writeln!(writer, "{}", "----- Balance changes ----".bold())?;
+                for change in balance_changes {
+                    writeln!(
+                        writer,
+                        "  {}: {:+} {}",
+                        change.owner, change.amount, change.coin_type
+                    )?;
+                }
+
+                writeln!(writer, "{}", "----- Object changes ----".bold())?;
+                for change in object_changes {
+                    writeln!(writer, "  {}", explain_object_change(change))?;
+                }
+            }
+        }
+        write!(f, "{}", writer.trim_end_matches('\n'))
+    }
+}
+
+fn explain_object_change(change: &ObjectChange) -> String {
+    match change {
+        ObjectChange::Published {
+            package_id,
+            modules,
+            ..
+        } => format!(
+            "Published package {package_id} with modules {}",
+            modules.join(", ")
+        ),
+        ObjectChange::Transferred {
+            recipient,
+            object_type,
+            object_id,
+            ..
+        } => format!("Transferred {object_id} of type {object_type} to {recipient}"),
+        ObjectChange::Mutated {
+            owner,
+            object_type,
+            object_id,
+            version,
+            previous_version,
+            ..
+        } => format!(
+            "Mutated {object_id} of type {object_type} from version {previous_version} to \
+             {version}, owned by {owner}"
+        ),
+        ObjectChange::Deleted {
+            object_type,
+            object_id,
+            ..
+        } => format!("Deleted {object_id} of type {object_type}"),
+        ObjectChange::Wrapped {
+            object_type,
+            object_id,
+            ..
+        } => format!("Wrapped {object_id} of type {object_type}"),
+        ObjectChange::Created {
+            owner,
+            object_type,
+            object_id,
+            ..
+        } => format!("Created {object_id} of type {object_type}, owned by {owner}"),
+    }
+}
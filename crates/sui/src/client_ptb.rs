@@ -0,0 +1,395 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Programmable transaction blocks composed on the command line with `sui client ptb`.
+//!
+//! The commands of the block are given as a JSON array, run in order, e.g.
+//!
+//! ```json
+//! [
+//!   {"SplitCoins": {"coin": "$gas", "amounts": [1000, 2000]}},
+//!   {"TransferObjects": {"objects": ["$0.0", "$0.1"], "recipient": "0x..."}}
+//! ]
+//! ```
+//!
+//! The commands are `MoveCall`, `SplitCoins`, `MergeCoins`, `TransferObjects`, `MakeMoveVec`,
+//! `Publish` and `Upgrade`. Their arguments are JSON values, or references to
+//!
+//! - `"$gas"`, the gas coin,
+//! - `"$N"`, the result of the N-th command, which must come earlier,
+//! - `"$N.M"`, the M-th result of the N-th command, for commands with several results.
+//!
+//! The arguments of `MoveCall` are resolved against the parameters of the function called, as
+//! with `sui client call`. Objects are given by ID, the amounts of `SplitCoins` are numbers and
+//! the recipient of `TransferObjects` is an address. `Publish` results in the upgrade capability
+//! of the package published, which must be transferred, while `Upgrade` authorizes, performs and
+//! commits the upgrade and has no result.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use move_core_types::language_storage::TypeTag;
+use move_core_types::value::MoveTypeLayout;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde::Deserialize;
+use serde_json::Value;
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::SuiTypeTag;
+use sui_sdk::SuiClient;
+use sui_transaction_builder::MoveCallArg;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, Command, ProgrammableTransaction};
+use sui_types::{parse_sui_type_tag, Identifier, SUI_FRAMEWORK_PACKAGE_ID};
+
+use crate::client_commands::{
+    compile_package, convert_number_to_string, get_upgrade_policy, published_at,
+};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum PtbCommand {
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        #[serde(default)]
+        type_args: Vec<String>,
+        #[serde(default)]
+        args: Vec<Value>,
+    },
+    SplitCoins {
+        coin: Value,
+        amounts: Vec<Value>,
+    },
+    MergeCoins {
+        target: Value,
+        sources: Vec<Value>,
+    },
+    TransferObjects {
+        objects: Vec<Value>,
+        recipient: Value,
+    },
+    MakeMoveVec {
+        /// Type of the elements, required if there are none. Elements of primitive type are
+        /// given as values, others as objects or references.
+        #[serde(default, rename = "type")]
+        type_: Option<String>,
+        elements: Vec<Value>,
+    },
+    Publish {
+        package_path: PathBuf,
+        #[serde(default)]
+        with_unpublished_dependencies: bool,
+        #[serde(default)]
+        skip_dependency_verification: bool,
+    },
+    Upgrade {
+        package_path: PathBuf,
+        upgrade_capability: ObjectID,
+        #[serde(default)]
+        with_unpublished_dependencies: bool,
+        #[serde(default)]
+        skip_dependency_verification: bool,
+    },
+}
+
+/// An argument of a command, see the module documentation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PtbArg {
+    GasCoin,
+    Result(u16),
+    NestedResult(u16, u16),
+    Value(Value),
+}
+
+impl From<Value> for PtbArg {
+    fn from(value: Value) -> Self {
+        match &value {
+            Value::String(s) if s == "$gas" => PtbArg::GasCoin,
+            Value::String(s) => match s.strip_prefix('$').map(parse_result_reference) {
+                Some(Some((command, None))) => PtbArg::Result(command),
+                Some(Some((command, Some(result)))) => PtbArg::NestedResult(command, result),
+                _ => PtbArg::Value(value),
+            },
+            _ => PtbArg::Value(value),
+        }
+    }
+}
+
+fn parse_result_reference(reference: &str) -> Option<(u16, Option<u16>)> {
+    match reference.split_once('.') {
+        Some((command, result)) => Some((command.parse().ok()?, Some(result.parse().ok()?))),
+        None => Some((reference.parse().ok()?, None)),
+    }
+}
+
+/// Parse the commands of a programmable transaction block from their JSON array.
+pub fn parse_ptb_commands(json: &str) -> Result<Vec<PtbCommand>, anyhow::Error> {
+    let commands: Vec<PtbCommand> =
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid PTB commands: {e}"))?;
+    if commands.is_empty() {
+        bail!("A programmable transaction block needs at least one command");
+    }
+    Ok(commands)
+}
+
+/// Builds a programmable transaction out of `PtbCommand`s, resolving their arguments.
+pub struct PtbBuilder<'a> {
+    client: &'a SuiClient,
+    builder: ProgrammableTransactionBuilder,
+    /// Result of every command added so far, `None` for commands without a usable result.
+    results: Vec<Option<Argument>>,
+}
+
+impl<'a> PtbBuilder<'a> {
+    pub fn new(client: &'a SuiClient) -> Self {
+        Self {
+            client,
+            builder: ProgrammableTransactionBuilder::new(),
+            results: vec![],
+        }
+    }
+
+    pub async fn build(
+        mut self,
+        commands: Vec<PtbCommand>,
+    ) -> Result<ProgrammableTransaction, anyhow::Error> {
+        for (index, command) in commands.into_iter().enumerate() {
+            let result = self
+                .command(command)
+                .await
+                .map_err(|e| anyhow!("Invalid command {index}: {e}"))?;
+            self.results.push(result);
+        }
+        Ok(self.builder.finish())
+    }
+
+    async fn command(&mut self, command: PtbCommand) -> Result<Option<Argument>, anyhow::Error> {
+        Ok(match command {
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_args,
+                args,
+            } => {
+                let args = args
+                    .into_iter()
+                    .map(|arg| match self.reference(PtbArg::from(arg))? {
+                        Ok(argument) => Ok(MoveCallArg::Argument(argument)),
+                        // Numbers are converted to strings so that they can be resolved to any
+                        // integer type
+                        Err(value) => Ok(MoveCallArg::Json(SuiJsonValue::new(
+                            convert_number_to_string(value),
+                        )?)),
+                    })
+                    .collect::<Result<_, anyhow::Error>>()?;
+                let result = self
+                    .client
+                    .transaction_builder()
+                    .programmable_move_call(
+                        &mut self.builder,
+                        package,
+                        &module,
+                        &function,
+                        type_args.into_iter().map(SuiTypeTag::new).collect(),
+                        args,
+                    )
+                    .await?;
+                Some(result)
+            }
+            PtbCommand::SplitCoins { coin, amounts } => {
+                let coin = self.object(coin, true).await?;
+                let amounts = amounts
+                    .into_iter()
+                    .map(|amount| self.pure(amount, &MoveTypeLayout::U64))
+                    .collect::<Result<_, _>>()?;
+                Some(self.builder.command(Command::SplitCoins(coin, amounts)))
+            }
+            PtbCommand::MergeCoins { target, sources } => {
+                let target = self.object(target, true).await?;
+                let mut coins = vec![];
+                for source in sources {
+                    coins.push(self.object(source, true).await?);
+                }
+                Some(self.builder.command(Command::MergeCoins(target, coins)))
+            }
+            PtbCommand::TransferObjects { objects, recipient } => {
+                let mut transferred = vec![];
+                for object in objects {
+                    transferred.push(self.object(object, false).await?);
+                }
+                let recipient = self.address(recipient)?;
+                Some(
+                    self.builder
+                        .command(Command::TransferObjects(transferred, recipient)),
+                )
+            }
+            PtbCommand::MakeMoveVec { type_, elements } => {
+                let type_ = type_.as_deref().map(parse_sui_type_tag).transpose()?;
+                if type_.is_none() && elements.is_empty() {
+                    bail!("The type of the elements is required when there are none");
+                }
+                let layout = type_.as_ref().and_then(primitive_layout);
+                let mut args = vec![];
+                for element in elements {
+                    args.push(match &layout {
+                        Some(layout) => self.pure(element, layout)?,
+                        None => self.object(element, false).await?,
+                    });
+                }
+                Some(self.builder.command(Command::MakeMoveVec(type_, args)))
+            }
+            PtbCommand::Publish {
+                package_path,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let (dependencies, modules, _, _) = compile_package(
+                    self.client,
+                    MoveBuildConfig::default(),
+                    package_path,
+                    with_unpublished_dependencies,
+                    skip_dependency_verification,
+                    false,
+                )
+                .await?;
+                Some(
+                    self.builder.publish_upgradeable(
+                        modules,
+                        dependencies.published.into_values().collect(),
+                    ),
+                )
+            }
+            PtbCommand::Upgrade {
+                package_path,
+                upgrade_capability,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let (dependencies, modules, compiled_package, package_id) = compile_package(
+                    self.client,
+                    MoveBuildConfig::default(),
+                    package_path,
+                    with_unpublished_dependencies,
+                    skip_dependency_verification,
+                    false,
+                )
+                .await?;
+                let package_id = published_at(package_id)?;
+                let upgrade_policy = get_upgrade_policy(self.client, upgrade_capability).await?;
+                let digest = compiled_package.get_package_digest(
+                    with_unpublished_dependencies,
+                    /* hash_modules */ true,
+                );
+
+                let capability = self.object_by_id(upgrade_capability, true).await?;
+                let upgrade_policy = self.builder.pure(upgrade_policy)?;
+                let digest = self.builder.pure(digest.to_vec())?;
+                let ticket = self.builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    Identifier::new("package")?,
+                    Identifier::new("authorize_upgrade")?,
+                    vec![],
+                    vec![capability, upgrade_policy, digest],
+                );
+                let receipt = self.builder.upgrade(
+                    package_id,
+                    ticket,
+                    dependencies.published.into_values().collect(),
+                    modules,
+                );
+                self.builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    Identifier::new("package")?,
+                    Identifier::new("commit_upgrade")?,
+                    vec![],
+                    vec![capability, receipt],
+                );
+                None
+            }
+        })
+    }
+
+    /// The argument `arg` refers to, or its value if it is not a reference.
+    fn reference(&self, arg: PtbArg) -> Result<Result<Argument, Value>, anyhow::Error> {
+        let result = |command: u16| match self.results.get(command as usize) {
+            Some(Some(Argument::Result(index))) => Ok(*index),
+            Some(_) => Err(anyhow!("Command {command} has no result to refer to")),
+            None => Err(anyhow!(
+                "Command {command} does not come before the command referring to it"
+            )),
+        };
+        Ok(Ok(match arg {
+            PtbArg::GasCoin => Argument::GasCoin,
+            PtbArg::Result(command) => Argument::Result(result(command)?),
+            PtbArg::NestedResult(command, index) => Argument::NestedResult(result(command)?, index),
+            PtbArg::Value(value) => return Ok(Err(value)),
+        }))
+    }
+
+    async fn object(
+        &mut self,
+        arg: Value,
+        is_mutable_ref: bool,
+    ) -> Result<Argument, anyhow::Error> {
+        match self.reference(PtbArg::from(arg))? {
+            Ok(argument) => Ok(argument),
+            Err(Value::String(id)) => {
+                self.object_by_id(ObjectID::from_str(&id)?, is_mutable_ref)
+                    .await
+            }
+            Err(value) => Err(anyhow!("Expected an object ID, found {value}")),
+        }
+    }
+
+    async fn object_by_id(
+        &mut self,
+        id: ObjectID,
+        is_mutable_ref: bool,
+    ) -> Result<Argument, anyhow::Error> {
+        self.client
+            .transaction_builder()
+            .object_input(&mut self.builder, id, is_mutable_ref)
+            .await
+    }
+
+    fn address(&mut self, arg: Value) -> Result<Argument, anyhow::Error> {
+        match self.reference(PtbArg::from(arg))? {
+            Ok(argument) => Ok(argument),
+            Err(Value::String(address)) => self.builder.pure(SuiAddress::from_str(&address)?),
+            Err(value) => Err(anyhow!("Expected an address, found {value}")),
+        }
+    }
+
+    fn pure(&mut self, arg: Value, layout: &MoveTypeLayout) -> Result<Argument, anyhow::Error> {
+        match self.reference(PtbArg::from(arg))? {
+            Ok(argument) => Ok(argument),
+            Err(value) => {
+                let bytes =
+                    SuiJsonValue::new(convert_number_to_string(value))?.to_bcs_bytes(layout)?;
+                Ok(self.builder.pure_bytes(bytes, false))
+            }
+        }
+    }
+}
+
+fn primitive_layout(type_: &TypeTag) -> Option<MoveTypeLayout> {
+    Some(match type_ {
+        TypeTag::Bool => MoveTypeLayout::Bool,
+        TypeTag::U8 => MoveTypeLayout::U8,
+        TypeTag::U16 => MoveTypeLayout::U16,
+        TypeTag::U32 => MoveTypeLayout::U32,
+        TypeTag::U64 => MoveTypeLayout::U64,
+        TypeTag::U128 => MoveTypeLayout::U128,
+        TypeTag::U256 => MoveTypeLayout::U256,
+        TypeTag::Address => MoveTypeLayout::Address,
+        TypeTag::Vector(inner) => MoveTypeLayout::Vector(Box::new(primitive_layout(inner)?)),
+        TypeTag::Signer | TypeTag::Struct(_) => return None,
+    })
+}
+
+#[cfg(test)]
+#[path = "unit_tests/client_ptb_tests.rs"]
+mod client_ptb_tests;
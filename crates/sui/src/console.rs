@@ -3,6 +3,7 @@
 
 use std::io::{stderr, Write};
 use std::ops::Deref;
+use std::path::Path;
 
 use async_trait::async_trait;
 use clap::Command;
@@ -10,12 +11,14 @@ use clap::CommandFactory;
 use clap::FromArgMatches;
 use clap::Parser;
 use colored::Colorize;
+use serde_json::Value;
 use sui_sdk::wallet_context::WalletContext;
 
 use crate::client_commands::SwitchResponse;
 use crate::client_commands::{SuiClientCommandResult, SuiClientCommands};
 use crate::shell::{
-    install_shell_plugins, AsyncHandler, CacheKey, CommandStructure, CompletionCache, Shell,
+    install_shell_plugins, AsyncHandler, CacheKey, CommandStructure, CompletionCache, Script,
+    ScriptHandler, Shell,
 };
 
 const SUI: &str = "   _____       _    ______                       __
@@ -88,6 +91,26 @@ pub async fn start_console(
     shell.run_async(out, err).await
 }
 
+/// Runs the console commands of a script file, see [`Script`].
+pub async fn run_script(
+    mut context: WalletContext,
+    script: &Path,
+    continue_on_error: bool,
+    out: &mut (dyn Write + Send),
+    err: &mut (dyn Write + Send),
+) -> Result<(), anyhow::Error> {
+    let script = Script::parse(&std::fs::read_to_string(script)?)?;
+    script
+        .run(
+            &mut context,
+            &ClientCommandHandler,
+            continue_on_error,
+            out,
+            err,
+        )
+        .await
+}
+
 struct ClientCommandHandler;
 
 #[async_trait]
@@ -108,6 +131,20 @@ impl AsyncHandler<WalletContext> for ClientCommandHandler {
     }
 }
 
+#[async_trait]
+impl ScriptHandler<WalletContext> for ClientCommandHandler {
+    async fn run_command(
+        &self,
+        args: Vec<String>,
+        context: &mut WalletContext,
+    ) -> Result<Value, anyhow::Error> {
+        let wallet_opts = get_command(args)?;
+        let result = wallet_opts.command.execute(context).await?;
+        result.print(!wallet_opts.json);
+        Ok(serde_json::to_value(&result)?)
+    }
+}
+
 fn get_command(args: Vec<String>) -> Result<ConsoleOpts, anyhow::Error> {
     let app: Command = install_shell_plugins(ConsoleOpts::command());
     Ok(ConsoleOpts::from_arg_matches(
@@ -14,6 +14,7 @@ use fastcrypto_zkp::bn254::zk_login::OAuthProvider;
 use fastcrypto_zkp::bn254::zk_login::{
     big_int_str_to_bytes, AuxInputs, PublicInputs, SupportedKeyClaim, ZkLoginProof,
 };
+use inquire::Password;
 use num_bigint::{BigInt, Sign};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
@@ -23,12 +24,14 @@ use shared_crypto::intent::{Intent, IntentMessage};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 use sui_keys::key_derive::generate_new_key;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
+use sui_keys::keystore_cipher::PASSPHRASE_ENV_VAR;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{
     get_authority_key_pair, get_key_pair_from_rng, EncodeDecodeBase64, SignatureScheme, SuiKeyPair,
@@ -251,6 +254,25 @@ pub enum KeyToolCommand {
         #[clap(long)]
         user_signature: String,
     },
+
+    /// Encrypt sui.keystore at rest with a passphrase. Once encrypted, the keystore is loaded
+    /// with the passphrase from the SUI_KEYSTORE_PASSPHRASE environment variable, or after
+    /// unlocking it with `sui keytool unlock`.
+    Encrypt,
+
+    /// Change the passphrase of an encrypted sui.keystore. This also locks it.
+    ChangePassphrase,
+
+    /// Unlock an encrypted sui.keystore for a session, so that it can be used without
+    /// passphrase until the session expires or `sui keytool lock` is run.
+    Unlock {
+        /// How long the keystore stays unlocked, in seconds.
+        #[clap(long, default_value = "3600")]
+        duration: u64,
+    },
+
+    /// Lock an encrypted sui.keystore unlocked with `sui keytool unlock`.
+    Lock,
 }
 
 impl KeyToolCommand {
@@ -688,9 +710,87 @@ impl KeyToolCommand {
                     sig.encode_base64()
                 );
             }
+
+            KeyToolCommand::Encrypt => {
+                let keystore = file_keystore(keystore)?;
+                if keystore.is_encrypted() {
+                    return Err(anyhow!(
+                        "Keystore is already encrypted, use `sui keytool change-passphrase` to change its passphrase"
+                    ));
+                }
+                keystore.encrypt(&read_new_passphrase()?)?;
+                println!("Keystore encrypted");
+            }
+
+            KeyToolCommand::ChangePassphrase => {
+                let keystore = file_keystore(keystore)?;
+                if !keystore.is_encrypted() {
+                    return Err(anyhow!(
+                        "Keystore is not encrypted, use `sui keytool encrypt` to encrypt it"
+                    ));
+                }
+                keystore.encrypt(&read_new_passphrase()?)?;
+                println!("Keystore passphrase changed");
+            }
+
+            KeyToolCommand::Unlock { duration } => {
+                let keystore = file_keystore(keystore)?;
+                keystore.unlock(Duration::from_secs(duration))?;
+                println!("Keystore unlocked for {duration} seconds");
+            }
+
+            KeyToolCommand::Lock => {
+                let keystore = file_keystore(keystore)?;
+                if let Some(path) = keystore.path() {
+                    FileBasedKeystore::lock(path)?;
+                }
+                println!("Keystore locked");
+            }
         }
         Ok(())
     }
+
+    /// Load the file keystore at `path` to execute this command with. Unlocking a keystore and
+    /// changing its passphrase ask for its current passphrase, unless it is set in the
+    /// environment, and a keystore can be locked without it.
+    pub fn load_keystore(&self, path: &PathBuf) -> Result<Keystore, anyhow::Error> {
+        let keystore = match self {
+            KeyToolCommand::Lock => {
+                let mut keystore = FileBasedKeystore::default();
+                keystore.set_path(path);
+                keystore
+            }
+            KeyToolCommand::Unlock { .. } | KeyToolCommand::ChangePassphrase
+                if FileBasedKeystore::is_encrypted_file(path)?
+                    && std::env::var(PASSPHRASE_ENV_VAR).is_err() =>
+            {
+                let passphrase = Password::new("Keystore passphrase:")
+                    .without_confirmation()
+                    .prompt()?;
+                FileBasedKeystore::new_with_passphrase(path, &passphrase)?
+            }
+            _ => FileBasedKeystore::new(path)?,
+        };
+        Ok(Keystore::from(keystore))
+    }
+}
+
+fn file_keystore(keystore: &mut Keystore) -> Result<&mut FileBasedKeystore, anyhow::Error> {
+    match keystore {
+        Keystore::File(keystore) => Ok(keystore),
+        _ => Err(anyhow!("Only file keystores can be encrypted")),
+    }
+}
+
+fn read_new_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = Password::new("New keystore passphrase:")
+        .with_custom_confirmation_message("Confirm passphrase:")
+        .with_custom_confirmation_error_message("The passphrases don't match")
+        .prompt()?;
+    if passphrase.is_empty() {
+        return Err(anyhow!("The passphrase can't be empty"));
+    }
+    Ok(passphrase)
 }
 
 fn convert_string_to_base64(value: String) -> Result<String, anyhow::Error> {
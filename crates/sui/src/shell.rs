@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail};
 use std::borrow::Cow;
 use std::borrow::Cow::Owned;
 use std::cmp::Ordering;
@@ -21,6 +21,7 @@ use rustyline::hint::Hinter;
 use rustyline::validate::Validator;
 use rustyline::{Config, Context, Editor};
 use rustyline_derive::Helper;
+use serde_json::Value;
 use unescape::unescape;
 
 #[path = "unit_tests/shell_tests.rs"]
@@ -139,6 +140,365 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
     }
 }
 
+/// A script of shell commands, run in order without interaction. On top of the commands of the
+/// shell, scripts have variables, loops and captured outputs:
+///
+/// ```text
+/// # Lines starting with `#` are comments, lines ending with `\` continue on the next line.
+/// let published = publish --path ./package --gas-budget 100000000
+/// set package ${published.objectChanges[type=published].0.packageId}
+/// let objects = objects
+/// for object in ${objects.data.objectId}
+///     echo ${package} owns ${object}
+/// end
+/// ```
+///
+/// - `let NAME = COMMAND` runs the command and captures its output, as JSON, into `NAME`.
+/// - `set NAME VALUE` sets `NAME` to the value, which may be a reference to another variable.
+/// - `for NAME in ${REFERENCE}` runs the commands up to the matching `end` once for each element
+///   of the array referenced.
+///
+/// References `${NAME.FIELD.INDEX...}` select fields of objects and elements of arrays by index,
+/// fields selected on an array are selected on each of its elements, and `FIELD[KEY=VALUE]`
+/// filters the elements of an array on the value of their `KEY` field. References are substituted
+/// by their value, strings unquoted, and `$ENV_VARIABLE`s as in the shell.
+pub struct Script {
+    statements: Vec<(usize, Statement)>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Statement {
+    Command {
+        capture: Option<String>,
+        command: String,
+    },
+    Set {
+        variable: String,
+        value: String,
+    },
+    For {
+        variable: String,
+        items: String,
+        /// Index of the matching `end` statement.
+        end: usize,
+    },
+    End,
+    Exit,
+}
+
+impl Script {
+    pub fn parse(script: &str) -> Result<Self, anyhow::Error> {
+        let mut statements = vec![];
+        let mut open_loops = vec![];
+        let mut lines = script.lines().enumerate();
+        while let Some((i, line)) = lines.next() {
+            let line_number = i + 1;
+            let mut line = line.trim().to_string();
+            while line.ends_with('\\') {
+                line.pop();
+                let (_, next) = lines
+                    .next()
+                    .ok_or_else(|| anyhow!("Line {line_number}: unterminated line"))?;
+                line.push_str(next.trim());
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (keyword, rest) = line
+                .split_once(char::is_whitespace)
+                .map(|(keyword, rest)| (keyword, rest.trim()))
+                .unwrap_or((line.as_str(), ""));
+            let statement = match keyword {
+                "let" => {
+                    let (variable, command) = rest
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("Line {line_number}: expected `let NAME = ...`"))?;
+                    Statement::Command {
+                        capture: Some(parse_variable(variable.trim(), line_number)?),
+                        command: command.trim().to_string(),
+                    }
+                }
+                "set" => {
+                    let (variable, value) =
+                        rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    Statement::Set {
+                        variable: parse_variable(variable, line_number)?,
+                        value: value.trim().to_string(),
+                    }
+                }
+                "for" => {
+                    let (variable, items) = rest
+                        .split_once(" in ")
+                        .ok_or_else(|| anyhow!("Line {line_number}: expected `for NAME in ...`"))?;
+                    let items = parse_reference(items.trim()).ok_or_else(|| {
+                        anyhow!("Line {line_number}: loops iterate over a `${{REFERENCE}}`")
+                    })?;
+                    open_loops.push(statements.len());
+                    Statement::For {
+                        variable: parse_variable(variable.trim(), line_number)?,
+                        items: items.to_string(),
+                        end: 0,
+                    }
+                }
+                "end" => {
+                    let start = open_loops
+                        .pop()
+                        .ok_or_else(|| anyhow!("Line {line_number}: `end` without `for`"))?;
+                    let index = statements.len();
+                    if let (_, Statement::For { end, .. }) = &mut statements[start] {
+                        *end = index;
+                    }
+                    Statement::End
+                }
+                "exit" | "quit" => Statement::Exit,
+                _ => Statement::Command {
+                    capture: None,
+                    command: line.clone(),
+                },
+            };
+            statements.push((line_number, statement));
+        }
+        if let Some(start) = open_loops.pop() {
+            bail!("Line {}: `for` without `end`", statements[start].0);
+        }
+        Ok(Self { statements })
+    }
+
+    /// Runs the script, stopping at the first command failing unless `continue_on_error` is set,
+    /// in which case the failures are reported and the script fails once complete.
+    pub async fn run<S: Send, H: ScriptHandler<S>>(
+        &self,
+        state: &mut S,
+        handler: &H,
+        continue_on_error: bool,
+        out: &mut (dyn Write + Send),
+        err: &mut (dyn Write + Send),
+    ) -> Result<(), anyhow::Error> {
+        let mut variables = ScriptVariables::default();
+        // The loops being run, with the index of their `for` statement and their next items.
+        let mut loops: Vec<(usize, std::vec::IntoIter<Value>)> = vec![];
+        let mut failures = 0;
+        let mut index = 0;
+        while let Some((line_number, statement)) = self.statements.get(index) {
+            let result = match statement {
+                Statement::Command { capture, command } => self
+                    .run_command(
+                        command,
+                        capture.as_deref(),
+                        &mut variables,
+                        state,
+                        handler,
+                        out,
+                    )
+                    .await
+                    .map(|_| index + 1),
+                Statement::Set { variable, value } => {
+                    let value = match parse_reference(value) {
+                        Some(reference) => variables.resolve(reference),
+                        None => variables.substitute(value).map(Value::String),
+                    };
+                    value.map(|value| {
+                        variables.set(variable, value);
+                        index + 1
+                    })
+                }
+                Statement::For {
+                    variable,
+                    items,
+                    end,
+                } => match variables.resolve(items) {
+                    Ok(Value::Array(items)) => {
+                        let mut items = items.into_iter();
+                        match items.next() {
+                            Some(item) => {
+                                variables.set(variable, item);
+                                loops.push((index, items));
+                                Ok(index + 1)
+                            }
+                            None => Ok(end + 1),
+                        }
+                    }
+                    Ok(value) => Err(anyhow!("`${{{items}}}` is not an array: {value}")),
+                    Err(e) => Err(e),
+                },
+                Statement::End => {
+                    let (start, items) = loops.last_mut().expect("Loops are balanced");
+                    let start = *start;
+                    match items.next() {
+                        Some(item) => {
+                            if let (_, Statement::For { variable, .. }) = &self.statements[start] {
+                                variables.set(variable, item);
+                            }
+                            Ok(start + 1)
+                        }
+                        None => {
+                            loops.pop();
+                            Ok(index + 1)
+                        }
+                    }
+                }
+                Statement::Exit => break,
+            };
+            index = match result {
+                Ok(next) => next,
+                Err(e) if continue_on_error => {
+                    writeln!(err, "{}", format!("Line {line_number}: {e}").red())?;
+                    failures += 1;
+                    // A loop which can't be run is skipped.
+                    match statement {
+                        Statement::For { end, .. } => end + 1,
+                        _ => index + 1,
+                    }
+                }
+                Err(e) => return Err(e.context(format!("Line {line_number}"))),
+            };
+        }
+        if failures > 0 {
+            bail!("{failures} command(s) of the script failed");
+        }
+        Ok(())
+    }
+
+    async fn run_command<S: Send, H: ScriptHandler<S>>(
+        &self,
+        command: &str,
+        capture: Option<&str>,
+        variables: &mut ScriptVariables,
+        state: &mut S,
+        handler: &H,
+        out: &mut (dyn Write + Send),
+    ) -> Result<(), anyhow::Error> {
+        let command = substitute_env_variables(variables.substitute(command)?);
+        let args = split_and_unescape(&command)?;
+        let output = match args.first().map(String::as_str) {
+            None => return Ok(()),
+            Some("echo") => {
+                let line = args[1..].join(" ");
+                writeln!(out, "{line}")?;
+                Value::String(line)
+            }
+            Some(_) => handler.run_command(args, state).await?,
+        };
+        if let Some(variable) = capture {
+            variables.set(variable, output);
+        }
+        Ok(())
+    }
+}
+
+fn parse_variable(name: &str, line_number: usize) -> Result<String, anyhow::Error> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!("Line {line_number}: invalid variable name `{name}`");
+    }
+    Ok(name.to_string())
+}
+
+/// The reference of `${REFERENCE}`.
+fn parse_reference(s: &str) -> Option<&str> {
+    s.strip_prefix("${")?
+        .strip_suffix('}')
+        .filter(|reference| !reference.contains('}'))
+}
+
+#[derive(Default)]
+struct ScriptVariables(BTreeMap<String, Value>);
+
+impl ScriptVariables {
+    fn set(&mut self, variable: &str, value: Value) {
+        self.0.insert(variable.to_string(), value);
+    }
+
+    /// The value referenced by `VARIABLE.FIELD.INDEX[KEY=VALUE]...`.
+    fn resolve(&self, reference: &str) -> Result<Value, anyhow::Error> {
+        let mut segments = reference.split('.');
+        let (variable, filters) = parse_segment(segments.next().unwrap_or_default())?;
+        let mut value = self
+            .0
+            .get(variable)
+            .cloned()
+            .ok_or_else(|| anyhow!("Variable `{variable}` is not set"))?;
+        value = apply_filters(value, &filters);
+        for segment in segments {
+            let (key, filters) = parse_segment(segment)?;
+            value = apply_filters(select(value, key), &filters);
+        }
+        if value.is_null() {
+            bail!("`${{{reference}}}` has no value");
+        }
+        Ok(value)
+    }
+
+    /// Substitutes the `${REFERENCE}`s of the text by their value.
+    fn substitute(&self, text: &str) -> Result<String, anyhow::Error> {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            let end = rest[start..]
+                .find('}')
+                .ok_or_else(|| anyhow!("Unterminated reference in `{text}`"))?;
+            result.push_str(&rest[..start]);
+            match self.resolve(&rest[start + 2..start + end])? {
+                Value::String(s) => result.push_str(&s),
+                value => result.push_str(&value.to_string()),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        Ok(result)
+    }
+}
+
+/// Splits `KEY[FIELD=VALUE]...` into the key and its filters.
+fn parse_segment(segment: &str) -> Result<(&str, Vec<(&str, &str)>), anyhow::Error> {
+    let mut parts = segment.split('[');
+    let key = parts.next().unwrap_or_default();
+    let filters = parts
+        .map(|filter| {
+            filter
+                .strip_suffix(']')
+                .and_then(|filter| filter.split_once('='))
+                .ok_or_else(|| anyhow!("Invalid filter `[{filter}` in `{segment}`"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((key, filters))
+}
+
+/// Selects an element of an array by index or a field of an object. Fields are selected on each
+/// element of arrays.
+fn select(value: Value, key: &str) -> Value {
+    match (value, key.parse::<usize>()) {
+        (Value::Array(items), Ok(index)) => items.into_iter().nth(index).unwrap_or_default(),
+        (Value::Array(items), Err(_)) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| select(item, key))
+                .filter(|item| !item.is_null())
+                .collect(),
+        ),
+        (Value::Object(mut fields), _) => fields.remove(key).unwrap_or_default(),
+        _ => Value::Null,
+    }
+}
+
+fn apply_filters(value: Value, filters: &[(&str, &str)]) -> Value {
+    match value {
+        Value::Array(items) if !filters.is_empty() => Value::Array(
+            items
+                .into_iter()
+                .filter(|item| {
+                    filters.iter().all(|(key, expected)| match item.get(key) {
+                        Some(Value::String(s)) => s == expected,
+                        Some(value) => value.to_string() == *expected,
+                        None => false,
+                    })
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
 fn split_and_unescape(line: &str) -> Result<Vec<String>, anyhow::Error> {
     let mut commands = Vec::new();
     let split: Vec<String> = shell_words::split(line)?;
@@ -322,6 +682,12 @@ pub trait AsyncHandler<T: Send> {
     ) -> bool;
 }
 
+#[async_trait]
+pub trait ScriptHandler<T: Send> {
+    /// Runs a command of a script, returning its output to capture.
+    async fn run_command(&self, args: Vec<String>, state: &mut T) -> Result<Value, anyhow::Error>;
+}
+
 pub type CompletionCache = Arc<RwLock<BTreeMap<CacheKey, Vec<String>>>>;
 
 #[derive(PartialEq)]
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client_commands::SuiClientCommands;
-use crate::console::start_console;
+use crate::console::{run_script, start_console};
 use crate::fire_drill::{run_fire_drill, FireDrill};
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
@@ -29,12 +29,14 @@ use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_move::{self, execute_move_command};
 use sui_move_build::SuiPackageHooks;
 use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
-use sui_sdk::wallet_context::WalletContext;
+use sui_sdk::wallet_context::{Finality, WalletContext};
 use sui_swarm::memory::Swarm;
 use sui_swarm_config::genesis_config::{GenesisConfig, DEFAULT_NUMBER_OF_AUTHORITIES};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_swarm_config::network_config_builder::ConfigBuilder;
 use sui_swarm_config::node_config_builder::FullnodeConfigBuilder;
+use sui_transaction_builder::offline::OfflineState;
+use sui_types::base_types::SuiAddress;
 use sui_types::crypto::{SignatureScheme, SuiKeyPair};
 use tracing::info;
 
@@ -104,6 +106,12 @@ pub enum SuiCommand {
         /// Sets the file storing the state of our user accounts (an empty one will be created if missing)
         #[clap(long = "client.config")]
         config: Option<PathBuf>,
+        /// Run the console commands of this script file instead of the interactive console
+        #[clap(long)]
+        script: Option<PathBuf>,
+        /// Keep running the script after a command failed, the script fails once complete
+        #[clap(long, requires = "script")]
+        continue_on_error: bool,
     },
     /// Client for interacting with the Sui network.
     #[clap(name = "client")]
@@ -118,6 +126,28 @@ pub enum SuiCommand {
         json: bool,
         #[clap(short = 'y', long = "yes")]
         accept_defaults: bool,
+        /// Build transactions without accessing the network, from the objects of the state file
+        /// given with `--state-file`. Transactions are serialized instead of executed. Publish,
+        /// upgrade and PTB transactions need the network and can't be built offline.
+        #[clap(long)]
+        offline: bool,
+        /// State file exported with `sui client export-state` to build transactions from offline
+        #[clap(long, requires = "offline")]
+        state_file: Option<PathBuf>,
+        /// Build sponsored transactions, whose gas is paid with the coins of this address. They can
+        /// only be executed directly if the wallet manages the keys of both the sender and the
+        /// sponsor, otherwise they are serialized with `--serialize-unsigned-transaction`, signed
+        /// with `sui keytool sign` by each and executed with `sui client execute-signed-tx`
+        #[clap(long)]
+        sponsor: Option<SuiAddress>,
+        /// Wait until executed transactions are included in a checkpoint before returning, instead
+        /// of until the fullnode executed them
+        #[clap(long)]
+        wait_for_checkpoint: bool,
+        /// Wait until executed transactions are checkpointed and can be read from the fullnode at
+        /// this URL before returning, e.g. the one an indexer reads from
+        #[clap(long)]
+        indexed_by: Option<String>,
     },
     /// A tool for validators and validator candidates.
     #[clap(name = "validator")]
@@ -260,24 +290,58 @@ impl SuiCommand {
             SuiCommand::KeyTool { keystore_path, cmd } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+                let mut keystore = cmd.load_keystore(&keystore_path)?;
                 cmd.execute(&mut keystore).await
             }
-            SuiCommand::Console { config } => {
+            SuiCommand::Console {
+                config,
+                script,
+                continue_on_error,
+            } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config, false).await?;
                 let context = WalletContext::new(&config, None, None).await?;
-                start_console(context, &mut stdout(), &mut stderr()).await
+                match script {
+                    Some(script) => {
+                        run_script(
+                            context,
+                            &script,
+                            continue_on_error,
+                            &mut stdout(),
+                            &mut stderr(),
+                        )
+                        .await
+                    }
+                    None => start_console(context, &mut stdout(), &mut stderr()).await,
+                }
             }
             SuiCommand::Client {
                 config,
                 cmd,
                 json,
                 accept_defaults,
+                offline,
+                state_file,
+                sponsor,
+                wait_for_checkpoint,
+                indexed_by,
             } => {
                 let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config_path, accept_defaults).await?;
                 let mut context = WalletContext::new(&config_path, None, None).await?;
+                if offline {
+                    let state_file = state_file
+                        .ok_or_else(|| anyhow!("A state file is required to work offline"))?;
+                    context.set_offline_state(OfflineState::load(&state_file)?);
+                }
+                if let Some(sponsor) = sponsor {
+                    context.set_sponsor(sponsor);
+                }
+                if let Some(url) = indexed_by {
+                    context.set_finality(Finality::IndexedBy(url));
+                } else if wait_for_checkpoint {
+                    context.set_finality(Finality::Checkpoint);
+                }
                 if let Some(cmd) = cmd {
                     cmd.execute(&mut context).await?.print(!json);
                 } else {
@@ -353,8 +417,11 @@ async fn genesis(
 
     if write_config.is_none() && !files.is_empty() {
         if force {
-            // check old keystore and client.yaml is compatible
-            let is_compatible = FileBasedKeystore::new(&keystore_path).is_ok()
+            // check old keystore and client.yaml is compatible, an encrypted keystore may not
+            // be loadable without its passphrase but is kept regardless
+            let is_compatible = (FileBasedKeystore::is_encrypted_file(&keystore_path)
+                .unwrap_or(false)
+                || FileBasedKeystore::new(&keystore_path).is_ok())
                 && PersistedConfig::<SuiClientConfig>::read(&client_path).is_ok();
             // Keep keystore and client.yaml if they are compatible
             if is_compatible {
@@ -626,6 +693,7 @@ async fn prompt_if_no_config(
                 envs: vec![env],
                 active_address: Some(new_address),
                 active_env: Some(alias),
+                gas_budget_margin: None,
             }
             .persisted(wallet_conf_path)
             .save()?;
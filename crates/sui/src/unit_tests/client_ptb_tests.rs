@@ -0,0 +1,68 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn test_parse_arguments() {
+    assert_eq!(PtbArg::from(json!("$gas")), PtbArg::GasCoin);
+    assert_eq!(PtbArg::from(json!("$3")), PtbArg::Result(3));
+    assert_eq!(PtbArg::from(json!("$3.1")), PtbArg::NestedResult(3, 1));
+    for value in [
+        json!("$"),
+        json!("$3."),
+        json!("$x.1"),
+        json!("0x2"),
+        json!(12),
+        json!(["$0"]),
+    ] {
+        assert_eq!(PtbArg::from(value.clone()), PtbArg::Value(value));
+    }
+}
+
+#[test]
+fn test_parse_commands() {
+    let commands = parse_ptb_commands(
+        r#"[
+            {"SplitCoins": {"coin": "$gas", "amounts": [1000, "2000"]}},
+            {"MoveCall": {"package": "0x2", "module": "coin", "function": "value",
+                "type_args": ["0x2::sui::SUI"], "args": ["$0.0"]}},
+            {"MakeMoveVec": {"type": "u64", "elements": []}}
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        commands,
+        vec![
+            PtbCommand::SplitCoins {
+                coin: json!("$gas"),
+                amounts: vec![json!(1000), json!("2000")],
+            },
+            PtbCommand::MoveCall {
+                package: ObjectID::from_single_byte(2),
+                module: "coin".to_string(),
+                function: "value".to_string(),
+                type_args: vec!["0x2::sui::SUI".to_string()],
+                args: vec![json!("$0.0")],
+            },
+            PtbCommand::MakeMoveVec {
+                type_: Some("u64".to_string()),
+                elements: vec![],
+            },
+        ]
+    );
+
+    assert!(parse_ptb_commands("[]").is_err());
+    assert!(parse_ptb_commands(r#"[{"SplitCoin": {"coin": "$gas"}}]"#).is_err());
+}
+
+#[test]
+fn test_primitive_layout() {
+    assert!(matches!(
+        primitive_layout(&parse_sui_type_tag("vector<u8>").unwrap()),
+        Some(MoveTypeLayout::Vector(inner)) if matches!(*inner, MoveTypeLayout::U8)
+    ));
+    assert!(primitive_layout(&parse_sui_type_tag("0x2::sui::SUI").unwrap()).is_none());
+}
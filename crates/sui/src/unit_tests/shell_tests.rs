@@ -5,15 +5,18 @@ use std::collections::BTreeMap;
 use std::env;
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use rustyline::completion::Completer;
 use rustyline::history::History;
 use rustyline::Context;
+use serde_json::{json, Value};
 
 use sui_types::base_types::ObjectID;
 
 use crate::shell::split_and_unescape;
 use crate::shell::{
-    substitute_env_variables, CacheKey, CommandStructure, CompletionCache, ShellHelper,
+    substitute_env_variables, CacheKey, CommandStructure, CompletionCache, Script, ScriptHandler,
+    ScriptVariables, ShellHelper, Statement,
 };
 
 #[test]
@@ -233,3 +236,158 @@ fn test_split_line() {
         result
     );
 }
+
+#[test]
+fn test_parse_script() {
+    let script = Script::parse(
+        "# Publish\n\
+         let published = publish --path . \\\n    --gas-budget 1000\n\
+         for id in ${published.created}\n\
+             echo ${id}\n\
+         end\n",
+    )
+    .unwrap();
+    assert_eq!(
+        vec![
+            (
+                2,
+                Statement::Command {
+                    capture: Some("published".to_string()),
+                    command: "publish --path . --gas-budget 1000".to_string(),
+                }
+            ),
+            (
+                4,
+                Statement::For {
+                    variable: "id".to_string(),
+                    items: "published.created".to_string(),
+                    end: 3,
+                }
+            ),
+            (
+                5,
+                Statement::Command {
+                    capture: None,
+                    command: "echo ${id}".to_string(),
+                }
+            ),
+            (6, Statement::End),
+        ],
+        script.statements
+    );
+
+    assert!(Script::parse("for id in ${ids}").is_err());
+    assert!(Script::parse("end").is_err());
+    assert!(Script::parse("let = objects").is_err());
+}
+
+#[test]
+fn test_script_variables() {
+    let mut variables = ScriptVariables::default();
+    variables.set(
+        "published",
+        json!({
+            "digest": "abc",
+            "objectChanges": [
+                {"type": "published", "packageId": "0x1"},
+                {"type": "created", "objectId": "0x2", "version": 3},
+                {"type": "created", "objectId": "0x4", "version": 5},
+            ]
+        }),
+    );
+
+    assert_eq!(
+        "tx abc",
+        variables.substitute("tx ${published.digest}").unwrap()
+    );
+    assert_eq!(
+        json!("0x1"),
+        variables
+            .resolve("published.objectChanges[type=published].0.packageId")
+            .unwrap()
+    );
+    assert_eq!(
+        json!(["0x2", "0x4"]),
+        variables
+            .resolve("published.objectChanges[type=created].objectId")
+            .unwrap()
+    );
+    assert_eq!(
+        json!(["0x4"]),
+        variables
+            .resolve("published.objectChanges[type=created][version=5].objectId")
+            .unwrap()
+    );
+    assert!(variables.resolve("published.missing").is_err());
+    assert!(variables.substitute("${unknown}").is_err());
+}
+
+struct TestScriptHandler;
+
+#[async_trait]
+impl ScriptHandler<Vec<String>> for TestScriptHandler {
+    async fn run_command(
+        &self,
+        args: Vec<String>,
+        commands: &mut Vec<String>,
+    ) -> Result<Value, anyhow::Error> {
+        commands.push(args.join(" "));
+        match args[0].as_str() {
+            "objects" => Ok(json!([{"objectId": "0x1"}, {"objectId": "0x2"}])),
+            "fail" => Err(anyhow::anyhow!("Command failed")),
+            _ => Ok(Value::Null),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_run_script() {
+    let script = Script::parse(
+        "let objects = objects\n\
+         for object in ${objects.objectId}\n\
+             object ${object}\n\
+             fail\n\
+         end\n\
+         set last ${object}\n\
+         transfer ${last}\n",
+    )
+    .unwrap();
+
+    // Fail fast
+    let mut commands = vec![];
+    let result = script
+        .run(
+            &mut commands,
+            &TestScriptHandler,
+            false,
+            &mut std::io::sink(),
+            &mut std::io::sink(),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(vec!["objects", "object 0x1", "fail"], commands);
+
+    // Continue on error
+    let mut commands = vec![];
+    let result = script
+        .run(
+            &mut commands,
+            &TestScriptHandler,
+            true,
+            &mut std::io::sink(),
+            &mut std::io::sink(),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(
+        vec![
+            "objects",
+            "object 0x1",
+            "fail",
+            "object 0x2",
+            "fail",
+            "transfer 0x2"
+        ],
+        commands
+    );
+}
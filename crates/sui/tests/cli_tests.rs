@@ -6,17 +6,20 @@ use std::os::unix::prelude::FileExt;
 use std::{fmt::Write, fs::read_dir, path::PathBuf, str, thread, time::Duration};
 
 use expect_test::expect;
+use fastcrypto::encoding::{Base64, Encoding};
 use serde_json::json;
+use shared_crypto::intent::Intent;
 use sui_test_transaction_builder::batch_make_transfer_transactions;
 use sui_types::object::Owner;
 use sui_types::transaction::{
-    TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+    TransactionDataAPI, TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
     TEST_ONLY_GAS_UNIT_FOR_PUBLISH, TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
     TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
 };
 use tokio::time::sleep;
 
 use sui::client_commands::SwitchResponse;
+use sui::client_explain::DryRunPreview;
 use sui::{
     client_commands::{SuiClientCommandResult, SuiClientCommands},
     sui_commands::SuiCommand,
@@ -35,12 +38,14 @@ use sui_keys::keystore::{AccountKeystore, FileBasedKeystore};
 use sui_macros::sim_test;
 use sui_move_build::{BuildConfig, SuiPackageHooks};
 use sui_sdk::sui_client_config::SuiClientConfig;
-use sui_sdk::wallet_context::WalletContext;
+use sui_sdk::wallet_context::{Finality, WalletContext};
 use sui_swarm_config::genesis_config::{AccountConfig, GenesisConfig};
 use sui_swarm_config::network_config::NetworkConfig;
-use sui_types::base_types::SuiAddress;
+use sui_transaction_builder::offline::OfflineState;
+use sui_types::base_types::{SequenceNumber, SuiAddress};
 use sui_types::crypto::{
-    Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
+    Ed25519SuiSignature, EncodeDecodeBase64, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair,
+    SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
@@ -389,7 +394,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
         to: SuiAddress::random_for_testing_only(),
         object_id: object_to_send,
         gas: Some(object_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -438,7 +443,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_PUBLISH * rgp),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -522,7 +527,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args,
         gas: None,
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -561,7 +566,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -587,7 +592,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp,
+        gas_budget: Some(TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS * rgp),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -615,7 +620,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -660,7 +665,7 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -730,7 +735,7 @@ async fn test_package_publish_command_with_unpublished_dependency_succeeds(
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -799,7 +804,7 @@ async fn test_package_publish_command_with_unpublished_dependency_fails(
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -846,7 +851,7 @@ async fn test_package_publish_command_non_zero_unpublished_dep_fails() -> Result
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -902,7 +907,7 @@ async fn test_package_publish_command_failure_invalid() -> Result<(), anyhow::Er
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies,
         serialize_unsigned_transaction: false,
@@ -945,7 +950,7 @@ async fn test_package_publish_nonexistent_dependency() -> Result<(), anyhow::Err
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1002,7 +1007,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         package_path: package_path.clone(),
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         serialize_unsigned_transaction: false,
@@ -1075,7 +1080,7 @@ async fn test_package_upgrade_command() -> Result<(), anyhow::Error> {
         upgrade_capability: cap.reference.object_id,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH),
         skip_dependency_verification: false,
         with_unpublished_dependencies: false,
         legacy_digest: false,
@@ -1141,7 +1146,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas: Some(gas_obj_id),
         to: recipient,
         object_id: obj_id,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -1246,7 +1251,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
         gas: None,
         to: recipient,
         object_id: obj_id,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -1535,7 +1540,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         primary_coin,
         coin_to_merge,
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -1590,7 +1595,7 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
         primary_coin,
         coin_to_merge,
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: false,
     }
@@ -1653,7 +1658,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: Some(gas),
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
@@ -1721,7 +1726,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test split coin into equal parts
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: None,
         count: Some(3),
@@ -1792,7 +1797,7 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with no gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN),
         coin_id: coin,
         amounts: Some(vec![1000, 10]),
         count: None,
@@ -1834,6 +1839,462 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+
+    let commands = json!([
+        {"SplitCoins": {"coin": "$gas", "amounts": [1000, 10]}},
+        {"MoveCall": {
+            "package": "0x2",
+            "module": "coin",
+            "function": "join",
+            "type_args": ["0x2::sui::SUI"],
+            "args": ["$0.0", "$0.1"],
+        }},
+        {"TransferObjects": {"objects": ["$0.0"], "recipient": recipient.to_string()}},
+    ])
+    .to_string();
+
+    // Dry run with an estimated gas budget
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(commands.clone()),
+        file: None,
+        gas: None,
+        gas_budget: None,
+        dry_run: true,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DryRun(dry_run) = resp else {
+        panic!("Command failed")
+    };
+    assert!(dry_run.effects.status().is_ok());
+    assert_eq!(dry_run.effects.created().len(), 1);
+
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(commands),
+        file: None,
+        gas: None,
+        gas_budget: None,
+        dry_run: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Ptb(r) = resp else {
+        panic!("Command failed")
+    };
+    assert!(r.status_ok().unwrap(), "Command failed: {:?}", r);
+    let created = r.effects.unwrap().created().to_vec();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].owner, Owner::AddressOwner(recipient));
+    let coin = get_parsed_object_assert_existence(created[0].reference.object_id, context).await;
+    assert_eq!(get_gas_value(&coin), 1010);
+
+    // References must refer to earlier commands
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(
+            json!([{"TransferObjects": {"objects": ["$1"], "recipient": recipient.to_string()}}])
+                .to_string(),
+        ),
+        file: None,
+        gas: None,
+        gas_budget: None,
+        dry_run: true,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_auto_gas_budget() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coins = context.gas_objects(address).await?;
+
+    // The budget is estimated and a gas coin selected automatically
+    let resp = SuiClientCommands::Transfer {
+        to: recipient,
+        object_id: coins[0].1.object_id,
+        gas: None,
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Transfer(response) = resp else {
+        panic!("Command failed")
+    };
+    assert!(
+        response.status_ok().unwrap(),
+        "Command failed: {:?}",
+        response
+    );
+
+    // The gas coin is one of the input coins
+    let resp = SuiClientCommands::PayAllSui {
+        input_coins: vec![coins[1].1.object_id, coins[2].1.object_id],
+        recipient,
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::PayAllSui(response) = resp else {
+        panic!("Command failed")
+    };
+    assert!(
+        response.status_ok().unwrap(),
+        "Command failed: {:?}",
+        response
+    );
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_offline_transaction() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coins = context.gas_objects(address).await?;
+    let temp_dir = tempfile::tempdir()?;
+    let state_file = temp_dir.path().join("state.json");
+
+    let resp = SuiClientCommands::ExportState {
+        state_file: state_file.clone(),
+        object_ids: vec![],
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExportState { objects, .. } = resp else {
+        panic!("Command failed")
+    };
+    assert!(objects >= coins.len());
+
+    context.set_offline_state(OfflineState::load(&state_file)?);
+    assert!(context.get_client().await.is_err());
+
+    // Transactions are built from the state, and serialized unsigned
+    let resp = SuiClientCommands::Transfer {
+        to: recipient,
+        object_id: coins[0].1.object_id,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(tx_data.sender(), address);
+    assert_eq!(tx_data.gas_price(), rgp);
+    assert_ne!(tx_data.gas()[0].0, coins[0].1.object_id);
+
+    // The budget can't be estimated offline
+    let resp = SuiClientCommands::Transfer {
+        to: recipient,
+        object_id: coins[0].1.object_id,
+        gas: None,
+        gas_budget: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(resp.is_err());
+
+    // Commands needing the network fail before accessing it
+    let resp = SuiClientCommands::Ptb {
+        commands: Some(json!([]).to_string()),
+        file: None,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        dry_run: false,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await;
+    assert!(resp
+        .unwrap_err()
+        .to_string()
+        .contains("can't be used offline"));
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_sponsored_transaction() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let sender = test_cluster.get_address_0();
+    let sponsor = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let sender_coins = context.gas_objects(sender).await?;
+    let sponsor_coins = context.gas_objects(sponsor).await?;
+    context.set_sponsor(sponsor);
+
+    // The wallet manages both keys, the transaction is signed by both and executed
+    let resp = SuiClientCommands::Transfer {
+        to: sponsor,
+        object_id: sender_coins[0].1.object_id,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Transfer(response) = resp else {
+        panic!("Command failed")
+    };
+    let gas_object = response.effects.unwrap().gas_object().clone();
+    assert_eq!(gas_object.owner, Owner::AddressOwner(sponsor));
+    assert!(sponsor_coins
+        .iter()
+        .any(|(_, coin)| coin.object_id == gas_object.reference.object_id));
+
+    // Signatures made separately are combined to execute the transaction
+    let resp = SuiClientCommands::Transfer {
+        to: sponsor,
+        object_id: sender_coins[1].1.object_id,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        serialize_unsigned_transaction: true,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(tx_data.sender(), sender);
+    assert_eq!(tx_data.gas_owner(), sponsor);
+
+    let signatures = [sponsor, sender]
+        .iter()
+        .map(|signer| {
+            context
+                .config
+                .keystore
+                .sign_secure(signer, &tx_data, Intent::sui_transaction())
+                .unwrap()
+                .encode_base64()
+        })
+        .collect();
+    let resp = SuiClientCommands::ExecuteSignedTx {
+        tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+        signatures,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExecuteSignedTx(response) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(
+        response.effects.unwrap().gas_object().owner,
+        Owner::AddressOwner(sponsor)
+    );
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_wait_for_finality() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coins = context.gas_objects(address).await?;
+    let coin = coins[0].1.clone();
+    context.set_finality(Finality::Checkpoint);
+
+    let resp = SuiClientCommands::Transfer {
+        to: recipient,
+        object_id: coin.object_id,
+        gas: None,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::Transfer(response) = resp else {
+        panic!("Command failed")
+    };
+    let checkpoint = response
+        .checkpoint
+        .expect("Transaction must be checkpointed");
+
+    let resp = SuiClientCommands::WaitForTx {
+        digest: response.digest,
+        indexed_by: None,
+        timeout: 60,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::WaitForTx(response) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(response.checkpoint, Some(checkpoint));
+
+    // The object was already modified past the version it had before the transfer
+    let resp = SuiClientCommands::WatchObject {
+        id: coin.object_id,
+        version: Some(coin.version.value()),
+        timeout: 60,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::WatchObject(response) = resp else {
+        panic!("Command failed")
+    };
+    let object = response.object()?;
+    assert!(object.version > coin.version);
+    assert_eq!(object.owner, Some(Owner::AddressOwner(recipient)));
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_explain_tx() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let coins = context.gas_objects(address).await?;
+
+    let mut serialized = vec![];
+    for to in [recipient, address] {
+        let resp = SuiClientCommands::Transfer {
+            to,
+            object_id: coins[0].1.object_id,
+            gas: Some(coins[1].1.object_id),
+            gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
+            serialize_unsigned_transaction: true,
+            serialize_signed_transaction: false,
+        }
+        .execute(context)
+        .await?;
+        let SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) = resp else {
+            panic!("Command failed")
+        };
+        serialized.push(Base64::encode(bcs::to_bytes(&tx_data)?));
+    }
+
+    let resp = SuiClientCommands::ExplainTx {
+        tx_bytes: serialized[0].clone(),
+        diff: None,
+        no_dry_run: false,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::ExplainTx(explanation) = resp else {
+        panic!("Command failed")
+    };
+    assert_eq!(explanation.sender, address);
+    assert!(explanation
+        .inputs
+        .iter()
+        .any(|input| input == &format!("Pure address: {recipient}")));
+    assert!(explanation
+        .inputs
+        .iter()
+        .any(|input| input.starts_with(&format!("Object {}", coins[0].1.object_id))));
+    let Some(DryRunPreview::Executed {
+        status,
+        object_changes,
+        ..
+    }) = explanation.dry_run
+    else {
+        panic!("Dry run failed")
+    };
+    assert!(status.is_ok());
+    assert!(object_changes
+        .iter()
+        .any(|change| change.object_id() == coins[0].1.object_id));
+
+    // Only the recipient differs between the two transactions
+    let resp = SuiClientCommands::ExplainTx {
+        tx_bytes: serialized[0].clone(),
+        diff: Some(serialized[1].clone()),
+        no_dry_run: true,
+    }
+    .execute(context)
+    .await?;
+    let SuiClientCommandResult::DiffTx(diff) = resp else {
+        panic!("Command failed")
+    };
+    assert!(diff.contains(&format!("Pure address: {recipient}")));
+    assert!(diff.contains(&format!("Pure address: {address}")));
+
+    Ok(())
+}
+
+#[sim_test]
+async fn test_wait_for_object_creation() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let context = &mut test_cluster.wallet;
+    let client = context.get_client().await?;
+    let coin = context.gas_objects(address).await?[0].1.object_id;
+
+    let data = client
+        .transaction_builder()
+        .split_coin(
+            address,
+            coin,
+            vec![1000],
+            None,
+            rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
+        )
+        .await?;
+    let tx = context.sign_transaction(&data);
+    // The coin split off is the first object created by the transaction
+    let new_coin = ObjectID::derive_id(*tx.digest(), 0);
+
+    let (response, _) = tokio::join!(
+        client.read_api().wait_for_object_change(
+            new_coin,
+            SequenceNumber::MIN,
+            SuiObjectDataOptions::full_content(),
+            Duration::from_secs(60),
+        ),
+        async {
+            sleep(Duration::from_secs(1)).await;
+            context.execute_transaction_must_succeed(tx).await
+        }
+    );
+    let object = response?.into_object()?;
+    assert_eq!(object.object_id, new_coin);
+    assert_eq!(object.owner, Some(Owner::AddressOwner(address)));
+
+    Ok(())
+}
+
 #[sim_test]
 async fn test_signature_flag() -> Result<(), anyhow::Error> {
     let res = SignatureScheme::from_flag("0");
@@ -1898,7 +2359,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: address1,
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: true,
         serialize_signed_transaction: false,
@@ -1909,7 +2370,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     SuiClientCommands::TransferSui {
         to: address1,
         sui_coin_object_id: coin,
-        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        gas_budget: Some(rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER),
         amount: Some(1),
         serialize_unsigned_transaction: false,
         serialize_signed_transaction: true,
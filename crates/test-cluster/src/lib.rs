@@ -817,6 +817,7 @@ impl TestClusterBuilder {
             envs: Default::default(),
             active_address,
             active_env: Default::default(),
+            gas_budget_margin: None,
         }
         .save(wallet_path)?;
 
@@ -69,6 +69,8 @@ pub struct VMProfilerConfig {
     pub track_bytecode_instructions: bool,
     /// Whether or not to use the long name for functions
     pub use_long_function_name: bool,
+    /// Whether or not to record profiles even if the `MOVE_VM_PROFILE` env var is not set
+    pub enabled: bool,
 }
 
 #[cfg(debug_assertions)]
@@ -78,6 +80,7 @@ impl std::default::Default for VMProfilerConfig {
             base_path: std::path::PathBuf::from("."),
             track_bytecode_instructions: false,
             use_long_function_name: false,
+            enabled: false,
         }
     }
 }
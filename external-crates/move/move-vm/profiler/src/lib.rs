@@ -129,6 +129,10 @@ impl GasProfiler {
         (self.profiles[0].end_value != 0) && (self.start_gas != 0)
     }
 
+    fn is_enabled(&self) -> bool {
+        *PROFILER_ENABLED || self.config.enabled
+    }
+
     fn start_gas(&self) -> u64 {
         self.start_gas
     }
@@ -154,7 +158,7 @@ impl GasProfiler {
     }
 
     pub fn open_frame(&mut self, frame_name: String, metadata: String, gas_start: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.is_enabled() || self.start_gas == 0 {
             return;
         }
 
@@ -169,7 +173,7 @@ impl GasProfiler {
     }
 
     pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.is_enabled() || self.start_gas == 0 {
             return;
         }
         let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
@@ -184,7 +188,7 @@ impl GasProfiler {
     }
 
     pub fn to_file(&self) {
-        if !*PROFILER_ENABLED || !self.is_metered() {
+        if !self.is_enabled() || !self.is_metered() {
             return;
         }
         // Get the unix timestamp
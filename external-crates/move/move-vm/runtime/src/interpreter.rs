@@ -122,6 +122,12 @@ impl Interpreter {
 
         profile_open_frame!(gas_meter, function.pretty_string());
 
+        if gas_meter.is_tracing() {
+            if let Some(module_id) = function.module_id() {
+                gas_meter.trace_entrypoint(module_id, function.name(), args.iter());
+            }
+        }
+
         if function.is_native() {
             for arg in args {
                 interpreter
@@ -219,6 +225,14 @@ impl Interpreter {
                     .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
             match exit_code {
                 ExitCode::Return => {
+                    if gas_meter.is_tracing() {
+                        gas_meter.trace_return(
+                            self.operand_stack
+                                .last_n(current_frame.function.return_type_count())
+                                .map_err(|e| self.set_location(e))?,
+                        );
+                    }
+
                     let non_ref_vals = current_frame
                         .locals
                         .drop_all_values()
@@ -233,6 +233,26 @@ pub trait GasMeter {
         locals: impl Iterator<Item = impl ValueView>,
     ) -> PartialVMResult<()>;
 
+    /// Whether the meter is tracing execution. `trace_entrypoint` and `trace_return` are only
+    /// called when it is.
+    fn is_tracing(&self) -> bool {
+        false
+    }
+
+    /// Called when execution enters a function from outside the VM, with the arguments it is
+    /// called with. Nothing is charged, this only lets meters trace execution.
+    fn trace_entrypoint(
+        &mut self,
+        _module_id: &ModuleId,
+        _func_name: &str,
+        _args: impl ExactSizeIterator<Item = impl ValueView>,
+    ) {
+    }
+
+    /// Called when a Move function returns, with the values it returns. Nothing is charged, this
+    /// only lets meters trace execution.
+    fn trace_return(&mut self, _ret_vals: impl ExactSizeIterator<Item = impl ValueView>) {}
+
     /// Returns the gas left
     fn remaining_gas(&self) -> InternalGas;
 
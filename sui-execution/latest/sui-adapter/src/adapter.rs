@@ -1,13 +1,15 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
 use anyhow::Result;
 use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
 use move_bytecode_verifier::meter::Meter;
 use move_bytecode_verifier::verify_module_with_config_metered;
 use move_core_types::account_address::AccountAddress;
+#[cfg(debug_assertions)]
+use move_vm_config::runtime::VMProfilerConfig;
 use move_vm_config::{
     runtime::{VMConfig, VMRuntimeLimitsConfig},
     verifier::VerifierConfig,
@@ -82,7 +84,10 @@ pub fn new_move_vm(
     natives: NativeFunctionTable,
     protocol_config: &ProtocolConfig,
     paranoid_type_checks: bool,
+    enable_profiler: Option<PathBuf>,
 ) -> Result<MoveVM, SuiError> {
+    #[cfg(not(debug_assertions))]
+    let _ = enable_profiler;
     MoveVM::new_with_config(
         natives,
         VMConfig {
@@ -100,7 +105,15 @@ pub fn new_move_vm(
                 .disable_invariant_violation_check_in_swap_loc(),
             check_no_extraneous_bytes_during_deserialization: protocol_config
                 .no_extraneous_module_bytes(),
-            #[cfg(debug_assertions)] profiler_config: std::default::Default::default(),
+            #[cfg(debug_assertions)]
+            profiler_config: enable_profiler
+                .map(|base_path| VMProfilerConfig {
+                    base_path,
+                    track_bytecode_instructions: true,
+                    enabled: true,
+                    ..Default::default()
+                })
+                .unwrap_or_default(),
             // Don't augment errors with execution state on-chain
             error_execution_state: false,
         },
@@ -52,6 +52,7 @@ use sui_types::{
     execution::{CommandKind, ObjectContents, TryFromValue, Value},
     execution_mode::ExecutionMode,
     execution_status::CommandArgumentError,
+    execution_trace::ObjectAccess,
 };
 
 use super::linkage_view::{LinkageView, SavedLinkage};
@@ -294,6 +295,11 @@ impl<'vm, 'state, 'a> ExecutionContext<'vm, 'state, 'a> {
     ) -> Result<(), ExecutionError> {
         let object_runtime: &mut ObjectRuntime = self.session.get_native_extensions().get_mut();
         let events = object_runtime.take_user_events();
+        if let Some(trace) = self.gas_charger.move_gas_status_mut().trace_mut() {
+            for (_, tag, value) in &events {
+                trace.event(tag.clone(), value);
+            }
+        }
         let num_events = self.user_events.len() + events.len();
         let max_events = self.protocol_config.max_num_event_emit();
         if num_events as u64 > max_events {
@@ -855,9 +861,34 @@ impl<'vm, 'state, 'a> ExecutionContext<'vm, 'state, 'a> {
         arg: Argument,
         usage: UsageKind,
     ) -> Result<(Option<&InputObjectMetadata>, &mut Option<Value>), CommandArgumentError> {
+        self.trace_object_read(arg, &usage);
         self.borrow_mut_impl(arg, Some(usage))
     }
 
+    /// Records the use of an input object in the execution trace, if one is being recorded
+    fn trace_object_read(&mut self, arg: Argument, usage: &UsageKind) {
+        let metadata = match arg {
+            Argument::GasCoin => self.gas.object_metadata.as_ref(),
+            Argument::Input(i) => self
+                .inputs
+                .get(i as usize)
+                .and_then(|input_value| input_value.object_metadata.as_ref()),
+            Argument::Result(_) | Argument::NestedResult(_, _) => None,
+        };
+        let Some(metadata) = metadata else {
+            return;
+        };
+        let Some(trace) = self.gas_charger.move_gas_status_mut().trace_mut() else {
+            return;
+        };
+        let access = match usage {
+            UsageKind::BorrowImm => ObjectAccess::ByImmutableReference,
+            UsageKind::BorrowMut => ObjectAccess::ByMutableReference,
+            UsageKind::ByValue => ObjectAccess::ByValue,
+        };
+        trace.object_read(metadata.id, metadata.version, access);
+    }
+
     /// Internal helper to borrow the value for an argument
     /// Updates the most recent usage if specified
     fn borrow_mut_impl(
@@ -40,6 +40,7 @@ use sui_types::{
         CommandKind, ExecutionResults, ExecutionState, ObjectContents, ObjectValue, RawValueType,
         Value,
     },
+    execution_trace::ObjectAccess,
     gas::GasCharger,
     id::{RESOLVED_SUI_ID, UID},
     metrics::LimitsMetrics,
@@ -47,7 +48,7 @@ use sui_types::{
         normalize_deserialized_modules, MovePackage, UpgradeCap, UpgradePolicy, UpgradeReceipt,
         UpgradeTicket,
     },
-    storage::get_packages,
+    storage::{get_packages, ObjectChange},
     transaction::{Argument, Command, ProgrammableMoveCall, ProgrammableTransaction},
     SUI_FRAMEWORK_ADDRESS,
 };
@@ -86,8 +87,20 @@ pub fn execute<Mode: ExecutionMode>(
     )?;
     // execute commands
     let mut mode_results = Mode::empty_results();
+    // child objects already recorded in the execution trace, if one is being recorded
+    let mut traced_child_objects = BTreeSet::new();
     for (idx, command) in commands.into_iter().enumerate() {
-        if let Err(err) = execute_command::<Mode>(&mut context, &mut mode_results, command) {
+        context
+            .gas_charger
+            .move_gas_status_mut()
+            .open_profile_frame(format_args!("{idx}: {command}"));
+        if let Some(trace) = context.gas_charger.move_gas_status_mut().trace_mut() {
+            trace.start_command(format_args!("{idx}: {command}"));
+        }
+        let result = execute_command::<Mode>(&mut context, &mut mode_results, command);
+        context.gas_charger.move_gas_status_mut().close_profile_frame();
+        trace_command_end(&mut context, &mut traced_child_objects, result.as_ref().err());
+        if let Err(err) = result {
             let object_runtime: &ObjectRuntime = context.session.get_native_extensions().get();
             // We still need to record the loaded child objects for replay
             let loaded_child_objects = object_runtime.loaded_child_objects();
@@ -111,6 +124,11 @@ pub fn execute<Mode: ExecutionMode>(
         object_changes,
         user_events,
     } = finished?;
+    if let Some(trace) = gas_charger.move_gas_status_mut().trace_mut() {
+        for (id, change) in &object_changes {
+            trace.object_change(*id, matches!(change, ObjectChange::Delete(_)));
+        }
+    }
     state_view.apply_object_changes(object_changes);
     for (module_id, tag, contents) in user_events {
         state_view.log_event(Event::new(
@@ -124,6 +142,31 @@ pub fn execute<Mode: ExecutionMode>(
     Ok(mode_results)
 }
 
+/// Record in the execution trace, if one is being recorded, the child objects loaded by the command
+/// that just ran and whether it failed.
+fn trace_command_end(
+    context: &mut ExecutionContext<'_, '_, '_>,
+    traced_child_objects: &mut BTreeSet<ObjectID>,
+    error: Option<&ExecutionError>,
+) {
+    if context.gas_charger.move_gas_status_mut().trace_mut().is_none() {
+        return;
+    }
+    let object_runtime: &ObjectRuntime = context.session.get_native_extensions().get();
+    let loaded_child_objects = object_runtime.loaded_child_objects();
+    let Some(trace) = context.gas_charger.move_gas_status_mut().trace_mut() else {
+        return;
+    };
+    for (id, version) in loaded_child_objects {
+        if traced_child_objects.insert(id) {
+            trace.object_read(id, version, ObjectAccess::Child);
+        }
+    }
+    if let Some(error) = error {
+        trace.fail_command(format_args!("{:?}", error.kind()));
+    }
+}
+
 /// Execute a single command
 fn execute_command<Mode: ExecutionMode>(
     context: &mut ExecutionContext<'_, '_, '_>,
@@ -760,17 +803,24 @@ fn vm_move_call(
             serialized_arguments.push(context.tx_context.to_vec());
         }
     }
+    context
+        .gas_charger
+        .move_gas_status_mut()
+        .open_profile_frame(format_args!(
+            "{}::{}",
+            module_id.short_str_lossless(),
+            function
+        ));
     // script visibility checked manually for entry points
-    let mut result = context
-        .session
-        .execute_function_bypass_visibility(
-            module_id,
-            function,
-            type_arguments,
-            serialized_arguments,
-            context.gas_charger.move_gas_status_mut(),
-        )
-        .map_err(|e| context.convert_vm_error(e))?;
+    let result = context.session.execute_function_bypass_visibility(
+        module_id,
+        function,
+        type_arguments,
+        serialized_arguments,
+        context.gas_charger.move_gas_status_mut(),
+    );
+    context.gas_charger.move_gas_status_mut().close_profile_frame();
+    let mut result = result.map_err(|e| context.convert_vm_error(e))?;
 
     // When this function is used during publishing, it
     // may be executed several times, with objects being
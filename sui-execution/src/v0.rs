@@ -3,6 +3,7 @@
 
 use std::{
     collections::{BTreeSet, HashSet},
+    path::PathBuf,
     sync::Arc,
 };
 
@@ -50,11 +51,13 @@ impl Executor {
         protocol_config: &ProtocolConfig,
         paranoid_type_checks: bool,
         silent: bool,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<Self, SuiError> {
         Ok(Executor(Arc::new(new_move_vm(
             all_natives(silent),
             protocol_config,
             paranoid_type_checks,
+            enable_profiler,
         )?)))
     }
 }